@@ -10,6 +10,14 @@ OR IGNORE INTO tv_show_actors (tv_show_id, actor_id)
 VALUES
     (?, ?);
 
+-- name: find_actor_id_by_name
+SELECT
+    id
+FROM
+    actors
+WHERE
+    name = ?;
+
 -- name: find_actors_by_media_id
 SELECT 
     a.name,
@@ -1,4 +1,5 @@
 -- name: find_episodes_by_media_id_and_library_id
+-- ?1 tv show, ?2 library, ?3 user whose progress is attached
 select
     e.id,
     e.title,
@@ -12,21 +13,39 @@ select
     e.episode_number,
     e.runtime,
     s.season_number,
-    s.title as season_title
+    s.title as season_title,
+    p.episode_id as progress_episode_id,
+    p.position as progress_position,
+    p.duration as progress_duration,
+    p.completed as progress_completed,
+    p.updated_at as progress_updated_at,
+    mi.id as media_info_id,
+    mi.container as media_container,
+    mi.duration as media_duration,
+    mi.bitrate as media_bitrate,
+    mi.video_codec as media_video_codec,
+    mi.width as media_width,
+    mi.height as media_height,
+    mi.frame_rate as media_frame_rate,
+    mi.hdr_format as media_hdr_format
 from
     episodes e
     join seasons s on e.season_id = s.id
+    left join playback_progress p on p.episode_id = e.id
+    and p.user_id = ?3
+    left join media_info mi on mi.episode_id = e.id
     join tv_shows t on s.tv_show_id = t.id
     join library_tv_shows lts on lts.tv_show_id = t.id
 where
-    t.id = ?
-    and lts.library_id = ?
+    t.id = ?1
+    and lts.library_id = ?2
     and e.deleted_at is null
 order by
     s.season_number,
     e.episode_number;
 
 -- name: find_episodes_by_media_id
+-- ?1 tv show, ?2 user whose progress is attached
 select
     e.id,
     e.title,
@@ -40,14 +59,31 @@ select
     e.episode_number,
     e.runtime,
     s.season_number,
-    s.title as season_title
+    s.title as season_title,
+    p.episode_id as progress_episode_id,
+    p.position as progress_position,
+    p.duration as progress_duration,
+    p.completed as progress_completed,
+    p.updated_at as progress_updated_at,
+    mi.id as media_info_id,
+    mi.container as media_container,
+    mi.duration as media_duration,
+    mi.bitrate as media_bitrate,
+    mi.video_codec as media_video_codec,
+    mi.width as media_width,
+    mi.height as media_height,
+    mi.frame_rate as media_frame_rate,
+    mi.hdr_format as media_hdr_format
 from
     episodes e
     join seasons s on e.season_id = s.id
+    left join playback_progress p on p.episode_id = e.id
+    and p.user_id = ?2
+    left join media_info mi on mi.episode_id = e.id
     join tv_shows t on s.tv_show_id = t.id
     join library_tv_shows lts on lts.tv_show_id = t.id
 where
-    t.id = ?
+    t.id = ?1
     and lts.library_id = 1
     and e.deleted_at is null
 order by
@@ -55,8 +91,9 @@ order by
     e.episode_number;
 
 -- name: save_episode
-INSERT
-OR IGNORE INTO episodes (
+-- A file found for an episode which is already stored, e.g. a renamed or replaced file,
+-- takes over the existing row so the episode keeps its id and playback progress.
+INSERT INTO episodes (
     season_id,
     title,
     original_title,
@@ -67,7 +104,128 @@ OR IGNORE INTO episodes (
     thumb_image_url,
     thumb_image,
     episode_number,
-    runtime
+    runtime,
+    file_size,
+    file_modified_at,
+    nfo_modified_at
 )
 VALUES
-    (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?);
+    (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+ON CONFLICT (season_id, episode_number) DO UPDATE
+SET
+    video_file_path = excluded.video_file_path,
+    file_size = excluded.file_size,
+    file_modified_at = excluded.file_modified_at,
+    updated_at = CURRENT_TIMESTAMP,
+    deleted_at = NULL
+WHERE
+    video_file_path != excluded.video_file_path
+    AND NOT EXISTS (
+        SELECT
+            1
+        FROM
+            episodes
+        WHERE
+            video_file_path = excluded.video_file_path
+    )
+ON CONFLICT DO NOTHING;
+
+-- name: update_episode_by_video_file_path
+UPDATE episodes
+SET
+    title = coalesce(?, title),
+    original_title = ?,
+    plot = ?,
+    nfo_path = ?,
+    subtitle_file_path = ?,
+    thumb_image_url = ?,
+    thumb_image = ?,
+    runtime = ?,
+    file_size = ?,
+    file_modified_at = ?,
+    nfo_modified_at = ?,
+    updated_at = CURRENT_TIMESTAMP,
+    deleted_at = NULL
+WHERE
+    video_file_path = ?;
+
+-- name: soft_delete_episode_by_video_file_path
+UPDATE episodes
+SET
+    deleted_at = CURRENT_TIMESTAMP
+WHERE
+    video_file_path = ?
+    AND deleted_at IS NULL;
+
+-- name: find_library_episodes_without_thumbnail
+select
+    e.id,
+    e.video_file_path,
+    mi.duration
+from
+    episodes e
+    join seasons s on e.season_id = s.id
+    join library_tv_shows lts on lts.tv_show_id = s.tv_show_id
+    left join media_info mi on mi.episode_id = e.id
+where
+    lts.library_id = ?
+    and e.thumb_image is null
+    and e.deleted_at is null
+order by
+    e.id;
+
+-- name: update_episode_thumbnail
+UPDATE episodes
+SET
+    thumb_image = ?,
+    updated_at = CURRENT_TIMESTAMP
+WHERE
+    id = ?
+    AND thumb_image IS NULL;
+
+-- name: find_library_episode_videos
+select
+    e.id,
+    e.video_file_path,
+    mi.duration
+from
+    episodes e
+    join seasons s on e.season_id = s.id
+    join library_tv_shows lts on lts.tv_show_id = s.tv_show_id
+    left join media_info mi on mi.episode_id = e.id
+where
+    lts.library_id = ?
+    and e.deleted_at is null
+order by
+    e.id;
+
+-- name: find_visible_episode_video_file_path
+-- ?1 episode, ?2 user whose granted libraries contain its tv show, ?3 highest certification
+-- level. Checks are skipped when null.
+select
+    e.video_file_path
+from
+    episodes e
+    join seasons s on s.id = e.season_id
+    join tv_shows ts on ts.id = s.tv_show_id
+where
+    e.id = ?1
+    and e.deleted_at is null
+    and ts.deleted_at is null
+    and (
+        ?3 is null
+        or ts.certification_level <= ?3
+    )
+    and (
+        ?2 is null
+        or exists (
+            select
+                1
+            from
+                library_tv_shows lts
+                join user_libraries ul on ul.library_id = lts.library_id
+            where
+                lts.tv_show_id = ts.id
+                and ul.user_id = ?2
+        )
+    );
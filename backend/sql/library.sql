@@ -40,16 +40,62 @@ WHERE
 
 -- name: get_library_posters
 select
-    ts.id,
-    ts.poster_path
+    id,
+    poster_path
 from
-    tv_shows ts
-    join library_tv_shows lts on lts.tv_show_id = ts.id
+    (
+        select
+            ts.id,
+            ts.poster_path,
+            lts.library_id
+        from
+            tv_shows ts
+            join library_tv_shows lts on lts.tv_show_id = ts.id
+        union all
+        select
+            m.id,
+            m.poster_path,
+            lm.library_id
+        from
+            movies m
+            join library_movies lm on lm.movie_id = m.id
+    )
 where
-    lts.library_id = ?
+    library_id = ?
 limit
     1;
 
+-- name: find_media_files_by_library_id
+-- The nfo fingerprint of an episode also covers the series metadata, the newer one wins.
+select
+    e.video_file_path,
+    e.file_size,
+    e.file_modified_at,
+    max(
+        coalesce(e.nfo_modified_at, ts.metadata_modified_at),
+        coalesce(ts.metadata_modified_at, e.nfo_modified_at)
+    ) as nfo_modified_at
+from
+    episodes e
+    join seasons s on e.season_id = s.id
+    join tv_shows ts on ts.id = s.tv_show_id
+    join library_tv_shows lts on lts.tv_show_id = s.tv_show_id
+where
+    lts.library_id = ?
+    and e.deleted_at is null
+union all
+select
+    m.video_file_path,
+    m.file_size,
+    m.file_modified_at,
+    m.nfo_modified_at
+from
+    movies m
+    join library_movies lm on lm.movie_id = m.id
+where
+    lm.library_id = ?
+    and m.deleted_at is null;
+
 -- name: save_library
 INSERT
 OR IGNORE INTO library (name, directory, category_id)
@@ -71,6 +117,53 @@ DELETE FROM library
 WHERE
     id = ?;
 
+-- name: update_library_status
+UPDATE library
+SET
+    current_status = ?,
+    error = ?,
+    last_scanned = coalesce(?, last_scanned),
+    updated_at = CURRENT_TIMESTAMP
+WHERE
+    id = ?;
+
+-- name: find_all_scan_schedules
+SELECT
+    library_id,
+    interval_hours,
+    daily_hour,
+    daily_minute
+FROM
+    library_scan_schedules;
+
+-- name: find_scan_schedule_by_library_id
+SELECT
+    library_id,
+    interval_hours,
+    daily_hour,
+    daily_minute
+FROM
+    library_scan_schedules
+WHERE
+    library_id = ?;
+
+-- name: save_scan_schedule
+INSERT INTO
+    library_scan_schedules (library_id, interval_hours, daily_hour, daily_minute)
+VALUES
+    (?, ?, ?, ?) ON CONFLICT (library_id) DO
+UPDATE
+SET
+    interval_hours = excluded.interval_hours,
+    daily_hour = excluded.daily_hour,
+    daily_minute = excluded.daily_minute,
+    updated_at = CURRENT_TIMESTAMP;
+
+-- name: delete_scan_schedule
+DELETE FROM library_scan_schedules
+WHERE
+    library_id = ?;
+
 -- name: update_library_by_id
 UPDATE library
 SET
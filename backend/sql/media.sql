@@ -27,7 +27,10 @@ group by
     ts.id,
     ts.title;
 
--- name: find_all_media
+-- name: find_library_media_page
+-- ?1 library, ?2 genre, ?3 studio, ?4 actor, ?5 and ?6 year range, ?7 minimum rating,
+-- ?8 country, ?9 highest certification level, ?10 sort key, ?11 sort order, ?12 limit,
+-- ?13 offset. Filters are skipped when null, unrated media is hidden by a certification cap.
 select
     ts.id,
     ts.title,
@@ -40,17 +43,214 @@ select
     ts.runtime,
     ts.year,
     ts.plot,
-    group_concat (g.name, ', ') AS genres,
-    group_concat (s.name, ', ') AS studios
+    (
+        select
+            group_concat (g.name)
+        from
+            tv_show_genres x
+            join genres g on g.id = x.genre_id
+        where
+            x.tv_show_id = ts.id
+    ) AS genres,
+    (
+        select
+            group_concat (s.name)
+        from
+            tv_show_studios x
+            join studios s on s.id = x.studio_id
+        where
+            x.tv_show_id = ts.id
+    ) AS studios
 from
     tv_shows ts
-    left join library_tv_shows lts on lts.tv_show_id = ts.id
-    left join tv_show_genres tsg on ts.id = tsg.tv_show_id
-    left join genres g on tsg.genre_id = g.id
-    left join tv_show_studios tss on ts.id = tss.tv_show_id
-    left join studios s on tss.studio_id = s.id
+    join library_tv_shows lts on lts.tv_show_id = ts.id
 where
-    lts.library_id = ?
-group by
-    ts.id,
-    ts.title;
+    lts.library_id = ?1
+    and ts.deleted_at is null
+    and (
+        ?9 is null
+        or ts.certification_level <= ?9
+    )
+    and (
+        ?2 is null
+        or exists (
+            select
+                1
+            from
+                tv_show_genres x
+                join genres g on g.id = x.genre_id
+            where
+                x.tv_show_id = ts.id
+                and g.name = ?2 collate nocase
+        )
+    )
+    and (
+        ?3 is null
+        or exists (
+            select
+                1
+            from
+                tv_show_studios x
+                join studios s on s.id = x.studio_id
+            where
+                x.tv_show_id = ts.id
+                and s.name = ?3 collate nocase
+        )
+    )
+    and (
+        ?4 is null
+        or exists (
+            select
+                1
+            from
+                tv_show_actors x
+                join actors a on a.id = x.actor_id
+            where
+                x.tv_show_id = ts.id
+                and a.name = ?4 collate nocase
+        )
+    )
+    and (
+        ?5 is null
+        or ts.year >= ?5
+    )
+    and (
+        ?6 is null
+        or ts.year <= ?6
+    )
+    and (
+        ?7 is null
+        or ts.rating >= ?7
+    )
+    and (
+        ?8 is null
+        or ts.country = ?8 collate nocase
+    )
+order by
+    -- Items without a year or rating come last in both directions
+    (
+        case ?10
+            when 'year' then ts.year
+            when 'rating' then ts.rating
+            else 0
+        end
+    ) is null,
+    case
+        when ?11 = 'desc' then null
+        else case ?10
+                when 'year' then ts.year
+                when 'rating' then ts.rating
+                when 'created_at' then ts.created_at
+                else ts.title collate nocase
+            end
+    end asc,
+    case
+        when ?11 = 'desc' then case ?10
+                when 'year' then ts.year
+                when 'rating' then ts.rating
+                when 'created_at' then ts.created_at
+                else ts.title collate nocase
+            end
+    end desc,
+    ts.id
+limit
+    ?12
+offset
+    ?13;
+
+-- name: count_library_media
+select
+    count(*) AS total
+from
+    tv_shows ts
+    join library_tv_shows lts on lts.tv_show_id = ts.id
+where
+    lts.library_id = ?1
+    and ts.deleted_at is null
+    and (
+        ?9 is null
+        or ts.certification_level <= ?9
+    )
+    and (
+        ?2 is null
+        or exists (
+            select
+                1
+            from
+                tv_show_genres x
+                join genres g on g.id = x.genre_id
+            where
+                x.tv_show_id = ts.id
+                and g.name = ?2 collate nocase
+        )
+    )
+    and (
+        ?3 is null
+        or exists (
+            select
+                1
+            from
+                tv_show_studios x
+                join studios s on s.id = x.studio_id
+            where
+                x.tv_show_id = ts.id
+                and s.name = ?3 collate nocase
+        )
+    )
+    and (
+        ?4 is null
+        or exists (
+            select
+                1
+            from
+                tv_show_actors x
+                join actors a on a.id = x.actor_id
+            where
+                x.tv_show_id = ts.id
+                and a.name = ?4 collate nocase
+        )
+    )
+    and (
+        ?5 is null
+        or ts.year >= ?5
+    )
+    and (
+        ?6 is null
+        or ts.year <= ?6
+    )
+    and (
+        ?7 is null
+        or ts.rating >= ?7
+    )
+    and (
+        ?8 is null
+        or ts.country = ?8 collate nocase
+    );
+
+-- name: count_visible_tv_show
+-- ?1 tv show, ?2 user whose granted libraries contain it, ?3 highest certification level.
+-- Checks are skipped when null.
+select
+    count(*) AS total
+from
+    tv_shows ts
+where
+    ts.id = ?1
+    and ts.deleted_at is null
+    and (
+        ?3 is null
+        or ts.certification_level <= ?3
+    )
+    and (
+        ?2 is null
+        or exists (
+            select
+                1
+            from
+                library_tv_shows lts
+                join user_libraries ul on ul.library_id = lts.library_id
+            where
+                lts.tv_show_id = ts.id
+                and ul.user_id = ?2
+        )
+    );
@@ -0,0 +1,160 @@
+-- name: save_episode_media_info
+INSERT INTO
+    media_info (
+        episode_id,
+        container,
+        duration,
+        bitrate,
+        video_codec,
+        width,
+        height,
+        frame_rate,
+        hdr_format
+    )
+SELECT
+    id,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?
+FROM
+    episodes
+WHERE
+    video_file_path = ?
+ON CONFLICT (episode_id) DO UPDATE
+SET
+    container = excluded.container,
+    duration = excluded.duration,
+    bitrate = excluded.bitrate,
+    video_codec = excluded.video_codec,
+    width = excluded.width,
+    height = excluded.height,
+    frame_rate = excluded.frame_rate,
+    hdr_format = excluded.hdr_format,
+    updated_at = CURRENT_TIMESTAMP;
+
+-- name: delete_episode_media_info
+DELETE FROM media_info
+WHERE
+    episode_id = (
+        SELECT
+            id
+        FROM
+            episodes
+        WHERE
+            video_file_path = ?
+    );
+
+-- name: delete_episode_audio_tracks
+DELETE FROM media_audio_tracks
+WHERE
+    media_info_id = (
+        SELECT
+            mi.id
+        FROM
+            media_info mi
+            JOIN episodes e ON mi.episode_id = e.id
+        WHERE
+            e.video_file_path = ?
+    );
+
+-- name: save_episode_audio_track
+INSERT INTO
+    media_audio_tracks (
+        media_info_id,
+        stream_index,
+        language,
+        title,
+        codec,
+        channels
+    )
+SELECT
+    mi.id,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?
+FROM
+    media_info mi
+    JOIN episodes e ON mi.episode_id = e.id
+WHERE
+    e.video_file_path = ?;
+
+-- name: delete_episode_subtitle_tracks
+DELETE FROM media_subtitle_tracks
+WHERE
+    media_info_id = (
+        SELECT
+            mi.id
+        FROM
+            media_info mi
+            JOIN episodes e ON mi.episode_id = e.id
+        WHERE
+            e.video_file_path = ?
+    );
+
+-- name: save_episode_subtitle_track
+INSERT INTO
+    media_subtitle_tracks (
+        media_info_id,
+        stream_index,
+        language,
+        title,
+        codec,
+        image_based
+    )
+SELECT
+    mi.id,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?
+FROM
+    media_info mi
+    JOIN episodes e ON mi.episode_id = e.id
+WHERE
+    e.video_file_path = ?;
+
+-- name: find_audio_tracks_by_media_id
+SELECT
+    mi.episode_id,
+    a.stream_index,
+    a.language,
+    a.title,
+    a.codec,
+    a.channels
+FROM
+    media_audio_tracks a
+    JOIN media_info mi ON a.media_info_id = mi.id
+    JOIN episodes e ON mi.episode_id = e.id
+    JOIN seasons s ON e.season_id = s.id
+WHERE
+    s.tv_show_id = ?
+ORDER BY
+    mi.episode_id,
+    a.stream_index;
+
+-- name: find_subtitle_tracks_by_media_id
+SELECT
+    mi.episode_id,
+    t.stream_index,
+    t.language,
+    t.title,
+    t.codec,
+    t.image_based
+FROM
+    media_subtitle_tracks t
+    JOIN media_info mi ON t.media_info_id = mi.id
+    JOIN episodes e ON mi.episode_id = e.id
+    JOIN seasons s ON e.season_id = s.id
+WHERE
+    s.tv_show_id = ?
+ORDER BY
+    mi.episode_id,
+    t.stream_index;
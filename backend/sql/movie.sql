@@ -0,0 +1,393 @@
+-- name: save_movie
+INSERT INTO
+    movies (
+        title,
+        original_title,
+        sort_title,
+        tagline,
+        nfo_path,
+        poster_path,
+        fanart_path,
+        video_file_path,
+        subtitle_file_path,
+        country,
+        year,
+        premiered,
+        rating,
+        certification,
+        certification_level,
+        runtime,
+        plot,
+        tmdb_id,
+        imdb_id,
+        wikidata_id,
+        file_size,
+        file_modified_at,
+        nfo_modified_at
+    )
+VALUES
+    (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) ON CONFLICT (video_file_path) DO
+UPDATE
+SET
+    id = id RETURNING id;
+
+-- name: save_library_movie
+INSERT
+OR IGNORE INTO library_movies (library_id, movie_id)
+VALUES
+    (?, ?);
+
+-- name: save_movie_genre
+INSERT
+OR IGNORE INTO movie_genres (movie_id, genre_id)
+VALUES
+    (?, ?);
+
+-- name: save_movie_studio
+INSERT
+OR IGNORE INTO movie_studios (movie_id, studio_id)
+VALUES
+    (?, ?);
+
+-- name: save_movie_actor
+INSERT
+OR IGNORE INTO movie_actors (movie_id, actor_id)
+VALUES
+    (?, ?);
+
+-- name: update_movie_by_video_file_path
+UPDATE movies
+SET
+    title = coalesce(?, title),
+    original_title = ?,
+    sort_title = ?,
+    tagline = ?,
+    nfo_path = ?,
+    poster_path = ?,
+    fanart_path = ?,
+    subtitle_file_path = ?,
+    country = ?,
+    year = ?,
+    premiered = ?,
+    rating = ?,
+    certification = ?,
+    certification_level = ?,
+    runtime = ?,
+    plot = ?,
+    tmdb_id = ?,
+    imdb_id = ?,
+    wikidata_id = ?,
+    file_size = ?,
+    file_modified_at = ?,
+    nfo_modified_at = ?,
+    updated_at = CURRENT_TIMESTAMP,
+    deleted_at = NULL
+WHERE
+    video_file_path = ?;
+
+-- name: soft_delete_movie_by_video_file_path
+UPDATE movies
+SET
+    deleted_at = CURRENT_TIMESTAMP
+WHERE
+    video_file_path = ?
+    AND deleted_at IS NULL;
+
+-- name: find_movie_by_id
+select
+    m.id,
+    m.title,
+    m.original_title,
+    m.poster_path,
+    m.fanart_path,
+    m.video_file_path,
+    m.country,
+    m.premiered,
+    m.rating,
+    m.runtime,
+    m.year,
+    m.plot,
+    group_concat (DISTINCT g.name) AS genres,
+    group_concat (DISTINCT s.name) AS studios
+from
+    movies m
+    join library_movies lm on lm.movie_id = m.id
+    left join movie_genres mg on m.id = mg.movie_id
+    left join genres g on mg.genre_id = g.id
+    left join movie_studios ms on m.id = ms.movie_id
+    left join studios s on ms.studio_id = s.id
+where
+    lm.library_id = ?
+    and m.id = ?
+    and m.deleted_at is null
+group by
+    m.id,
+    m.title;
+
+-- name: find_actors_by_movie_id
+SELECT 
+    a.name,
+    a.role,
+    a.thumb,
+    a.profile,
+    a.tmdb_id
+FROM actors a
+JOIN movie_actors ma ON a.id = ma.actor_id
+WHERE ma.movie_id = ?;
+
+-- name: find_library_movies_page
+-- ?1 library, ?2 genre, ?3 studio, ?4 actor, ?5 and ?6 year range, ?7 minimum rating,
+-- ?8 country, ?9 highest certification level, ?10 sort key, ?11 sort order, ?12 limit,
+-- ?13 offset. Filters are skipped when null, unrated media is hidden by a certification cap.
+select
+    m.id,
+    m.title,
+    m.original_title,
+    m.poster_path,
+    m.fanart_path,
+    m.video_file_path,
+    m.country,
+    m.premiered,
+    m.rating,
+    m.runtime,
+    m.year,
+    m.plot,
+    (
+        select
+            group_concat (g.name)
+        from
+            movie_genres x
+            join genres g on g.id = x.genre_id
+        where
+            x.movie_id = m.id
+    ) AS genres,
+    (
+        select
+            group_concat (s.name)
+        from
+            movie_studios x
+            join studios s on s.id = x.studio_id
+        where
+            x.movie_id = m.id
+    ) AS studios
+from
+    movies m
+    join library_movies lm on lm.movie_id = m.id
+where
+    lm.library_id = ?1
+    and m.deleted_at is null
+    and (
+        ?9 is null
+        or m.certification_level <= ?9
+    )
+    and (
+        ?2 is null
+        or exists (
+            select
+                1
+            from
+                movie_genres x
+                join genres g on g.id = x.genre_id
+            where
+                x.movie_id = m.id
+                and g.name = ?2 collate nocase
+        )
+    )
+    and (
+        ?3 is null
+        or exists (
+            select
+                1
+            from
+                movie_studios x
+                join studios s on s.id = x.studio_id
+            where
+                x.movie_id = m.id
+                and s.name = ?3 collate nocase
+        )
+    )
+    and (
+        ?4 is null
+        or exists (
+            select
+                1
+            from
+                movie_actors x
+                join actors a on a.id = x.actor_id
+            where
+                x.movie_id = m.id
+                and a.name = ?4 collate nocase
+        )
+    )
+    and (
+        ?5 is null
+        or m.year >= ?5
+    )
+    and (
+        ?6 is null
+        or m.year <= ?6
+    )
+    and (
+        ?7 is null
+        or m.rating >= ?7
+    )
+    and (
+        ?8 is null
+        or m.country = ?8 collate nocase
+    )
+order by
+    -- Items without a year or rating come last in both directions
+    (
+        case ?10
+            when 'year' then m.year
+            when 'rating' then m.rating
+            else 0
+        end
+    ) is null,
+    case
+        when ?11 = 'desc' then null
+        else case ?10
+                when 'year' then m.year
+                when 'rating' then m.rating
+                when 'created_at' then m.created_at
+                else coalesce(m.sort_title, m.title) collate nocase
+            end
+    end asc,
+    case
+        when ?11 = 'desc' then case ?10
+                when 'year' then m.year
+                when 'rating' then m.rating
+                when 'created_at' then m.created_at
+                else coalesce(m.sort_title, m.title) collate nocase
+            end
+    end desc,
+    m.id
+limit
+    ?12
+offset
+    ?13;
+
+-- name: count_library_movies
+select
+    count(*) AS total
+from
+    movies m
+    join library_movies lm on lm.movie_id = m.id
+where
+    lm.library_id = ?1
+    and m.deleted_at is null
+    and (
+        ?9 is null
+        or m.certification_level <= ?9
+    )
+    and (
+        ?2 is null
+        or exists (
+            select
+                1
+            from
+                movie_genres x
+                join genres g on g.id = x.genre_id
+            where
+                x.movie_id = m.id
+                and g.name = ?2 collate nocase
+        )
+    )
+    and (
+        ?3 is null
+        or exists (
+            select
+                1
+            from
+                movie_studios x
+                join studios s on s.id = x.studio_id
+            where
+                x.movie_id = m.id
+                and s.name = ?3 collate nocase
+        )
+    )
+    and (
+        ?4 is null
+        or exists (
+            select
+                1
+            from
+                movie_actors x
+                join actors a on a.id = x.actor_id
+            where
+                x.movie_id = m.id
+                and a.name = ?4 collate nocase
+        )
+    )
+    and (
+        ?5 is null
+        or m.year >= ?5
+    )
+    and (
+        ?6 is null
+        or m.year <= ?6
+    )
+    and (
+        ?7 is null
+        or m.rating >= ?7
+    )
+    and (
+        ?8 is null
+        or m.country = ?8 collate nocase
+    );
+
+-- name: count_visible_movie
+-- ?1 movie, ?2 user whose granted libraries contain it, ?3 highest certification level.
+-- Checks are skipped when null.
+select
+    count(*) AS total
+from
+    movies m
+where
+    m.id = ?1
+    and m.deleted_at is null
+    and (
+        ?3 is null
+        or m.certification_level <= ?3
+    )
+    and (
+        ?2 is null
+        or exists (
+            select
+                1
+            from
+                library_movies lm
+                join user_libraries ul on ul.library_id = lm.library_id
+            where
+                lm.movie_id = m.id
+                and ul.user_id = ?2
+        )
+    );
+
+-- name: find_visible_movie_video_file_path
+-- ?1 movie, ?2 user whose granted libraries contain it, ?3 highest certification level.
+-- Checks are skipped when null.
+select
+    m.video_file_path
+from
+    movies m
+where
+    m.id = ?1
+    and m.deleted_at is null
+    and (
+        ?3 is null
+        or m.certification_level <= ?3
+    )
+    and (
+        ?2 is null
+        or exists (
+            select
+                1
+            from
+                library_movies lm
+                join user_libraries ul on ul.library_id = lm.library_id
+            where
+                lm.movie_id = m.id
+                and ul.user_id = ?2
+        )
+    );
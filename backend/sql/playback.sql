@@ -0,0 +1,195 @@
+-- name: save_playback_progress
+INSERT INTO
+    playback_progress (user_id, episode_id, position, duration, completed)
+VALUES
+    (?, ?, ?, ?, ?) ON CONFLICT (user_id, episode_id) DO
+UPDATE
+SET
+    position = excluded.position,
+    duration = coalesce(excluded.duration, duration),
+    completed = excluded.completed,
+    updated_at = CURRENT_TIMESTAMP;
+
+-- name: find_continue_watching
+-- ?1 limit, ?2 user whose granted libraries are visible, ?3 highest certification level,
+-- ?4 user whose progress is listed
+select
+    t.id as media_id,
+    t.title as media_title,
+    t.poster_path,
+    e.id,
+    e.title,
+    e.original_title,
+    e.plot,
+    e.nfo_path,
+    e.video_file_path,
+    e.subtitle_file_path,
+    e.thumb_image_url,
+    e.thumb_image,
+    e.episode_number,
+    e.runtime,
+    s.season_number,
+    s.title as season_title,
+    p.episode_id as progress_episode_id,
+    p.position as progress_position,
+    p.duration as progress_duration,
+    p.completed as progress_completed,
+    p.updated_at as progress_updated_at,
+    mi.id as media_info_id,
+    mi.container as media_container,
+    mi.duration as media_duration,
+    mi.bitrate as media_bitrate,
+    mi.video_codec as media_video_codec,
+    mi.width as media_width,
+    mi.height as media_height,
+    mi.frame_rate as media_frame_rate,
+    mi.hdr_format as media_hdr_format
+from
+    playback_progress p
+    join episodes e on p.episode_id = e.id
+    join seasons s on e.season_id = s.id
+    join tv_shows t on s.tv_show_id = t.id
+    left join media_info mi on mi.episode_id = e.id
+where
+    p.user_id = ?4
+    and p.completed = FALSE
+    and p.position > 0
+    and e.deleted_at is null
+    and t.deleted_at is null
+    and (
+        ?3 is null
+        or t.certification_level <= ?3
+    )
+    and (
+        ?2 is null
+        or exists (
+            select
+                1
+            from
+                library_tv_shows lts
+                join user_libraries ul on ul.library_id = lts.library_id
+            where
+                lts.tv_show_id = t.id
+                and ul.user_id = ?2
+        )
+    )
+order by
+    p.updated_at desc,
+    p.id desc
+limit
+    ?1;
+
+-- name: find_next_up
+-- ?1 limit, ?2 user whose granted libraries are visible, ?3 highest certification level,
+-- ?4 user whose progress is followed
+with
+    last_watched as (
+        select
+            s.tv_show_id,
+            s.season_number,
+            e.episode_number,
+            p.updated_at,
+            row_number() over (
+                partition by s.tv_show_id
+                order by
+                    p.updated_at desc,
+                    p.id desc
+            ) as watch_rank
+        from
+            playback_progress p
+            join episodes e on p.episode_id = e.id
+            join seasons s on e.season_id = s.id
+        where
+            p.user_id = ?4
+            and p.completed = TRUE
+    ),
+    candidates as (
+        select
+            lw.updated_at as last_watched_at,
+            t.id as media_id,
+            t.title as media_title,
+            t.poster_path,
+            e.id,
+            e.title,
+            e.original_title,
+            e.plot,
+            e.nfo_path,
+            e.video_file_path,
+            e.subtitle_file_path,
+            e.thumb_image_url,
+            e.thumb_image,
+            e.episode_number,
+            e.runtime,
+            s.season_number,
+            s.title as season_title,
+            p.episode_id as progress_episode_id,
+            p.position as progress_position,
+            p.duration as progress_duration,
+            p.completed as progress_completed,
+            p.updated_at as progress_updated_at,
+            mi.id as media_info_id,
+            mi.container as media_container,
+            mi.duration as media_duration,
+            mi.bitrate as media_bitrate,
+            mi.video_codec as media_video_codec,
+            mi.width as media_width,
+            mi.height as media_height,
+            mi.frame_rate as media_frame_rate,
+            mi.hdr_format as media_hdr_format,
+            row_number() over (
+                partition by t.id
+                order by
+                    s.season_number,
+                    e.episode_number
+            ) as episode_rank
+        from
+            last_watched lw
+            join tv_shows t on t.id = lw.tv_show_id
+            join seasons s on s.tv_show_id = t.id
+            join episodes e on e.season_id = s.id
+            left join playback_progress p on p.episode_id = e.id
+            and p.user_id = ?4
+            left join media_info mi on mi.episode_id = e.id
+        where
+            lw.watch_rank = 1
+            and (
+                s.season_number > lw.season_number
+                or (
+                    s.season_number = lw.season_number
+                    and e.episode_number > lw.episode_number
+                )
+            )
+            and (
+                p.completed is null
+                or p.completed = FALSE
+            )
+            and e.deleted_at is null
+            and t.deleted_at is null
+            and (
+                ?3 is null
+                or t.certification_level <= ?3
+            )
+            and (
+                ?2 is null
+                or exists (
+                    select
+                        1
+                    from
+                        library_tv_shows lts
+                        join user_libraries ul on ul.library_id = lts.library_id
+                    where
+                        lts.tv_show_id = t.id
+                        and ul.user_id = ?2
+                )
+            )
+    )
+select
+    *
+from
+    candidates
+where
+    episode_rank = 1
+order by
+    last_watched_at desc
+limit
+    ?1;
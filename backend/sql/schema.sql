@@ -16,6 +16,17 @@ CREATE TABLE IF NOT EXISTS library (
     FOREIGN KEY (category_id) REFERENCES category_mapping (id) ON DELETE SET NULL ON UPDATE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS library_scan_schedules (
+    library_id INTEGER PRIMARY KEY,
+    interval_hours INTEGER,
+    daily_hour INTEGER,
+    daily_minute INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (library_id) REFERENCES library (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    CHECK ((interval_hours IS NULL) != (daily_hour IS NULL))
+);
+
 CREATE TABLE IF NOT EXISTS tv_shows (
     id INTEGER PRIMARY KEY,
     title TEXT NOT NULL UNIQUE,
@@ -27,12 +38,15 @@ CREATE TABLE IF NOT EXISTS tv_shows (
     year INTEGER,
     premiered TEXT,
     rating INTEGER,
+    certification TEXT,
+    certification_level INTEGER,
     runtime INTEGER,
     plot TEXT,
     tmdb_id TEXT,
     imdb_id TEXT,
     wikidata_id TEXT,
     tvdb_id TEXT,
+    metadata_modified_at INTEGER,
     reference_count INTEGER DEFAULT 0,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
@@ -77,6 +91,9 @@ CREATE TABLE IF NOT EXISTS episodes (
     thumb_image TEXT,
     episode_number INTEGER NOT NULL,
     runtime INTEGER,
+    file_size INTEGER,
+    file_modified_at INTEGER,
+    nfo_modified_at INTEGER,
     created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
     deleted_at DATETIME,
@@ -148,6 +165,256 @@ CREATE TABLE IF NOT EXISTS tv_show_actors (
     FOREIGN KEY (actor_id) REFERENCES actors (id) ON DELETE CASCADE ON UPDATE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS movies (
+    id INTEGER PRIMARY KEY,
+    title TEXT NOT NULL,
+    original_title TEXT,
+    sort_title TEXT,
+    tagline TEXT,
+    nfo_path TEXT,
+    poster_path TEXT,
+    fanart_path TEXT,
+    video_file_path TEXT NOT NULL UNIQUE,
+    subtitle_file_path TEXT,
+    country TEXT,
+    year INTEGER,
+    premiered TEXT,
+    rating INTEGER,
+    certification TEXT,
+    certification_level INTEGER,
+    runtime INTEGER,
+    plot TEXT,
+    tmdb_id TEXT,
+    imdb_id TEXT,
+    wikidata_id TEXT,
+    file_size INTEGER,
+    file_modified_at INTEGER,
+    nfo_modified_at INTEGER,
+    reference_count INTEGER DEFAULT 0,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    deleted_at DATETIME
+);
+
+CREATE TABLE IF NOT EXISTS library_movies (
+    library_id INTEGER NOT NULL,
+    movie_id INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    deleted_at DATETIME,
+    PRIMARY KEY (library_id, movie_id),
+    FOREIGN KEY (library_id) REFERENCES library (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (movie_id) REFERENCES movies (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS movie_genres (
+    movie_id INTEGER NOT NULL,
+    genre_id INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    deleted_at DATETIME,
+    PRIMARY KEY (movie_id, genre_id),
+    FOREIGN KEY (movie_id) REFERENCES movies (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (genre_id) REFERENCES genres (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS movie_studios (
+    movie_id INTEGER NOT NULL,
+    studio_id INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    deleted_at DATETIME,
+    PRIMARY KEY (movie_id, studio_id),
+    FOREIGN KEY (movie_id) REFERENCES movies (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (studio_id) REFERENCES studios (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS movie_actors (
+    movie_id INTEGER NOT NULL,
+    actor_id INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    deleted_at DATETIME,
+    PRIMARY KEY (movie_id, actor_id),
+    FOREIGN KEY (movie_id) REFERENCES movies (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (actor_id) REFERENCES actors (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS subtitles (
+    id INTEGER PRIMARY KEY,
+    episode_id INTEGER,
+    movie_id INTEGER,
+    language TEXT,
+    title TEXT,
+    format TEXT NOT NULL,
+    file_path TEXT,
+    stream_index INTEGER,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (episode_id) REFERENCES episodes (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (movie_id) REFERENCES movies (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    CHECK ((episode_id IS NULL) != (movie_id IS NULL)),
+    CHECK ((file_path IS NULL) != (stream_index IS NULL))
+);
+
+CREATE TABLE IF NOT EXISTS media_info (
+    id INTEGER PRIMARY KEY,
+    episode_id INTEGER NOT NULL UNIQUE,
+    container TEXT,
+    duration INTEGER,
+    bitrate INTEGER,
+    video_codec TEXT,
+    width INTEGER,
+    height INTEGER,
+    frame_rate REAL,
+    hdr_format TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (episode_id) REFERENCES episodes (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS media_audio_tracks (
+    id INTEGER PRIMARY KEY,
+    media_info_id INTEGER NOT NULL,
+    stream_index INTEGER NOT NULL,
+    language TEXT,
+    title TEXT,
+    codec TEXT,
+    channels INTEGER,
+    FOREIGN KEY (media_info_id) REFERENCES media_info (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    UNIQUE (media_info_id, stream_index)
+);
+
+CREATE TABLE IF NOT EXISTS media_subtitle_tracks (
+    id INTEGER PRIMARY KEY,
+    media_info_id INTEGER NOT NULL,
+    stream_index INTEGER NOT NULL,
+    language TEXT,
+    title TEXT,
+    codec TEXT,
+    image_based BOOLEAN NOT NULL DEFAULT FALSE,
+    FOREIGN KEY (media_info_id) REFERENCES media_info (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    UNIQUE (media_info_id, stream_index)
+);
+
+CREATE TABLE IF NOT EXISTS playback_progress (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    episode_id INTEGER NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    duration INTEGER,
+    completed BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (episode_id) REFERENCES episodes (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    UNIQUE (user_id, episode_id)
+);
+
+CREATE TABLE IF NOT EXISTS users (
+    id INTEGER PRIMARY KEY,
+    username TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    password_hash TEXT NOT NULL,
+    is_admin BOOLEAN NOT NULL DEFAULT FALSE,
+    max_certification TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    deleted_at DATETIME
+);
+
+CREATE TABLE IF NOT EXISTS user_sessions (
+    id TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    expires_at DATETIME NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- Long-lived keys of headless clients, only the SHA-256 hash of a key is stored
+CREATE TABLE IF NOT EXISTS api_keys (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    scope TEXT NOT NULL CHECK (scope IN ('read_only', 'playback', 'admin')),
+    key_hash TEXT NOT NULL UNIQUE,
+    last_used_at DATETIME,
+    revoked_at DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- Libraries a user who is not an admin may see
+CREATE TABLE IF NOT EXISTS user_libraries (
+    user_id INTEGER NOT NULL,
+    library_id INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, library_id),
+    FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (library_id) REFERENCES library (id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- Tasks which finished, to see what ran while nobody watched and why it failed
+CREATE TABLE IF NOT EXISTS task_history (
+    id INTEGER PRIMARY KEY,
+    task_id TEXT NOT NULL UNIQUE,
+    task_type TEXT NOT NULL,
+    status TEXT NOT NULL,
+    error TEXT,
+    started_at DATETIME,
+    finished_at DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_task_history_finished_at ON task_history (finished_at);
+
+-- Full-text search, each index reads its rows from the table it covers
+CREATE VIRTUAL TABLE IF NOT EXISTS tv_shows_fts USING fts5 (
+    title,
+    original_title,
+    plot,
+    content = 'tv_shows',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS movies_fts USING fts5 (
+    title,
+    original_title,
+    plot,
+    content = 'movies',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS episodes_fts USING fts5 (
+    title,
+    plot,
+    content = 'episodes',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS actors_fts USING fts5 (
+    name,
+    content = 'actors',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS genres_fts USING fts5 (
+    name,
+    content = 'genres',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS studios_fts USING fts5 (
+    name,
+    content = 'studios',
+    content_rowid = 'id',
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
 CREATE TABLE category_mapping (
     id INTEGER PRIMARY KEY,
     name TEXT NOT NULL UNIQUE,
@@ -257,6 +524,278 @@ WHERE
 
 END;
 
+CREATE TRIGGER increment_library_movie_reference_count AFTER INSERT ON library_movies BEGIN
+UPDATE movies
+SET
+    reference_count = reference_count + 1
+WHERE
+    id = NEW.movie_id;
+
+END;
+
+CREATE TRIGGER decrement_library_movie_reference_count AFTER DELETE ON library_movies BEGIN
+UPDATE movies
+SET
+    reference_count = reference_count - 1
+WHERE
+    id = OLD.movie_id;
+
+DELETE FROM movies
+WHERE
+    id = OLD.movie_id
+    AND reference_count = 0;
+
+END;
+
+CREATE TRIGGER increment_movie_genre_reference_count AFTER INSERT ON movie_genres BEGIN
+UPDATE genres
+SET
+    reference_count = reference_count + 1
+WHERE
+    id = NEW.genre_id;
+
+END;
+
+CREATE TRIGGER decrement_movie_genre_reference_count AFTER DELETE ON movie_genres BEGIN
+UPDATE genres
+SET
+    reference_count = reference_count - 1
+WHERE
+    id = OLD.genre_id;
+
+DELETE FROM genres
+WHERE
+    id = OLD.genre_id
+    AND reference_count = 0;
+
+END;
+
+CREATE TRIGGER increment_movie_studio_reference_count AFTER INSERT ON movie_studios BEGIN
+UPDATE studios
+SET
+    reference_count = reference_count + 1
+WHERE
+    id = NEW.studio_id;
+
+END;
+
+CREATE TRIGGER decrement_movie_studio_reference_count AFTER DELETE ON movie_studios BEGIN
+UPDATE studios
+SET
+    reference_count = reference_count - 1
+WHERE
+    id = OLD.studio_id;
+
+DELETE FROM studios
+WHERE
+    id = OLD.studio_id
+    AND reference_count = 0;
+
+END;
+
+CREATE TRIGGER increment_movie_actor_reference_count AFTER INSERT ON movie_actors BEGIN
+UPDATE actors
+SET
+    reference_count = reference_count + 1
+WHERE
+    id = NEW.actor_id;
+
+END;
+
+CREATE TRIGGER decrement_movie_actor_reference_count AFTER DELETE ON movie_actors BEGIN
+UPDATE actors
+SET
+    reference_count = reference_count - 1
+WHERE
+    id = OLD.actor_id;
+
+DELETE FROM actors
+WHERE
+    id = OLD.actor_id
+    AND reference_count = 0;
+
+END;
+
+CREATE TRIGGER tv_shows_fts_insert AFTER INSERT ON tv_shows BEGIN
+INSERT INTO
+    tv_shows_fts (rowid, title, original_title, plot)
+VALUES
+    (NEW.id, NEW.title, NEW.original_title, NEW.plot);
+
+END;
+
+CREATE TRIGGER tv_shows_fts_delete AFTER DELETE ON tv_shows BEGIN
+INSERT INTO
+    tv_shows_fts (tv_shows_fts, rowid, title, original_title, plot)
+VALUES
+    ('delete', OLD.id, OLD.title, OLD.original_title, OLD.plot);
+
+END;
+
+CREATE TRIGGER tv_shows_fts_update AFTER
+UPDATE OF title, original_title, plot ON tv_shows BEGIN
+INSERT INTO
+    tv_shows_fts (tv_shows_fts, rowid, title, original_title, plot)
+VALUES
+    ('delete', OLD.id, OLD.title, OLD.original_title, OLD.plot);
+
+INSERT INTO
+    tv_shows_fts (rowid, title, original_title, plot)
+VALUES
+    (NEW.id, NEW.title, NEW.original_title, NEW.plot);
+
+END;
+
+CREATE TRIGGER movies_fts_insert AFTER INSERT ON movies BEGIN
+INSERT INTO
+    movies_fts (rowid, title, original_title, plot)
+VALUES
+    (NEW.id, NEW.title, NEW.original_title, NEW.plot);
+
+END;
+
+CREATE TRIGGER movies_fts_delete AFTER DELETE ON movies BEGIN
+INSERT INTO
+    movies_fts (movies_fts, rowid, title, original_title, plot)
+VALUES
+    ('delete', OLD.id, OLD.title, OLD.original_title, OLD.plot);
+
+END;
+
+CREATE TRIGGER movies_fts_update AFTER
+UPDATE OF title, original_title, plot ON movies BEGIN
+INSERT INTO
+    movies_fts (movies_fts, rowid, title, original_title, plot)
+VALUES
+    ('delete', OLD.id, OLD.title, OLD.original_title, OLD.plot);
+
+INSERT INTO
+    movies_fts (rowid, title, original_title, plot)
+VALUES
+    (NEW.id, NEW.title, NEW.original_title, NEW.plot);
+
+END;
+
+CREATE TRIGGER episodes_fts_insert AFTER INSERT ON episodes BEGIN
+INSERT INTO
+    episodes_fts (rowid, title, plot)
+VALUES
+    (NEW.id, NEW.title, NEW.plot);
+
+END;
+
+CREATE TRIGGER episodes_fts_delete AFTER DELETE ON episodes BEGIN
+INSERT INTO
+    episodes_fts (episodes_fts, rowid, title, plot)
+VALUES
+    ('delete', OLD.id, OLD.title, OLD.plot);
+
+END;
+
+CREATE TRIGGER episodes_fts_update AFTER
+UPDATE OF title, plot ON episodes BEGIN
+INSERT INTO
+    episodes_fts (episodes_fts, rowid, title, plot)
+VALUES
+    ('delete', OLD.id, OLD.title, OLD.plot);
+
+INSERT INTO
+    episodes_fts (rowid, title, plot)
+VALUES
+    (NEW.id, NEW.title, NEW.plot);
+
+END;
+
+CREATE TRIGGER actors_fts_insert AFTER INSERT ON actors BEGIN
+INSERT INTO
+    actors_fts (rowid, name)
+VALUES
+    (NEW.id, NEW.name);
+
+END;
+
+CREATE TRIGGER actors_fts_delete AFTER DELETE ON actors BEGIN
+INSERT INTO
+    actors_fts (actors_fts, rowid, name)
+VALUES
+    ('delete', OLD.id, OLD.name);
+
+END;
+
+CREATE TRIGGER actors_fts_update AFTER
+UPDATE OF name ON actors BEGIN
+INSERT INTO
+    actors_fts (actors_fts, rowid, name)
+VALUES
+    ('delete', OLD.id, OLD.name);
+
+INSERT INTO
+    actors_fts (rowid, name)
+VALUES
+    (NEW.id, NEW.name);
+
+END;
+
+CREATE TRIGGER genres_fts_insert AFTER INSERT ON genres BEGIN
+INSERT INTO
+    genres_fts (rowid, name)
+VALUES
+    (NEW.id, NEW.name);
+
+END;
+
+CREATE TRIGGER genres_fts_delete AFTER DELETE ON genres BEGIN
+INSERT INTO
+    genres_fts (genres_fts, rowid, name)
+VALUES
+    ('delete', OLD.id, OLD.name);
+
+END;
+
+CREATE TRIGGER genres_fts_update AFTER
+UPDATE OF name ON genres BEGIN
+INSERT INTO
+    genres_fts (genres_fts, rowid, name)
+VALUES
+    ('delete', OLD.id, OLD.name);
+
+INSERT INTO
+    genres_fts (rowid, name)
+VALUES
+    (NEW.id, NEW.name);
+
+END;
+
+CREATE TRIGGER studios_fts_insert AFTER INSERT ON studios BEGIN
+INSERT INTO
+    studios_fts (rowid, name)
+VALUES
+    (NEW.id, NEW.name);
+
+END;
+
+CREATE TRIGGER studios_fts_delete AFTER DELETE ON studios BEGIN
+INSERT INTO
+    studios_fts (studios_fts, rowid, name)
+VALUES
+    ('delete', OLD.id, OLD.name);
+
+END;
+
+CREATE TRIGGER studios_fts_update AFTER
+UPDATE OF name ON studios BEGIN
+INSERT INTO
+    studios_fts (studios_fts, rowid, name)
+VALUES
+    ('delete', OLD.id, OLD.name);
+
+INSERT INTO
+    studios_fts (rowid, name)
+VALUES
+    (NEW.id, NEW.name);
+
+END;
+
 -- Category Mapping
 
 INSERT INTO
@@ -0,0 +1,336 @@
+-- name: search_media
+-- Matches every index with the same query, ?2 limits the results. Tv shows, episodes and
+-- movies are limited to the granted libraries of user ?3 and certification level ?4 unless
+-- they are null, actors, genres and studios to those of a tv show or movie within them.
+-- bm25 ranks are negative, the best match comes first.
+SELECT
+    *
+FROM
+    (
+        SELECT
+            'TvShow' AS kind,
+            t.id AS id,
+            t.id AS media_id,
+            t.title AS title,
+            snippet (tv_shows_fts, -1, '<mark>', '</mark>', '…', 16) AS snippet,
+            t.poster_path AS image,
+            bm25 (tv_shows_fts, 10.0, 10.0, 1.0) AS rank
+        FROM
+            tv_shows_fts
+            JOIN tv_shows t ON t.id = tv_shows_fts.rowid
+        WHERE
+            tv_shows_fts MATCH ?1
+            AND t.deleted_at IS NULL
+            AND (
+                ?4 IS NULL
+                OR t.certification_level <= ?4
+            )
+            AND (
+                ?3 IS NULL
+                OR EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        library_tv_shows lts
+                        JOIN user_libraries ul ON ul.library_id = lts.library_id
+                    WHERE
+                        lts.tv_show_id = t.id
+                        AND ul.user_id = ?3
+                )
+            )
+        UNION ALL
+        SELECT
+            'Episode' AS kind,
+            e.id AS id,
+            s.tv_show_id AS media_id,
+            e.title AS title,
+            snippet (episodes_fts, -1, '<mark>', '</mark>', '…', 16) AS snippet,
+            e.thumb_image AS image,
+            bm25 (episodes_fts, 5.0, 1.0) AS rank
+        FROM
+            episodes_fts
+            JOIN episodes e ON e.id = episodes_fts.rowid
+            JOIN seasons s ON s.id = e.season_id
+            JOIN tv_shows t ON t.id = s.tv_show_id
+        WHERE
+            episodes_fts MATCH ?1
+            AND e.deleted_at IS NULL
+            AND (
+                ?4 IS NULL
+                OR t.certification_level <= ?4
+            )
+            AND (
+                ?3 IS NULL
+                OR EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        library_tv_shows lts
+                        JOIN user_libraries ul ON ul.library_id = lts.library_id
+                    WHERE
+                        lts.tv_show_id = t.id
+                        AND ul.user_id = ?3
+                )
+            )
+        UNION ALL
+        SELECT
+            'Movie' AS kind,
+            m.id AS id,
+            m.id AS media_id,
+            m.title AS title,
+            snippet (movies_fts, -1, '<mark>', '</mark>', '…', 16) AS snippet,
+            m.poster_path AS image,
+            bm25 (movies_fts, 10.0, 10.0, 1.0) AS rank
+        FROM
+            movies_fts
+            JOIN movies m ON m.id = movies_fts.rowid
+        WHERE
+            movies_fts MATCH ?1
+            AND m.deleted_at IS NULL
+            AND (
+                ?4 IS NULL
+                OR m.certification_level <= ?4
+            )
+            AND (
+                ?3 IS NULL
+                OR EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        library_movies lm
+                        JOIN user_libraries ul ON ul.library_id = lm.library_id
+                    WHERE
+                        lm.movie_id = m.id
+                        AND ul.user_id = ?3
+                )
+            )
+        UNION ALL
+        SELECT
+            'Actor' AS kind,
+            a.id AS id,
+            NULL AS media_id,
+            a.name AS title,
+            snippet (actors_fts, -1, '<mark>', '</mark>', '…', 16) AS snippet,
+            a.thumb AS image,
+            bm25 (actors_fts) AS rank
+        FROM
+            actors_fts
+            JOIN actors a ON a.id = actors_fts.rowid
+        WHERE
+            actors_fts MATCH ?1
+            AND a.deleted_at IS NULL
+            AND (
+                (
+                    ?3 IS NULL
+                    AND ?4 IS NULL
+                )
+                OR EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        tv_show_actors tx
+                        JOIN tv_shows t ON t.id = tx.tv_show_id
+                    WHERE
+                        tx.actor_id = a.id
+                        AND t.deleted_at IS NULL
+                        AND (
+                            ?4 IS NULL
+                            OR t.certification_level <= ?4
+                        )
+                        AND (
+                            ?3 IS NULL
+                            OR EXISTS (
+                                SELECT
+                                    1
+                                FROM
+                                    library_tv_shows lts
+                                    JOIN user_libraries ul ON ul.library_id = lts.library_id
+                                WHERE
+                                    lts.tv_show_id = t.id
+                                    AND ul.user_id = ?3
+                            )
+                        )
+                )
+                OR EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        movie_actors mx
+                        JOIN movies m ON m.id = mx.movie_id
+                    WHERE
+                        mx.actor_id = a.id
+                        AND m.deleted_at IS NULL
+                        AND (
+                            ?4 IS NULL
+                            OR m.certification_level <= ?4
+                        )
+                        AND (
+                            ?3 IS NULL
+                            OR EXISTS (
+                                SELECT
+                                    1
+                                FROM
+                                    library_movies lm
+                                    JOIN user_libraries ul ON ul.library_id = lm.library_id
+                                WHERE
+                                    lm.movie_id = m.id
+                                    AND ul.user_id = ?3
+                            )
+                        )
+                )
+            )
+        UNION ALL
+        SELECT
+            'Genre' AS kind,
+            g.id AS id,
+            NULL AS media_id,
+            g.name AS title,
+            snippet (genres_fts, -1, '<mark>', '</mark>', '…', 16) AS snippet,
+            NULL AS image,
+            bm25 (genres_fts) AS rank
+        FROM
+            genres_fts
+            JOIN genres g ON g.id = genres_fts.rowid
+        WHERE
+            genres_fts MATCH ?1
+            AND g.deleted_at IS NULL
+            AND (
+                (
+                    ?3 IS NULL
+                    AND ?4 IS NULL
+                )
+                OR EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        tv_show_genres tx
+                        JOIN tv_shows t ON t.id = tx.tv_show_id
+                    WHERE
+                        tx.genre_id = g.id
+                        AND t.deleted_at IS NULL
+                        AND (
+                            ?4 IS NULL
+                            OR t.certification_level <= ?4
+                        )
+                        AND (
+                            ?3 IS NULL
+                            OR EXISTS (
+                                SELECT
+                                    1
+                                FROM
+                                    library_tv_shows lts
+                                    JOIN user_libraries ul ON ul.library_id = lts.library_id
+                                WHERE
+                                    lts.tv_show_id = t.id
+                                    AND ul.user_id = ?3
+                            )
+                        )
+                )
+                OR EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        movie_genres mx
+                        JOIN movies m ON m.id = mx.movie_id
+                    WHERE
+                        mx.genre_id = g.id
+                        AND m.deleted_at IS NULL
+                        AND (
+                            ?4 IS NULL
+                            OR m.certification_level <= ?4
+                        )
+                        AND (
+                            ?3 IS NULL
+                            OR EXISTS (
+                                SELECT
+                                    1
+                                FROM
+                                    library_movies lm
+                                    JOIN user_libraries ul ON ul.library_id = lm.library_id
+                                WHERE
+                                    lm.movie_id = m.id
+                                    AND ul.user_id = ?3
+                            )
+                        )
+                )
+            )
+        UNION ALL
+        SELECT
+            'Studio' AS kind,
+            st.id AS id,
+            NULL AS media_id,
+            st.name AS title,
+            snippet (studios_fts, -1, '<mark>', '</mark>', '…', 16) AS snippet,
+            NULL AS image,
+            bm25 (studios_fts) AS rank
+        FROM
+            studios_fts
+            JOIN studios st ON st.id = studios_fts.rowid
+        WHERE
+            studios_fts MATCH ?1
+            AND st.deleted_at IS NULL
+            AND (
+                (
+                    ?3 IS NULL
+                    AND ?4 IS NULL
+                )
+                OR EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        tv_show_studios tx
+                        JOIN tv_shows t ON t.id = tx.tv_show_id
+                    WHERE
+                        tx.studio_id = st.id
+                        AND t.deleted_at IS NULL
+                        AND (
+                            ?4 IS NULL
+                            OR t.certification_level <= ?4
+                        )
+                        AND (
+                            ?3 IS NULL
+                            OR EXISTS (
+                                SELECT
+                                    1
+                                FROM
+                                    library_tv_shows lts
+                                    JOIN user_libraries ul ON ul.library_id = lts.library_id
+                                WHERE
+                                    lts.tv_show_id = t.id
+                                    AND ul.user_id = ?3
+                            )
+                        )
+                )
+                OR EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        movie_studios mx
+                        JOIN movies m ON m.id = mx.movie_id
+                    WHERE
+                        mx.studio_id = st.id
+                        AND m.deleted_at IS NULL
+                        AND (
+                            ?4 IS NULL
+                            OR m.certification_level <= ?4
+                        )
+                        AND (
+                            ?3 IS NULL
+                            OR EXISTS (
+                                SELECT
+                                    1
+                                FROM
+                                    library_movies lm
+                                    JOIN user_libraries ul ON ul.library_id = lm.library_id
+                                WHERE
+                                    lm.movie_id = m.id
+                                    AND ul.user_id = ?3
+                            )
+                        )
+                )
+            )
+    )
+ORDER BY
+    rank
+LIMIT
+    ?2;
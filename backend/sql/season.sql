@@ -17,4 +17,7 @@ VALUES
     (?, ?, ?, ?, ?) ON CONFLICT (tv_show_id, season_number) DO
 UPDATE
 SET
-    id = id RETURNING id;
+    title = coalesce(excluded.title, title),
+    plot = coalesce(excluded.plot, plot),
+    nfo_path = coalesce(excluded.nfo_path, nfo_path),
+    updated_at = CURRENT_TIMESTAMP RETURNING id;
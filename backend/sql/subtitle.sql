@@ -0,0 +1,85 @@
+-- name: find_subtitles_by_video_file_path
+SELECT
+    s.id,
+    s.language,
+    s.title,
+    s.format,
+    s.file_path,
+    s.stream_index
+FROM
+    subtitles s
+    LEFT JOIN episodes e ON s.episode_id = e.id
+    LEFT JOIN movies m ON s.movie_id = m.id
+WHERE
+    e.video_file_path = ?
+    OR m.video_file_path = ?
+ORDER BY
+    s.id;
+
+-- name: delete_episode_subtitles
+DELETE FROM subtitles
+WHERE
+    episode_id = (
+        SELECT
+            id
+        FROM
+            episodes
+        WHERE
+            video_file_path = ?
+    );
+
+-- name: save_episode_subtitle
+INSERT INTO
+    subtitles (
+        episode_id,
+        language,
+        title,
+        format,
+        file_path,
+        stream_index
+    )
+SELECT
+    id,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?
+FROM
+    episodes
+WHERE
+    video_file_path = ?;
+
+-- name: delete_movie_subtitles
+DELETE FROM subtitles
+WHERE
+    movie_id = (
+        SELECT
+            id
+        FROM
+            movies
+        WHERE
+            video_file_path = ?
+    );
+
+-- name: save_movie_subtitle
+INSERT INTO
+    subtitles (
+        movie_id,
+        language,
+        title,
+        format,
+        file_path,
+        stream_index
+    )
+SELECT
+    id,
+    ?,
+    ?,
+    ?,
+    ?,
+    ?
+FROM
+    movies
+WHERE
+    video_file_path = ?;
@@ -0,0 +1,40 @@
+-- name: save_task_record
+INSERT
+OR REPLACE INTO task_history (
+    task_id,
+    task_type,
+    status,
+    error,
+    started_at,
+    finished_at
+)
+VALUES
+    (?, ?, ?, ?, ?, ?);
+
+-- name: delete_expired_task_records
+-- The history only keeps the tasks of the last 30 days
+DELETE FROM task_history
+WHERE
+    finished_at < datetime ('now', '-30 days');
+
+-- name: find_task_records
+-- The status filter is optional, the newest tasks come first
+SELECT
+    task_id,
+    task_type,
+    status,
+    error,
+    started_at,
+    finished_at
+FROM
+    task_history
+WHERE
+    ?1 IS NULL
+    OR status = ?1
+ORDER BY
+    finished_at DESC,
+    id DESC
+LIMIT
+    ?2
+OFFSET
+    ?3;
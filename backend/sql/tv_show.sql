@@ -10,18 +10,38 @@ INSERT INTO
         year,
         premiered,
         rating,
+        certification,
+        certification_level,
         runtime,
         plot,
         tmdb_id,
         imdb_id,
         wikidata_id,
-        tvdb_id
+        tvdb_id,
+        metadata_modified_at
     )
 VALUES
-    (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) ON CONFLICT (title) DO
+    (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) ON CONFLICT (title) DO
 UPDATE
 SET
-    id = id RETURNING id;
+    original_title = coalesce(excluded.original_title, original_title),
+    nfo_path = coalesce(excluded.nfo_path, nfo_path),
+    poster_path = coalesce(excluded.poster_path, poster_path),
+    fanart_path = coalesce(excluded.fanart_path, fanart_path),
+    country = coalesce(excluded.country, country),
+    year = coalesce(excluded.year, year),
+    premiered = coalesce(excluded.premiered, premiered),
+    rating = coalesce(excluded.rating, rating),
+    certification = coalesce(excluded.certification, certification),
+    certification_level = coalesce(excluded.certification_level, certification_level),
+    runtime = coalesce(excluded.runtime, runtime),
+    plot = coalesce(excluded.plot, plot),
+    tmdb_id = coalesce(excluded.tmdb_id, tmdb_id),
+    imdb_id = coalesce(excluded.imdb_id, imdb_id),
+    wikidata_id = coalesce(excluded.wikidata_id, wikidata_id),
+    tvdb_id = coalesce(excluded.tvdb_id, tvdb_id),
+    metadata_modified_at = coalesce(excluded.metadata_modified_at, metadata_modified_at),
+    updated_at = CURRENT_TIMESTAMP RETURNING id;
 
 -- name: save_library_tv_show
 INSERT
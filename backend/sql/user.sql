@@ -0,0 +1,162 @@
+-- name: save_user
+INSERT INTO
+    users (username, password_hash, is_admin)
+VALUES
+    (?, ?, ?) RETURNING id;
+
+-- name: find_users
+SELECT
+    id,
+    username,
+    is_admin,
+    max_certification,
+    created_at
+FROM
+    users
+WHERE
+    deleted_at IS NULL
+ORDER BY
+    username;
+
+-- name: find_user_credentials_by_username
+SELECT
+    id,
+    username,
+    is_admin,
+    max_certification,
+    created_at,
+    password_hash
+FROM
+    users
+WHERE
+    username = ?
+    AND deleted_at IS NULL;
+
+-- name: count_users
+SELECT
+    count(*) AS total
+FROM
+    users
+WHERE
+    deleted_at IS NULL;
+
+-- name: save_session
+-- The session expires after the given number of seconds
+INSERT INTO
+    user_sessions (id, user_id, expires_at)
+VALUES
+    (?, ?, datetime ('now', ? || ' seconds'));
+
+-- name: find_session_user
+SELECT
+    u.id,
+    u.username,
+    u.is_admin,
+    u.max_certification,
+    u.created_at
+FROM
+    user_sessions us
+    JOIN users u ON u.id = us.user_id
+WHERE
+    us.id = ?
+    AND us.expires_at > datetime ('now')
+    AND u.deleted_at IS NULL;
+
+-- name: delete_session
+DELETE FROM user_sessions
+WHERE
+    id = ?;
+
+-- name: delete_expired_sessions
+DELETE FROM user_sessions
+WHERE
+    expires_at <= datetime ('now');
+
+-- name: find_user_library_ids
+SELECT
+    library_id
+FROM
+    user_libraries
+WHERE
+    user_id = ?
+ORDER BY
+    library_id;
+
+-- name: delete_user_libraries
+DELETE FROM user_libraries
+WHERE
+    user_id = ?;
+
+-- name: save_user_library
+INSERT
+OR IGNORE INTO user_libraries (user_id, library_id)
+VALUES
+    (?, ?);
+
+-- name: update_user_max_certification
+UPDATE users
+SET
+    max_certification = ?,
+    updated_at = CURRENT_TIMESTAMP
+WHERE
+    id = ?
+    AND deleted_at IS NULL;
+
+-- name: save_api_key
+INSERT INTO
+    api_keys (user_id, name, scope, key_hash)
+VALUES
+    (?, ?, ?, ?) RETURNING id;
+
+-- name: find_api_keys_by_user
+SELECT
+    id,
+    name,
+    scope,
+    created_at,
+    last_used_at
+FROM
+    api_keys
+WHERE
+    user_id = ?
+    AND revoked_at IS NULL
+ORDER BY
+    created_at DESC,
+    id DESC;
+
+-- name: find_api_key_grant
+SELECT
+    k.id AS api_key_id,
+    k.scope,
+    u.id,
+    u.username,
+    u.is_admin,
+    u.max_certification,
+    u.created_at
+FROM
+    api_keys k
+    JOIN users u ON u.id = k.user_id
+WHERE
+    k.key_hash = ?
+    AND k.revoked_at IS NULL
+    AND u.deleted_at IS NULL;
+
+-- name: update_api_key_last_used
+UPDATE api_keys
+SET
+    last_used_at = CURRENT_TIMESTAMP
+WHERE
+    id = ?;
+
+-- name: revoke_api_key
+-- Only keys of the given user are revoked unless the user is null
+UPDATE api_keys
+SET
+    revoked_at = CURRENT_TIMESTAMP
+WHERE
+    id = ?1
+    AND (
+        ?2 IS NULL
+        OR user_id = ?2
+    )
+    AND revoked_at IS NULL;
@@ -0,0 +1,23 @@
+use actix_web::web::Data;
+use anyhow::*;
+use tracing::*;
+
+use crate::{infrastructure::artwork::store::Artwork, init::app_state::AppState};
+
+/// Looks up an artwork by its hash, `width` selects a resized variant which is
+/// created on demand. Returns `None` when the store has no artwork with this hash.
+#[instrument(skip(app_state))]
+pub async fn get_artwork_service(
+    hash: String,
+    width: Option<u32>,
+    app_state: Data<AppState>,
+) -> Result<Option<Artwork>> {
+    let artwork_store = app_state.storage().artwork_store().clone();
+
+    // Decoding and resizing images is blocking work
+    tokio::task::spawn_blocking(move || match width {
+        Some(width) => artwork_store.find_variant(&hash, width),
+        None => Ok(artwork_store.find(&hash)),
+    })
+    .await?
+}
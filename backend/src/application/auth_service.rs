@@ -0,0 +1,300 @@
+use actix_web::web::Data;
+use anyhow::*;
+use std::result::Result::Ok;
+use tracing::*;
+
+use crate::{
+    domain::user::{
+        access::certification_level,
+        api_key::{ApiKey, ApiKeyGrant},
+        model::User,
+    },
+    infrastructure::auth::{
+        api_key::{generate_api_key, hash_api_key},
+        password::{hash_password, verify_password},
+    },
+    init::app_state::AppState,
+    interfaces::{
+        dtos::CreatedApiKeyDto,
+        http_api::controllers::api_models::{
+            CreateApiKeyPayload, CreateUserPayload, LoginPayload, UserAccessPayload,
+        },
+    },
+};
+
+const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Checks the credentials and starts a session, returns the token of the session and the
+/// user, or `None` when the username or the password is wrong.
+#[instrument(skip(payload, app_state), fields(username = %payload.username))]
+pub async fn login_service(
+    payload: LoginPayload,
+    app_state: Data<AppState>,
+) -> Result<Option<(String, User)>> {
+    let user_repository = app_state.storage().repositories().user.clone();
+    let security = app_state.security();
+
+    // Logins are rare enough to clean up the sessions nobody logged out of
+    user_repository.delete_expired_sessions().await?;
+
+    let Some(credentials) = user_repository
+        .get_user_credentials(payload.username)
+        .await?
+    else {
+        return Ok(None);
+    };
+    // Hashing is deliberately slow, so it must not block the async runtime
+    let password_hash = credentials.password_hash;
+    let is_valid =
+        tokio::task::spawn_blocking(move || verify_password(&payload.password, &password_hash))
+            .await?;
+    if !is_valid {
+        return Ok(None);
+    }
+
+    let (session_id, token) = security.session_signer().issue();
+    user_repository
+        .create_session(
+            session_id,
+            credentials.user.id,
+            security.session_ttl().as_secs() as i64,
+        )
+        .await?;
+    info!("User {} logged in", credentials.user.username);
+
+    Ok(Some((token, credentials.user)))
+}
+
+#[instrument(skip(token, app_state))]
+pub async fn logout_service(token: &str, app_state: Data<AppState>) -> Result<()> {
+    let Some(session_id) = app_state.security().session_signer().verify(token) else {
+        return Ok(());
+    };
+
+    app_state
+        .storage()
+        .repositories()
+        .user
+        .delete_session(session_id)
+        .await
+}
+
+/// The user of the session token, `None` when the token is forged, expired or was
+/// logged out.
+#[instrument(skip(token, app_state))]
+pub async fn authenticate_service(token: &str, app_state: &AppState) -> Result<Option<User>> {
+    let Some(session_id) = app_state.security().session_signer().verify(token) else {
+        return Ok(None);
+    };
+
+    app_state
+        .storage()
+        .repositories()
+        .user
+        .get_session_user(session_id)
+        .await
+}
+
+/// The owner and the scope of the API key, `None` when the key is unknown or was revoked.
+#[instrument(skip(key, app_state))]
+pub async fn authenticate_api_key_service(
+    key: &str,
+    app_state: &AppState,
+) -> Result<Option<ApiKeyGrant>> {
+    let user_repository = app_state.storage().repositories().user.clone();
+
+    let Some(grant) = user_repository.get_api_key_grant(hash_api_key(key)).await? else {
+        return Ok(None);
+    };
+    user_repository
+        .update_api_key_last_used(grant.api_key_id)
+        .await?;
+
+    Ok(Some(grant))
+}
+
+#[instrument(skip(payload, app_state), fields(name = %payload.name))]
+pub async fn create_api_key_service(
+    user: User,
+    payload: CreateApiKeyPayload,
+    app_state: Data<AppState>,
+) -> Result<CreatedApiKeyDto> {
+    let name = payload.name.trim().to_string();
+    if name.is_empty() {
+        return Err(anyhow!("API key name must not be empty"));
+    }
+
+    let key = generate_api_key();
+    let id = app_state
+        .storage()
+        .repositories()
+        .user
+        .create_api_key(user.id, name.clone(), payload.scope, hash_api_key(&key))
+        .await?;
+    info!(
+        "User {} created API key {} with scope {:?}",
+        user.username, id, payload.scope
+    );
+
+    Ok(CreatedApiKeyDto {
+        key,
+        api_key: ApiKey {
+            id,
+            name,
+            scope: payload.scope,
+            created_at: None,
+            last_used_at: None,
+        },
+    })
+}
+
+#[instrument(skip(app_state))]
+pub async fn get_api_keys_service(user: User, app_state: Data<AppState>) -> Result<Vec<ApiKey>> {
+    app_state
+        .storage()
+        .repositories()
+        .user
+        .get_api_keys(user.id)
+        .await
+}
+
+/// Revokes one of the keys of the user, admins may revoke the keys of every user.
+#[instrument(skip(app_state))]
+pub async fn revoke_api_key_service(
+    api_key_id: i64,
+    user: User,
+    app_state: Data<AppState>,
+) -> Result<()> {
+    let owner = (!user.is_admin).then_some(user.id);
+
+    app_state
+        .storage()
+        .repositories()
+        .user
+        .revoke_api_key(api_key_id, owner)
+        .await?;
+    info!("User {} revoked API key {}", user.username, api_key_id);
+
+    Ok(())
+}
+
+#[instrument(skip(payload, app_state), fields(username = %payload.username))]
+pub async fn create_user_service(
+    payload: CreateUserPayload,
+    app_state: Data<AppState>,
+) -> Result<User> {
+    create_user(
+        &app_state,
+        payload.username,
+        payload.password,
+        payload.is_admin,
+    )
+    .await
+}
+
+#[instrument(skip(app_state))]
+pub async fn get_users_service(app_state: Data<AppState>) -> Result<Vec<User>> {
+    app_state.storage().repositories().user.get_users().await
+}
+
+#[instrument(skip(app_state))]
+pub async fn get_user_library_ids_service(
+    user_id: i64,
+    app_state: Data<AppState>,
+) -> Result<Vec<i64>> {
+    app_state
+        .storage()
+        .repositories()
+        .user
+        .get_user_library_ids(user_id)
+        .await
+}
+
+#[instrument(skip(payload, app_state))]
+pub async fn update_user_access_service(
+    user_id: i64,
+    payload: UserAccessPayload,
+    app_state: Data<AppState>,
+) -> Result<()> {
+    let max_certification = payload
+        .max_certification
+        .map(|certification| certification.trim().to_string())
+        .filter(|certification| !certification.is_empty());
+    // A cap which cannot be compared would silently hide every rated item
+    if let Some(certification) = &max_certification {
+        if certification_level(certification).is_none() {
+            return Err(anyhow!("Unknown certification {}", certification));
+        }
+    }
+
+    app_state
+        .storage()
+        .repositories()
+        .user
+        .update_user_access(user_id, payload.library_ids, max_certification)
+        .await
+}
+
+/// Creates the first admin from the configured credentials when there is no user yet,
+/// otherwise nobody could log in to a new installation.
+#[instrument(skip(app_state, password))]
+pub async fn ensure_admin_user(
+    app_state: &AppState,
+    username: String,
+    password: Option<String>,
+) -> Result<()> {
+    if app_state
+        .storage()
+        .repositories()
+        .user
+        .count_users()
+        .await?
+        > 0
+    {
+        return Ok(());
+    }
+
+    let Some(password) = password else {
+        warn!("There is no user yet, set ADMIN_PASSWORD to create the first admin");
+        return Ok(());
+    };
+
+    let user = create_user(app_state, username, password, true).await?;
+    info!("Created admin user {}", user.username);
+
+    Ok(())
+}
+
+async fn create_user(
+    app_state: &AppState,
+    username: String,
+    password: String,
+    is_admin: bool,
+) -> Result<User> {
+    let username = username.trim().to_string();
+    if username.is_empty() {
+        return Err(anyhow!("Username must not be empty"));
+    }
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(anyhow!(
+            "Password must have at least {} characters",
+            MIN_PASSWORD_LENGTH
+        ));
+    }
+
+    let password_hash = tokio::task::spawn_blocking(move || hash_password(&password)).await??;
+    let id = app_state
+        .storage()
+        .repositories()
+        .user
+        .create_user(username.clone(), password_hash, is_admin)
+        .await?;
+
+    Ok(User {
+        id,
+        username,
+        is_admin,
+        max_certification: None,
+        created_at: None,
+    })
+}
@@ -1,9 +1,10 @@
 use anyhow::*;
-use std::sync::Arc;
+use std::{io::ErrorKind, sync::Arc};
+use tokio::fs;
 
 use crate::{
     domain::file_system::file_repository::FileRepository,
-    infrastructure::file::finder_options::FinderOptions,
+    infrastructure::file::finder_options::{all_files, FinderOptions},
 };
 
 #[derive(Clone)]
@@ -31,6 +32,20 @@ impl FileService {
         }
         Ok(())
     }
+
+    /// Deletes every file of a folder including its subfolders and then the folders themselves.
+    pub async fn delete_folder(&self, folder_path: &str) -> Result<()> {
+        let options = FinderOptions::new()
+            .filters(all_files())
+            .recursive(true)
+            .include_hidden(true);
+        self.delete_files_in_folder(folder_path, options).await?;
+
+        match fs::remove_dir_all(folder_path).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
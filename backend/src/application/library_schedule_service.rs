@@ -0,0 +1,200 @@
+use anyhow::{Ok, *};
+use chrono::{DateTime, Local};
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::*;
+
+use crate::{
+    application::media_item_service::apply_library_rescan,
+    chain_events,
+    domain::{
+        library::model::LibraryStatus,
+        media_library::{
+            event::LibraryEventType, library::get_library_by_id, rescan::ScanTrigger,
+            schedule::ScanSchedule, task::LibraryRescanTask,
+        },
+        task::async_task::TaskType,
+    },
+    infrastructure::event_dispatcher::{domain_event::DomainEvent, handler::EventHandlerConfig},
+    init::app_state::AppState,
+};
+
+/// Loads the stored scan schedules and rescans each library once its schedule is due,
+/// the library status moves to `Scanning` while the scan runs and to `Active` or `Error` after.
+#[instrument(skip(app_state, due_libraries))]
+pub async fn start_library_schedule_service(
+    app_state: AppState,
+    mut due_libraries: UnboundedReceiver<i64>,
+) -> Result<()> {
+    let database_addr = app_state.storage().database_addr().clone();
+    let event_bus = app_state.infrastructure().event_bus();
+    let scan_scheduler = app_state.infrastructure().scan_scheduler();
+    let library_repository = app_state.storage().repositories().library.clone();
+
+    for scan_schedule in library_repository.get_scan_schedules().await? {
+        let library =
+            match get_library_by_id(scan_schedule.library_id, library_repository.clone()).await {
+                Result::Ok(library) => library,
+                Err(e) => {
+                    error!(
+                        "Failed to load scheduled library {}: {:?}",
+                        scan_schedule.library_id, e
+                    );
+                    continue;
+                }
+            };
+
+        scan_scheduler
+            .schedule(
+                library.id,
+                scan_schedule.schedule,
+                parse_last_scanned(library.last_scanned),
+            )
+            .await;
+    }
+
+    let saved_library_repository = library_repository.clone();
+    let failed_library_repository = library_repository.clone();
+    chain_events!(
+        event_bus,
+        {
+            match_pattern: DomainEvent::Library(LibraryEventType::LibraryRescanned { trigger: ScanTrigger::Schedule, .. }),
+            handler: move |event, event_bus| {
+                let database_addr = database_addr.clone();
+                let library_repository = saved_library_repository.clone();
+
+                async move {
+                    if let DomainEvent::Library(LibraryEventType::LibraryRescanned { library_id, library, diff, .. }) = event {
+                        match apply_library_rescan(library_id, library, &diff, Arc::new(database_addr.clone())).await {
+                            Result::Ok(_) => {
+                                let summary = diff.summary();
+                                info!("Scheduled scan of library {} finished: {:?}", library_id, summary);
+                                library_repository
+                                    .update_library_status(library_id, LibraryStatus::Active, None)
+                                    .await?;
+                                event_bus.publish(DomainEvent::Library(LibraryEventType::LibraryScheduledScanSaved {
+                                    library_id,
+                                    summary,
+                                }))?;
+                            }
+                            Err(e) => {
+                                error!("Failed to apply scheduled scan of library {}: {:?}", library_id, e);
+                                library_repository
+                                    .update_library_status(library_id, LibraryStatus::Error, Some(e.to_string()))
+                                    .await?;
+                            }
+                        }
+                    }
+                    Ok(())
+                }
+            },
+            config: EventHandlerConfig::default()
+        },
+        {
+            match_pattern: DomainEvent::Library(LibraryEventType::LibraryRescanFailed { trigger: ScanTrigger::Schedule, .. }),
+            handler: move |event, _| {
+                let library_repository = failed_library_repository.clone();
+
+                async move {
+                    if let DomainEvent::Library(LibraryEventType::LibraryRescanFailed { library_id, error, .. }) = event {
+                        error!("Scheduled scan of library {} failed: {}", library_id, error);
+                        library_repository
+                            .update_library_status(library_id, LibraryStatus::Error, Some(error))
+                            .await?;
+                    }
+                    Ok(())
+                }
+            },
+            config: EventHandlerConfig::default()
+        }
+    );
+
+    tokio::spawn(async move {
+        while let Some(library_id) = due_libraries.recv().await {
+            if let Err(e) = queue_scheduled_scan(library_id, &app_state).await {
+                error!(
+                    "Failed to queue scheduled scan of library {}: {:?}",
+                    library_id, e
+                );
+                let _ = library_repository
+                    .update_library_status(library_id, LibraryStatus::Error, Some(e.to_string()))
+                    .await;
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Stores the new schedule of a library and applies it to the running scheduler,
+/// `None` removes the schedule.
+#[instrument(skip(app_state))]
+pub async fn update_library_schedule(
+    library_id: i64,
+    schedule: Option<ScanSchedule>,
+    app_state: &AppState,
+) -> Result<()> {
+    let scan_scheduler = app_state.infrastructure().scan_scheduler();
+    let library_repository = app_state.storage().repositories().library.clone();
+
+    library_repository
+        .set_scan_schedule(library_id, schedule.clone())
+        .await?;
+
+    match schedule {
+        Some(schedule) => {
+            let library = get_library_by_id(library_id, library_repository).await?;
+            scan_scheduler
+                .schedule(
+                    library_id,
+                    schedule,
+                    parse_last_scanned(library.last_scanned),
+                )
+                .await;
+        }
+        None => scan_scheduler.unschedule(library_id).await,
+    }
+
+    Ok(())
+}
+
+#[instrument(skip(app_state))]
+async fn queue_scheduled_scan(library_id: i64, app_state: &AppState) -> Result<()> {
+    let parser_addr = app_state.media().parser_addr();
+    let task_pool = app_state.infrastructure().task_pool();
+    let library_repository = app_state.storage().repositories().library.clone();
+    let media_repository = app_state.storage().repositories().media.clone();
+
+    let library = get_library_by_id(library_id, library_repository.clone()).await?;
+    let stored = media_repository.get_library_media_files(library_id).await?;
+
+    library_repository
+        .update_library_status(library_id, LibraryStatus::Scanning, None)
+        .await?;
+
+    let task = LibraryRescanTask::new(
+        library_id,
+        library.directory,
+        library.category,
+        stored,
+        Arc::new(parser_addr.clone()),
+    )
+    .with_trigger(ScanTrigger::Schedule);
+    task_pool
+        .register_task(
+            TaskType::MediaLibraryRescan,
+            None,
+            None,
+            Box::new(task),
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+fn parse_last_scanned(last_scanned: Option<String>) -> Option<DateTime<Local>> {
+    last_scanned
+        .and_then(|last_scanned| DateTime::parse_from_rfc3339(&last_scanned).ok())
+        .map(|last_scanned| last_scanned.with_timezone(&Local))
+}
@@ -4,26 +4,33 @@ use std::sync::Arc;
 use tracing::*;
 
 use crate::{
-    application::media_item_service::insert_media_item,
+    application::{
+        library_schedule_service::update_library_schedule,
+        library_watcher_service::sync_library_watch,
+        media_item_service::{apply_library_rescan, insert_media_item, insert_movie},
+    },
     chain_events,
     domain::{
         media_library::{
             constant::SENTINEL_LIBRARY_ID,
             event::LibraryEventType,
-            library::{create_library, populate_library_metadata},
-            task::LibraryScanTask,
+            library::{
+                create_library, delete_library, get_library_by_id, populate_library_metadata,
+            },
+            rescan::ScanTrigger,
+            task::{LibraryRescanTask, LibraryScanTask},
         },
         task::async_task::{AsyncTaskResponse, TaskIdentifiable, TaskType},
     },
     infrastructure::event_dispatcher::{domain_event::DomainEvent, handler::EventHandlerConfig},
     init::app_state::AppState,
-    interfaces::http_api::controllers::api_models::SaveLibraryPayload,
+    interfaces::http_api::controllers::api_models::{SaveLibraryPayload, UpdateLibraryPayload},
 };
 
 #[instrument(skip(app_state))]
 pub async fn create_library_service(
     payload: SaveLibraryPayload,
-    ws_client_key: String,
+    ws_client_key: Option<String>,
     app_state: Data<AppState>,
 ) -> Result<AsyncTaskResponse<i64>> {
     let database_addr = app_state.storage().database_addr().clone();
@@ -35,6 +42,7 @@ pub async fn create_library_service(
 
     let directory_clone = payload.directory.clone();
     let library_name = payload.name.clone();
+    let category = payload.category.clone();
 
     let library_id = create_library(payload, library_repository.clone()).await?;
     debug!("Library created with id: {:?}", library_id);
@@ -44,12 +52,19 @@ pub async fn create_library_service(
         return Err(anyhow!("Failed to create library"));
     }
 
-    let ws_connection = match ws_connections.get(ws_client_key.clone()).await {
-        Some(ws_connection) => ws_connection,
-        None => {
-            error!("WebSocket connection not found");
-            return Err(anyhow!("WebSocket connection not found"));
-        }
+    if let Err(e) = sync_library_watch(library_id, &app_state).await {
+        error!("Failed to watch library {}: {:?}", library_id, e);
+    }
+
+    let ws_connection = match ws_client_key.clone() {
+        Some(key) => match ws_connections.get(key).await {
+            Some(ws_connection) => Some(ws_connection),
+            None => {
+                error!("WebSocket connection not found");
+                return Err(anyhow!("WebSocket connection not found"));
+            }
+        },
+        None => None,
     };
 
     chain_events!(
@@ -68,6 +83,12 @@ pub async fn create_library_service(
                                 .await
                                 .inspect_err(|e| error!("Failed to insert media item: {:?}", e))?;
                         }
+                        for movie in library.movie {
+                            debug!("Processing movie: {:?}", movie.title);
+                            insert_movie(library_id, movie, Arc::new(database_addr.clone()))
+                                .await
+                                .inspect_err(|e| error!("Failed to insert movie: {:?}", e))?;
+                        }
                         event_bus.publish(DomainEvent::Library(LibraryEventType::LibrarySaved {
                             task_identifier,
                             library_id,
@@ -86,7 +107,7 @@ pub async fn create_library_service(
 
                 async move {
                     if let DomainEvent::Library(LibraryEventType::LibraryScanned { library, .. }) = event {
-                        let item_count = library.tv_show.len();
+                        let item_count = library.tv_show.len() + library.movie.len();
                         populate_library_metadata(library_id, item_count, library_repository.clone()).await?;
                     }
 
@@ -100,19 +121,26 @@ pub async fn create_library_service(
             handler: move |event, _| {
                 let ws_connection_clone = ws_connection.clone();
                 async move {
-                    event.send_notification::<serde_json::Value>(ws_connection_clone)
+                    match ws_connection_clone {
+                        Some(ws_connection) => event.send_notification::<serde_json::Value>(ws_connection),
+                        None => Ok(()),
+                    }
                 }
             },
             config: EventHandlerConfig::one_time()
         }
     );
 
-    let mut task = LibraryScanTask::new(directory_clone, Arc::new(parser_addr.clone()));
-    task.set_ws_client_id(ws_client_key.clone());
+    let mut task = LibraryScanTask::new(directory_clone, category, Arc::new(parser_addr.clone()));
+    // Clients without a WebSocket connection poll the state of the task instead
+    if let Some(ws_client_key) = ws_client_key.clone() {
+        task.set_ws_client_id(ws_client_key);
+    }
     let task_id = task_pool
         .register_task(
             TaskType::MediaLibraryScan,
-            ws_client_key.clone(),
+            ws_client_key,
+            None,
             Box::new(task),
             None,
         )
@@ -124,3 +152,144 @@ pub async fn create_library_service(
         payload: Some(library_id),
     })
 }
+
+/// Rescans an existing library and only writes the files which were added, changed or
+/// removed since the last scan, the counts are sent to the client once they are saved.
+#[instrument(skip(app_state))]
+pub async fn rescan_library_service(
+    library_id: i64,
+    ws_client_key: Option<String>,
+    app_state: Data<AppState>,
+) -> Result<AsyncTaskResponse<i64>> {
+    let database_addr = app_state.storage().database_addr().clone();
+    let parser_addr = app_state.media().parser_addr();
+    let ws_connections = app_state.communication().ws_connections();
+    let task_pool = app_state.infrastructure().task_pool();
+    let event_bus = app_state.infrastructure().event_bus();
+    let library_repository = app_state.storage().repositories().library.clone();
+    let media_repository = app_state.storage().repositories().media.clone();
+
+    let library = get_library_by_id(library_id, library_repository).await?;
+    let stored = media_repository.get_library_media_files(library_id).await?;
+    debug!(
+        "Found {} stored media files for library {}",
+        stored.len(),
+        library_id
+    );
+
+    let ws_connection = match ws_client_key.clone() {
+        Some(key) => match ws_connections.get(key).await {
+            Some(ws_connection) => Some(ws_connection),
+            None => {
+                error!("WebSocket connection not found");
+                return Err(anyhow!("WebSocket connection not found"));
+            }
+        },
+        None => None,
+    };
+
+    chain_events!(
+        event_bus,
+        {
+            match_pattern: DomainEvent::Library(LibraryEventType::LibraryRescanned { trigger: ScanTrigger::Manual, .. }),
+            handler: move |event, event_bus| {
+                let database_addr = database_addr.clone();
+
+                async move {
+                    if let DomainEvent::Library(LibraryEventType::LibraryRescanned { task_identifier, library_id, library, diff, .. }) = event {
+                        apply_library_rescan(library_id, library, &diff, Arc::new(database_addr.clone()))
+                            .await
+                            .inspect_err(|e| error!("Failed to apply library rescan: {:?}", e))?;
+
+                        let summary = diff.summary();
+                        info!("Library {} rescanned: {:?}", library_id, summary);
+                        event_bus.publish(DomainEvent::Library(LibraryEventType::LibraryRescanSaved {
+                            task_identifier,
+                            library_id,
+                            summary,
+                        }))?;
+                    }
+                    Ok(())
+                }
+            },
+            config: EventHandlerConfig::one_time()
+        },
+        {
+            match_pattern: DomainEvent::Library(LibraryEventType::LibraryRescanSaved { .. }),
+            handler: move |event, _| {
+                let ws_connection_clone = ws_connection.clone();
+                async move {
+                    match ws_connection_clone {
+                        Some(ws_connection) => event.send_notification::<serde_json::Value>(ws_connection),
+                        None => Ok(()),
+                    }
+                }
+            },
+            config: EventHandlerConfig::one_time()
+        }
+    );
+
+    let mut task = LibraryRescanTask::new(
+        library_id,
+        library.directory,
+        library.category,
+        stored,
+        Arc::new(parser_addr.clone()),
+    );
+    // Clients without a WebSocket connection poll the state of the task instead
+    if let Some(ws_client_key) = ws_client_key.clone() {
+        task.set_ws_client_id(ws_client_key);
+    }
+    let task_id = task_pool
+        .register_task(
+            TaskType::MediaLibraryRescan,
+            ws_client_key,
+            None,
+            Box::new(task),
+            None,
+        )
+        .await?;
+
+    Ok(AsyncTaskResponse {
+        task_id,
+        task_type: TaskType::MediaLibraryRescan,
+        payload: Some(library_id),
+    })
+}
+
+#[instrument(skip(app_state))]
+pub async fn update_library_service(
+    library_id: i64,
+    payload: UpdateLibraryPayload,
+    app_state: Data<AppState>,
+) -> Result<()> {
+    let library_repository = app_state.storage().repositories().library.clone();
+
+    update_library_schedule(library_id, payload.scan_schedule.clone(), &app_state).await?;
+    library_repository
+        .update_library(library_id, payload)
+        .await?;
+
+    // The directory may have moved, so the watch has to follow it
+    if let Err(e) = sync_library_watch(library_id, &app_state).await {
+        error!("Failed to watch library {}: {:?}", library_id, e);
+    }
+
+    Ok(())
+}
+
+#[instrument(skip(app_state))]
+pub async fn delete_library_service(library_id: i64, app_state: Data<AppState>) -> Result<()> {
+    let library_watcher = app_state.infrastructure().library_watcher();
+    let scan_scheduler = app_state.infrastructure().scan_scheduler();
+    let library_repository = app_state.storage().repositories().library.clone();
+
+    delete_library(library_id, library_repository).await?;
+    scan_scheduler.unschedule(library_id).await;
+
+    if let Err(e) = library_watcher.unwatch(library_id).await {
+        error!("Failed to unwatch library {}: {:?}", library_id, e);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use anyhow::{Ok, *};
+use std::{path::Path, sync::Arc};
+use tokio::sync::mpsc::UnboundedReceiver;
+use tracing::*;
+
+use crate::{
+    application::media_item_service::apply_library_rescan,
+    chain_events,
+    domain::{
+        media_library::{
+            event::LibraryEventType,
+            library::{get_libraries, get_library_by_id},
+            rescan::ScanTrigger,
+            task::LibraryRescanTask,
+        },
+        task::async_task::TaskType,
+    },
+    infrastructure::{
+        event_dispatcher::{domain_event::DomainEvent, handler::EventHandlerConfig},
+        library_watcher::watcher::LibraryEntryChange,
+    },
+    init::app_state::AppState,
+};
+
+/// Watches every library with auto scan enabled and queues a rescan of the series
+/// directory or movie whose files changed, all websocket clients are notified once saved.
+#[instrument(skip(app_state, changes))]
+pub async fn start_library_watcher_service(
+    app_state: AppState,
+    mut changes: UnboundedReceiver<LibraryEntryChange>,
+) -> Result<()> {
+    let database_addr = app_state.storage().database_addr().clone();
+    let ws_connections = app_state.communication().ws_connections().clone();
+    let event_bus = app_state.infrastructure().event_bus();
+    let library_watcher = app_state.infrastructure().library_watcher();
+    let library_repository = app_state.storage().repositories().library.clone();
+
+    for library in get_libraries(library_repository).await? {
+        if !library.auto_scan {
+            continue;
+        }
+        if let Err(e) = library_watcher
+            .watch(library.id, Path::new(&library.directory))
+            .await
+        {
+            error!("Failed to watch library {}: {:?}", library.id, e);
+        }
+    }
+
+    chain_events!(
+        event_bus,
+        {
+            match_pattern: DomainEvent::Library(LibraryEventType::LibraryRescanned { trigger: ScanTrigger::Watcher, .. }),
+            handler: move |event, event_bus| {
+                let database_addr = database_addr.clone();
+
+                async move {
+                    if let DomainEvent::Library(LibraryEventType::LibraryRescanned { library_id, entry_path: Some(entry_path), library, diff, .. }) = event {
+                        apply_library_rescan(library_id, library, &diff, Arc::new(database_addr.clone()))
+                            .await
+                            .inspect_err(|e| error!("Failed to apply library rescan: {:?}", e))?;
+
+                        let summary = diff.summary();
+                        info!("Library {} entry {} rescanned: {:?}", library_id, entry_path, summary);
+                        event_bus.publish(DomainEvent::Library(LibraryEventType::LibraryChanged {
+                            library_id,
+                            entry_path,
+                            summary,
+                        }))?;
+                    }
+                    Ok(())
+                }
+            },
+            config: EventHandlerConfig::default()
+        },
+        {
+            match_pattern: DomainEvent::Library(LibraryEventType::LibraryChanged { .. }),
+            handler: move |event, _| {
+                let ws_connections = ws_connections.clone();
+
+                async move {
+                    for ws_connection in ws_connections.all().await {
+                        if let Err(e) = event.clone().send_notification::<serde_json::Value>(ws_connection) {
+                            warn!("Failed to notify client about library change: {:?}", e);
+                        }
+                    }
+                    Ok(())
+                }
+            },
+            config: EventHandlerConfig::default()
+        }
+    );
+
+    tokio::spawn(async move {
+        while let Some(change) = changes.recv().await {
+            if let Err(e) = queue_library_entry_rescan(change, &app_state).await {
+                error!("Failed to queue library entry rescan: {:?}", e);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Starts or stops watching a library depending on its current auto scan setting,
+/// should be called whenever a library is created or updated.
+#[instrument(skip(app_state))]
+pub async fn sync_library_watch(library_id: i64, app_state: &AppState) -> Result<()> {
+    let library_watcher = app_state.infrastructure().library_watcher();
+    let library_repository = app_state.storage().repositories().library.clone();
+
+    let library = get_library_by_id(library_id, library_repository).await?;
+    if library.auto_scan {
+        library_watcher
+            .watch(library_id, Path::new(&library.directory))
+            .await
+    } else {
+        library_watcher.unwatch(library_id).await
+    }
+}
+
+#[instrument(skip(app_state))]
+async fn queue_library_entry_rescan(
+    change: LibraryEntryChange,
+    app_state: &AppState,
+) -> Result<()> {
+    let parser_addr = app_state.media().parser_addr();
+    let task_pool = app_state.infrastructure().task_pool();
+    let library_repository = app_state.storage().repositories().library.clone();
+    let media_repository = app_state.storage().repositories().media.clone();
+
+    let library = get_library_by_id(change.library_id, library_repository).await?;
+    let stored = media_repository
+        .get_library_media_files(change.library_id)
+        .await?;
+    let entry_path = change.entry_path.to_string_lossy().to_string();
+    debug!(
+        "Queueing rescan of {} in library {}",
+        entry_path, change.library_id
+    );
+
+    let task = LibraryRescanTask::new(
+        change.library_id,
+        library.directory,
+        library.category,
+        stored,
+        Arc::new(parser_addr.clone()),
+    )
+    .with_entry_path(entry_path)
+    .with_trigger(ScanTrigger::Watcher);
+    task_pool
+        .register_task(
+            TaskType::MediaLibraryRescan,
+            None,
+            None,
+            Box::new(task),
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
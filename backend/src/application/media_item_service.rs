@@ -1,12 +1,21 @@
 use actix::Addr;
 use anyhow::Result;
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 use tracing::*;
 
 use crate::{
-    domain::{episode::model::Episode, season::model::Season, tv_show::model::TvShow},
+    domain::{
+        episode::model::Episode,
+        media_library::{model::Library, rescan::LibraryDiff},
+        movie::model::Movie,
+        season::model::Season,
+        tv_show::model::TvShow,
+    },
     infrastructure::media_db::{
-        actor::{SaveActor, SaveEpisode, SaveGenre, SaveSeason, SaveStudio, SaveTvShow},
+        actor::{
+            SaveActor, SaveEpisode, SaveGenre, SaveMovie, SaveSeason, SaveStudio, SaveTvShow,
+            SoftDeleteMediaFiles, UpdateEpisode, UpdateMovie,
+        },
         database::Database,
     },
 };
@@ -91,3 +100,88 @@ pub async fn insert_media_item(
 
     Ok(())
 }
+
+#[instrument(skip(movie, database_addr))]
+pub async fn insert_movie(
+    library_id: i64,
+    movie: Movie,
+    database_addr: Arc<Addr<Database>>,
+) -> Result<()> {
+    debug!("Inserting movie: {:?}", movie.title);
+
+    let movie_id = database_addr
+        .send(SaveMovie { movie, library_id })
+        .await
+        .map_err(|e| anyhow::anyhow!("Error inserting movie: {:?}", e))?;
+
+    if movie_id == -1 {
+        return Err(anyhow::anyhow!("Error inserting movie"));
+    }
+
+    Ok(())
+}
+
+/// Writes the result of an incremental rescan, `library` only holds the entries with
+/// added or updated files. New rows are inserted first so that the following update
+/// can refresh the metadata of existing rows and restore soft deleted ones, a renamed
+/// episode file takes over the row of its episode before the old path is soft deleted.
+#[instrument(skip(library, diff, database_addr))]
+pub async fn apply_library_rescan(
+    library_id: i64,
+    library: Library,
+    diff: &LibraryDiff,
+    database_addr: Arc<Addr<Database>>,
+) -> Result<()> {
+    if diff.is_empty() {
+        debug!("No media files of library {} changed", library_id);
+        return Ok(());
+    }
+
+    let changed_files: HashSet<&String> = diff.added.iter().chain(diff.updated.iter()).collect();
+
+    for tv_show in library.tv_show {
+        let changed_episodes: Vec<Episode> = tv_show
+            .seasons
+            .values()
+            .flat_map(|season| season.episodes.values())
+            .filter(|episode| changed_files.contains(&episode.video_file_path))
+            .cloned()
+            .collect();
+
+        insert_media_item(library_id, tv_show, database_addr.clone()).await?;
+
+        for episode in changed_episodes {
+            debug!("Updating episode: {:?}", episode.video_file_path);
+            database_addr
+                .send(UpdateEpisode { episode })
+                .await
+                .map_err(|e| anyhow::anyhow!("Error updating episode: {:?}", e))?;
+        }
+    }
+
+    for movie in library.movie {
+        if !changed_files.contains(&movie.video_file_path) {
+            continue;
+        }
+
+        insert_movie(library_id, movie.clone(), database_addr.clone()).await?;
+
+        debug!("Updating movie: {:?}", movie.video_file_path);
+        database_addr
+            .send(UpdateMovie { movie })
+            .await
+            .map_err(|e| anyhow::anyhow!("Error updating movie: {:?}", e))?;
+    }
+
+    if !diff.removed.is_empty() {
+        debug!("Soft deleting {} media files", diff.removed.len());
+        database_addr
+            .send(SoftDeleteMediaFiles {
+                video_file_paths: diff.removed.clone(),
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("Error soft deleting media files: {:?}", e))?;
+    }
+
+    Ok(())
+}
@@ -1,4 +1,11 @@
+pub mod artwork_service;
+pub mod auth_service;
 pub mod file_service;
+pub mod library_schedule_service;
 pub mod library_service;
+pub mod library_watcher_service;
 pub mod media_item_service;
 pub mod pipeline_service;
+pub mod task_service;
+pub mod trickplay_service;
+pub mod video_preview_service;
@@ -1,17 +1,35 @@
-use actix::{Actor, Addr};
+use actix::Actor;
 use anyhow::*;
-use std::{result::Result::Ok, sync::Arc};
-use tokio::{spawn, sync::RwLock};
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    result::Result::Ok,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    fs, spawn,
+    sync::RwLock,
+    task::spawn_blocking,
+    time::{sleep, Instant},
+};
 use tracing::*;
 
 use super::file_service::FileService;
 use crate::domain::task::async_task::TaskIdentifiable;
 use crate::{
     domain::{
+        media_info::model::MediaInfo,
         pipeline::{
-            builder::build_pipeline, event::PipelineEvent, model::Position,
+            builder::build_pipeline,
+            event::PipelineEvent,
+            model::Position,
+            profile::TranscodingProfile,
+            rendition::{AudioRendition, Rendition},
             task::PipelinePreparationTask,
         },
+        playback::method::{ClientCapabilities, PlaybackMethod},
+        subtitle::model::Subtitle,
         task::async_task::{TaskId, TaskType},
         websocket::event::WebSocketEventType,
     },
@@ -20,25 +38,60 @@ use crate::{
         event_dispatcher::{
             domain_event::DomainEvent, event_bus::EventBus, handler::EventHandlerConfig,
         },
-        file::finder_options::{all_files, FinderOptions},
-        hls::hls_state_actor::{HlsStateActor, Reset, SetPipelineAddr},
-        video_pipeline::actor::PipelineAction,
+        hls::{
+            hls_state_actor::{
+                GetPipelineAddr, GetSegmentDuration, GetSegmentState, SegmentState,
+                SetPipelineAddr, SetRenditions, SetSegmentIndex, SetSubtitleRenditions,
+            },
+            session::{PlaybackSession, PlaybackSessions, SESSION_IDLE_TIMEOUT},
+            subtitle::SubtitleRenditions,
+        },
+        video_pipeline::{actor::PipelineAction, probe::probe_media},
     },
     interfaces::ws::utils::WsConnections,
     listen_event,
 };
 
+// Segments this far ahead of the pipeline are waited for instead of seeking to them
+const SEGMENT_LOOKAHEAD: u32 = 3;
+const SEGMENT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const SEGMENT_WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+const FILE_WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+const IDLE_SESSION_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// What the client asked to play.
+#[derive(Debug)]
+pub struct PlaybackRequest {
+    // The user who plays the video, only they and admins may see the preparation task
+    pub user_id: i64,
+    pub path: String,
+    pub start_position: Option<Position>,
+    pub capabilities: ClientCapabilities,
+    // Index of the audio track, the default track is played when missing
+    pub audio_track: Option<u32>,
+    // Index of the subtitle stream to burn into the video, which always transcodes it
+    pub burn_in_subtitle: Option<u32>,
+    // The encoder settings in case the video is transcoded
+    pub profile: TranscodingProfile,
+    pub subtitles: Vec<Subtitle>,
+}
+
 #[derive(Clone)]
 pub struct PipelineService {
     event_bus: Arc<EventBus>,
-    hls_state_actor_addr: Addr<HlsStateActor>,
+    sessions: PlaybackSessions,
+    // The configured ladder, sessions only use the renditions which fit the video
+    renditions: Vec<Rendition>,
+    // The first profile is used when the client doesn't pick one
+    profiles: Vec<TranscodingProfile>,
 }
 
 impl PipelineService {
     #[instrument(skip(event_bus))]
     pub fn new(
         event_bus: Arc<EventBus>,
-        hls_state_actor_addr: Addr<HlsStateActor>,
+        renditions: Vec<Rendition>,
+        profiles: Vec<TranscodingProfile>,
     ) -> Result<Self> {
         let event_bus_clone = event_bus.clone();
         let ws_client_key = Arc::new(RwLock::new(Vec::new()));
@@ -54,45 +107,268 @@ impl PipelineService {
             }
         });
 
-        Ok(Self {
+        let pipeline_service = Self {
             event_bus,
-            hls_state_actor_addr,
-        })
+            sessions: PlaybackSessions::default(),
+            renditions,
+            profiles,
+        };
+
+        // Players which are closed without stopping their session only stop requesting segments
+        let sweeping_service = pipeline_service.clone();
+        spawn(async move {
+            loop {
+                sleep(IDLE_SESSION_SWEEP_INTERVAL).await;
+                let idle_sessions = sweeping_service
+                    .sessions
+                    .idle_sessions(Instant::now(), SESSION_IDLE_TIMEOUT)
+                    .await;
+                for session_id in idle_sessions {
+                    info!("Stopping idle session {}", session_id);
+                    sweeping_service.discard_session(&session_id).await;
+                }
+            }
+        });
+
+        Ok(pipeline_service)
     }
 
-    #[instrument(skip(self, file_service, task_pool))]
+    pub fn profiles(&self) -> &[TranscodingProfile] {
+        &self.profiles
+    }
+
+    /// The profile with the given name, or the default one when no name is given.
+    pub fn profile(&self, name: Option<&str>) -> Option<&TranscodingProfile> {
+        match name {
+            Some(name) => self.profiles.iter().find(|profile| profile.name == name),
+            None => self.profiles.first(),
+        }
+    }
+
+    /// Starts streaming the video in a new session and returns it together with the
+    /// id of the preparation task, playing a video never affects other sessions.
+    /// The file is probed first so it is only transcoded when the client can't play it.
+    #[instrument(skip(self, request, file_service, task_pool))]
     pub async fn start_playback(
         &self,
-        path: &str,
+        request: PlaybackRequest,
         file_service: Arc<FileService>,
         task_pool: Arc<TaskPool>,
-        ws_client_key: String,
+        ws_client_key: Option<String>,
         ws_connections: WsConnections,
-    ) -> Result<TaskId> {
+    ) -> Result<(PlaybackSession, TaskId)> {
+        let PlaybackRequest {
+            user_id,
+            path,
+            start_position,
+            capabilities,
+            audio_track,
+            burn_in_subtitle,
+            profile,
+            subtitles,
+        } = request;
+        // Resolved before anything is started, so an unknown connection leaks no session or
+        // pipeline. Clients without a WebSocket connection poll the playlist until it exists
+        let ws_connection = match ws_client_key.clone() {
+            Some(key) => match ws_connections.get(key).await {
+                Some(ws_connection) => Some(ws_connection),
+                None => {
+                    error!("WebSocket connection not found");
+                    return Err(anyhow!("WebSocket connection not found"));
+                }
+            },
+            None => None,
+        };
+        let path = path.as_str();
         let event_bus = self.event_bus.clone();
-        let mut task = PipelinePreparationTask::new(
-            file_service.clone(),
+        // Without the media info the file is transcoded with the whole ladder
+        let media_info = probe(path)
+            .await
+            .inspect_err(|e| {
+                warn!(
+                    "Failed to probe {}, falling back to transcoding: {}",
+                    path, e
+                )
+            })
+            .ok();
+        let burn_in_subtitle = burn_in_subtitle.filter(|index| {
+            let exists = media_info
+                .as_ref()
+                .is_none_or(|media_info| media_info.subtitle_track(*index).is_some());
+            if !exists {
+                warn!(
+                    "Subtitle {} of {} doesn't exist, not burning it in",
+                    index, path
+                );
+            }
+            exists
+        });
+        let method = match (&media_info, burn_in_subtitle) {
+            (Some(media_info), None) => {
+                PlaybackMethod::decide(media_info, &capabilities, audio_track)
+            }
+            _ => PlaybackMethod::Transcode,
+        };
+        let source_height = media_info.as_ref().and_then(|media_info| media_info.height);
+        let renditions = match method {
+            PlaybackMethod::DirectPlay => vec![],
+            PlaybackMethod::Remux => vec![Rendition::source(source_height)],
+            PlaybackMethod::Transcode => {
+                Rendition::fit_ladder(&self.renditions, profile.limit_height(source_height))
+            }
+        };
+        let audio_renditions = match method {
+            PlaybackMethod::DirectPlay => vec![],
+            _ => AudioRendition::for_tracks(
+                media_info
+                    .as_ref()
+                    .map(|media_info| media_info.audio_tracks.as_slice())
+                    .unwrap_or_default(),
+                audio_track,
+                method == PlaybackMethod::Transcode,
+            ),
+        };
+        let session = PlaybackSession::new(
+            user_id,
+            path.to_string(),
+            method,
+            renditions,
+            audio_renditions,
+            burn_in_subtitle,
+            profile,
             event_bus.clone(),
-            self.hls_state_actor_addr.clone(),
         );
-        task.set_ws_client_id(ws_client_key.clone());
+        let session_id = session.id.clone();
+        info!(
+            "Playing {} with {:?} in session {}",
+            path, method, session_id
+        );
+
+        // Registered before the directory exists so the preparation task of another
+        // session never mistakes it for a stale one
+        self.sessions.add(session.clone()).await;
+        let started = self
+            .launch_session(&session, subtitles, file_service, task_pool, ws_client_key)
+            .await;
+        let task_id = match started {
+            Ok(task_id) => task_id,
+            Err(e) => {
+                self.discard_session(&session_id).await;
+                return Err(e);
+            }
+        };
+
+        // The client fetches the file itself, there is no pipeline to run
+        if method == PlaybackMethod::DirectPlay {
+            return Ok((session, task_id));
+        }
+
+        let pipeline_service = self.clone();
+        let initialized_session_id = session_id.clone();
+        let seek_session_id = session_id.clone();
+        listen_event!(
+            event_bus,
+            DomainEvent::Pipeline(PipelineEvent::HlsStreamInitialized { session_id, .. })
+                if *session_id == initialized_session_id,
+            move |event, _| {
+                let ws_connection_clone = ws_connection.clone();
+                let pipeline_service = pipeline_service.clone();
+                let start_position = start_position.clone();
+                let session_id = seek_session_id.clone();
+                async move {
+                    // The segment duration is only known once the first playlist was written,
+                    // seeking before the player is notified keeps it from loading the start
+                    if let Some(position) = start_position {
+                        if let Err(e) = pipeline_service
+                            .seek_to_position(&session_id, position)
+                            .await
+                        {
+                            error!("Failed to seek to the start position: {:?}", e);
+                        }
+                    }
+
+                    if let Some(ws_connection) = ws_connection_clone {
+                        if let Err(e) = event.send_notification::<serde_json::Value>(ws_connection)
+                        {
+                            error!("Failed to send hls stream initialized event: {:?}", e);
+                        }
+                    }
+                    Ok(())
+                }
+            },
+            EventHandlerConfig::one_time(),
+        );
+
+        Ok((session, task_id))
+    }
+
+    /// Creates the directories of a registered session and starts its pipeline, the caller
+    /// discards the session when any step fails.
+    async fn launch_session(
+        &self,
+        session: &PlaybackSession,
+        subtitles: Vec<Subtitle>,
+        file_service: Arc<FileService>,
+        task_pool: Arc<TaskPool>,
+        ws_client_key: Option<String>,
+    ) -> Result<TaskId> {
+        let path = session.video_file_path.as_str();
+        let event_bus = self.event_bus.clone();
+        for rendition in session.rendition_names() {
+            fs::create_dir_all(session.output_dir.join(rendition))
+                .await
+                .map_err(|e| anyhow!("Failed to create session directory: {}", e))?;
+        }
+
+        let mut task =
+            PipelinePreparationTask::new(file_service, event_bus.clone(), self.sessions.clone());
+        if let Some(ws_client_key) = ws_client_key.clone() {
+            task.set_ws_client_id(ws_client_key);
+        }
         let task_id = task_pool
             .register_task(
                 TaskType::PipelinePreparation,
-                ws_client_key.clone(),
+                ws_client_key,
+                Some(session.user_id),
                 Box::new(task),
                 None,
             )
             .await?;
 
-        let pipeline =
-            match build_pipeline(path, event_bus.clone(), self.hls_state_actor_addr.clone()) {
-                Ok(pipeline) => pipeline,
-                Err(e) => return Err(anyhow::anyhow!("Failed to build pipeline: {}", e)),
-            };
+        if session.method == PlaybackMethod::DirectPlay {
+            return Ok(task_id);
+        }
+
+        // Must be known before hlssink2 writes the first playlists, the last one of them also
+        // writes the master
+        session
+            .hls_state_actor_addr
+            .send(SetRenditions {
+                video: session.renditions.clone(),
+                audio: session.audio_renditions.clone(),
+                profile: (session.method == PlaybackMethod::Transcode)
+                    .then(|| session.profile.clone()),
+            })
+            .await
+            .inspect_err(|e| error!("Failed to set renditions: {}", e))?;
+        session
+            .hls_state_actor_addr
+            .send(SetSubtitleRenditions(SubtitleRenditions::new(
+                path.to_string(),
+                subtitles,
+            )))
+            .await
+            .inspect_err(|e| error!("Failed to set subtitle renditions: {}", e))?;
+
+        let pipeline = match build_pipeline(path, session, event_bus) {
+            Ok(pipeline) => pipeline,
+            Err(e) => return Err(anyhow::anyhow!("Failed to build pipeline: {}", e)),
+        };
 
         let pipeline_addr = pipeline.start();
-        self.hls_state_actor_addr
+        // Sent before anything else can fail, so discarding the session also stops the pipeline
+        session
+            .hls_state_actor_addr
             .send(SetPipelineAddr(pipeline_addr.clone()))
             .await
             .inspect_err(|e| error!("Failed to set hls state actor pipeline address: {}", e))?;
@@ -102,91 +378,181 @@ impl PipelineService {
             .await
             .inspect_err(|e| error!("Failed to start playback: {:?}", e))?;
 
-        let ws_connection = match ws_connections.get(ws_client_key.clone()).await {
-            Some(ws_connection) => ws_connection,
-            None => {
-                error!("WebSocket connection not found");
-                return Err(anyhow!("WebSocket connection not found"));
-            }
-        };
+        Ok(task_id)
+    }
 
-        listen_event!(
-            event_bus,
-            DomainEvent::Pipeline(PipelineEvent::HlsStreamInitialized { .. }),
-            move |event, _| {
-                let ws_connection_clone = ws_connection.clone();
-                async move {
-                    if let Err(e) =
-                        event.send_notification::<serde_json::Value>(ws_connection_clone)
-                    {
-                        error!("Failed to send hls stream initialized event: {:?}", e);
-                    }
-                    Ok(())
-                }
-            },
-            EventHandlerConfig::one_time(),
-        );
+    #[instrument(skip(self))]
+    pub async fn seek_to_position(&self, session_id: &str, position: Position) -> Result<()> {
+        let session = self.require_session(session_id).await?;
+        let segment_duration = session
+            .hls_state_actor_addr
+            .send(GetSegmentDuration)
+            .await??;
 
-        let hls_state_actor_addr_clone = self.hls_state_actor_addr.clone();
-        listen_event!(
-            event_bus,
-            DomainEvent::Pipeline(PipelineEvent::PipelineStopped),
-            move |_, _| {
-                let pipeline_addr_clone = pipeline_addr.clone();
-                let hls_state_actor_addr_clone = hls_state_actor_addr_clone.clone();
-                let file_service_clone = file_service.clone();
+        let segment_index = position.as_nanos() / segment_duration.max(1);
+        self.seek_to_segment(&session, segment_index as u32).await
+    }
 
-                async move {
-                    let _ = pipeline_addr_clone
-                        .send(PipelineAction::Stop)
-                        .await
-                        .map(|_| {
-                            info!("Pipeline stopped");
-                            Ok(())
-                        })
-                        .unwrap_or_else(|e| {
-                            error!("Failed to stop pipeline: {:?}", e);
-                            Err(anyhow::anyhow!("Failed to stop pipeline: {:?}", e))
-                        });
-
-                    let _ = hls_state_actor_addr_clone
-                        .send(Reset)
-                        .await
-                        .inspect_err(|e| error!("Failed to reset hls state actor: {:?}", e));
-
-                    let options = FinderOptions::new()
-                        .filters(all_files())
-                        .include_hidden(true);
-                    let _ = file_service_clone
-                        .delete_files_in_folder("./tmp", options)
-                        .await
-                        .inspect_err(|e| error!("Failed to delete files in tmp folder: {}", e));
+    /// Returns the path of a segment once hlssink2 closed it, half written segments are never
+    /// served. Segments the pipeline is about to write are waited for, any other segment is
+    /// generated by seeking the pipeline to its start, which makes every segment of the
+    /// placeholder VOD playlist playable.
+    #[instrument(skip(self))]
+    pub async fn wait_for_segment(
+        &self,
+        session_id: &str,
+        rendition: &str,
+        segment_index: u32,
+    ) -> Result<PathBuf> {
+        let session = self.require_session(session_id).await?;
+        if !session
+            .rendition_names()
+            .iter()
+            .any(|name| name == rendition)
+        {
+            return Err(anyhow!(
+                "Session {} has no rendition {}",
+                session_id,
+                rendition
+            ));
+        }
 
-                    Ok(())
-                }
-            },
-            EventHandlerConfig::one_time(),
-        );
+        let rendition_dir = session.output_dir.join(rendition);
+        let segment_path = rendition_dir.join(format!("segment_{:05}.ts", segment_index));
+        let get_state = || {
+            session.hls_state_actor_addr.send(GetSegmentState(
+                rendition_dir.to_string_lossy().to_string(),
+                segment_index,
+            ))
+        };
 
-        Ok(task_id)
+        if let SegmentState::NotWritten { next_index } = get_state().await? {
+            if segment_index < next_index || segment_index > next_index + SEGMENT_LOOKAHEAD {
+                info!(
+                    "Segment {} of {} is not generated, seeking from segment {}",
+                    segment_index, rendition, next_index
+                );
+                self.seek_to_segment(&session, segment_index).await?;
+            }
+        }
+
+        let deadline = Instant::now() + SEGMENT_WAIT_TIMEOUT;
+        while get_state().await? != SegmentState::Complete {
+            if Instant::now() >= deadline {
+                return Err(anyhow!(
+                    "Timed out waiting for segment {} of {}",
+                    segment_index,
+                    rendition
+                ));
+            }
+            sleep(SEGMENT_POLL_INTERVAL).await;
+        }
+
+        Ok(segment_path)
     }
 
+    /// Returns the path of a playlist or subtitle of the session, they are written shortly
+    /// after the session started so a request which is a bit early is kept waiting.
     #[instrument(skip(self))]
-    pub async fn seek_to_position(&self, position: Position) -> Result<()> {
+    pub async fn wait_for_file(&self, session_id: &str, relative_path: &Path) -> Result<PathBuf> {
+        let session = self.require_session(session_id).await?;
+        let path = session.output_dir.join(relative_path);
+
+        let deadline = Instant::now() + FILE_WAIT_TIMEOUT;
+        while !fs::try_exists(&path).await? {
+            if Instant::now() >= deadline {
+                return Err(anyhow!("{:?} does not exist", path));
+            }
+            sleep(SEGMENT_POLL_INTERVAL).await;
+        }
+
+        Ok(path)
+    }
+
+    async fn seek_to_segment(&self, session: &PlaybackSession, segment_index: u32) -> Result<()> {
+        let hls_state_actor_addr = &session.hls_state_actor_addr;
+
+        let pipeline_addr = hls_state_actor_addr
+            .send(GetPipelineAddr)
+            .await??
+            .ok_or_else(|| anyhow!("No video is playing"))?;
+        let segment_duration = hls_state_actor_addr.send(GetSegmentDuration).await??;
+
+        // Segments written after the seek must carry the names the VOD playlist expects
+        hls_state_actor_addr
+            .send(SetSegmentIndex(segment_index))
+            .await??;
+
+        let position = segment_index as u64 * segment_duration / 1_000_000_000;
+        pipeline_addr
+            .send(PipelineAction::Seek(position as u32))
+            .await
+            .inspect_err(|e| error!("Failed to seek: {:?}", e))?;
+
         Ok(())
     }
 
+    async fn require_session(&self, session_id: &str) -> Result<PlaybackSession> {
+        self.sessions
+            .get(session_id)
+            .await
+            .ok_or_else(|| anyhow!("Playback session {} not found", session_id))
+    }
+
+    /// The session if the user started it, sessions of other users are reported as missing.
+    pub async fn get_session(&self, session_id: &str, user_id: i64) -> Option<PlaybackSession> {
+        self.sessions
+            .get(session_id)
+            .await
+            .filter(|session| session.user_id == user_id)
+    }
+
+    pub async fn is_session_expired(&self, session_id: &str) -> bool {
+        self.sessions.is_expired(session_id).await
+    }
+
+    /// Keeps the session from being stopped as idle, called for every segment the player requests.
+    pub async fn touch_session(&self, session_id: &str) {
+        self.sessions.touch(session_id).await
+    }
+
     #[instrument(skip(self))]
-    pub async fn stop_and_clean(&self) -> Result<()> {
-        match self
-            .event_bus
-            .publish(DomainEvent::Pipeline(PipelineEvent::PipelineStopped))
+    pub async fn stop_and_clean(&self, session_id: &str) -> Result<()> {
+        self.require_session(session_id).await?;
+        self.discard_session(session_id).await;
+        Ok(())
+    }
+
+    /// Stops the pipeline of the session, removes the session and deletes its files.
+    /// Failures are only logged since the session is gone either way.
+    async fn discard_session(&self, session_id: &str) {
+        // Dropping the session also stops its hls state actor
+        let Some(session) = self.sessions.remove(session_id).await else {
+            return;
+        };
+
+        if let Ok(Ok(Some(pipeline_addr))) =
+            session.hls_state_actor_addr.send(GetPipelineAddr).await
         {
-            Ok(_) => Ok(()),
-            Err(e) => Err(anyhow::anyhow!(
-                "Failed to publish pipeline stopped event: {:?}",
-                e
-            )),
+            match pipeline_addr.send(PipelineAction::Stop).await {
+                Ok(_) => info!("Pipeline of session {} stopped", session_id),
+                Err(e) => error!("Failed to stop pipeline: {:?}", e),
+            }
+        }
+
+        match fs::remove_dir_all(&session.output_dir).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => {
+                error!("Failed to delete files of session {}: {}", session_id, e)
+            }
+            _ => {}
         }
     }
 }
+
+// Probing reads the container headers, which blocks
+pub async fn probe(path: &str) -> Result<MediaInfo> {
+    let video_path = PathBuf::from(path);
+    spawn_blocking(move || probe_media(&video_path))
+        .await
+        .map_err(|e| anyhow!("Probe task of {} panicked: {}", path, e))?
+}
@@ -0,0 +1,158 @@
+use actix_web::web::Data;
+use anyhow::{Ok, *};
+use tracing::*;
+
+use crate::{
+    chain_events,
+    domain::{
+        task::async_task::{AsyncTaskEvent, TaskId, TaskIdentifiable, TaskRecord, TaskStatus},
+        user::model::User,
+    },
+    infrastructure::{
+        async_task_pool::task_pool::TaskPool,
+        event_dispatcher::{domain_event::DomainEvent, handler::EventHandlerConfig},
+    },
+    init::app_state::AppState,
+    interfaces::dtos::TaskDto,
+};
+
+/// Keeps the history of the finished tasks and forwards the progress of each task to the
+/// client which started it, if that client is still connected.
+#[instrument(skip(app_state))]
+pub async fn start_task_event_service(app_state: AppState) -> Result<()> {
+    let event_bus = app_state.infrastructure().event_bus();
+    let ws_connections = app_state.communication().ws_connections().clone();
+    let task_repository = app_state.storage().repositories().task.clone();
+
+    chain_events!(
+        event_bus,
+        {
+            match_pattern: DomainEvent::AsyncTask(AsyncTaskEvent::Finished { .. }),
+            handler: move |event, _| {
+                let task_repository = task_repository.clone();
+
+                async move {
+                    if let DomainEvent::AsyncTask(AsyncTaskEvent::Finished { record, .. }) = event {
+                        task_repository
+                            .save_task_record(record)
+                            .await
+                            .inspect_err(|e| error!("Failed to save task record: {:?}", e))?;
+                    }
+                    Ok(())
+                }
+            },
+            config: EventHandlerConfig::default()
+        },
+        {
+            match_pattern: DomainEvent::AsyncTask(
+                AsyncTaskEvent::ProgressUpdated { .. } | AsyncTaskEvent::Finished { .. }
+            ),
+            handler: move |event, _| {
+                let ws_connections = ws_connections.clone();
+
+                async move {
+                    let ws_client_id = match &event {
+                        DomainEvent::AsyncTask(
+                            AsyncTaskEvent::ProgressUpdated { identifier, .. }
+                            | AsyncTaskEvent::Finished { identifier, .. },
+                        ) => identifier.get_ws_client_id(),
+                        _ => None,
+                    };
+                    // Tasks of headless clients and background services have no one to notify
+                    let Some(ws_client_id) = ws_client_id else {
+                        return Ok(());
+                    };
+                    if let Some(ws_connection) = ws_connections.get(ws_client_id).await {
+                        if let Err(e) = event.send_notification::<serde_json::Value>(ws_connection) {
+                            debug!("Failed to notify client about task: {:?}", e);
+                        }
+                    }
+                    Ok(())
+                }
+            },
+            config: EventHandlerConfig::default()
+        }
+    );
+
+    Ok(())
+}
+
+/// Users who aren't admins only see the tasks they started, other tasks are reported as
+/// missing so their ids don't leak.
+async fn ensure_task_visible(task_id: &TaskId, user: &User, task_pool: &TaskPool) -> Result<()> {
+    if user.is_admin {
+        return Ok(());
+    }
+
+    match task_pool.get_task_owner(task_id.clone()).await? {
+        Some(owner_id) if owner_id == user.id => Ok(()),
+        _ => Err(anyhow!("Task not found")),
+    }
+}
+
+async fn get_task_dto(task_id: TaskId, task_pool: &TaskPool) -> Result<TaskDto> {
+    Ok(TaskDto {
+        task_type: task_pool.get_task_type(task_id.clone()).await?,
+        status: task_pool.get_task_status(task_id.clone()).await?,
+        progress: task_pool.get_task_progress(task_id.clone()).await?,
+        task_id,
+    })
+}
+
+/// The state of a task which is queued, running or finished within its retention period.
+#[instrument(skip(app_state))]
+pub async fn get_task_service(
+    task_id: TaskId,
+    user: &User,
+    app_state: Data<AppState>,
+) -> Result<TaskDto> {
+    let task_pool = app_state.infrastructure().task_pool();
+    ensure_task_visible(&task_id, user, task_pool).await?;
+
+    get_task_dto(task_id, task_pool).await
+}
+
+#[instrument(skip(app_state))]
+pub async fn get_tasks_service(app_state: Data<AppState>) -> Result<Vec<TaskDto>> {
+    let task_pool = app_state.infrastructure().task_pool();
+    let task_ids = task_pool.get_task_ids().await;
+
+    let mut tasks = Vec::with_capacity(task_ids.len());
+    for task_id in task_ids {
+        // The task may have been cleaned up in the meantime
+        match get_task_dto(task_id, task_pool).await {
+            Result::Ok(task) => tasks.push(task),
+            Err(e) => debug!("Skipping task: {}", e),
+        }
+    }
+
+    Ok(tasks)
+}
+
+/// Cancels a queued or running task, a running task stops at its next checkpoint.
+#[instrument(skip(app_state))]
+pub async fn cancel_task_service(
+    task_id: TaskId,
+    user: &User,
+    app_state: Data<AppState>,
+) -> Result<()> {
+    let task_pool = app_state.infrastructure().task_pool();
+    ensure_task_visible(&task_id, user, task_pool).await?;
+
+    task_pool.cancel_task(task_id).await
+}
+
+/// The finished tasks, newest first, including the ones which are no longer in the pool.
+#[instrument(skip(app_state))]
+pub async fn get_task_history_service(
+    status: Option<TaskStatus>,
+    limit: i64,
+    offset: i64,
+    app_state: Data<AppState>,
+) -> Result<Vec<TaskRecord>> {
+    let task_repository = app_state.storage().repositories().task.clone();
+
+    task_repository
+        .get_task_records(status, limit, offset)
+        .await
+}
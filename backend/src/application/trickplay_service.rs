@@ -0,0 +1,36 @@
+use actix_web::web::Data;
+use anyhow::*;
+use std::path::PathBuf;
+use tracing::*;
+
+use crate::{
+    domain::user::{access::AccessScope, model::User},
+    init::app_state::AppState,
+};
+
+/// Looks up a file of the trickplay of an episode. Returns `None` when the episode does not
+/// belong to the media or its trickplay has not been generated yet, and an error when the
+/// user may not see the media.
+#[instrument(skip(app_state))]
+pub async fn get_trickplay_file_service(
+    media_id: i64,
+    episode_id: i64,
+    file: String,
+    user: User,
+    app_state: Data<AppState>,
+) -> Result<Option<PathBuf>> {
+    let episodes = app_state
+        .storage()
+        .repositories()
+        .media
+        .get_media_episodes(media_id, user.id, &AccessScope::for_user(&user))
+        .await?;
+    if !episodes.iter().any(|episode| episode.id == episode_id) {
+        return Ok(None);
+    }
+
+    Ok(app_state
+        .storage()
+        .trickplay_store()
+        .find(episode_id, &file))
+}
@@ -0,0 +1,156 @@
+use anyhow::{Ok, *};
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
+use tokio::sync::mpsc;
+use tracing::*;
+
+use crate::{
+    chain_events,
+    domain::{
+        media_library::event::LibraryEventType, task::async_task::TaskType,
+        thumbnail::task::ThumbnailExtractionTask, trickplay::task::TrickplayGenerationTask,
+    },
+    infrastructure::event_dispatcher::{domain_event::DomainEvent, handler::EventHandlerConfig},
+    init::app_state::AppState,
+};
+
+// Watcher rescans of single entries come in bursts, so a library is only queued once
+// it has not been saved for a while
+const VIDEO_PREVIEW_DEBOUNCE: Duration = Duration::from_secs(30);
+
+/// Generates the missing episode thumbnails and trickplay of a library after each of its
+/// scans is saved, whether it was the initial scan, a manual, scheduled or watcher triggered
+/// rescan.
+#[instrument(skip(app_state))]
+pub async fn start_video_preview_service(
+    app_state: AppState,
+    thumbnail_interval: Duration,
+    trickplay_interval: Duration,
+) -> Result<()> {
+    let event_bus = app_state.infrastructure().event_bus();
+    let (saved_tx, mut saved_rx) = mpsc::unbounded_channel::<i64>();
+
+    chain_events!(
+        event_bus,
+        {
+            match_pattern: DomainEvent::Library(
+                LibraryEventType::LibrarySaved { .. }
+                    | LibraryEventType::LibraryRescanSaved { .. }
+                    | LibraryEventType::LibraryScheduledScanSaved { .. }
+                    | LibraryEventType::LibraryChanged { .. }
+            ),
+            handler: move |event, _| {
+                let saved_tx = saved_tx.clone();
+
+                async move {
+                    let library_id = match event {
+                        DomainEvent::Library(
+                            LibraryEventType::LibrarySaved { library_id, .. }
+                            | LibraryEventType::LibraryRescanSaved { library_id, .. }
+                            | LibraryEventType::LibraryScheduledScanSaved { library_id, .. }
+                            | LibraryEventType::LibraryChanged { library_id, .. },
+                        ) => library_id,
+                        _ => return Ok(()),
+                    };
+                    saved_tx.send(library_id)?;
+                    Ok(())
+                }
+            },
+            config: EventHandlerConfig::default()
+        }
+    );
+
+    tokio::spawn(async move {
+        while let Some(library_id) = saved_rx.recv().await {
+            let mut library_ids = BTreeSet::from([library_id]);
+            loop {
+                tokio::time::sleep(VIDEO_PREVIEW_DEBOUNCE).await;
+                let mut saved_again = false;
+                while let Result::Ok(library_id) = saved_rx.try_recv() {
+                    library_ids.insert(library_id);
+                    saved_again = true;
+                }
+                if !saved_again {
+                    break;
+                }
+            }
+
+            for library_id in library_ids {
+                if let Err(e) =
+                    queue_thumbnail_extraction(library_id, thumbnail_interval, &app_state).await
+                {
+                    error!(
+                        "Failed to queue thumbnail extraction of library {}: {:?}",
+                        library_id, e
+                    );
+                }
+                // Queued after the thumbnails since it takes far longer
+                if let Err(e) =
+                    queue_trickplay_generation(library_id, trickplay_interval, &app_state).await
+                {
+                    error!(
+                        "Failed to queue trickplay generation of library {}: {:?}",
+                        library_id, e
+                    );
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[instrument(skip(app_state))]
+async fn queue_thumbnail_extraction(
+    library_id: i64,
+    interval: Duration,
+    app_state: &AppState,
+) -> Result<()> {
+    let database_addr = app_state.storage().database_addr().clone();
+    let artwork_store = app_state.storage().artwork_store().clone();
+    let task_pool = app_state.infrastructure().task_pool();
+    debug!("Queueing thumbnail extraction of library {}", library_id);
+
+    let task =
+        ThumbnailExtractionTask::new(library_id, interval, Arc::new(database_addr), artwork_store);
+    task_pool
+        .register_task(
+            TaskType::ThumbnailExtraction,
+            None,
+            None,
+            Box::new(task),
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+#[instrument(skip(app_state))]
+async fn queue_trickplay_generation(
+    library_id: i64,
+    interval: Duration,
+    app_state: &AppState,
+) -> Result<()> {
+    let database_addr = app_state.storage().database_addr().clone();
+    let trickplay_store = app_state.storage().trickplay_store().clone();
+    let task_pool = app_state.infrastructure().task_pool();
+    debug!("Queueing trickplay generation of library {}", library_id);
+
+    let task = TrickplayGenerationTask::new(
+        library_id,
+        interval,
+        Arc::new(database_addr),
+        trickplay_store,
+    );
+    task_pool
+        .register_task(
+            TaskType::TrickplayGeneration,
+            None,
+            None,
+            Box::new(task),
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
@@ -1,23 +1,57 @@
 use sqlx::{sqlite::SqliteRow, Row};
 
-use crate::{interfaces::dtos::EpisodeDto, shared::util_traits::SqliteRowMapper};
+use super::model::EpisodeVideo;
+use crate::{
+    domain::{media_info::mapping::media_info_from_row, playback::mapping::progress_from_row},
+    interfaces::dtos::{EpisodeDto, WatchingEpisodeDto},
+    shared::util_traits::SqliteRowMapper,
+};
 
 impl SqliteRowMapper<EpisodeDto> for EpisodeDto {
     fn from_row(row: SqliteRow) -> Self {
-        EpisodeDto {
-            id: row.get::<i64, _>("id"),
-            title: row.get::<Option<String>, _>("title"),
-            original_title: row.get::<Option<String>, _>("original_title"),
-            plot: row.get::<Option<String>, _>("plot"),
-            nfo_path: row.get::<Option<String>, _>("nfo_path"),
+        episode_from_row(&row)
+    }
+}
+
+impl SqliteRowMapper<WatchingEpisodeDto> for WatchingEpisodeDto {
+    fn from_row(row: SqliteRow) -> Self {
+        WatchingEpisodeDto {
+            media_id: row.get::<i64, _>("media_id"),
+            media_title: row.get::<String, _>("media_title"),
+            poster_path: row.get::<Option<String>, _>("poster_path"),
+            episode: episode_from_row(&row),
+        }
+    }
+}
+
+impl SqliteRowMapper<EpisodeVideo> for EpisodeVideo {
+    fn from_row(row: SqliteRow) -> Self {
+        EpisodeVideo {
+            episode_id: row.get::<i64, _>("id"),
             video_file_path: row.get::<String, _>("video_file_path"),
-            subtitle_file_path: row.get::<Option<String>, _>("subtitle_file_path"),
-            thumb_image_url: row.get::<Option<String>, _>("thumb_image_url"),
-            thumb_image: row.get::<Option<String>, _>("thumb_image"),
-            episode_number: row.get::<Option<i64>, _>("episode_number"),
-            runtime: row.get::<Option<i64>, _>("runtime"),
-            season_number: row.get::<Option<i64>, _>("season_number"),
-            season_title: row.get::<Option<String>, _>("season_title"),
+            duration: row
+                .get::<Option<i64>, _>("duration")
+                .map(|duration| duration as u64),
         }
     }
 }
+
+fn episode_from_row(row: &SqliteRow) -> EpisodeDto {
+    EpisodeDto {
+        id: row.get::<i64, _>("id"),
+        title: row.get::<Option<String>, _>("title"),
+        original_title: row.get::<Option<String>, _>("original_title"),
+        plot: row.get::<Option<String>, _>("plot"),
+        nfo_path: row.get::<Option<String>, _>("nfo_path"),
+        video_file_path: row.get::<String, _>("video_file_path"),
+        subtitle_file_path: row.get::<Option<String>, _>("subtitle_file_path"),
+        thumb_image_url: row.get::<Option<String>, _>("thumb_image_url"),
+        thumb_image: row.get::<Option<String>, _>("thumb_image"),
+        episode_number: row.get::<Option<i64>, _>("episode_number"),
+        runtime: row.get::<Option<i64>, _>("runtime"),
+        season_number: row.get::<Option<i64>, _>("season_number"),
+        season_title: row.get::<Option<String>, _>("season_title"),
+        progress: progress_from_row(row),
+        media_info: media_info_from_row(row),
+    }
+}
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-// TODO: maybe add file info fields here, we can get these info from the nfo file.
+use crate::domain::{media_info::model::MediaInfo, subtitle::model::Subtitle};
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize, TS)]
 #[ts(export)]
 pub struct Episode {
@@ -15,6 +16,14 @@ pub struct Episode {
     pub thumb_image: Option<String>,
     pub episode_number: Option<String>,
     pub runtime: Option<String>,
+    pub subtitles: Vec<Subtitle>,
+    // Streams of the video file, missing when probing it failed
+    pub media_info: Option<MediaInfo>,
+
+    // File fingerprint used by the incremental rescan, unix timestamps in seconds
+    pub file_size: Option<i64>,
+    pub file_modified_at: Option<i64>,
+    pub nfo_modified_at: Option<i64>,
 }
 
 impl Episode {
@@ -37,6 +46,12 @@ impl Episode {
         if let Some(subtitle_file_path) = other.subtitle_file_path {
             self.subtitle_file_path = Some(subtitle_file_path);
         }
+        if !other.subtitles.is_empty() {
+            self.subtitles = other.subtitles;
+        }
+        if let Some(media_info) = other.media_info {
+            self.media_info = Some(media_info);
+        }
         if let Some(thumb_image_url) = other.thumb_image_url {
             self.thumb_image_url = Some(thumb_image_url);
         }
@@ -49,5 +64,23 @@ impl Episode {
         if let Some(runtime) = other.runtime {
             self.runtime = Some(runtime);
         }
+        if let Some(file_size) = other.file_size {
+            self.file_size = Some(file_size);
+        }
+        if let Some(file_modified_at) = other.file_modified_at {
+            self.file_modified_at = Some(file_modified_at);
+        }
+        if let Some(nfo_modified_at) = other.nfo_modified_at {
+            self.nfo_modified_at = Some(nfo_modified_at);
+        }
     }
 }
+
+/// The video file of a stored episode, used by the tasks which generate images from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpisodeVideo {
+    pub episode_id: i64,
+    pub video_file_path: String,
+    // Seconds, missing when the file was never probed
+    pub duration: Option<u64>,
+}
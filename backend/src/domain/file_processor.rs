@@ -1,20 +1,19 @@
-use std::sync::Arc;
-
 use actix::Addr;
 use actix_web::web::Data;
 use anyhow::Result;
 use tracing::*;
 
-use super::media_library::model::Library;
-use crate::infrastructure::{
-    event_dispatcher::event_bus::EventBus,
-    library_organizer::organizer::{ParserActor, ScanLibrary},
+use super::{media_library::model::Library, task::async_task::TaskContext};
+use crate::{
+    infrastructure::library_organizer::organizer::{ParserActor, ScanLibrary},
+    interfaces::http_api::controllers::api_models::LibraryCategory,
 };
 
-#[instrument(skip(event_bus))]
+#[instrument(skip(context))]
 pub async fn scan_library(
     directory: String,
-    event_bus: Arc<EventBus>,
+    category: LibraryCategory,
+    context: TaskContext,
     parser_addr: Data<Addr<ParserActor>>,
 ) -> Result<Library> {
     debug!("Scanning library: {}", directory);
@@ -23,7 +22,8 @@ pub async fn scan_library(
     match parser_addr
         .send(ScanLibrary {
             library_path: directory,
-            event_bus,
+            category,
+            context,
         })
         .await
     {
@@ -0,0 +1,56 @@
+use sqlx::{sqlite::SqliteRow, Row};
+
+use super::model::{AudioTrack, HdrFormat, MediaInfo, SubtitleTrack};
+
+/// Reads the `media_` prefixed columns which queries get by left joining `media_info`,
+/// returns `None` for episodes which were never probed. The tracks live in their own
+/// tables and are attached by the caller.
+pub fn media_info_from_row(row: &SqliteRow) -> Option<MediaInfo> {
+    row.get::<Option<i64>, _>("media_info_id")?;
+
+    Some(MediaInfo {
+        container: row.get::<Option<String>, _>("media_container"),
+        duration: row
+            .get::<Option<i64>, _>("media_duration")
+            .map(|duration| duration as u64),
+        bitrate: row
+            .get::<Option<i64>, _>("media_bitrate")
+            .map(|bitrate| bitrate as u64),
+        video_codec: row.get::<Option<String>, _>("media_video_codec"),
+        width: row.get::<Option<u32>, _>("media_width"),
+        height: row.get::<Option<u32>, _>("media_height"),
+        frame_rate: row.get::<Option<f64>, _>("media_frame_rate"),
+        hdr_format: row
+            .get::<Option<String>, _>("media_hdr_format")
+            .and_then(|format| HdrFormat::parse(&format)),
+        audio_tracks: vec![],
+        subtitle_tracks: vec![],
+    })
+}
+
+/// Maps a row of `find_audio_tracks_by_media_id` to the episode it belongs to and the track.
+pub fn audio_track_from_row(row: SqliteRow) -> (i64, AudioTrack) {
+    (
+        row.get::<i64, _>("episode_id"),
+        AudioTrack {
+            index: row.get::<u32, _>("stream_index"),
+            language: row.get::<Option<String>, _>("language"),
+            title: row.get::<Option<String>, _>("title"),
+            codec: row.get::<Option<String>, _>("codec"),
+            channels: row.get::<Option<u32>, _>("channels"),
+        },
+    )
+}
+
+pub fn subtitle_track_from_row(row: SqliteRow) -> (i64, SubtitleTrack) {
+    (
+        row.get::<i64, _>("episode_id"),
+        SubtitleTrack {
+            index: row.get::<u32, _>("stream_index"),
+            language: row.get::<Option<String>, _>("language"),
+            title: row.get::<Option<String>, _>("title"),
+            codec: row.get::<Option<String>, _>("codec"),
+            image_based: row.get::<bool, _>("image_based"),
+        },
+    )
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Technical details of a video file as found by probing it. Codecs and containers use
+/// short lowercase names like `h264`, `aac` or `mp4`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct MediaInfo {
+    pub container: Option<String>,
+    // Seconds
+    pub duration: Option<u64>,
+    // Bits per second of all streams together
+    pub bitrate: Option<u64>,
+    pub video_codec: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    // Frames per second, e.g. 23.976
+    pub frame_rate: Option<f64>,
+    pub hdr_format: Option<HdrFormat>,
+    // In stream order, the first track is the one played by default
+    pub audio_tracks: Vec<AudioTrack>,
+    pub subtitle_tracks: Vec<SubtitleTrack>,
+}
+
+impl MediaInfo {
+    /// The track with the given index, or the default one when no track was chosen.
+    pub fn audio_track(&self, index: Option<u32>) -> Option<&AudioTrack> {
+        match index {
+            Some(index) => self.audio_tracks.iter().find(|track| track.index == index),
+            None => self.audio_tracks.first(),
+        }
+    }
+
+    pub fn subtitle_track(&self, index: u32) -> Option<&SubtitleTrack> {
+        self.subtitle_tracks
+            .iter()
+            .find(|track| track.index == index)
+    }
+}
+
+/// The transfer function of a high dynamic range video.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum HdrFormat {
+    Hdr10,
+    Hlg,
+}
+
+impl HdrFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hdr10 => "hdr10",
+            Self::Hlg => "hlg",
+        }
+    }
+
+    pub fn parse(format: &str) -> Option<Self> {
+        match format {
+            "hdr10" => Some(Self::Hdr10),
+            "hlg" => Some(Self::Hlg),
+            _ => None,
+        }
+    }
+}
+
+/// An audio stream of a video file, the index counts only the audio streams.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct AudioTrack {
+    pub index: u32,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub codec: Option<String>,
+    pub channels: Option<u32>,
+}
+
+impl AudioTrack {
+    /// Human readable name, e.g. for the track list of the player.
+    pub fn display_name(&self) -> String {
+        self.title
+            .clone()
+            .or_else(|| self.language.clone())
+            .unwrap_or_else(|| format!("Audio {}", self.index + 1))
+    }
+}
+
+/// A subtitle stream of a video file, the index counts only the subtitle streams and
+/// matches the stream index of embedded subtitles.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct SubtitleTrack {
+    pub index: u32,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub codec: Option<String>,
+    // Bitmap subtitles like PGS or VobSub can only be shown by burning them into the video
+    pub image_based: bool,
+}
@@ -17,16 +17,17 @@ impl SqliteRowMapper<MediaItemDto> for MediaItemDto {
             runtime: row.get::<Option<i64>, _>("runtime"),
             year: row.get::<Option<i64>, _>("year").map(|y| y.to_string()),
             genres: row
-                .get::<String, _>("genres")
-                .split(',')
-                .map(|s| s.to_string())
-                .collect(),
+                .get::<Option<String>, _>("genres")
+                .map(|genres| genres.split(',').map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
             studios: row
-                .get::<String, _>("studios")
-                .split(',')
-                .map(|s| s.to_string())
-                .collect(),
+                .get::<Option<String>, _>("studios")
+                .map(|studios| studios.split(',').map(|s| s.to_string()).collect())
+                .unwrap_or_default(),
             actors: vec![],
+            video_file_path: row
+                .try_get::<Option<String>, _>("video_file_path")
+                .unwrap_or_default(),
         }
     }
 }
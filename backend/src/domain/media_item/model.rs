@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaSort {
+    #[default]
+    SortTitle,
+    Year,
+    Rating,
+    CreatedAt,
+}
+
+impl MediaSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SortTitle => "sort_title",
+            Self::Year => "year",
+            Self::Rating => "rating",
+            Self::CreatedAt => "created_at",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Asc => "asc",
+            Self::Desc => "desc",
+        }
+    }
+}
+
+/// Which media of a library are listed and in what order, filters which are `None` match
+/// every item. Genres, studios, actors and countries are compared case-insensitively.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, TS)]
+pub struct MediaFilter {
+    pub genre: Option<String>,
+    pub studio: Option<String>,
+    pub actor: Option<String>,
+    pub year_from: Option<i64>,
+    pub year_to: Option<i64>,
+    pub rating_min: Option<f64>,
+    pub country: Option<String>,
+    pub sort: MediaSort,
+    pub order: SortOrder,
+    pub limit: i64,
+    pub offset: i64,
+}
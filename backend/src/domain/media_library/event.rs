@@ -1,12 +1,17 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
-use super::model::Library;
+use super::{
+    model::Library,
+    rescan::{LibraryDiff, RescanSummary, ScanTrigger},
+};
 use crate::{
     define_payload, domain::task::async_task::TaskIdentifier,
     interfaces::ws::notification::ToJsonPayload,
 };
 
+// The variant names are sent to the client as notification types
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub enum LibraryEventType {
@@ -19,6 +24,34 @@ pub enum LibraryEventType {
         library_id: i64,
         library_name: String,
     },
+    LibraryRescanned {
+        task_identifier: TaskIdentifier,
+        library_id: i64,
+        entry_path: Option<String>,
+        trigger: ScanTrigger,
+        library: Library,
+        diff: LibraryDiff,
+    },
+    LibraryRescanFailed {
+        task_identifier: TaskIdentifier,
+        library_id: i64,
+        trigger: ScanTrigger,
+        error: String,
+    },
+    LibraryRescanSaved {
+        task_identifier: TaskIdentifier,
+        library_id: i64,
+        summary: RescanSummary,
+    },
+    LibraryChanged {
+        library_id: i64,
+        entry_path: String,
+        summary: RescanSummary,
+    },
+    LibraryScheduledScanSaved {
+        library_id: i64,
+        summary: RescanSummary,
+    },
 }
 
 define_payload!(LibraryScanned {
@@ -32,6 +65,39 @@ define_payload!(LibrarySaved {
     library_name: String,
 });
 
+define_payload!(LibraryRescanned {
+    task_identifier: TaskIdentifier,
+    library_id: i64,
+    entry_path: Option<String>,
+    trigger: ScanTrigger,
+    library: Library,
+    diff: LibraryDiff,
+});
+
+define_payload!(LibraryRescanFailed {
+    task_identifier: TaskIdentifier,
+    library_id: i64,
+    trigger: ScanTrigger,
+    error: String,
+});
+
+define_payload!(LibraryRescanSaved {
+    task_identifier: TaskIdentifier,
+    library_id: i64,
+    summary: RescanSummary,
+});
+
+define_payload!(LibraryChanged {
+    library_id: i64,
+    entry_path: String,
+    summary: RescanSummary,
+});
+
+define_payload!(LibraryScheduledScanSaved {
+    library_id: i64,
+    summary: RescanSummary,
+});
+
 impl ToJsonPayload for LibraryEventType {
     fn to_json_payload(&self) -> serde_json::Value {
         match self {
@@ -55,6 +121,67 @@ impl ToJsonPayload for LibraryEventType {
                     library_name.to_owned()
                 ))
             }
+            LibraryEventType::LibraryRescanned {
+                task_identifier,
+                library_id,
+                entry_path,
+                trigger,
+                library,
+                diff,
+            } => {
+                serde_json::json!(LibraryRescanned::new(
+                    task_identifier.to_owned(),
+                    library_id.to_owned(),
+                    entry_path.to_owned(),
+                    trigger.to_owned(),
+                    library.to_owned(),
+                    diff.to_owned()
+                ))
+            }
+            LibraryEventType::LibraryRescanFailed {
+                task_identifier,
+                library_id,
+                trigger,
+                error,
+            } => {
+                serde_json::json!(LibraryRescanFailed::new(
+                    task_identifier.to_owned(),
+                    library_id.to_owned(),
+                    trigger.to_owned(),
+                    error.to_owned()
+                ))
+            }
+            LibraryEventType::LibraryRescanSaved {
+                task_identifier,
+                library_id,
+                summary,
+            } => {
+                serde_json::json!(LibraryRescanSaved::new(
+                    task_identifier.to_owned(),
+                    library_id.to_owned(),
+                    summary.to_owned()
+                ))
+            }
+            LibraryEventType::LibraryChanged {
+                library_id,
+                entry_path,
+                summary,
+            } => {
+                serde_json::json!(LibraryChanged::new(
+                    library_id.to_owned(),
+                    entry_path.to_owned(),
+                    summary.to_owned()
+                ))
+            }
+            LibraryEventType::LibraryScheduledScanSaved {
+                library_id,
+                summary,
+            } => {
+                serde_json::json!(LibraryScheduledScanSaved::new(
+                    library_id.to_owned(),
+                    summary.to_owned()
+                ))
+            }
         }
     }
 }
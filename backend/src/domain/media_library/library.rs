@@ -3,6 +3,7 @@ use std::sync::Arc;
 use tracing::*;
 
 use crate::{
+    domain::user::access::AccessScope,
     infrastructure::media_db::library::{
         repository::LibraryRepository, wrapper::LibraryDatabaseWrapper,
     },
@@ -78,6 +79,32 @@ pub async fn get_library_by_id(
     Ok(library)
 }
 
+#[instrument(skip(library_repository))]
+pub async fn get_visible_libraries(
+    scope: &AccessScope,
+    library_repository: Arc<LibraryRepository<LibraryDatabaseWrapper>>,
+) -> Result<Vec<LibraryDto>> {
+    debug!("Getting libraries visible to {:?}", scope);
+    let media_libraries = library_repository.get_visible_libraries(scope).await?;
+
+    Ok(media_libraries)
+}
+
+#[instrument(skip(library_repository))]
+pub async fn get_visible_library_by_id(
+    id: i64,
+    scope: &AccessScope,
+    library_repository: Arc<LibraryRepository<LibraryDatabaseWrapper>>,
+) -> Result<LibraryDto> {
+    debug!("Getting library for id: {}", id);
+
+    let library = library_repository
+        .get_visible_library_by_id(id, scope)
+        .await?;
+
+    Ok(library)
+}
+
 #[instrument(skip(library_repository))]
 pub async fn delete_library(
     id: i64,
@@ -6,7 +6,11 @@ use crate::{
     shared::util_traits::SqliteRowMapper,
 };
 
-use super::model::{LibraryBrief, LibraryPoster};
+use super::{
+    model::{LibraryBrief, LibraryPoster},
+    rescan::MediaFileState,
+    schedule::{LibraryScanSchedule, ScanSchedule},
+};
 
 // TODO: consider if we can use try_get for non-panicking mapping
 // Maybe we can filter out the invalid ones at domain layer and return appropriate http code to frontend
@@ -39,3 +43,34 @@ impl SqliteRowMapper<LibraryPoster> for LibraryPoster {
         }
     }
 }
+
+impl SqliteRowMapper<MediaFileState> for MediaFileState {
+    fn from_row(row: SqliteRow) -> Self {
+        MediaFileState {
+            video_file_path: row.get::<String, _>("video_file_path"),
+            file_size: row.get::<Option<i64>, _>("file_size"),
+            file_modified_at: row.get::<Option<i64>, _>("file_modified_at"),
+            nfo_modified_at: row.get::<Option<i64>, _>("nfo_modified_at"),
+        }
+    }
+}
+
+impl SqliteRowMapper<LibraryScanSchedule> for LibraryScanSchedule {
+    fn from_row(row: SqliteRow) -> Self {
+        // The table guarantees that either the interval or the daily time is set
+        let schedule = match row.get::<Option<u32>, _>("interval_hours") {
+            Some(hours) => ScanSchedule::Interval { hours },
+            None => ScanSchedule::Daily {
+                hour: row.get::<Option<u32>, _>("daily_hour").unwrap_or_default(),
+                minute: row
+                    .get::<Option<u32>, _>("daily_minute")
+                    .unwrap_or_default(),
+            },
+        };
+
+        LibraryScanSchedule {
+            library_id: row.get::<i64, _>("library_id"),
+            schedule,
+        }
+    }
+}
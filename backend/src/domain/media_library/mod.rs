@@ -3,4 +3,6 @@ pub mod event;
 pub mod library;
 pub mod mapping;
 pub mod model;
+pub mod rescan;
+pub mod schedule;
 pub mod task;
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 use crate::{
-    domain::{library::model::LibraryStatus, tv_show::model::TvShow},
+    domain::{library::model::LibraryStatus, movie::model::Movie, tv_show::model::TvShow},
     interfaces::http_api::controllers::api_models::LibraryCategory,
 };
 
@@ -11,6 +11,7 @@ use crate::{
 pub struct Library {
     // TODO: maybe generic type
     pub tv_show: Vec<TvShow>,
+    pub movie: Vec<Movie>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
@@ -34,6 +35,6 @@ pub struct LibraryBrief {
 #[ts(export)]
 pub struct LibraryPoster {
     pub id: i64,
-    // Base64 encoded image, would be changed in the future
+    // Url of the poster in the artwork store, e.g. /artwork/{hash}
     pub poster_path: Option<String>,
 }
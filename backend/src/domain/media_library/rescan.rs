@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use ts_rs::TS;
+
+/// The fingerprint of a playable file, either from the database or from the filesystem.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct MediaFileState {
+    pub video_file_path: String,
+    pub file_size: Option<i64>,
+    pub file_modified_at: Option<i64>,
+    pub nfo_modified_at: Option<i64>,
+}
+
+impl MediaFileState {
+    fn is_changed(&self, other: &MediaFileState) -> bool {
+        self.file_size != other.file_size
+            || self.file_modified_at != other.file_modified_at
+            || self.nfo_modified_at != other.nfo_modified_at
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct LibraryDiff {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl LibraryDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+
+    pub fn summary(&self) -> RescanSummary {
+        RescanSummary {
+            added: self.added.len(),
+            updated: self.updated.len(),
+            removed: self.removed.len(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct RescanSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+/// What started a rescan, each trigger saves its results with its own event handlers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub enum ScanTrigger {
+    #[default]
+    Manual,
+    Watcher,
+    Schedule,
+}
+
+/// Compares the files stored in the database with the files found on disk by their path,
+/// a file is updated when its size, modified time or the modified time of its nfo changed.
+pub fn diff_media_files(stored: &[MediaFileState], scanned: &[MediaFileState]) -> LibraryDiff {
+    let stored_map: HashMap<&str, &MediaFileState> = stored
+        .iter()
+        .map(|state| (state.video_file_path.as_str(), state))
+        .collect();
+    let scanned_map: HashMap<&str, &MediaFileState> = scanned
+        .iter()
+        .map(|state| (state.video_file_path.as_str(), state))
+        .collect();
+
+    let mut diff = LibraryDiff::default();
+
+    for state in scanned {
+        match stored_map.get(state.video_file_path.as_str()) {
+            Some(stored_state) if stored_state.is_changed(state) => {
+                diff.updated.push(state.video_file_path.clone())
+            }
+            Some(_) => {}
+            None => diff.added.push(state.video_file_path.clone()),
+        }
+    }
+
+    diff.removed = stored
+        .iter()
+        .filter(|state| !scanned_map.contains_key(state.video_file_path.as_str()))
+        .map(|state| state.video_file_path.clone())
+        .collect();
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(
+        path: &str,
+        size: i64,
+        modified_at: i64,
+        nfo_modified_at: Option<i64>,
+    ) -> MediaFileState {
+        MediaFileState {
+            video_file_path: path.to_string(),
+            file_size: Some(size),
+            file_modified_at: Some(modified_at),
+            nfo_modified_at,
+        }
+    }
+
+    #[test]
+    fn test_diff_media_files_without_changes() {
+        let stored = vec![state("/a/S01E01.mkv", 10, 100, Some(100))];
+        let scanned = stored.clone();
+
+        let diff = diff_media_files(&stored, &scanned);
+
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_diff_media_files_detects_added_updated_and_removed() {
+        let stored = vec![
+            state("/a/S01E01.mkv", 10, 100, None),
+            state("/a/S01E02.mkv", 10, 100, Some(100)),
+            state("/a/S01E03.mkv", 10, 100, None),
+        ];
+        let scanned = vec![
+            state("/a/S01E01.mkv", 10, 100, None),
+            state("/a/S01E02.mkv", 10, 100, Some(200)),
+            state("/a/S01E04.mkv", 10, 100, None),
+        ];
+
+        let diff = diff_media_files(&stored, &scanned);
+
+        assert_eq!(diff.added, vec!["/a/S01E04.mkv".to_string()]);
+        assert_eq!(diff.updated, vec!["/a/S01E02.mkv".to_string()]);
+        assert_eq!(diff.removed, vec!["/a/S01E03.mkv".to_string()]);
+        assert_eq!(
+            diff.summary(),
+            RescanSummary {
+                added: 1,
+                updated: 1,
+                removed: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_diff_media_files_treats_missing_fingerprint_as_updated() {
+        let stored = vec![MediaFileState {
+            video_file_path: "/a/movie.mkv".to_string(),
+            file_size: None,
+            file_modified_at: None,
+            nfo_modified_at: None,
+        }];
+        let scanned = vec![state("/a/movie.mkv", 10, 100, None)];
+
+        let diff = diff_media_files(&stored, &scanned);
+
+        assert_eq!(diff.updated, vec!["/a/movie.mkv".to_string()]);
+    }
+}
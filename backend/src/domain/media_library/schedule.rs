@@ -0,0 +1,116 @@
+use anyhow::*;
+use chrono::{DateTime, Days, TimeDelta, TimeZone};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// How often a library is rescanned in the background.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum ScanSchedule {
+    // Once a day at the given local time, e.g. nightly at 03:00
+    Daily { hour: u32, minute: u32 },
+    // Every N hours after the last scan
+    Interval { hours: u32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryScanSchedule {
+    pub library_id: i64,
+    pub schedule: ScanSchedule,
+}
+
+impl ScanSchedule {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            ScanSchedule::Daily { hour, minute } if *hour > 23 || *minute > 59 => Err(anyhow!(
+                "Invalid daily scan time: {:02}:{:02}",
+                hour,
+                minute
+            )),
+            ScanSchedule::Interval { hours } if *hours == 0 => {
+                Err(anyhow!("Scan interval must be at least one hour"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the first time after `after` at which the library should be scanned again.
+    pub fn next_run_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> DateTime<Tz> {
+        match self {
+            ScanSchedule::Daily { hour, minute } => {
+                let timezone = after.timezone();
+                let mut date = after.date_naive();
+
+                loop {
+                    // Days where the time falls into a DST gap are skipped
+                    let candidate = date
+                        .and_hms_opt(*hour, *minute, 0)
+                        .and_then(|time| timezone.from_local_datetime(&time).earliest());
+
+                    match candidate {
+                        Some(candidate) if candidate > *after => return candidate,
+                        _ => date = date + Days::new(1),
+                    }
+                }
+            }
+            ScanSchedule::Interval { hours } => after.clone() + TimeDelta::hours(i64::from(*hours)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    #[test]
+    fn test_daily_schedule_runs_later_the_same_day() {
+        let schedule = ScanSchedule::Daily { hour: 3, minute: 0 };
+        let after = Utc.with_ymd_and_hms(2024, 11, 1, 1, 30, 0).unwrap();
+
+        assert_eq!(
+            schedule.next_run_after(&after),
+            Utc.with_ymd_and_hms(2024, 11, 1, 3, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_daily_schedule_runs_next_day_once_passed() {
+        let schedule = ScanSchedule::Daily { hour: 3, minute: 0 };
+        let after = Utc.with_ymd_and_hms(2024, 11, 1, 3, 0, 0).unwrap();
+
+        assert_eq!(
+            schedule.next_run_after(&after),
+            Utc.with_ymd_and_hms(2024, 11, 2, 3, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_interval_schedule_adds_hours() {
+        let schedule = ScanSchedule::Interval { hours: 6 };
+        let after = Utc.with_ymd_and_hms(2024, 11, 1, 22, 0, 0).unwrap();
+
+        assert_eq!(
+            schedule.next_run_after(&after),
+            Utc.with_ymd_and_hms(2024, 11, 2, 4, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_schedules() {
+        assert!(ScanSchedule::Daily {
+            hour: 24,
+            minute: 0
+        }
+        .validate()
+        .is_err());
+        assert!(ScanSchedule::Interval { hours: 0 }.validate().is_err());
+        assert!(ScanSchedule::Daily {
+            hour: 3,
+            minute: 30
+        }
+        .validate()
+        .is_ok());
+    }
+}
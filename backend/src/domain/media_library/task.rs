@@ -4,15 +4,20 @@ use anyhow::*;
 use async_trait::async_trait;
 use std::{result::Result::Ok, sync::Arc};
 
-use super::event::LibraryEventType;
+use super::{
+    event::LibraryEventType,
+    rescan::{MediaFileState, ScanTrigger},
+};
 use crate::{
     domain::task::async_task::{
-        ambassador_impl_TaskIdentifiable, AsyncTask, TaskId, TaskIdentifiable, TaskIdentifier,
+        ambassador_impl_TaskIdentifiable, AsyncTask, TaskContext, TaskId, TaskIdentifiable,
+        TaskIdentifier,
     },
     infrastructure::{
-        event_dispatcher::{domain_event::DomainEvent, event_bus::EventBus, model::GeneralEvent},
-        library_organizer::organizer::{ParserActor, ScanLibrary},
+        event_dispatcher::{domain_event::DomainEvent, event_bus::EventBus},
+        library_organizer::organizer::{ParserActor, RescanLibrary, ScanLibrary},
     },
+    interfaces::http_api::controllers::api_models::LibraryCategory,
 };
 
 #[derive(Delegate)]
@@ -20,19 +25,24 @@ use crate::{
 pub struct LibraryScanTask {
     identifier: TaskIdentifier,
     library_path: String,
+    category: LibraryCategory,
     parser_addr: Arc<Addr<ParserActor>>,
 }
 
 #[async_trait]
 impl AsyncTask for LibraryScanTask {
-    async fn execute(&self, _identifier: TaskIdentifier, event_bus: Arc<EventBus>) -> Result<()> {
-        let _ = event_bus.publish(DomainEvent::General(GeneralEvent::TaskStarted));
-
+    async fn execute(
+        &self,
+        _identifier: TaskIdentifier,
+        event_bus: Arc<EventBus>,
+        context: TaskContext,
+    ) -> Result<()> {
         let library = match self
             .parser_addr
             .send(ScanLibrary {
                 library_path: self.library_path.clone(),
-                event_bus: event_bus.clone(),
+                category: self.category.clone(),
+                context: context.clone(),
             })
             .await
             .map_err(|_| anyhow!("Failed to send scan library message"))?
@@ -40,6 +50,8 @@ impl AsyncTask for LibraryScanTask {
             Ok(library) => library,
             Err(e) => return Err(anyhow!("Failed to scan library: {:?}", e)),
         };
+        // A cancelled scan skipped entries, saving it would leave the library incomplete
+        context.ensure_not_cancelled()?;
 
         // Artificial delay to test async task execution and UI feedback.
         // TODO: Remove this delay before production
@@ -55,11 +67,112 @@ impl AsyncTask for LibraryScanTask {
 }
 
 impl LibraryScanTask {
-    pub fn new(library_path: String, parser_addr: Arc<Addr<ParserActor>>) -> Self {
+    pub fn new(
+        library_path: String,
+        category: LibraryCategory,
+        parser_addr: Arc<Addr<ParserActor>>,
+    ) -> Self {
         Self {
             identifier: TaskIdentifier::default(),
             library_path,
+            category,
             parser_addr,
         }
     }
 }
+
+#[derive(Delegate)]
+#[delegate(TaskIdentifiable, target = "identifier")]
+pub struct LibraryRescanTask {
+    identifier: TaskIdentifier,
+    library_id: i64,
+    library_path: String,
+    category: LibraryCategory,
+    entry_path: Option<String>,
+    trigger: ScanTrigger,
+    stored: Vec<MediaFileState>,
+    parser_addr: Arc<Addr<ParserActor>>,
+}
+
+#[async_trait]
+impl AsyncTask for LibraryRescanTask {
+    async fn execute(
+        &self,
+        _identifier: TaskIdentifier,
+        event_bus: Arc<EventBus>,
+        context: TaskContext,
+    ) -> Result<()> {
+        let result = self
+            .parser_addr
+            .send(RescanLibrary {
+                library_path: self.library_path.clone(),
+                category: self.category.clone(),
+                entry_path: self.entry_path.clone(),
+                stored: self.stored.clone(),
+                context: context.clone(),
+            })
+            .await
+            .map_err(|_| anyhow!("Failed to send rescan library message"))
+            .and_then(|result| result.map_err(|e| anyhow!("Failed to rescan library: {:?}", e)))
+            // The diff of a cancelled rescan would report the skipped entries as unchanged
+            .and_then(|result| context.ensure_not_cancelled().map(|_| result));
+
+        let (library, diff) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                let _ = event_bus.publish(DomainEvent::Library(
+                    LibraryEventType::LibraryRescanFailed {
+                        task_identifier: self.identifier.clone(),
+                        library_id: self.library_id,
+                        trigger: self.trigger,
+                        error: e.to_string(),
+                    },
+                ));
+                return Err(e);
+            }
+        };
+
+        let _ = event_bus.publish(DomainEvent::Library(LibraryEventType::LibraryRescanned {
+            task_identifier: self.identifier.clone(),
+            library_id: self.library_id,
+            entry_path: self.entry_path.clone(),
+            trigger: self.trigger,
+            library,
+            diff,
+        }));
+
+        Ok(())
+    }
+}
+
+impl LibraryRescanTask {
+    pub fn new(
+        library_id: i64,
+        library_path: String,
+        category: LibraryCategory,
+        stored: Vec<MediaFileState>,
+        parser_addr: Arc<Addr<ParserActor>>,
+    ) -> Self {
+        Self {
+            identifier: TaskIdentifier::default(),
+            library_id,
+            library_path,
+            category,
+            entry_path: None,
+            trigger: ScanTrigger::default(),
+            stored,
+            parser_addr,
+        }
+    }
+
+    /// Limits the rescan to a single entry of the library, e.g. a series directory.
+    pub fn with_entry_path(mut self, entry_path: String) -> Self {
+        self.entry_path = Some(entry_path);
+        self
+    }
+
+    pub fn with_trigger(mut self, trigger: ScanTrigger) -> Self {
+        self.trigger = trigger;
+        self
+    }
+}
@@ -4,11 +4,19 @@ pub mod file_processor;
 pub mod file_system;
 pub mod library;
 pub mod media_actor;
+pub mod media_info;
 pub mod media_item;
 pub mod media_library;
+pub mod movie;
 pub mod pipeline;
+pub mod playback;
+pub mod search;
 pub mod season;
+pub mod subtitle;
 pub mod task;
+pub mod thumbnail;
 pub mod time;
+pub mod trickplay;
 pub mod tv_show;
+pub mod user;
 pub mod websocket;
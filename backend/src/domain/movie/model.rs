@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::domain::{media_actor::model::MediaActor, subtitle::model::Subtitle};
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct Movie {
+    pub title: Option<String>,
+    pub original_title: Option<String>,
+    pub sort_title: Option<String>,
+    pub tagline: Option<String>,
+    pub year: Option<String>,
+    pub plot: Option<String>,
+    pub genres: Vec<String>,
+    pub studios: Vec<String>,
+    pub country: Option<String>,
+    pub premiered: Option<String>,
+    pub rating: Option<f32>,
+    pub certification: Option<String>,
+    pub runtime: Option<String>,
+    pub actors: Vec<MediaActor>,
+    pub tmdb_id: Option<String>,
+    pub imdb_id: Option<String>,
+    pub wikidata_id: Option<String>,
+
+    // Information from the folder scanner
+    pub nfo_path: Option<String>,
+    pub poster_path: Option<String>,
+    pub fanart_path: Option<String>,
+    pub video_file_path: String,
+    pub subtitle_file_path: Option<String>,
+    pub subtitles: Vec<Subtitle>,
+
+    // File fingerprint used by the incremental rescan, unix timestamps in seconds
+    pub file_size: Option<i64>,
+    pub file_modified_at: Option<i64>,
+    pub nfo_modified_at: Option<i64>,
+}
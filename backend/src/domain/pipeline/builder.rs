@@ -1,21 +1,23 @@
-use actix::Addr;
 use anyhow::*;
 use std::{result::Result::Ok, sync::Arc};
 use tracing::*;
 
 use crate::{
-    domain::pipeline::ports::{Decoder, HlsSink, Source, StreamBranch},
+    domain::{
+        pipeline::ports::{Decoder, HlsSink, Source, StreamBranch},
+        playback::method::PlaybackMethod,
+    },
     infrastructure::{
         event_dispatcher::event_bus::EventBus,
-        hls::hls_state_actor::HlsStateActor,
+        hls::session::PlaybackSession,
         video_pipeline::{
             elements::{
-                branch::{AudioBranch, VideoBranch},
-                decode::Decodebin,
+                branch::{AudioBranch, RemuxAudioBranch, RemuxVideoBranch, VideoBranch},
+                decode::{Decodebin, Parsebin},
                 hlssink::HlsSinkImpl,
                 source::FileSource,
             },
-            pipeline::Pipeline,
+            pipeline::{AlternateAudio, Pipeline, PipelineAudio},
         },
     },
     shared::utils::ElementFactory,
@@ -27,9 +29,13 @@ use crate::{
 #[instrument(skip(event_bus))]
 pub fn build_pipeline(
     source_path: &str,
+    session: &PlaybackSession,
     event_bus: Arc<EventBus>,
-    hls_state_actor_addr: Addr<HlsStateActor>,
 ) -> Result<Pipeline> {
+    if session.method == PlaybackMethod::Remux {
+        return build_remux_pipeline(source_path, session, event_bus);
+    }
+
     debug!("Building pipeline");
 
     let element_factory = Arc::new(ElementFactory);
@@ -46,33 +52,119 @@ pub fn build_pipeline(
     };
     debug!("Decoder created");
 
-    let video_branch = match VideoBranch::new(&*element_factory) {
-        Ok(video_branch) => video_branch,
-        Err(e) => return Err(anyhow::anyhow!("Failed to create video branch: {}", e)),
-    };
-    debug!("Video branch created");
+    let mut video_branches: Vec<Arc<dyn StreamBranch + Send>> = vec![];
+    let mut hls_sinks: Vec<Arc<dyn HlsSink + Send>> = vec![];
+    for rendition in &session.renditions {
+        let video_branch =
+            VideoBranch::for_rendition(&*element_factory, rendition, &session.profile).and_then(
+                |video_branch| match session.burn_in_subtitle {
+                    Some(_) => video_branch.with_subtitle_overlay(&*element_factory),
+                    None => Ok(video_branch),
+                },
+            );
+        let video_branch = match video_branch {
+            Ok(video_branch) => video_branch,
+            Err(e) => return Err(anyhow::anyhow!("Failed to create video branch: {}", e)),
+        };
+        let hls_sink = match HlsSinkImpl::new(
+            &session.output_dir.join(&rendition.name),
+            session.hls_state_actor_addr.clone(),
+        ) {
+            Ok(hls_sink) => hls_sink,
+            Err(e) => return Err(anyhow::anyhow!("Failed to initialize hls sink: {}", e)),
+        };
+        video_branches.push(Arc::new(video_branch));
+        hls_sinks.push(Arc::new(hls_sink));
+    }
+    debug!("Created {} renditions", session.renditions.len());
 
-    let audio_branch = match AudioBranch::new(&*element_factory) {
+    let audio_branch = match AudioBranch::for_profile(&*element_factory, &session.profile) {
         Ok(audio_branch) => audio_branch,
         Err(e) => return Err(anyhow::anyhow!("Failed to create audio branch: {}", e)),
     };
     debug!("Audio branch created");
 
-    let hls_sink = match HlsSinkImpl::new(hls_state_actor_addr.clone()) {
-        Ok(hls_sink) => hls_sink,
-        Err(e) => return Err(anyhow::anyhow!("Failed to initialize hls sink: {}", e)),
-    };
-    debug!("Hls sink created");
+    let mut alternates = vec![];
+    for rendition in session.audio_renditions.iter().filter(|r| !r.muxed) {
+        let audio_branch = match AudioBranch::for_profile(&*element_factory, &session.profile) {
+            Ok(audio_branch) => audio_branch,
+            Err(e) => return Err(anyhow::anyhow!("Failed to create audio branch: {}", e)),
+        };
+        let hls_sink = match HlsSinkImpl::new(
+            &session.output_dir.join(rendition.name()),
+            session.hls_state_actor_addr.clone(),
+        ) {
+            Ok(hls_sink) => hls_sink,
+            Err(e) => return Err(anyhow::anyhow!("Failed to initialize hls sink: {}", e)),
+        };
+        alternates.push(AlternateAudio {
+            track: rendition.track.index,
+            branch: Arc::new(audio_branch),
+            hls_sink: Arc::new(hls_sink),
+        });
+    }
+    debug!("Created {} alternate audio tracks", alternates.len());
 
     let pipeline = Pipeline::new(
         Arc::new(source),
         Arc::new(decoder),
-        Arc::new(video_branch),
-        Arc::new(audio_branch),
-        Arc::new(hls_sink),
+        video_branches,
+        hls_sinks,
+        PipelineAudio {
+            track: session.muxed_audio_track(),
+            branch: Arc::new(audio_branch),
+            alternates,
+        },
+        session.burn_in_subtitle,
         event_bus,
     );
     debug!("Pipeline created");
 
     Ok(pipeline)
 }
+
+/// Builds a pipeline which copies the streams into HLS segments without transcoding them,
+/// so there is only the single rendition of the session.
+#[instrument(skip(event_bus))]
+fn build_remux_pipeline(
+    source_path: &str,
+    session: &PlaybackSession,
+    event_bus: Arc<EventBus>,
+) -> Result<Pipeline> {
+    debug!("Building remux pipeline");
+
+    let element_factory = Arc::new(ElementFactory);
+
+    let source = FileSource::new(source_path)
+        .map_err(|e| anyhow::anyhow!("Failed to create file source: {}", e))?;
+    let parser = Parsebin::new(&*element_factory)
+        .map_err(|e| anyhow::anyhow!("Failed to create parser: {}", e))?;
+    let video_branch = RemuxVideoBranch::new(&*element_factory)
+        .map_err(|e| anyhow::anyhow!("Failed to create video branch: {}", e))?;
+    let audio_branch = RemuxAudioBranch::new(&*element_factory)
+        .map_err(|e| anyhow::anyhow!("Failed to create audio branch: {}", e))?;
+    let rendition = session
+        .renditions
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("Remux session has no rendition"))?;
+    let hls_sink = HlsSinkImpl::new(
+        &session.output_dir.join(&rendition.name),
+        session.hls_state_actor_addr.clone(),
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to initialize hls sink: {}", e))?;
+    debug!("Remux elements created");
+
+    Ok(Pipeline::new(
+        Arc::new(source),
+        Arc::new(parser),
+        vec![Arc::new(video_branch)],
+        vec![Arc::new(hls_sink)],
+        PipelineAudio {
+            track: session.muxed_audio_track(),
+            branch: Arc::new(audio_branch),
+            alternates: vec![],
+        },
+        None,
+        event_bus,
+    ))
+}
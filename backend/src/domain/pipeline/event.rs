@@ -25,16 +25,20 @@ pub enum PipelineEvent {
     PreparationStarted,
     PreparationFinished,
     HlsStreamInitialized {
+        session_id: String,
         path: String,
     },
-    PipelineStopped,
 }
 
 impl ToJsonPayload for PipelineEvent {
     fn to_json_payload(&self) -> serde_json::Value {
         match self {
-            PipelineEvent::HlsStreamInitialized { path } => {
-                serde_json::json!({ "type": "HlsStreamInitialized", "path": path })
+            PipelineEvent::HlsStreamInitialized { session_id, path } => {
+                serde_json::json!({
+                    "type": "HlsStreamInitialized",
+                    "session_id": session_id,
+                    "path": path
+                })
             }
             _ => unimplemented!(),
         }
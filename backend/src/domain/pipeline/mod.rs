@@ -2,4 +2,6 @@ pub mod builder;
 pub mod event;
 pub mod model;
 pub mod ports;
+pub mod profile;
+pub mod rendition;
 pub mod task;
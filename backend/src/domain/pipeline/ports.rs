@@ -1,7 +1,7 @@
 use actix::Addr;
 use anyhow::Result;
 use gstreamer::{prelude::*, Element};
-use std::fmt::Debug;
+use std::{fmt::Debug, path::Path};
 use tracing::*;
 
 use super::model::{Duration, PipelineState, Position};
@@ -53,6 +53,11 @@ pub trait StreamBranch: Send + Sync {
 
     fn get_entry(&self) -> Element;
     fn get_elements(&self) -> Vec<&Element>;
+
+    // The element whose subtitle_sink pad takes the subtitles to burn in, if the branch has one
+    fn get_subtitle_entry(&self) -> Option<Element> {
+        None
+    }
 }
 
 #[derive(Debug)]
@@ -60,17 +65,26 @@ pub enum DecodebinSignal {
     ConnectPadAdded,
 }
 
+/// The elements the streams of a decoder are linked to, tracks are identified by their
+/// index among the streams of the same type.
+#[derive(Debug)]
+pub struct DecoderSinks {
+    pub video: Element,
+    pub audio: Vec<(u32, Element)>,
+    pub subtitle: Option<(u32, Element)>,
+}
+
 pub trait Decoder: Send + Sync {
     fn new(factory: &(impl ElementFactoryTrait + Debug)) -> Result<Self>
     where
         Self: Sized;
 
-    fn handle_signal(&mut self, signal: DecodebinSignal, video_sink: Element, audio_sink: Element);
+    fn handle_signal(&mut self, signal: DecodebinSignal, sinks: DecoderSinks);
     fn get_element(&self) -> &Element;
 }
 
 pub trait HlsSink: Send + Sync {
-    fn new(hls_state_actor_addr: Addr<HlsStateActor>) -> Result<Self>
+    fn new(output_dir: &Path, hls_state_actor_addr: Addr<HlsStateActor>) -> Result<Self>
     where
         Self: Sized;
     fn get_element(&self) -> &Element;
@@ -0,0 +1,219 @@
+use anyhow::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, path::Path, result::Result::Ok};
+use ts_rs::TS;
+
+/// The codecs a video can be transcoded to. hlssink2 muxes the segments into MPEG-TS, which
+/// can't carry VP9 or AV1, those would need fMP4 segments.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum VideoCodec {
+    #[default]
+    H264,
+    Hevc,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum AudioCodec {
+    #[default]
+    Aac,
+    Mp3,
+    Ac3,
+}
+
+/// How the video encoder spends bits, written as `{ "bitrate": 4000 }` or `{ "crf": 23 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub enum RateControl {
+    // kbps, renditions of the ladder with a higher bitrate are capped to it
+    Bitrate(u32),
+    // Constant quality, lower is better
+    Crf(u32),
+}
+
+/// A named set of encoder settings used when a video is transcoded. Remuxed and directly
+/// played videos keep their original streams.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct TranscodingProfile {
+    pub name: String,
+    #[serde(default)]
+    pub video_codec: VideoCodec,
+    // Without a rate control every rendition is encoded with the bitrate of the ladder
+    #[serde(default)]
+    pub rate_control: Option<RateControl>,
+    // Speed preset of the encoder, e.g. `superfast` for x264 and x265
+    #[serde(default)]
+    pub preset: Option<String>,
+    // Renditions above this height are dropped from the ladder
+    #[serde(default)]
+    pub max_height: Option<u32>,
+    #[serde(default)]
+    pub audio_codec: AudioCodec,
+    // kbps
+    #[serde(default = "default_audio_bitrate")]
+    pub audio_bitrate: u32,
+    // The channels of the source are kept when missing
+    #[serde(default)]
+    pub audio_channels: Option<u32>,
+}
+
+fn default_audio_bitrate() -> u32 {
+    128
+}
+
+impl Default for TranscodingProfile {
+    fn default() -> Self {
+        Self {
+            name: "default".to_string(),
+            video_codec: VideoCodec::H264,
+            rate_control: None,
+            preset: Some("superfast".to_string()),
+            max_height: None,
+            audio_codec: AudioCodec::Aac,
+            audio_bitrate: default_audio_bitrate(),
+            audio_channels: None,
+        }
+    }
+}
+
+impl TranscodingProfile {
+    pub fn default_profiles() -> Vec<Self> {
+        vec![
+            Self::default(),
+            Self {
+                name: "data_saver".to_string(),
+                rate_control: Some(RateControl::Bitrate(1500)),
+                max_height: Some(480),
+                audio_bitrate: 96,
+                audio_channels: Some(2),
+                ..Self::default()
+            },
+        ]
+    }
+
+    /// Reads the profiles from a JSON file holding a list of them, the first one is used
+    /// when a client doesn't pick a profile.
+    pub fn load(path: &Path) -> Result<Vec<Self>> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read transcoding profiles {:?}: {}", path, e))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Vec<Self>> {
+        let profiles: Vec<Self> = serde_json::from_str(content)
+            .map_err(|e| anyhow!("Invalid transcoding profiles: {}", e))?;
+
+        if profiles.is_empty() {
+            return Err(anyhow!("No transcoding profile is configured"));
+        }
+        let mut names = HashSet::new();
+        for profile in &profiles {
+            if !names.insert(profile.name.as_str()) {
+                return Err(anyhow!("Duplicate transcoding profile {}", profile.name));
+            }
+            profile.validate()?;
+        }
+
+        Ok(profiles)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.audio_bitrate == 0 {
+            return Err(anyhow!(
+                "The audio bitrate of profile {} is zero",
+                self.name
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Bitrate of a rendition encoded with this profile in kbps, `None` for constant quality.
+    pub fn video_bitrate(&self, rendition_bitrate: u32) -> Option<u32> {
+        match self.rate_control {
+            None => Some(rendition_bitrate),
+            Some(RateControl::Bitrate(bitrate)) => Some(bitrate.min(rendition_bitrate)),
+            Some(RateControl::Crf(_)) => None,
+        }
+    }
+
+    /// The height renditions are limited to, the lower of the source and the profile.
+    pub fn limit_height(&self, source_height: Option<u32>) -> Option<u32> {
+        match (source_height, self.max_height) {
+            (Some(source_height), Some(max_height)) => Some(source_height.min(max_height)),
+            (source_height, max_height) => source_height.or(max_height),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profiles() {
+        let profiles = TranscodingProfile::parse(
+            r#"[
+                { "name": "hevc", "video_codec": "hevc", "rate_control": { "crf": 26 }, "preset": "fast" },
+                { "name": "mobile", "rate_control": { "bitrate": 1000 }, "max_height": 360, "audio_channels": 2 }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(profiles[0].video_codec, VideoCodec::Hevc);
+        assert_eq!(profiles[0].rate_control, Some(RateControl::Crf(26)));
+        assert_eq!(profiles[0].audio_codec, AudioCodec::Aac);
+        assert_eq!(profiles[0].audio_bitrate, 128);
+        assert_eq!(profiles[1].video_codec, VideoCodec::H264);
+        assert_eq!(profiles[1].max_height, Some(360));
+    }
+
+    #[test]
+    fn test_parse_invalid_profiles() {
+        assert!(TranscodingProfile::parse("[]").is_err());
+        assert!(TranscodingProfile::parse(r#"[{ "name": "a" }, { "name": "a" }]"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_profiles_rejects_codecs_without_hls_support() {
+        for codec in ["vp9", "av1"] {
+            let result = TranscodingProfile::parse(&format!(
+                r#"[{{ "name": "web", "video_codec": "{}" }}]"#,
+                codec
+            ));
+
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn test_video_bitrate() {
+        let mut profile = TranscodingProfile::default();
+        assert_eq!(profile.video_bitrate(4000), Some(4000));
+
+        profile.rate_control = Some(RateControl::Bitrate(1500));
+        assert_eq!(profile.video_bitrate(4000), Some(1500));
+        assert_eq!(profile.video_bitrate(1000), Some(1000));
+
+        profile.rate_control = Some(RateControl::Crf(23));
+        assert_eq!(profile.video_bitrate(4000), None);
+    }
+
+    #[test]
+    fn test_limit_height() {
+        let profile = TranscodingProfile {
+            max_height: Some(480),
+            ..Default::default()
+        };
+
+        assert_eq!(profile.limit_height(Some(1080)), Some(480));
+        assert_eq!(profile.limit_height(Some(360)), Some(360));
+        assert_eq!(profile.limit_height(None), Some(480));
+        assert_eq!(TranscodingProfile::default().limit_height(None), None);
+    }
+}
@@ -0,0 +1,206 @@
+use anyhow::*;
+use std::result::Result::Ok;
+
+use super::profile::TranscodingProfile;
+use crate::domain::media_info::model::AudioTrack;
+
+// Advertised bitrates of remuxed streams, the real ones aren't known without reading the file
+const SOURCE_VIDEO_BITRATE: u32 = 8000;
+const SOURCE_AUDIO_BITRATE: u32 = 128;
+
+/// One quality level of the adaptive HLS stream, written to a directory named after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendition {
+    pub name: String,
+    pub height: u32,
+    // kbps
+    pub video_bitrate: u32,
+}
+
+impl Rendition {
+    pub fn new(height: u32, video_bitrate: u32) -> Self {
+        Self {
+            name: format!("{}p", height),
+            height,
+            video_bitrate,
+        }
+    }
+
+    /// The untouched video stream of a remuxed session.
+    pub fn source(height: Option<u32>) -> Self {
+        Self {
+            name: "source".to_string(),
+            height: height.unwrap_or_default(),
+            video_bitrate: SOURCE_VIDEO_BITRATE,
+        }
+    }
+
+    /// Peak bits per second as advertised in the master playlist. `profile` is the one the
+    /// rendition is encoded with and `None` for remuxed streams, constant quality has no
+    /// target bitrate so the one of the ladder is advertised.
+    pub fn bandwidth(&self, profile: Option<&TranscodingProfile>) -> u64 {
+        let (video_bitrate, audio_bitrate) = match profile {
+            Some(profile) => (
+                profile
+                    .video_bitrate(self.video_bitrate)
+                    .unwrap_or(self.video_bitrate),
+                profile.audio_bitrate,
+            ),
+            None => (self.video_bitrate, SOURCE_AUDIO_BITRATE),
+        };
+
+        (video_bitrate + audio_bitrate) as u64 * 1000
+    }
+
+    pub fn default_ladder() -> Vec<Self> {
+        vec![
+            Self::new(1080, 8000),
+            Self::new(720, 4000),
+            Self::new(480, 1500),
+        ]
+    }
+
+    /// Parses a ladder like `1080:8000,720:4000`, each entry is the height and the
+    /// video bitrate in kbps.
+    pub fn parse_ladder(ladder: &str) -> Result<Vec<Self>> {
+        let renditions = ladder
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (height, bitrate) = entry
+                    .split_once(':')
+                    .ok_or_else(|| anyhow!("Invalid rendition {}, expected height:kbps", entry))?;
+                Ok(Self::new(height.trim().parse()?, bitrate.trim().parse()?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if renditions.is_empty() {
+            return Err(anyhow!("The rendition ladder is empty"));
+        }
+
+        Ok(renditions)
+    }
+
+    /// Drops the renditions above the source resolution since upscaling only costs
+    /// bandwidth, the smallest one is always kept.
+    pub fn fit_ladder(ladder: &[Self], source_height: Option<u32>) -> Vec<Self> {
+        let Some(source_height) = source_height else {
+            return ladder.to_vec();
+        };
+
+        let fitting: Vec<Self> = ladder
+            .iter()
+            .filter(|rendition| rendition.height <= source_height)
+            .cloned()
+            .collect();
+        if !fitting.is_empty() {
+            return fitting;
+        }
+
+        ladder
+            .iter()
+            .min_by_key(|rendition| rendition.height)
+            .cloned()
+            .into_iter()
+            .collect()
+    }
+}
+
+/// An audio track offered as alternate HLS audio, so the player can switch tracks while
+/// playing. The chosen track is muxed into the video renditions, every other track is
+/// written to a directory of its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioRendition {
+    pub track: AudioTrack,
+    pub muxed: bool,
+}
+
+impl AudioRendition {
+    pub fn name(&self) -> String {
+        format!("audio_{}", self.track.index)
+    }
+
+    /// One rendition per track, only transcoded sessions offer more than the chosen one
+    /// since the other tracks have to be encoded to AAC.
+    pub fn for_tracks(tracks: &[AudioTrack], selected: Option<u32>, alternates: bool) -> Vec<Self> {
+        let selected = selected
+            .filter(|index| tracks.iter().any(|track| track.index == *index))
+            .or_else(|| tracks.first().map(|track| track.index));
+
+        tracks
+            .iter()
+            .filter(|track| alternates || Some(track.index) == selected)
+            .map(|track| Self {
+                track: track.clone(),
+                muxed: Some(track.index) == selected,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ladder() {
+        assert_eq!(
+            Rendition::parse_ladder("1080:8000, 480:1500").unwrap(),
+            vec![Rendition::new(1080, 8000), Rendition::new(480, 1500)]
+        );
+        assert!(Rendition::parse_ladder("1080").is_err());
+        assert!(Rendition::parse_ladder("").is_err());
+    }
+
+    #[test]
+    fn test_bandwidth() {
+        let rendition = Rendition::new(1080, 8000);
+        let data_saver = &TranscodingProfile::default_profiles()[1];
+
+        assert_eq!(
+            rendition.bandwidth(Some(&TranscodingProfile::default())),
+            8_128_000
+        );
+        assert_eq!(rendition.bandwidth(Some(data_saver)), 1_596_000);
+        assert_eq!(Rendition::source(Some(1080)).bandwidth(None), 8_128_000);
+    }
+
+    #[test]
+    fn test_fit_ladder() {
+        let ladder = Rendition::default_ladder();
+
+        assert_eq!(Rendition::fit_ladder(&ladder, Some(720)).len(), 2);
+        assert_eq!(Rendition::fit_ladder(&ladder, None).len(), 3);
+        assert_eq!(
+            Rendition::fit_ladder(&ladder, Some(360)),
+            vec![Rendition::new(480, 1500)]
+        );
+    }
+
+    #[test]
+    fn test_audio_renditions_for_tracks() {
+        let tracks: Vec<AudioTrack> = (0..3)
+            .map(|index| AudioTrack {
+                index,
+                ..Default::default()
+            })
+            .collect();
+
+        let renditions = AudioRendition::for_tracks(&tracks, Some(1), true);
+        assert_eq!(renditions.len(), 3);
+        assert_eq!(
+            renditions
+                .iter()
+                .filter(|rendition| rendition.muxed)
+                .map(AudioRendition::name)
+                .collect::<Vec<_>>(),
+            vec!["audio_1"]
+        );
+
+        let renditions = AudioRendition::for_tracks(&tracks, Some(7), false);
+        assert_eq!(renditions.len(), 1);
+        assert_eq!(renditions[0].track.index, 0);
+        assert!(renditions[0].muxed);
+    }
+}
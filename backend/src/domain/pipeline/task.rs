@@ -1,8 +1,8 @@
-use actix::Addr;
 use ambassador::Delegate;
 use anyhow::Result;
 use async_trait::async_trait;
 use std::sync::Arc;
+use tokio::fs;
 use tracing::*;
 
 use crate::{
@@ -10,13 +10,13 @@ use crate::{
     domain::{
         pipeline::event::PipelineEvent,
         task::async_task::{
-            ambassador_impl_TaskIdentifiable, AsyncTask, TaskId, TaskIdentifiable, TaskIdentifier,
+            ambassador_impl_TaskIdentifiable, AsyncTask, TaskContext, TaskId, TaskIdentifiable,
+            TaskIdentifier,
         },
     },
     infrastructure::{
         event_dispatcher::{domain_event::DomainEvent, event_bus::EventBus},
-        file::finder_options::{all_files, FinderOptions},
-        hls::hls_state_actor::HlsStateActor,
+        hls::session::{PlaybackSessions, HLS_OUTPUT_DIR},
     },
 };
 
@@ -27,26 +27,26 @@ pub struct PipelinePreparationTask {
     file_service: Arc<FileService>,
     #[allow(dead_code)]
     event_bus: Arc<EventBus>,
-    #[allow(dead_code)]
-    hls_state_actor_addr: Addr<HlsStateActor>,
+    sessions: PlaybackSessions,
 }
 
 #[async_trait]
 impl AsyncTask for PipelinePreparationTask {
-    async fn execute(&self, _identifier: TaskIdentifier, event_bus: Arc<EventBus>) -> Result<()> {
+    async fn execute(
+        &self,
+        _identifier: TaskIdentifier,
+        event_bus: Arc<EventBus>,
+        _context: TaskContext,
+    ) -> Result<()> {
         debug!("Preparing pipeline");
 
         event_bus
             .publish(DomainEvent::Pipeline(PipelineEvent::PreparationStarted))
             .inspect_err(|e| error!("Failed to publish preparation started event: {}", e))?;
 
-        let options = FinderOptions::new()
-            .filters(all_files())
-            .include_hidden(true);
-        self.file_service
-            .delete_files_in_folder("./tmp", options)
+        self.remove_stale_sessions()
             .await
-            .inspect_err(|e| error!("Failed to delete files in tmp folder: {}", e))?;
+            .inspect_err(|e| error!("Failed to remove stale sessions: {}", e))?;
 
         event_bus
             .publish(DomainEvent::Pipeline(PipelineEvent::PreparationFinished))
@@ -60,13 +60,36 @@ impl PipelinePreparationTask {
     pub fn new(
         file_service: Arc<FileService>,
         event_bus: Arc<EventBus>,
-        hls_state_actor_addr: Addr<HlsStateActor>,
+        sessions: PlaybackSessions,
     ) -> Self {
         Self {
             identifier: TaskIdentifier::default(),
             file_service,
             event_bus,
-            hls_state_actor_addr,
+            sessions,
         }
     }
+
+    // Sessions which were never stopped, e.g. because the server was killed, would
+    // otherwise keep their segments on disk forever
+    async fn remove_stale_sessions(&self) -> Result<()> {
+        let mut entries = match fs::read_dir(HLS_OUTPUT_DIR).await {
+            Ok(entries) => entries,
+            Err(_) => return Ok(()),
+        };
+
+        while let Some(entry) = entries.next_entry().await? {
+            let session_id = entry.file_name().to_string_lossy().to_string();
+            if !entry.file_type().await?.is_dir() || self.sessions.contains(&session_id).await {
+                continue;
+            }
+
+            debug!("Removing output of stale session {}", session_id);
+            self.file_service
+                .delete_folder(&entry.path().to_string_lossy())
+                .await?;
+        }
+
+        Ok(())
+    }
 }
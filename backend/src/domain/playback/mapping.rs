@@ -0,0 +1,15 @@
+use sqlx::{sqlite::SqliteRow, Row};
+
+use super::model::PlaybackProgress;
+
+/// Reads the `progress_` prefixed columns which queries get by left joining
+/// `playback_progress`, returns `None` for episodes which were never played.
+pub fn progress_from_row(row: &SqliteRow) -> Option<PlaybackProgress> {
+    Some(PlaybackProgress {
+        episode_id: row.get::<Option<i64>, _>("progress_episode_id")?,
+        position: row.get::<i64, _>("progress_position"),
+        duration: row.get::<Option<i64>, _>("progress_duration"),
+        completed: row.get::<bool, _>("progress_completed"),
+        updated_at: row.get::<Option<String>, _>("progress_updated_at"),
+    })
+}
@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::domain::media_info::model::MediaInfo;
+
+// Codecs which can be copied into MPEG-TS segments without re-encoding
+const REMUX_VIDEO_CODEC: &str = "h264";
+const REMUX_AUDIO_CODEC: &str = "aac";
+
+/// The codecs and containers a client can decode, names match the ones of `MediaInfo`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct ClientCapabilities {
+    #[serde(default)]
+    pub containers: Vec<String>,
+    #[serde(default)]
+    pub video_codecs: Vec<String>,
+    #[serde(default)]
+    pub audio_codecs: Vec<String>,
+}
+
+impl ClientCapabilities {
+    fn supports(supported: &[String], value: Option<&str>) -> bool {
+        value.is_some_and(|value| supported.iter().any(|s| s.eq_ignore_ascii_case(value)))
+    }
+
+    fn supports_container(&self, container: Option<&str>) -> bool {
+        Self::supports(&self.containers, container)
+    }
+
+    fn supports_video(&self, codec: Option<&str>) -> bool {
+        Self::supports(&self.video_codecs, codec)
+    }
+
+    fn supports_audio(&self, codec: Option<&str>) -> bool {
+        Self::supports(&self.audio_codecs, codec)
+    }
+}
+
+/// How a video reaches the client, ordered from the cheapest to the most expensive.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub enum PlaybackMethod {
+    // The file is served as is
+    DirectPlay,
+    // The streams are copied into HLS segments
+    Remux,
+    // The streams are decoded and encoded again
+    #[default]
+    Transcode,
+}
+
+impl PlaybackMethod {
+    /// Picks the cheapest method for the chosen audio track, `None` stands for the default one.
+    pub fn decide(
+        media_info: &MediaInfo,
+        capabilities: &ClientCapabilities,
+        audio_track: Option<u32>,
+    ) -> Self {
+        let video_codec = media_info.video_codec.as_deref();
+        let audio_codec = media_info
+            .audio_track(audio_track)
+            .and_then(|track| track.codec.as_deref());
+        // A file without audio has nothing the client would need to decode
+        let audio_supported = audio_codec.is_none() || capabilities.supports_audio(audio_codec);
+        // Players only play the first audio track of a file they load themselves
+        let default_track = audio_track.is_none_or(|index| {
+            media_info
+                .audio_tracks
+                .first()
+                .is_none_or(|track| track.index == index)
+        });
+
+        if default_track
+            && capabilities.supports_container(media_info.container.as_deref())
+            && capabilities.supports_video(video_codec)
+            && audio_supported
+        {
+            return Self::DirectPlay;
+        }
+
+        // hlssink2 always expects an audio stream, so silent files are transcoded
+        if video_codec == Some(REMUX_VIDEO_CODEC)
+            && audio_codec == Some(REMUX_AUDIO_CODEC)
+            && capabilities.supports_video(video_codec)
+            && capabilities.supports_audio(audio_codec)
+        {
+            return Self::Remux;
+        }
+
+        Self::Transcode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::media_info::model::AudioTrack;
+
+    fn media_info(container: &str, video_codec: &str, audio_codecs: &[&str]) -> MediaInfo {
+        MediaInfo {
+            container: Some(container.to_string()),
+            duration: Some(1440),
+            video_codec: Some(video_codec.to_string()),
+            audio_tracks: audio_codecs
+                .iter()
+                .enumerate()
+                .map(|(index, codec)| AudioTrack {
+                    index: index as u32,
+                    codec: Some(codec.to_string()),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn browser() -> ClientCapabilities {
+        ClientCapabilities {
+            containers: vec!["mp4".to_string(), "webm".to_string()],
+            video_codecs: vec!["h264".to_string(), "vp9".to_string()],
+            audio_codecs: vec!["aac".to_string(), "opus".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_decide_direct_play() {
+        assert_eq!(
+            PlaybackMethod::decide(&media_info("mp4", "h264", &["aac"]), &browser(), None),
+            PlaybackMethod::DirectPlay
+        );
+        assert_eq!(
+            PlaybackMethod::decide(&media_info("webm", "vp9", &[]), &browser(), None),
+            PlaybackMethod::DirectPlay
+        );
+    }
+
+    #[test]
+    fn test_decide_remux() {
+        assert_eq!(
+            PlaybackMethod::decide(
+                &media_info("mkv", "h264", &["aac", "ac3"]),
+                &browser(),
+                None
+            ),
+            PlaybackMethod::Remux
+        );
+    }
+
+    #[test]
+    fn test_decide_with_audio_track() {
+        let dual_audio = media_info("mp4", "h264", &["aac", "aac", "flac"]);
+
+        assert_eq!(
+            PlaybackMethod::decide(&dual_audio, &browser(), Some(0)),
+            PlaybackMethod::DirectPlay
+        );
+        assert_eq!(
+            PlaybackMethod::decide(&dual_audio, &browser(), Some(1)),
+            PlaybackMethod::Remux
+        );
+        assert_eq!(
+            PlaybackMethod::decide(&dual_audio, &browser(), Some(2)),
+            PlaybackMethod::Transcode
+        );
+    }
+
+    #[test]
+    fn test_decide_transcode() {
+        assert_eq!(
+            PlaybackMethod::decide(&media_info("mkv", "hevc", &["aac"]), &browser(), None),
+            PlaybackMethod::Transcode
+        );
+        assert_eq!(
+            PlaybackMethod::decide(&media_info("mkv", "h264", &["flac"]), &browser(), None),
+            PlaybackMethod::Transcode
+        );
+        assert_eq!(
+            PlaybackMethod::decide(
+                &media_info("mp4", "h264", &["aac"]),
+                &ClientCapabilities::default(),
+                None
+            ),
+            PlaybackMethod::Transcode
+        );
+    }
+}
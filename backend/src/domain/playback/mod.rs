@@ -0,0 +1,3 @@
+pub mod mapping;
+pub mod method;
+pub mod model;
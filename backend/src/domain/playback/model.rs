@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+// Credits usually make up the last minutes, so an episode counts as watched a bit before its end
+const COMPLETED_RATIO: f64 = 0.9;
+
+/// Where the viewer stopped watching an episode, positions are in seconds.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct PlaybackProgress {
+    pub episode_id: i64,
+    pub position: i64,
+    pub duration: Option<i64>,
+    pub completed: bool,
+    pub updated_at: Option<String>,
+}
+
+impl PlaybackProgress {
+    pub fn new(episode_id: i64, position: i64, duration: Option<i64>) -> Self {
+        let position = position.max(0);
+
+        Self {
+            episode_id,
+            position,
+            duration,
+            completed: is_completed(position, duration),
+            updated_at: None,
+        }
+    }
+}
+
+/// The episode or movie a playback is for, its file is looked up so clients never pass paths.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS)]
+pub enum PlayableMedia {
+    Episode(i64),
+    Movie(i64),
+}
+
+fn is_completed(position: i64, duration: Option<i64>) -> bool {
+    match duration {
+        Some(duration) if duration > 0 => position as f64 >= duration as f64 * COMPLETED_RATIO,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_marks_progress_near_the_end_as_completed() {
+        assert!(!PlaybackProgress::new(1, 1200, Some(1500)).completed);
+        assert!(PlaybackProgress::new(1, 1400, Some(1500)).completed);
+        assert!(!PlaybackProgress::new(1, 1400, None).completed);
+        assert_eq!(PlaybackProgress::new(1, -5, None).position, 0);
+    }
+}
@@ -0,0 +1,19 @@
+use sqlx::{sqlite::SqliteRow, Row};
+
+use super::model::{SearchResult, SearchResultKind};
+use crate::shared::util_traits::SqliteRowMapper;
+
+impl SqliteRowMapper<SearchResult> for SearchResult {
+    fn from_row(row: SqliteRow) -> Self {
+        SearchResult {
+            kind: SearchResultKind::parse(&row.get::<String, _>("kind"))
+                .unwrap_or(SearchResultKind::TvShow),
+            id: row.get::<i64, _>("id"),
+            media_id: row.get::<Option<i64>, _>("media_id"),
+            title: row.get::<String, _>("title"),
+            snippet: row.get::<String, _>("snippet"),
+            image: row.get::<Option<String>, _>("image"),
+            rank: row.get::<f64, _>("rank"),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub enum SearchResultKind {
+    TvShow,
+    Episode,
+    Movie,
+    Actor,
+    Genre,
+    Studio,
+}
+
+impl SearchResultKind {
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "TvShow" => Some(Self::TvShow),
+            "Episode" => Some(Self::Episode),
+            "Movie" => Some(Self::Movie),
+            "Actor" => Some(Self::Actor),
+            "Genre" => Some(Self::Genre),
+            "Studio" => Some(Self::Studio),
+            _ => None,
+        }
+    }
+}
+
+/// A match of a search, `media_id` is the tv show which a tv show or episode belongs to,
+/// or the movie itself.
+/// The matched terms of the snippet are wrapped in `<mark>` tags.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct SearchResult {
+    pub kind: SearchResultKind,
+    pub id: i64,
+    pub media_id: Option<i64>,
+    pub title: String,
+    pub snippet: String,
+    pub image: Option<String>,
+    pub rank: f64,
+}
+
+/// Turns what the user typed into an FTS5 query. Every word is quoted so FTS5 operators
+/// are matched literally, and the last one is a prefix since it may still be typed.
+/// Returns `None` when there is no word to search for.
+pub fn fts_query(input: &str) -> Option<String> {
+    let words = input
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+    let (last, rest) = words.split_last()?;
+
+    let mut terms = rest
+        .iter()
+        .map(|word| format!("\"{}\"", word))
+        .collect::<Vec<_>>();
+    terms.push(format!("\"{}\"*", last));
+
+    Some(terms.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(fts_query("breaking"), Some("\"breaking\"*".to_string()));
+        assert_eq!(
+            fts_query("  Breaking   ba"),
+            Some("\"Breaking\" \"ba\"*".to_string())
+        );
+        assert_eq!(
+            fts_query("\"NEAR(a b)\" OR c*"),
+            Some("\"NEAR\" \"a\" \"b\" \"OR\" \"c\"*".to_string())
+        );
+        assert_eq!(fts_query("Café"), Some("\"Café\"*".to_string()));
+        assert_eq!(fts_query(" -*\" "), None);
+    }
+}
@@ -0,0 +1,18 @@
+use sqlx::{sqlite::SqliteRow, Row};
+
+use super::model::{Subtitle, SubtitleFormat};
+use crate::shared::util_traits::SqliteRowMapper;
+
+impl SqliteRowMapper<Subtitle> for Subtitle {
+    fn from_row(row: SqliteRow) -> Self {
+        let format = row.get::<String, _>("format");
+
+        Subtitle {
+            language: row.get::<Option<String>, _>("language"),
+            title: row.get::<Option<String>, _>("title"),
+            format: SubtitleFormat::from_extension(&format).unwrap_or_default(),
+            file_path: row.get::<Option<String>, _>("file_path"),
+            stream_index: row.get::<Option<i64>, _>("stream_index"),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+// Tags which commonly follow the language in sidecar file names, e.g. movie.en.sdh.srt
+const NON_LANGUAGE_TAGS: [&str; 4] = ["sdh", "cc", "forced", "default"];
+
+static LANGUAGE_TAG_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([a-zA-Z]{2,3})(?:[-_]([a-zA-Z]{2}|[0-9]{3}))?$").unwrap());
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub enum SubtitleFormat {
+    #[default]
+    Srt,
+    Ass,
+    WebVtt,
+}
+
+impl SubtitleFormat {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "srt" => Some(Self::Srt),
+            "ass" | "ssa" => Some(Self::Ass),
+            "vtt" => Some(Self::WebVtt),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Ass => "ass",
+            Self::WebVtt => "vtt",
+        }
+    }
+}
+
+/// A subtitle of an episode or movie, either a sidecar file next to the video
+/// or a text stream embedded in the video container.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct Subtitle {
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub format: SubtitleFormat,
+    // Path of the sidecar file, e.g. Show - S01E01.en.srt
+    pub file_path: Option<String>,
+    // Position among the subtitle streams of the video file, only set for embedded subtitles
+    pub stream_index: Option<i64>,
+}
+
+impl Subtitle {
+    pub fn sidecar(file_path: String, format: SubtitleFormat, language: Option<String>) -> Self {
+        Self {
+            language,
+            title: None,
+            format,
+            file_path: Some(file_path),
+            stream_index: None,
+        }
+    }
+}
+
+/// Extracts the language tag from the part of a sidecar file name which follows the
+/// video name, e.g. `.en` or `.pt-BR.forced`. Returns `None` for untagged files.
+pub fn parse_subtitle_language(suffix: &str) -> Option<String> {
+    suffix
+        .split('.')
+        .skip(1)
+        .filter(|tag| !NON_LANGUAGE_TAGS.contains(&tag.to_lowercase().as_str()))
+        .find_map(|tag| {
+            let caps = LANGUAGE_TAG_PATTERN.captures(tag)?;
+            let language = caps.get(1)?.as_str().to_lowercase();
+
+            Some(match caps.get(2) {
+                Some(region) => format!("{}-{}", language, region.as_str().to_uppercase()),
+                None => language,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_subtitle_language() {
+        assert_eq!(parse_subtitle_language(".en"), Some("en".to_string()));
+        assert_eq!(parse_subtitle_language(".ENG"), Some("eng".to_string()));
+        assert_eq!(
+            parse_subtitle_language(".pt-br.forced"),
+            Some("pt-BR".to_string())
+        );
+        assert_eq!(parse_subtitle_language(".sdh.de"), Some("de".to_string()));
+        assert_eq!(
+            parse_subtitle_language(" - Pilot.fr"),
+            Some("fr".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_subtitle_language_without_tag() {
+        assert_eq!(parse_subtitle_language(""), None);
+        assert_eq!(parse_subtitle_language(" - Pilot"), None);
+        assert_eq!(parse_subtitle_language(".forced"), None);
+    }
+}
@@ -2,8 +2,12 @@ use ambassador::delegatable_trait;
 use anyhow::*;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::{fmt::Display, sync::Arc, time::Duration};
-use tokio::{sync::Mutex, task::JoinHandle};
+use std::{fmt::Display, result::Result::Ok, sync::Arc, time::Duration};
+use tokio::{
+    sync::{mpsc, Mutex},
+    task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
 use ts_rs::TS;
 use uuid::Uuid;
 
@@ -12,16 +16,49 @@ use crate::{
     interfaces::ws::notification::ToJsonPayload,
 };
 
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub enum TaskType {
     MediaLibraryScan,
+    MediaLibraryRescan,
     PipelinePreparation,
+    ThumbnailExtraction,
+    TrickplayGeneration,
     /// Test task used only in unit tests
     TestTask,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl TaskType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MediaLibraryScan => "MediaLibraryScan",
+            Self::MediaLibraryRescan => "MediaLibraryRescan",
+            Self::PipelinePreparation => "PipelinePreparation",
+            Self::ThumbnailExtraction => "ThumbnailExtraction",
+            Self::TrickplayGeneration => "TrickplayGeneration",
+            Self::TestTask => "TestTask",
+        }
+    }
+}
+
+impl TryFrom<&str> for TaskType {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(match value {
+            "MediaLibraryScan" => Self::MediaLibraryScan,
+            "MediaLibraryRescan" => Self::MediaLibraryRescan,
+            "PipelinePreparation" => Self::PipelinePreparation,
+            "ThumbnailExtraction" => Self::ThumbnailExtraction,
+            "TrickplayGeneration" => Self::TrickplayGeneration,
+            "TestTask" => Self::TestTask,
+            _ => return Err(format!("Invalid task type {}", value)),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
 pub enum TaskStatus {
     Queued,
     Processing,
@@ -30,6 +67,38 @@ pub enum TaskStatus {
     Cancelled,
 }
 
+impl TaskStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "Queued",
+            Self::Processing => "Processing",
+            Self::Completed => "Completed",
+            Self::Failed => "Failed",
+            Self::Cancelled => "Cancelled",
+        }
+    }
+
+    /// Whether the task won't run anymore.
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+impl TryFrom<&str> for TaskStatus {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(match value {
+            "Queued" => Self::Queued,
+            "Processing" => Self::Processing,
+            "Completed" => Self::Completed,
+            "Failed" => Self::Failed,
+            "Cancelled" => Self::Cancelled,
+            _ => return Err(format!("Invalid task status {}", value)),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, TS)]
 #[ts(export)]
 #[ts(type = "string")]
@@ -53,6 +122,19 @@ pub struct AsyncTaskProgress {
     pub error: Option<String>,
 }
 
+/// A finished task as it is kept in the task history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TaskRecord {
+    pub task_id: TaskId,
+    pub task_type: TaskType,
+    pub status: TaskStatus,
+    /// Why the task failed
+    pub error: Option<String>,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AsyncTaskEvent {
     Started {
@@ -62,12 +144,10 @@ pub enum AsyncTaskEvent {
         identifier: TaskIdentifier,
         progress: f32,
     },
-    Completed {
-        identifier: TaskIdentifier,
-    },
-    Error {
+    /// The task completed, failed or was cancelled
+    Finished {
         identifier: TaskIdentifier,
-        error: String,
+        record: TaskRecord,
     },
 }
 
@@ -80,18 +160,78 @@ impl ToJsonPayload for AsyncTaskEvent {
 #[derive(Debug, Clone)]
 pub struct AsyncTaskInfo {
     pub _id: TaskId,
-    pub _task_type: TaskType,
+    pub task_type: TaskType,
     pub status: TaskStatus,
     pub progress: f32,
-    /// used to notify the client
-    pub _websocket_client_key: String,
+    /// used to notify the client, `None` when it has no WebSocket connection
+    pub _websocket_client_key: Option<String>,
+    /// The user who started the task, `None` for tasks which only admins may see
+    pub owner_id: Option<i64>,
     pub cleanup_handle: Option<Arc<Mutex<Option<JoinHandle<()>>>>>,
     pub retention_period: Duration,
+    pub cancellation_token: CancellationToken,
+    /// When the task started processing, formatted like the timestamps of the database
+    pub started_at: Option<String>,
+}
+
+/// Handed to a running task to report its progress and to learn that it was cancelled.
+/// Cancellation is cooperative, a task only stops where it checks the token.
+#[derive(Clone)]
+pub struct TaskContext {
+    cancellation_token: CancellationToken,
+    progress_tx: mpsc::UnboundedSender<f32>,
+}
+
+impl TaskContext {
+    pub fn new(
+        cancellation_token: CancellationToken,
+        progress_tx: mpsc::UnboundedSender<f32>,
+    ) -> Self {
+        Self {
+            cancellation_token,
+            progress_tx,
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation_token.is_cancelled()
+    }
+
+    /// Resolves once the task is cancelled, e.g. to stop waiting in a `select!`.
+    pub async fn cancelled(&self) {
+        self.cancellation_token.cancelled().await
+    }
+
+    /// Fails when the task was cancelled, so a task can stop with `?`.
+    pub fn ensure_not_cancelled(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(anyhow!("Task was cancelled"));
+        }
+        Ok(())
+    }
+
+    /// Reports the progress in percent, it is clamped to 0 to 100.
+    pub fn report_progress(&self, progress: f32) {
+        // The pool stops listening once the task finished
+        let _ = self.progress_tx.send(progress.clamp(0.0, 100.0));
+    }
+
+    /// Reports the progress of `done` out of `total` steps.
+    pub fn report_steps(&self, done: usize, total: usize) {
+        if total > 0 {
+            self.report_progress(done as f32 / total as f32 * 100.0);
+        }
+    }
 }
 
 #[async_trait]
 pub trait AsyncTask: Send + Sync + TaskIdentifiable {
-    async fn execute(&self, identifier: TaskIdentifier, event_bus: Arc<EventBus>) -> Result<()>;
+    async fn execute(
+        &self,
+        identifier: TaskIdentifier,
+        event_bus: Arc<EventBus>,
+        context: TaskContext,
+    ) -> Result<()>;
 }
 
 #[delegatable_trait]
@@ -159,3 +299,42 @@ impl TaskIdentifiable for TaskIdentifier {
         self.ws_client_id.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_task_context() {
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+        let cancellation_token = CancellationToken::new();
+        let context = TaskContext::new(cancellation_token.clone(), progress_tx);
+
+        context.report_steps(1, 4);
+        context.report_progress(120.0);
+        context.report_steps(1, 0);
+        assert_eq!(progress_rx.recv().await, Some(25.0));
+        assert_eq!(progress_rx.recv().await, Some(100.0));
+        assert!(progress_rx.try_recv().is_err());
+
+        assert!(context.ensure_not_cancelled().is_ok());
+        cancellation_token.cancel();
+        assert!(context.is_cancelled());
+        assert!(context.ensure_not_cancelled().is_err());
+    }
+
+    #[test]
+    fn test_task_names_round_trip() {
+        assert_eq!(
+            TaskType::try_from(TaskType::MediaLibraryRescan.as_str()),
+            Ok(TaskType::MediaLibraryRescan)
+        );
+        assert_eq!(
+            TaskStatus::try_from(TaskStatus::Cancelled.as_str()),
+            Ok(TaskStatus::Cancelled)
+        );
+        assert!(TaskStatus::try_from("Paused").is_err());
+        assert!(TaskStatus::Cancelled.is_finished());
+        assert!(!TaskStatus::Processing.is_finished());
+    }
+}
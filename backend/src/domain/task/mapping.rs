@@ -0,0 +1,18 @@
+use sqlx::{sqlite::SqliteRow, Row};
+use uuid::Uuid;
+
+use super::async_task::{TaskId, TaskRecord, TaskStatus, TaskType};
+use crate::shared::util_traits::SqliteRowMapper;
+
+impl SqliteRowMapper<TaskRecord> for TaskRecord {
+    fn from_row(row: SqliteRow) -> Self {
+        TaskRecord {
+            task_id: TaskId(Uuid::parse_str(row.get::<&str, _>("task_id")).unwrap()),
+            task_type: TaskType::try_from(row.get::<&str, _>("task_type")).unwrap(),
+            status: TaskStatus::try_from(row.get::<&str, _>("status")).unwrap(),
+            error: row.get::<Option<String>, _>("error"),
+            started_at: row.get::<Option<String>, _>("started_at"),
+            finished_at: row.get::<Option<String>, _>("finished_at"),
+        }
+    }
+}
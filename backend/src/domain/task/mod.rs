@@ -1 +1,2 @@
 pub mod async_task;
+pub mod mapping;
@@ -0,0 +1,142 @@
+use actix::Addr;
+use ambassador::Delegate;
+use anyhow::*;
+use async_trait::async_trait;
+use std::{path::PathBuf, result::Result::Ok, sync::Arc, time::Duration};
+use tracing::*;
+
+use crate::{
+    domain::task::async_task::{
+        ambassador_impl_TaskIdentifiable, AsyncTask, TaskContext, TaskId, TaskIdentifiable,
+        TaskIdentifier,
+    },
+    infrastructure::{
+        artwork::store::{artwork_url, ArtworkStore},
+        event_dispatcher::event_bus::EventBus,
+        media_db::{
+            actor::{QueryEpisodesWithoutThumbnail, SaveEpisodeThumbnail},
+            database::Database,
+        },
+        video_pipeline::snapshot::extract_snapshot,
+    },
+};
+
+const THUMBNAIL_WIDTH: u32 = 960;
+// Skips the opening credits of most episodes without getting close to the end
+const THUMBNAIL_POSITION_RATIO: u64 = 10;
+
+/// Generates a thumbnail for every episode of a library which has no thumb image, one
+/// episode at a time with a pause in between so playback is not slowed down.
+#[derive(Delegate)]
+#[delegate(TaskIdentifiable, target = "identifier")]
+pub struct ThumbnailExtractionTask {
+    identifier: TaskIdentifier,
+    library_id: i64,
+    interval: Duration,
+    database_addr: Arc<Addr<Database>>,
+    artwork_store: ArtworkStore,
+}
+
+#[async_trait]
+impl AsyncTask for ThumbnailExtractionTask {
+    async fn execute(
+        &self,
+        _identifier: TaskIdentifier,
+        _event_bus: Arc<EventBus>,
+        context: TaskContext,
+    ) -> Result<()> {
+        let episodes = self
+            .database_addr
+            .send(QueryEpisodesWithoutThumbnail {
+                library_id: self.library_id,
+            })
+            .await
+            .map_err(|_| anyhow!("Failed to send query episodes without thumbnail message"))?;
+        debug!(
+            "Extracting {} thumbnails of library {}",
+            episodes.len(),
+            self.library_id
+        );
+
+        let total = episodes.len();
+        let mut extracted = 0;
+        for (index, episode) in episodes.into_iter().enumerate() {
+            if index > 0 {
+                // Cancelling must not wait for the pause to end
+                tokio::select! {
+                    _ = tokio::time::sleep(self.interval) => {}
+                    _ = context.cancelled() => {}
+                }
+            }
+            context.ensure_not_cancelled()?;
+
+            let video_path = PathBuf::from(&episode.video_file_path);
+            let position = thumbnail_position(episode.duration);
+            let artwork_store = self.artwork_store.clone();
+            // Decoding the frame is blocking work
+            let hash = tokio::task::spawn_blocking(move || {
+                let snapshot = extract_snapshot(&video_path, position, THUMBNAIL_WIDTH)?;
+                artwork_store.import_data(&snapshot)
+            })
+            .await?;
+
+            match hash {
+                Ok(hash) => {
+                    self.database_addr
+                        .send(SaveEpisodeThumbnail {
+                            episode_id: episode.episode_id,
+                            thumb_image: artwork_url(&hash),
+                        })
+                        .await
+                        .map_err(|_| anyhow!("Failed to send save episode thumbnail message"))?;
+                    extracted += 1;
+                }
+                Err(e) => warn!(
+                    "Failed to extract thumbnail of {}: {:?}",
+                    episode.video_file_path, e
+                ),
+            }
+            context.report_steps(index + 1, total);
+        }
+        info!(
+            "Extracted {} thumbnails of library {}",
+            extracted, self.library_id
+        );
+
+        Ok(())
+    }
+}
+
+impl ThumbnailExtractionTask {
+    pub fn new(
+        library_id: i64,
+        interval: Duration,
+        database_addr: Arc<Addr<Database>>,
+        artwork_store: ArtworkStore,
+    ) -> Self {
+        Self {
+            identifier: TaskIdentifier::default(),
+            library_id,
+            interval,
+            database_addr,
+            artwork_store,
+        }
+    }
+}
+
+/// Where the snapshot is taken, the start of the video when its duration is unknown since
+/// seeking past the end fails.
+fn thumbnail_position(duration: Option<u64>) -> Duration {
+    Duration::from_secs(duration.unwrap_or(0) / THUMBNAIL_POSITION_RATIO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbnail_position() {
+        assert_eq!(thumbnail_position(Some(1440)), Duration::from_secs(144));
+        assert_eq!(thumbnail_position(None), Duration::ZERO);
+    }
+}
@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use crate::infrastructure::hls::webvtt::{render_webvtt, SubtitleCue};
+
+pub const TRICKPLAY_INDEX: &str = "index.vtt";
+
+/// How the frames of a video are tiled onto the sprite sheets of its trickplay, every sheet
+/// holds `columns * rows` frames taken `interval_seconds` apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrickplayLayout {
+    pub interval_seconds: u64,
+    pub tile_width: u32,
+    pub columns: u32,
+    pub rows: u32,
+}
+
+impl Default for TrickplayLayout {
+    fn default() -> Self {
+        Self {
+            interval_seconds: 10,
+            tile_width: 320,
+            columns: 10,
+            rows: 10,
+        }
+    }
+}
+
+impl TrickplayLayout {
+    pub fn frames_per_sheet(&self) -> u32 {
+        self.columns * self.rows
+    }
+
+    /// The sheet of the frame and the pixel offset of its tile on the sheet.
+    pub fn tile_position(&self, frame: u32, tile_height: u32) -> (u32, u32, u32) {
+        let tile = frame % self.frames_per_sheet();
+
+        (
+            frame / self.frames_per_sheet(),
+            tile % self.columns * self.tile_width,
+            tile / self.columns * tile_height,
+        )
+    }
+
+    /// The WebVTT thumbnail track, every cue points at the tile of its frame with a
+    /// `#xywh=` media fragment so players can show it while scrubbing.
+    pub fn index(&self, frame_count: u32, tile_height: u32) -> String {
+        let interval = Duration::from_secs(self.interval_seconds);
+        let cues = (0..frame_count)
+            .map(|frame| {
+                let (sheet, x, y) = self.tile_position(frame, tile_height);
+                SubtitleCue {
+                    start: interval * frame,
+                    end: interval * (frame + 1),
+                    text: format!(
+                        "{}#xywh={},{},{},{}",
+                        sheet_name(sheet),
+                        x,
+                        y,
+                        self.tile_width,
+                        tile_height
+                    ),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        render_webvtt(&cues)
+    }
+}
+
+pub fn sheet_name(sheet: u32) -> String {
+    format!("sprite_{}.jpg", sheet)
+}
+
+/// Whether the name is one of the files of a trickplay, anything else must not be served.
+pub fn is_trickplay_file(name: &str) -> bool {
+    name == TRICKPLAY_INDEX
+        || name
+            .strip_prefix("sprite_")
+            .and_then(|name| name.strip_suffix(".jpg"))
+            .is_some_and(|sheet| !sheet.is_empty() && sheet.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// The encoded sprite sheets of a video and the index which points into them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trickplay {
+    pub sheets: Vec<Vec<u8>>,
+    pub index: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tile_position() {
+        let layout = TrickplayLayout {
+            columns: 3,
+            rows: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(layout.tile_position(0, 180), (0, 0, 0));
+        assert_eq!(layout.tile_position(4, 180), (0, 320, 180));
+        assert_eq!(layout.tile_position(6, 180), (1, 0, 0));
+    }
+
+    #[test]
+    fn test_index() {
+        let layout = TrickplayLayout {
+            columns: 2,
+            rows: 1,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            layout.index(3, 180),
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:10.000\nsprite_0.jpg#xywh=0,0,320,180\n\n\
+             00:00:10.000 --> 00:00:20.000\nsprite_0.jpg#xywh=320,0,320,180\n\n\
+             00:00:20.000 --> 00:00:30.000\nsprite_1.jpg#xywh=0,0,320,180\n\n"
+        );
+    }
+
+    #[test]
+    fn test_is_trickplay_file() {
+        assert!(is_trickplay_file("index.vtt"));
+        assert!(is_trickplay_file("sprite_12.jpg"));
+        assert!(!is_trickplay_file("sprite_.jpg"));
+        assert!(!is_trickplay_file("../sprite_1.jpg"));
+        assert!(!is_trickplay_file("sprite_1.jpg/../../x"));
+    }
+}
@@ -0,0 +1,116 @@
+use actix::Addr;
+use ambassador::Delegate;
+use anyhow::*;
+use async_trait::async_trait;
+use std::{path::PathBuf, result::Result::Ok, sync::Arc, time::Duration};
+use tracing::*;
+
+use super::model::TrickplayLayout;
+use crate::{
+    domain::task::async_task::{
+        ambassador_impl_TaskIdentifiable, AsyncTask, TaskContext, TaskId, TaskIdentifiable,
+        TaskIdentifier,
+    },
+    infrastructure::{
+        event_dispatcher::event_bus::EventBus,
+        media_db::{actor::QueryLibraryEpisodeVideos, database::Database},
+        trickplay::store::TrickplayStore,
+        video_pipeline::trickplay::generate_trickplay,
+    },
+};
+
+/// Generates the trickplay of every episode of a library which has none yet. Each video is
+/// decoded from start to end, so episodes are processed one at a time with a pause in between.
+#[derive(Delegate)]
+#[delegate(TaskIdentifiable, target = "identifier")]
+pub struct TrickplayGenerationTask {
+    identifier: TaskIdentifier,
+    library_id: i64,
+    interval: Duration,
+    layout: TrickplayLayout,
+    database_addr: Arc<Addr<Database>>,
+    trickplay_store: TrickplayStore,
+}
+
+#[async_trait]
+impl AsyncTask for TrickplayGenerationTask {
+    async fn execute(
+        &self,
+        _identifier: TaskIdentifier,
+        _event_bus: Arc<EventBus>,
+        context: TaskContext,
+    ) -> Result<()> {
+        let episodes = self
+            .database_addr
+            .send(QueryLibraryEpisodeVideos {
+                library_id: self.library_id,
+            })
+            .await
+            .map_err(|_| anyhow!("Failed to send query library episode videos message"))?;
+        let episodes = episodes
+            .into_iter()
+            .filter(|episode| !self.trickplay_store.exists(episode.episode_id))
+            .collect::<Vec<_>>();
+        debug!(
+            "Generating trickplay of {} episodes of library {}",
+            episodes.len(),
+            self.library_id
+        );
+
+        let total = episodes.len();
+        let mut generated = 0;
+        for (index, episode) in episodes.into_iter().enumerate() {
+            if index > 0 {
+                // Cancelling must not wait for the pause to end
+                tokio::select! {
+                    _ = tokio::time::sleep(self.interval) => {}
+                    _ = context.cancelled() => {}
+                }
+            }
+            context.ensure_not_cancelled()?;
+
+            let video_path = PathBuf::from(&episode.video_file_path);
+            let layout = self.layout;
+            let trickplay_store = self.trickplay_store.clone();
+            // Decoding the whole video is blocking work
+            let result = tokio::task::spawn_blocking(move || {
+                let trickplay = generate_trickplay(&video_path, layout)?;
+                trickplay_store.save(episode.episode_id, &trickplay)
+            })
+            .await?;
+
+            match result {
+                Ok(()) => generated += 1,
+                Err(e) => warn!(
+                    "Failed to generate trickplay of {}: {:?}",
+                    episode.video_file_path, e
+                ),
+            }
+            context.report_steps(index + 1, total);
+        }
+        info!(
+            "Generated trickplay of {} episodes of library {}",
+            generated, self.library_id
+        );
+
+        Ok(())
+    }
+}
+
+impl TrickplayGenerationTask {
+    pub fn new(
+        library_id: i64,
+        interval: Duration,
+        database_addr: Arc<Addr<Database>>,
+        trickplay_store: TrickplayStore,
+    ) -> Self {
+        Self {
+            identifier: TaskIdentifier::default(),
+            library_id,
+            interval,
+            layout: TrickplayLayout::default(),
+            database_addr,
+            trickplay_store,
+        }
+    }
+}
@@ -18,6 +18,7 @@ pub struct TvShow {
     pub country: Option<String>,
     pub premiered: Option<String>,
     pub rating: Option<f32>,
+    pub certification: Option<String>,
     pub runtime: Option<String>,
     pub actors: Vec<MediaActor>,
     pub tmdb_id: Option<String>,
@@ -30,4 +31,6 @@ pub struct TvShow {
     pub poster_path: Option<String>,
     pub fanart_path: Option<String>,
     pub seasons: HashMap<u8, Season>,
+    // Newest modification time of the series nfo files and artwork, unix timestamp in seconds
+    pub metadata_modified_at: Option<i64>,
 }
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::model::User;
+
+/// What a request may see. Admins see everything, other users only the libraries they were
+/// granted and, when they are capped, only media rated at most their certification. Media
+/// without a known certification is hidden from capped users.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, TS)]
+pub struct AccessScope {
+    /// The user whose granted libraries are visible, `None` when every library is
+    pub user_id: Option<i64>,
+    pub max_certification_level: Option<i64>,
+}
+
+impl AccessScope {
+    /// The scope of background work and admins.
+    pub fn unrestricted() -> Self {
+        Self::default()
+    }
+
+    pub fn for_user(user: &User) -> Self {
+        if user.is_admin {
+            return Self::unrestricted();
+        }
+
+        Self {
+            user_id: Some(user.id),
+            max_certification_level: user
+                .max_certification
+                .as_deref()
+                .and_then(certification_level),
+        }
+    }
+
+    pub fn can_see_library(&self, library_id: i64, granted_library_ids: &[i64]) -> bool {
+        self.user_id.is_none() || granted_library_ids.contains(&library_id)
+    }
+}
+
+/// The minimum age of a certification as NFO files write it, e.g. `TV-14`, `Rated PG-13`,
+/// `US:R` or `DE:FSK 16`. Only the first of several certifications separated by `/` is
+/// used, `None` when it is unknown or the media is not rated.
+pub fn certification_level(certification: &str) -> Option<i64> {
+    let certification = certification.split('/').next()?;
+    // Kodi prefixes the country, e.g. "US:TV-14"
+    let certification = certification.rsplit(':').next()?.trim().to_uppercase();
+    let certification = certification
+        .strip_prefix("RATED ")
+        .unwrap_or(&certification)
+        .trim();
+
+    match certification {
+        "G" | "TV-Y" | "TV-G" | "U" => Some(0),
+        "TV-Y7" | "TV-Y7-FV" => Some(7),
+        "PG" | "TV-PG" => Some(10),
+        "PG-13" => Some(13),
+        "TV-14" => Some(14),
+        "R" | "TV-MA" => Some(17),
+        "NC-17" | "X" => Some(18),
+        "NR" | "UR" | "NOT RATED" | "UNRATED" => None,
+        // Most other countries rate by age, e.g. "FSK 12", "15" or "12A"
+        _ => certification
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|digits| !digits.is_empty())
+            .and_then(|digits| digits.parse::<i64>().ok())
+            .filter(|age| *age <= 21),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(is_admin: bool, max_certification: Option<&str>) -> User {
+        User {
+            id: 7,
+            username: "kid".to_string(),
+            is_admin,
+            max_certification: max_certification.map(str::to_string),
+            created_at: None,
+        }
+    }
+
+    #[test]
+    fn test_certification_level() {
+        assert_eq!(certification_level("TV-14"), Some(14));
+        assert_eq!(certification_level("Rated PG-13"), Some(13));
+        assert_eq!(certification_level("US:TV-MA"), Some(17));
+        assert_eq!(certification_level("us:tv-y7"), Some(7));
+        assert_eq!(certification_level("DE:FSK 16"), Some(16));
+        assert_eq!(certification_level("UK:12A / US:PG-13"), Some(12));
+        assert_eq!(certification_level("Not Rated"), None);
+        assert_eq!(certification_level(""), None);
+    }
+
+    #[test]
+    fn test_access_scope_for_user() {
+        assert_eq!(
+            AccessScope::for_user(&user(true, Some("TV-14"))),
+            AccessScope::unrestricted()
+        );
+
+        let scope = AccessScope::for_user(&user(false, Some("TV-14")));
+        assert_eq!(scope.user_id, Some(7));
+        assert_eq!(scope.max_certification_level, Some(14));
+        assert!(scope.can_see_library(1, &[1, 2]));
+        assert!(!scope.can_see_library(3, &[1, 2]));
+        assert!(AccessScope::unrestricted().can_see_library(3, &[]));
+    }
+}
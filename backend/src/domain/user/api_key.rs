@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::model::User;
+
+/// What a request authenticated with an API key may do. Each scope includes the ones
+/// before it, and none of them grants more than the user who owns the key may do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyScope {
+    /// Only reading libraries, media and artwork
+    ReadOnly,
+    /// Reading and playing media, including saving the watch progress
+    Playback,
+    /// Everything the user may do, e.g. creating and scanning libraries
+    Admin,
+}
+
+impl ApiKeyScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ReadOnly => "read_only",
+            Self::Playback => "playback",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// The scope a key needs for a request, decided by its method and path.
+    pub fn required_for(method: &str, path: &str) -> Self {
+        if path.starts_with("/users") {
+            return Self::Admin;
+        }
+        if path.starts_with("/video-player") || path.starts_with("/hls") {
+            return Self::Playback;
+        }
+
+        match method {
+            "GET" | "HEAD" => Self::ReadOnly,
+            _ => Self::Admin,
+        }
+    }
+
+    pub fn allows(&self, required: ApiKeyScope) -> bool {
+        *self >= required
+    }
+}
+
+impl TryFrom<&str> for ApiKeyScope {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Ok(match value {
+            "read_only" => Self::ReadOnly,
+            "playback" => Self::Playback,
+            "admin" => Self::Admin,
+            _ => return Err(format!("Invalid API key scope {}", value)),
+        })
+    }
+}
+
+/// An API key as it is listed, the key itself is only shown once when it is created.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct ApiKey {
+    pub id: i64,
+    pub name: String,
+    pub scope: ApiKeyScope,
+    pub created_at: Option<String>,
+    pub last_used_at: Option<String>,
+}
+
+/// The owner and the scope of a valid API key.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+pub struct ApiKeyGrant {
+    pub api_key_id: i64,
+    pub scope: ApiKeyScope,
+    pub user: User,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(
+            ApiKeyScope::required_for("GET", "/library/1/media"),
+            ApiKeyScope::ReadOnly
+        );
+        assert_eq!(
+            ApiKeyScope::required_for("POST", "/library/1/rescan"),
+            ApiKeyScope::Admin
+        );
+        assert_eq!(
+            ApiKeyScope::required_for("POST", "/video-player/play"),
+            ApiKeyScope::Playback
+        );
+        assert_eq!(
+            ApiKeyScope::required_for("GET", "/hls/abc/master.m3u8"),
+            ApiKeyScope::Playback
+        );
+        assert_eq!(
+            ApiKeyScope::required_for("GET", "/users/"),
+            ApiKeyScope::Admin
+        );
+    }
+
+    #[test]
+    fn test_scope_allows() {
+        assert!(ApiKeyScope::Admin.allows(ApiKeyScope::Playback));
+        assert!(ApiKeyScope::Playback.allows(ApiKeyScope::ReadOnly));
+        assert!(!ApiKeyScope::ReadOnly.allows(ApiKeyScope::Playback));
+        assert!(!ApiKeyScope::Playback.allows(ApiKeyScope::Admin));
+    }
+
+    #[test]
+    fn test_scope_round_trip() {
+        for scope in [
+            ApiKeyScope::ReadOnly,
+            ApiKeyScope::Playback,
+            ApiKeyScope::Admin,
+        ] {
+            assert_eq!(ApiKeyScope::try_from(scope.as_str()), Ok(scope));
+        }
+        assert!(ApiKeyScope::try_from("root").is_err());
+    }
+}
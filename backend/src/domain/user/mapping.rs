@@ -0,0 +1,50 @@
+use sqlx::{sqlite::SqliteRow, Row};
+
+use super::{
+    api_key::{ApiKey, ApiKeyGrant, ApiKeyScope},
+    model::{User, UserCredentials},
+};
+use crate::shared::util_traits::SqliteRowMapper;
+
+impl SqliteRowMapper<User> for User {
+    fn from_row(row: SqliteRow) -> Self {
+        User {
+            id: row.get::<i64, _>("id"),
+            username: row.get::<String, _>("username"),
+            is_admin: row.get::<bool, _>("is_admin"),
+            max_certification: row.get::<Option<String>, _>("max_certification"),
+            created_at: row.get::<Option<String>, _>("created_at"),
+        }
+    }
+}
+
+impl SqliteRowMapper<UserCredentials> for UserCredentials {
+    fn from_row(row: SqliteRow) -> Self {
+        UserCredentials {
+            password_hash: row.get::<String, _>("password_hash"),
+            user: User::from_row(row),
+        }
+    }
+}
+
+impl SqliteRowMapper<ApiKey> for ApiKey {
+    fn from_row(row: SqliteRow) -> Self {
+        ApiKey {
+            id: row.get::<i64, _>("id"),
+            name: row.get::<String, _>("name"),
+            scope: ApiKeyScope::try_from(row.get::<&str, _>("scope")).unwrap(),
+            created_at: row.get::<Option<String>, _>("created_at"),
+            last_used_at: row.get::<Option<String>, _>("last_used_at"),
+        }
+    }
+}
+
+impl SqliteRowMapper<ApiKeyGrant> for ApiKeyGrant {
+    fn from_row(row: SqliteRow) -> Self {
+        ApiKeyGrant {
+            api_key_id: row.get::<i64, _>("api_key_id"),
+            scope: ApiKeyScope::try_from(row.get::<&str, _>("scope")).unwrap(),
+            user: User::from_row(row),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod access;
+pub mod api_key;
+pub mod mapping;
+pub mod model;
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    pub is_admin: bool,
+    /// The highest certification the user may watch, e.g. `TV-14`, uncapped when `None`
+    pub max_certification: Option<String>,
+    pub created_at: Option<String>,
+}
+
+/// A user with the argon2 hash of their password, only read to check a login.
+#[derive(Clone, Deserialize, Serialize, TS)]
+pub struct UserCredentials {
+    pub user: User,
+    pub password_hash: String,
+}
+
+// The hash must never end up in the logs
+impl Debug for UserCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserCredentials")
+            .field("user", &self.user)
+            .finish_non_exhaustive()
+    }
+}
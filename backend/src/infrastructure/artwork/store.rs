@@ -0,0 +1,300 @@
+use anyhow::*;
+use image::{imageops::FilterType, ImageFormat};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::*;
+use uuid::Uuid;
+
+const ARTWORK_URL_PREFIX: &str = "/artwork";
+const SUPPORTED_FORMATS: [ImageFormat; 4] = [
+    ImageFormat::Jpeg,
+    ImageFormat::Png,
+    ImageFormat::WebP,
+    ImageFormat::Gif,
+];
+// Variants are only created in these widths, so clients can't fill the cache with one
+// variant per requested pixel
+const VARIANT_WIDTHS: [u32; 8] = [150, 300, 384, 600, 768, 1280, 1920, 3840];
+
+/// An image file of the store together with the format it was detected as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artwork {
+    pub path: PathBuf,
+    pub format: ImageFormat,
+}
+
+impl Artwork {
+    pub fn mime_type(&self) -> &'static str {
+        self.format.to_mime_type()
+    }
+}
+
+/// Keeps a copy of every poster, fanart and thumb keyed by the sha256 of its content,
+/// so identical images are only stored once and a url never changes its content.
+/// Resized variants are cached next to the originals once requested.
+#[derive(Debug, Clone)]
+pub struct ArtworkStore {
+    root: PathBuf,
+}
+
+impl ArtworkStore {
+    #[instrument]
+    pub fn new(root: PathBuf) -> Result<Self> {
+        fs::create_dir_all(root.join("cache"))
+            .with_context(|| format!("Failed to create artwork directory {:?}", root))?;
+
+        Ok(Self { root })
+    }
+
+    /// Copies the image into the store unless it is already there and returns its hash.
+    /// The format is detected from the content since the extension is not always right.
+    #[instrument(skip(self))]
+    pub fn import(&self, source: &Path) -> Result<String> {
+        let data =
+            fs::read(source).with_context(|| format!("Failed to read image {:?}", source))?;
+        let format = detect_format(&data, source)?;
+
+        self.store(&data, format)
+    }
+
+    /// Stores an image which was created in memory, e.g. a snapshot of a video.
+    #[instrument(skip(self, data))]
+    pub fn import_data(&self, data: &[u8]) -> Result<String> {
+        let format = image::guess_format(data)
+            .ok()
+            .filter(|format| SUPPORTED_FORMATS.contains(format))
+            .ok_or_else(|| anyhow!("Unsupported image data"))?;
+
+        self.store(data, format)
+    }
+
+    #[instrument(skip(self))]
+    pub fn find(&self, hash: &str) -> Option<Artwork> {
+        if !is_valid_hash(hash) {
+            return None;
+        }
+
+        SUPPORTED_FORMATS.iter().find_map(|format| {
+            let path = self.original_path(hash, *format);
+            path.exists().then_some(Artwork {
+                path,
+                format: *format,
+            })
+        })
+    }
+
+    /// Returns the artwork scaled down to the variant width of the given width, the variant
+    /// is created on the first request and served from the cache afterwards. Images which
+    /// are already narrower are returned as they are.
+    #[instrument(skip(self))]
+    pub fn find_variant(&self, hash: &str, width: u32) -> Result<Option<Artwork>> {
+        let width = variant_width(width)?;
+
+        let original = match self.find(hash) {
+            Some(original) => original,
+            None => return Ok(None),
+        };
+
+        let path = self.variant_path(hash, width, original.format);
+        if path.exists() {
+            return Ok(Some(Artwork {
+                path,
+                format: original.format,
+            }));
+        }
+
+        let image = image::open(&original.path)
+            .with_context(|| format!("Failed to decode artwork {}", hash))?;
+        if image.width() <= width {
+            return Ok(Some(original));
+        }
+
+        debug!("Creating {}px variant of artwork {}", width, hash);
+        let resized = image.resize(width, u32::MAX, FilterType::Lanczos3);
+        let mut data = std::io::Cursor::new(Vec::new());
+        resized
+            .write_to(&mut data, original.format)
+            .with_context(|| format!("Failed to encode artwork variant {}", hash))?;
+        write_atomically(&path, data.get_ref())?;
+
+        Ok(Some(Artwork {
+            path,
+            format: original.format,
+        }))
+    }
+
+    fn store(&self, data: &[u8], format: ImageFormat) -> Result<String> {
+        let hash = format!("{:x}", Sha256::digest(data));
+
+        let target = self.original_path(&hash, format);
+        if !target.exists() {
+            trace!("Storing artwork as {:?}", target);
+            write_atomically(&target, data)?;
+        }
+
+        Ok(hash)
+    }
+
+    fn original_path(&self, hash: &str, format: ImageFormat) -> PathBuf {
+        self.root
+            .join(format!("{}.{}", hash, format.extensions_str()[0]))
+    }
+
+    fn variant_path(&self, hash: &str, width: u32, format: ImageFormat) -> PathBuf {
+        self.root.join("cache").join(format!(
+            "{}_w{}.{}",
+            hash,
+            width,
+            format.extensions_str()[0]
+        ))
+    }
+}
+
+/// The width of the variant which is served for a requested width, the narrowest one which
+/// is at least as wide. Zero and widths beyond the widest variant are invalid.
+pub fn variant_width(width: u32) -> Result<u32> {
+    VARIANT_WIDTHS
+        .into_iter()
+        .find(|variant_width| width > 0 && *variant_width >= width)
+        .ok_or_else(|| anyhow!("Invalid artwork width: {}", width))
+}
+
+pub fn artwork_url(hash: &str) -> String {
+    format!("{}/{}", ARTWORK_URL_PREFIX, hash)
+}
+
+fn detect_format(data: &[u8], source: &Path) -> Result<ImageFormat> {
+    let format = image::guess_format(data)
+        .ok()
+        .or_else(|| ImageFormat::from_path(source).ok())
+        .ok_or_else(|| anyhow!("Unknown image format: {:?}", source))?;
+
+    if !SUPPORTED_FORMATS.contains(&format) {
+        return Err(anyhow!(
+            "Unsupported image format {:?}: {:?}",
+            format,
+            source
+        ));
+    }
+
+    Ok(format)
+}
+
+fn is_valid_hash(hash: &str) -> bool {
+    hash.len() == 64 && hash.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+}
+
+// Readers never see a partially written image since the rename is atomic, the temp
+// file is unique because parallel scans may store the same image at the same time
+fn write_atomically(path: &Path, data: &[u8]) -> Result<()> {
+    let temp_path = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+    fs::write(&temp_path, data).with_context(|| format!("Failed to write {:?}", temp_path))?;
+    fs::rename(&temp_path, path).with_context(|| format!("Failed to write {:?}", path))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageBuffer, Rgb};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn create_png(dir: &Path, name: &str, width: u32, height: u32) -> PathBuf {
+        let path = dir.join(name);
+        ImageBuffer::from_pixel(width, height, Rgb([200u8, 30, 30]))
+            .save_with_format(&path, ImageFormat::Png)
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn test_import_deduplicates_by_content() {
+        let source_dir = TempDir::new().unwrap();
+        let store_dir = TempDir::new().unwrap();
+        let store = ArtworkStore::new(store_dir.path().to_path_buf()).unwrap();
+
+        let poster = create_png(source_dir.path(), "poster.png", 8, 8);
+        let copy = source_dir.path().join("fanart.png");
+        fs::copy(&poster, &copy).unwrap();
+
+        let hash = store.import(&poster).unwrap();
+
+        assert_eq!(store.import(&copy).unwrap(), hash);
+        assert_eq!(artwork_url(&hash), format!("/artwork/{}", hash));
+        assert_eq!(store.find(&hash).unwrap().mime_type(), "image/png");
+    }
+
+    #[test]
+    fn test_import_detects_real_format() {
+        let source_dir = TempDir::new().unwrap();
+        let store_dir = TempDir::new().unwrap();
+        let store = ArtworkStore::new(store_dir.path().to_path_buf()).unwrap();
+
+        // A png which was saved with a jpg extension
+        let png = create_png(source_dir.path(), "poster.png", 8, 8);
+        let poster = source_dir.path().join("poster.jpg");
+        fs::rename(&png, &poster).unwrap();
+
+        let hash = store.import(&poster).unwrap();
+
+        assert_eq!(store.find(&hash).unwrap().format, ImageFormat::Png);
+    }
+
+    #[test]
+    fn test_import_data() {
+        let source_dir = TempDir::new().unwrap();
+        let store_dir = TempDir::new().unwrap();
+        let store = ArtworkStore::new(store_dir.path().to_path_buf()).unwrap();
+
+        let thumb = create_png(source_dir.path(), "thumb.png", 8, 8);
+        let hash = store.import_data(&fs::read(&thumb).unwrap()).unwrap();
+
+        assert_eq!(store.import(&thumb).unwrap(), hash);
+        assert!(store.import_data(b"not an image").is_err());
+    }
+
+    #[test]
+    fn test_find_rejects_invalid_hash() {
+        let store_dir = TempDir::new().unwrap();
+        let store = ArtworkStore::new(store_dir.path().to_path_buf()).unwrap();
+
+        assert_eq!(store.find("../../etc/passwd"), None);
+        assert_eq!(store.find(&"a".repeat(64)), None);
+    }
+
+    #[test]
+    fn test_find_variant_resizes_and_caches() {
+        let source_dir = TempDir::new().unwrap();
+        let store_dir = TempDir::new().unwrap();
+        let store = ArtworkStore::new(store_dir.path().to_path_buf()).unwrap();
+
+        let poster = create_png(source_dir.path(), "poster.png", 600, 900);
+        let hash = store.import(&poster).unwrap();
+
+        let variant = store.find_variant(&hash, 300).unwrap().unwrap();
+        let image = image::open(&variant.path).unwrap();
+
+        assert_eq!((image.width(), image.height()), (300, 450));
+        assert_eq!(store.find_variant(&hash, 250).unwrap().unwrap(), variant);
+        assert_eq!(
+            store.find_variant(&hash, 1000).unwrap().unwrap(),
+            store.find(&hash).unwrap()
+        );
+        assert!(store.find_variant(&hash, 0).is_err());
+    }
+
+    #[test]
+    fn test_variant_width() {
+        assert_eq!(variant_width(1).unwrap(), 150);
+        assert_eq!(variant_width(300).unwrap(), 300);
+        assert_eq!(variant_width(301).unwrap(), 384);
+        assert_eq!(variant_width(3840).unwrap(), 3840);
+        assert!(variant_width(0).is_err());
+        assert!(variant_width(3841).is_err());
+    }
+}
@@ -1,2 +1,3 @@
 pub mod model;
+pub mod scheduler;
 pub mod task_pool;
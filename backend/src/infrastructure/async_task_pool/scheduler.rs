@@ -0,0 +1,82 @@
+use chrono::{DateTime, Local};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::{
+    sync::{mpsc, RwLock},
+    time::interval,
+};
+use tracing::*;
+
+use crate::domain::media_library::schedule::ScanSchedule;
+
+#[derive(Debug, Clone)]
+struct ScheduledScan {
+    schedule: ScanSchedule,
+    next_run: DateTime<Local>,
+}
+
+/// Keeps the scan schedule of every library in memory and emits the id of a library
+/// once its next run is due, the receiver decides how the scan is executed.
+#[derive(Clone)]
+pub struct ScanScheduler {
+    scans: Arc<RwLock<HashMap<i64, ScheduledScan>>>,
+}
+
+impl ScanScheduler {
+    #[instrument]
+    pub fn new(tick_interval: Duration) -> (Self, mpsc::UnboundedReceiver<i64>) {
+        let (due_tx, due_rx) = mpsc::unbounded_channel::<i64>();
+        let scans = Arc::new(RwLock::new(HashMap::<i64, ScheduledScan>::new()));
+        let scans_clone = scans.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = interval(tick_interval);
+
+            loop {
+                ticker.tick().await;
+
+                let now = Local::now();
+                let mut scans = scans_clone.write().await;
+                for (library_id, scan) in scans.iter_mut() {
+                    if scan.next_run > now {
+                        continue;
+                    }
+
+                    scan.next_run = scan.schedule.next_run_after(&now);
+                    debug!(
+                        "Scheduled scan of library {} is due, next run at {}",
+                        library_id, scan.next_run
+                    );
+                    if due_tx.send(*library_id).is_err() {
+                        debug!("Scan scheduler stopped");
+                        return;
+                    }
+                }
+            }
+        });
+
+        (Self { scans }, due_rx)
+    }
+
+    /// Schedules a library, the first run is computed from its last scan so that runs
+    /// missed while the server was down are caught up right away.
+    #[instrument(skip(self))]
+    pub async fn schedule(
+        &self,
+        library_id: i64,
+        schedule: ScanSchedule,
+        last_scanned: Option<DateTime<Local>>,
+    ) {
+        let next_run = schedule.next_run_after(&last_scanned.unwrap_or_else(Local::now));
+        debug!("Next scan of library {} at {}", library_id, next_run);
+
+        self.scans
+            .write()
+            .await
+            .insert(library_id, ScheduledScan { schedule, next_run });
+    }
+
+    #[instrument(skip(self))]
+    pub async fn unschedule(&self, library_id: i64) {
+        self.scans.write().await.remove(&library_id);
+    }
+}
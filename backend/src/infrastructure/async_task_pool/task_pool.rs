@@ -1,10 +1,12 @@
 use anyhow::*;
+use chrono::Utc;
 use futures::future;
 use std::result::Result::Ok;
 use std::time::Duration;
 use std::{collections::HashMap, sync::Arc};
 use tokio::sync::{mpsc, Mutex};
 use tokio::{sync::RwLock, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
 use tracing::*;
 use uuid::Uuid;
 
@@ -13,10 +15,16 @@ use crate::domain::task::async_task::ambassador_impl_TaskIdentifiable;
 use crate::domain::task::async_task::{AsyncTask, TaskId, TaskIdentifier};
 use crate::{
     domain::{
-        task::async_task::{AsyncTaskInfo, TaskIdentifiable, TaskStatus, TaskType},
+        task::async_task::{
+            AsyncTaskEvent, AsyncTaskInfo, TaskContext, TaskIdentifiable, TaskRecord, TaskStatus,
+            TaskType,
+        },
         time::TimeProvider,
     },
-    infrastructure::{event_dispatcher::event_bus::EventBus, time::default::DefaultTimeProvider},
+    infrastructure::{
+        event_dispatcher::{domain_event::DomainEvent, event_bus::EventBus},
+        time::default::DefaultTimeProvider,
+    },
 };
 
 #[derive(Clone)]
@@ -58,14 +66,9 @@ impl TaskPool {
 
             while let Some(task) = task_rx.recv().await {
                 let task_id = task.get_task_id();
-                let ws_client_id = match task.get_ws_client_id() {
-                    Some(ws_client_id) => ws_client_id,
-                    None => {
-                        error!("Task ws client id is not set");
-                        continue;
-                    }
-                };
-                let identifier = TaskIdentifier::new(task_id, Some(ws_client_id));
+                // Tasks of clients without a WebSocket connection run all the same, their
+                // state is polled instead
+                let identifier = TaskIdentifier::new(task_id, task.get_ws_client_id());
                 debug!("Task identifier: {}", identifier);
 
                 if handles.len() >= max_concurrent_tasks {
@@ -80,30 +83,37 @@ impl TaskPool {
                 let time_provider = time_provider_clone.clone();
 
                 handles.push(tokio::spawn(async move {
+                    let Some(cancellation_token) =
+                        TaskPool::start_task(&tasks, &identifier, &event_bus).await
+                    else {
+                        debug!("Task was cancelled before it started: {}", identifier);
+                        TaskPool::finish_task(
+                            &tasks,
+                            &identifier,
+                            Err(anyhow!("Task was cancelled")),
+                            &event_bus,
+                            time_provider,
+                        )
+                        .await;
+                        return;
+                    };
+
+                    let (progress_tx, progress_rx) = mpsc::unbounded_channel();
+                    TaskPool::forward_progress(
+                        tasks.clone(),
+                        identifier.clone(),
+                        event_bus.clone(),
+                        progress_rx,
+                    );
+
                     debug!("Executing task: {}", identifier);
-                    let result = task.execute(identifier.clone(), event_bus).await;
-
-                    let mut tasks_write = tasks.write().await;
-                    if let Some(task_info) = tasks_write.get_mut(&identifier.get_task_id()) {
-                        task_info.status = match result {
-                            Ok(_) => TaskStatus::Completed,
-                            Err(e) => {
-                                debug!("Task {:?} failed: {}", identifier.get_task_id(), e);
-                                TaskStatus::Failed
-                            }
-                        };
-                        task_info.progress = 100.0;
-                        debug!("Task completed: {}", identifier);
-
-                        debug!("Scheduling cleanup for task: {}", identifier);
-                        let cleanup_handle = TaskPool::schedule_cleanup(
-                            tasks.clone(),
-                            identifier.get_task_id(),
-                            task_info.retention_period,
-                            time_provider.clone(),
-                        );
-                        task_info.cleanup_handle = Some(Arc::new(Mutex::new(Some(cleanup_handle))));
-                    }
+                    let context = TaskContext::new(cancellation_token, progress_tx);
+                    let result = task
+                        .execute(identifier.clone(), event_bus.clone(), context)
+                        .await;
+
+                    TaskPool::finish_task(&tasks, &identifier, result, &event_bus, time_provider)
+                        .await;
                 }));
             }
         });
@@ -115,6 +125,112 @@ impl TaskPool {
         }
     }
 
+    /// Marks the task as processing and returns its cancellation token, `None` when it was
+    /// cancelled while it was queued.
+    async fn start_task(
+        tasks: &RwLock<HashMap<TaskId, AsyncTaskInfo>>,
+        identifier: &TaskIdentifier,
+        event_bus: &EventBus,
+    ) -> Option<CancellationToken> {
+        let mut tasks = tasks.write().await;
+        let task_info = tasks.get_mut(&identifier.get_task_id())?;
+        if task_info.cancellation_token.is_cancelled() {
+            return None;
+        }
+
+        task_info.status = TaskStatus::Processing;
+        task_info.started_at = Some(now_timestamp());
+        if let Err(e) = event_bus.publish(DomainEvent::AsyncTask(AsyncTaskEvent::Started {
+            identifier: identifier.clone(),
+        })) {
+            debug!("Failed to publish task started event: {}", e);
+        }
+
+        Some(task_info.cancellation_token.clone())
+    }
+
+    /// Updates the progress of the task until it finished, every update is published so
+    /// clients can follow the task.
+    fn forward_progress(
+        tasks: Arc<RwLock<HashMap<TaskId, AsyncTaskInfo>>>,
+        identifier: TaskIdentifier,
+        event_bus: Arc<EventBus>,
+        mut progress_rx: mpsc::UnboundedReceiver<f32>,
+    ) {
+        tokio::spawn(async move {
+            while let Some(progress) = progress_rx.recv().await {
+                match tasks.write().await.get_mut(&identifier.get_task_id()) {
+                    Some(task_info) if task_info.status == TaskStatus::Processing => {
+                        task_info.progress = progress;
+                    }
+                    _ => break,
+                }
+
+                if let Err(e) =
+                    event_bus.publish(DomainEvent::AsyncTask(AsyncTaskEvent::ProgressUpdated {
+                        identifier: identifier.clone(),
+                        progress,
+                    }))
+                {
+                    debug!("Failed to publish task progress: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Stores the outcome of the task, schedules its cleanup and publishes it for the task
+    /// history. A task which fails after it was cancelled counts as cancelled.
+    async fn finish_task(
+        tasks: &Arc<RwLock<HashMap<TaskId, AsyncTaskInfo>>>,
+        identifier: &TaskIdentifier,
+        result: Result<()>,
+        event_bus: &EventBus,
+        time_provider: Arc<dyn TimeProvider>,
+    ) {
+        let mut tasks_write = tasks.write().await;
+        let Some(task_info) = tasks_write.get_mut(&identifier.get_task_id()) else {
+            return;
+        };
+
+        let (status, error) = match result {
+            Ok(_) => (TaskStatus::Completed, None),
+            Err(_) if task_info.cancellation_token.is_cancelled() => (TaskStatus::Cancelled, None),
+            Err(e) => {
+                debug!("Task {:?} failed: {}", identifier.get_task_id(), e);
+                (TaskStatus::Failed, Some(e.to_string()))
+            }
+        };
+        if status == TaskStatus::Completed {
+            task_info.progress = 100.0;
+        }
+        task_info.status = status.clone();
+        debug!("Task finished with {:?}: {}", status, identifier);
+
+        debug!("Scheduling cleanup for task: {}", identifier);
+        let cleanup_handle = TaskPool::schedule_cleanup(
+            tasks.clone(),
+            identifier.get_task_id(),
+            task_info.retention_period,
+            time_provider,
+        );
+        task_info.cleanup_handle = Some(Arc::new(Mutex::new(Some(cleanup_handle))));
+
+        let record = TaskRecord {
+            task_id: identifier.get_task_id(),
+            task_type: task_info.task_type.clone(),
+            status,
+            error,
+            started_at: task_info.started_at.clone(),
+            finished_at: Some(now_timestamp()),
+        };
+        if let Err(e) = event_bus.publish(DomainEvent::AsyncTask(AsyncTaskEvent::Finished {
+            identifier: identifier.clone(),
+            record,
+        })) {
+            debug!("Failed to publish task finished event: {}", e);
+        }
+    }
+
     #[instrument(skip(tasks, time_provider))]
     fn schedule_cleanup(
         tasks: Arc<RwLock<HashMap<TaskId, AsyncTaskInfo>>>,
@@ -134,7 +250,8 @@ impl TaskPool {
     pub async fn register_task(
         &self,
         task_type: TaskType,
-        websocket_client_key: String,
+        websocket_client_key: Option<String>,
+        owner_id: Option<i64>,
         mut task: Box<dyn AsyncTask>,
         retention_period: Option<Duration>,
     ) -> Result<TaskId> {
@@ -145,12 +262,15 @@ impl TaskPool {
 
         let task_info = AsyncTaskInfo {
             _id: TaskId(task_id),
-            _task_type: task_type,
+            task_type,
             status: TaskStatus::Queued,
             progress: 0.0,
             _websocket_client_key: websocket_client_key,
+            owner_id,
             cleanup_handle: None,
             retention_period: retention_period.unwrap_or(Duration::from_secs(60 * 10)), // 10 minutes
+            cancellation_token: CancellationToken::new(),
+            started_at: None,
         };
 
         debug!("Inserting task info into tasks map");
@@ -246,6 +366,57 @@ impl TaskPool {
             .map(|t| t.progress)
             .ok_or(anyhow!("Task not found"))
     }
+
+    /// Asks the task to stop, a queued task never starts and a running one stops the next
+    /// time it checks its cancellation token.
+    #[instrument(skip(self))]
+    pub async fn cancel_task(&self, task_id: TaskId) -> Result<()> {
+        let mut tasks = self.tasks.write().await;
+        let task_info = tasks.get_mut(&task_id).ok_or(anyhow!("Task not found"))?;
+        if task_info.status.is_finished() {
+            return Err(anyhow!("Task already finished"));
+        }
+
+        debug!("Cancelling task: {:?}", task_id);
+        task_info.cancellation_token.cancel();
+        // A queued task may wait for a free slot for a long time
+        if task_info.status == TaskStatus::Queued {
+            task_info.status = TaskStatus::Cancelled;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_task_type(&self, task_id: TaskId) -> Result<TaskType> {
+        self.tasks
+            .read()
+            .await
+            .get(&task_id)
+            .map(|t| t.task_type.clone())
+            .ok_or(anyhow!("Task not found"))
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_task_owner(&self, task_id: TaskId) -> Result<Option<i64>> {
+        self.tasks
+            .read()
+            .await
+            .get(&task_id)
+            .map(|t| t.owner_id)
+            .ok_or(anyhow!("Task not found"))
+    }
+
+    /// The ids of the tasks which are queued, running or finished within their retention.
+    #[instrument(skip(self))]
+    pub async fn get_task_ids(&self) -> Vec<TaskId> {
+        self.tasks.read().await.keys().cloned().collect()
+    }
+}
+
+/// The current time in the format of the timestamps of the database.
+fn now_timestamp() -> String {
+    Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
 #[cfg(test)]
@@ -269,11 +440,11 @@ mod tests {
     }
 
     impl MockTask {
-        fn new(task_id: TaskId, ws_client_id: String, should_fail: bool) -> Self {
+        fn new(task_id: TaskId, ws_client_id: Option<String>, should_fail: bool) -> Self {
             let completed = Arc::new(Notify::new());
 
             Self {
-                base: TaskIdentifier::new(task_id, Some(ws_client_id)),
+                base: TaskIdentifier::new(task_id, ws_client_id),
                 should_fail,
                 completed,
             }
@@ -290,6 +461,7 @@ mod tests {
             &self,
             _identifier: TaskIdentifier,
             _event_bus: Arc<EventBus>,
+            _context: TaskContext,
         ) -> Result<()> {
             self.completed.notify_one();
             match self.should_fail {
@@ -299,15 +471,44 @@ mod tests {
         }
     }
 
+    /// Reports half of its progress and then waits until it is cancelled.
+    #[derive(Delegate)]
+    #[delegate(TaskIdentifiable, target = "base")]
+    struct CancellableTask {
+        base: TaskIdentifier,
+        halfway: Arc<Notify>,
+    }
+
+    #[async_trait]
+    impl AsyncTask for CancellableTask {
+        async fn execute(
+            &self,
+            _identifier: TaskIdentifier,
+            _event_bus: Arc<EventBus>,
+            context: TaskContext,
+        ) -> Result<()> {
+            context.report_progress(50.0);
+            self.halfway.notify_one();
+            context.cancelled().await;
+            context.ensure_not_cancelled()
+        }
+    }
+
     #[tokio::test]
     async fn test_register_task() {
         let event_bus = Arc::new(EventBus::new(16));
         let pool = TaskPool::new(5, event_bus);
 
         let task_id = TaskId(Uuid::new_v4());
-        let task = Box::new(MockTask::new(task_id, "client1".to_string(), false));
+        let task = Box::new(MockTask::new(task_id, Some("client1".to_string()), false));
         let task_id = pool
-            .register_task(TaskType::TestTask, "client1".to_string(), task, None)
+            .register_task(
+                TaskType::TestTask,
+                Some("client1".to_string()),
+                None,
+                task,
+                None,
+            )
             .await
             .unwrap();
 
@@ -320,10 +521,16 @@ mod tests {
         let pool = TaskPool::new(5, event_bus);
 
         let task_id = TaskId(Uuid::new_v4());
-        let task = Box::new(MockTask::new(task_id, "client1".to_string(), false));
+        let task = Box::new(MockTask::new(task_id, Some("client1".to_string()), false));
         let completed = task.get_completed();
         let task_id = pool
-            .register_task(TaskType::TestTask, "client1".to_string(), task, None)
+            .register_task(
+                TaskType::TestTask,
+                Some("client1".to_string()),
+                None,
+                task,
+                None,
+            )
             .await
             .unwrap();
 
@@ -339,10 +546,16 @@ mod tests {
         let pool = TaskPool::new(5, event_bus);
 
         let task_id = TaskId(Uuid::new_v4());
-        let task = Box::new(MockTask::new(task_id, "client1".to_string(), true));
+        let task = Box::new(MockTask::new(task_id, Some("client1".to_string()), true));
         let completed = task.get_completed();
         let task_id = pool
-            .register_task(TaskType::TestTask, "client1".to_string(), task, None)
+            .register_task(
+                TaskType::TestTask,
+                Some("client1".to_string()),
+                None,
+                task,
+                None,
+            )
             .await
             .unwrap();
 
@@ -352,6 +565,74 @@ mod tests {
         assert!(matches!(status, TaskStatus::Failed));
     }
 
+    #[tokio::test]
+    async fn test_task_without_ws_client() {
+        let event_bus = Arc::new(EventBus::new(16));
+        let pool = TaskPool::new(5, event_bus);
+
+        let task_id = TaskId(Uuid::new_v4());
+        let task = Box::new(MockTask::new(task_id, None, false));
+        let completed = task.get_completed();
+        let task_id = pool
+            .register_task(TaskType::TestTask, None, Some(7), task, None)
+            .await
+            .unwrap();
+
+        completed.notified().await;
+
+        let status = pool.get_task_status(task_id.clone()).await.unwrap();
+        assert!(matches!(status, TaskStatus::Completed));
+        assert!(matches!(
+            pool.get_task_type(task_id.clone()).await.unwrap(),
+            TaskType::TestTask
+        ));
+        assert_eq!(pool.get_task_owner(task_id.clone()).await.unwrap(), Some(7));
+        assert_eq!(pool.get_task_ids().await, vec![task_id]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_running_task() {
+        let event_bus = Arc::new(EventBus::new(16));
+        let pool = TaskPool::new(5, event_bus);
+
+        let halfway = Arc::new(Notify::new());
+        let task = Box::new(CancellableTask {
+            base: TaskIdentifier::default(),
+            halfway: halfway.clone(),
+        });
+        let task_id = pool
+            .register_task(TaskType::TestTask, None, None, task, None)
+            .await
+            .unwrap();
+
+        halfway.notified().await;
+        // The progress is forwarded by another task
+        while pool.get_task_progress(task_id.clone()).await.unwrap() < 50.0 {
+            tokio::task::yield_now().await;
+        }
+        assert!(matches!(
+            pool.get_task_status(task_id.clone()).await.unwrap(),
+            TaskStatus::Processing
+        ));
+
+        pool.cancel_task(task_id.clone()).await.unwrap();
+        while !pool
+            .get_task_status(task_id.clone())
+            .await
+            .unwrap()
+            .is_finished()
+        {
+            tokio::task::yield_now().await;
+        }
+
+        assert!(matches!(
+            pool.get_task_status(task_id.clone()).await.unwrap(),
+            TaskStatus::Cancelled
+        ));
+        assert_eq!(pool.get_task_progress(task_id.clone()).await.unwrap(), 50.0);
+        assert!(pool.cancel_task(task_id).await.is_err());
+    }
+
     #[tokio::test(start_paused = true)]
     async fn test_scheduled_cleanup() {
         let event_bus = Arc::new(EventBus::new(16));
@@ -359,12 +640,13 @@ mod tests {
         let pool = TaskPool::new_with_time_provider(5, event_bus, time_provider);
 
         let task_id = TaskId(Uuid::new_v4());
-        let task = Box::new(MockTask::new(task_id, "client1".to_string(), false));
+        let task = Box::new(MockTask::new(task_id, Some("client1".to_string()), false));
         let completed = task.get_completed();
         let _ = pool
             .register_task(
                 TaskType::TestTask,
-                "client1".to_string(),
+                Some("client1".to_string()),
+                None,
                 task,
                 Some(Duration::from_secs(60)),
             )
@@ -383,12 +665,13 @@ mod tests {
         let pool = TaskPool::new_with_time_provider(5, event_bus, time_provider);
 
         let task_id = TaskId(Uuid::new_v4());
-        let task = Box::new(MockTask::new(task_id, "client1".to_string(), false));
+        let task = Box::new(MockTask::new(task_id, Some("client1".to_string()), false));
         let completed = task.get_completed();
         let task_id = pool
             .register_task(
                 TaskType::TestTask,
-                "client1".to_string(),
+                Some("client1".to_string()),
+                None,
                 task,
                 Some(Duration::from_secs(60)),
             )
@@ -411,9 +694,15 @@ mod tests {
         let pool = TaskPool::new_with_time_provider(5, event_bus, time_provider);
 
         let task_id = TaskId(Uuid::new_v4());
-        let task = Box::new(MockTask::new(task_id, "client1".to_string(), false));
+        let task = Box::new(MockTask::new(task_id, Some("client1".to_string()), false));
         let task_id = pool
-            .register_task(TaskType::TestTask, "client1".to_string(), task, None)
+            .register_task(
+                TaskType::TestTask,
+                Some("client1".to_string()),
+                None,
+                task,
+                None,
+            )
             .await
             .unwrap();
 
@@ -0,0 +1,35 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+const API_KEY_PREFIX: &str = "cirno_";
+const API_KEY_BYTES: usize = 32;
+
+/// Creates a new random API key, the prefix makes leaked keys easy to recognize.
+pub fn generate_api_key() -> String {
+    let mut bytes = [0u8; API_KEY_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    format!("{}{}", API_KEY_PREFIX, URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// The hash the key is stored and looked up by. Keys are random enough that a fast hash
+/// is sufficient, unlike passwords.
+pub fn hash_api_key(key: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(key.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_and_hash_api_key() {
+        let key = generate_api_key();
+
+        assert!(key.starts_with(API_KEY_PREFIX));
+        assert_ne!(generate_api_key(), key);
+        assert_eq!(hash_api_key(&key), hash_api_key(&key));
+        assert_ne!(hash_api_key(&key), hash_api_key(&generate_api_key()));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod api_key;
+pub mod password;
+pub mod token;
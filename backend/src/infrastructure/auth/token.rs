@@ -0,0 +1,78 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const SESSION_ID_BYTES: usize = 32;
+
+/// Issues session tokens of the form `<session id>.<signature>`. The signature lets forged
+/// tokens be rejected without touching the database, the session id is still looked up so
+/// sessions can expire and be revoked on logout.
+#[derive(Clone)]
+pub struct SessionSigner {
+    key: Vec<u8>,
+}
+
+impl SessionSigner {
+    pub fn new(key: &[u8]) -> Self {
+        Self { key: key.to_vec() }
+    }
+
+    /// A signer with a random key, every token it issued is invalid after a restart.
+    pub fn random() -> Self {
+        let mut key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+
+        Self::new(&key)
+    }
+
+    /// Creates a new session id and the token which is handed out for it.
+    pub fn issue(&self) -> (String, String) {
+        let mut bytes = [0u8; SESSION_ID_BYTES];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let session_id = URL_SAFE_NO_PAD.encode(bytes);
+        let token = format!(
+            "{}.{}",
+            session_id,
+            URL_SAFE_NO_PAD.encode(self.mac(&session_id).finalize().into_bytes())
+        );
+
+        (session_id, token)
+    }
+
+    /// Returns the session id of the token when its signature is valid.
+    pub fn verify(&self, token: &str) -> Option<String> {
+        let (session_id, signature) = token.split_once('.')?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+
+        self.mac(session_id)
+            .verify_slice(&signature)
+            .ok()
+            .map(|_| session_id.to_string())
+    }
+
+    fn mac(&self, session_id: &str) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts any key size");
+        mac.update(session_id.as_bytes());
+        mac
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_issue_and_verify() {
+        let signer = SessionSigner::new(b"secret");
+        let (session_id, token) = signer.issue();
+
+        assert_eq!(signer.verify(&token), Some(session_id.clone()));
+        assert_ne!(signer.issue().0, session_id);
+        assert_eq!(SessionSigner::new(b"other").verify(&token), None);
+        assert_eq!(signer.verify(&format!("{}.AAAA", session_id)), None);
+        assert_eq!(signer.verify(&session_id), None);
+    }
+}
@@ -19,7 +19,6 @@ use crate::{
 #[derive(Debug, Clone, Serialize)]
 pub enum DomainEvent {
     General(GeneralEvent),
-    #[allow(dead_code)]
     AsyncTask(AsyncTaskEvent),
     Library(LibraryEventType),
     Pipeline(PipelineEvent),
@@ -34,12 +33,21 @@ impl IntoNotification for DomainEvent {
                 AsyncTaskEvent::ProgressUpdated { .. } => {
                     Notification::new(NotificationType::TaskProgressUpdated, event)
                 }
+                AsyncTaskEvent::Finished { .. } => {
+                    Notification::new(NotificationType::TaskFinished, event)
+                }
                 _ => unimplemented!(),
             },
             DomainEvent::Library(event) => match event {
                 LibraryEventType::LibrarySaved { .. } => {
                     Notification::new(NotificationType::LibrarySaved, event)
                 }
+                LibraryEventType::LibraryRescanSaved { .. } => {
+                    Notification::new(NotificationType::LibraryRescanSaved, event)
+                }
+                LibraryEventType::LibraryChanged { .. } => {
+                    Notification::new(NotificationType::LibraryChanged, event)
+                }
                 _ => unimplemented!(),
             },
             DomainEvent::Pipeline(event) => match event {
@@ -3,7 +3,8 @@
 /// ## Arguments
 ///
 /// * `$event_bus` - The event bus instance to register the handler with
-/// * `$event_type` - The pattern to match events against
+/// * `$event_type` - The pattern to match events against, optionally followed by an `if` guard
+///   which may use variables moved in from the surrounding scope
 /// * `$handler` - The handler function to execute when events match
 /// * `$config` - Optional event handler configuration (defaults to one_time)
 ///
@@ -21,6 +22,26 @@
 ///
 #[macro_export]
 macro_rules! listen_event {
+    ($event_bus:expr, $event_type:pat if $guard:expr, $handler:expr $(,)?) => {
+        $event_bus
+            .on(
+                move |event| matches!(event, $event_type if $guard),
+                $handler,
+                EventHandlerConfig::one_time(),
+            )
+            .await
+    };
+
+    ($event_bus:expr, $event_type:pat if $guard:expr, $handler:expr, $config:expr $(,)?) => {
+        $event_bus
+            .on(
+                move |event| matches!(event, $event_type if $guard),
+                $handler,
+                $config,
+            )
+            .await
+    };
+
     ($event_bus:expr, $event_type:pat, $handler:expr $(,)?) => {
         $event_bus
             .on(
@@ -14,6 +14,11 @@ impl FinderOptions {
         Self::default()
     }
 
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
     pub fn include_hidden(mut self, include_hidden: bool) -> Self {
         self.include_hidden = include_hidden;
         self
@@ -0,0 +1,96 @@
+use anyhow::*;
+use std::path::{Component, Path, PathBuf};
+
+/// A file of a playback session as requested below `/hls/{session_id}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HlsFile {
+    Segment { rendition: String, index: u32 },
+    Playlist(PathBuf),
+    Subtitle(PathBuf),
+}
+
+impl HlsFile {
+    /// Parses the path relative to the session directory, anything which could leave the
+    /// directory or isn't written by a session is rejected.
+    pub fn parse(relative_path: &str) -> Result<Self> {
+        let path = Path::new(relative_path);
+        if !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Err(anyhow!("Invalid hls file path: {}", relative_path));
+        }
+
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("Invalid hls file path: {}", relative_path))?;
+
+        if let Some(index) = file_name
+            .strip_prefix("segment_")
+            .and_then(|name| name.strip_suffix(".ts"))
+        {
+            let rendition = path
+                .parent()
+                .and_then(|parent| parent.to_str())
+                .filter(|parent| !parent.is_empty() && !parent.contains('/'))
+                .ok_or_else(|| anyhow!("Segment outside of a rendition: {}", relative_path))?;
+
+            return Ok(Self::Segment {
+                rendition: rendition.to_string(),
+                index: index.parse()?,
+            });
+        }
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("m3u8") => Ok(Self::Playlist(path.to_path_buf())),
+            Some("vtt") => Ok(Self::Subtitle(path.to_path_buf())),
+            _ => Err(anyhow!("Unknown hls file: {}", relative_path)),
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Segment { .. } => "video/mp2t",
+            Self::Playlist(_) => "application/vnd.apple.mpegurl",
+            Self::Subtitle(_) => "text/vtt; charset=utf-8",
+        }
+    }
+
+    /// Playlists are rewritten while the session runs, segments and subtitles never change
+    /// once they are complete.
+    pub fn cache_control(&self) -> &'static str {
+        match self {
+            Self::Playlist(_) => "no-cache",
+            _ => "private, max-age=3600",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            HlsFile::parse("720p/segment_00012.ts").unwrap(),
+            HlsFile::Segment {
+                rendition: "720p".to_string(),
+                index: 12
+            }
+        );
+        assert_eq!(
+            HlsFile::parse("master.m3u8").unwrap(),
+            HlsFile::Playlist(PathBuf::from("master.m3u8"))
+        );
+        assert_eq!(
+            HlsFile::parse("subtitles_0.vtt").unwrap(),
+            HlsFile::Subtitle(PathBuf::from("subtitles_0.vtt"))
+        );
+        assert!(HlsFile::parse("../other/master.m3u8").is_err());
+        assert!(HlsFile::parse("/etc/passwd.m3u8").is_err());
+        assert!(HlsFile::parse("segment_00001.ts").is_err());
+        assert!(HlsFile::parse("720p/event.txt").is_err());
+    }
+}
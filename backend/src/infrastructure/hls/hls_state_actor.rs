@@ -1,19 +1,35 @@
 use super::stream::HlsStream;
+use super::subtitle::SubtitleRenditions;
+use crate::domain::pipeline::profile::TranscodingProfile;
+use crate::domain::pipeline::rendition::{AudioRendition, Rendition};
 use crate::infrastructure::event_dispatcher::event_bus::EventBus;
 use crate::infrastructure::video_pipeline::pipeline::Pipeline;
-use actix::{Actor, Addr, AsyncContext, Context, Handler, Message};
+use actix::{Actor, Addr, AsyncContext, Context, Handler, Message, MessageResponse};
 use anyhow::*;
-use std::collections::HashMap;
-use std::sync::atomic::AtomicU32;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+/// The HLS state of one playback session.
 pub struct HlsStateActor {
+    session_id: String,
     // path -> playlist stream
     streams: HashMap<String, HlsStream>,
     pipeline_addr: Option<Addr<Pipeline>>,
-    segment_index: AtomicU32,
+    // rendition directory -> index of the next segment
+    segment_indices: HashMap<String, u32>,
+    // rendition directory -> index of the segment hlssink2 is writing
+    writing_segments: HashMap<String, u32>,
+    // rendition directory -> indices of the segments which were closed by hlssink2
+    complete_segments: HashMap<String, HashSet<u32>>,
     pipeline_duration: Option<u64>,
     segment_duration: Option<u64>,
+    subtitle_renditions: Option<SubtitleRenditions>,
+    renditions: Vec<Rendition>,
+    audio_renditions: Vec<AudioRendition>,
+    // profile the renditions are encoded with, none when they are remuxed
+    profile: Option<TranscodingProfile>,
+    // names of the renditions whose playlist was written
+    ready_renditions: HashSet<String>,
 
     event_bus: Arc<EventBus>,
 }
@@ -23,35 +39,26 @@ impl Actor for HlsStateActor {
 }
 
 impl HlsStateActor {
-    pub fn new(event_bus: Arc<EventBus>) -> Self {
+    pub fn new(session_id: String, event_bus: Arc<EventBus>) -> Self {
         Self {
+            session_id,
             pipeline_addr: None,
             streams: HashMap::new(),
-            segment_index: AtomicU32::new(0),
+            segment_indices: HashMap::new(),
+            writing_segments: HashMap::new(),
+            complete_segments: HashMap::new(),
             pipeline_duration: None,
             segment_duration: None,
+            subtitle_renditions: None,
+            renditions: vec![],
+            audio_renditions: vec![],
+            profile: None,
+            ready_renditions: HashSet::new(),
             event_bus,
         }
     }
 }
 
-#[derive(Debug, Message)]
-#[rtype(result = "Result<()>")]
-pub struct Reset;
-
-impl Handler<Reset> for HlsStateActor {
-    type Result = Result<()>;
-
-    fn handle(&mut self, _: Reset, _: &mut Self::Context) -> Self::Result {
-        self.segment_index
-            .store(0, std::sync::atomic::Ordering::Relaxed);
-        self.pipeline_duration = None;
-        self.segment_duration = None;
-
-        Ok(())
-    }
-}
-
 #[derive(Debug, Message)]
 #[rtype(result = "Result<HlsStream>")]
 pub struct GetPlaylistStream(pub String); // path
@@ -65,6 +72,7 @@ impl Handler<GetPlaylistStream> for HlsStateActor {
             .entry(msg.0.clone())
             .or_insert_with(|| {
                 HlsStream::new(
+                    self.session_id.clone(),
                     msg.0.clone(),
                     context.address().clone(),
                     self.event_bus.clone(),
@@ -102,29 +110,111 @@ impl Handler<GetPipelineAddr> for HlsStateActor {
 
 #[derive(Debug, Message)]
 #[rtype(result = "Result<()>")]
-pub struct IncrementSegmentIndex;
+pub struct IncrementSegmentIndex(pub String); // rendition directory
 
 impl Handler<IncrementSegmentIndex> for HlsStateActor {
     type Result = Result<()>;
 
-    fn handle(&mut self, _: IncrementSegmentIndex, _: &mut Self::Context) -> Self::Result {
-        self.segment_index
-            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    fn handle(&mut self, msg: IncrementSegmentIndex, _: &mut Self::Context) -> Self::Result {
+        let index = self.segment_indices.entry(msg.0.clone()).or_insert(0);
+        let opened = *index;
+        *index += 1;
+
+        // hlssink2 closes a fragment before it opens the next one
+        let complete = self.complete_segments.entry(msg.0.clone()).or_default();
+        complete.remove(&opened);
+        if let Some(closed) = self.writing_segments.insert(msg.0, opened) {
+            complete.insert(closed);
+        }
+        Ok(())
+    }
+}
+
+/// Marks the segment which is written last as complete, there is no following fragment
+/// which would do that once the stream ended.
+#[derive(Debug, Message)]
+#[rtype(result = "()")]
+pub struct FinishSegments(pub String); // rendition directory
+
+impl Handler<FinishSegments> for HlsStateActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: FinishSegments, _: &mut Self::Context) -> Self::Result {
+        if let Some(closed) = self.writing_segments.remove(&msg.0) {
+            self.complete_segments
+                .entry(msg.0)
+                .or_default()
+                .insert(closed);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, MessageResponse)]
+pub enum SegmentState {
+    Complete,
+    Writing,
+    // The segment isn't being written, next_index is the one hlssink2 writes next
+    NotWritten { next_index: u32 },
+}
+
+#[derive(Debug, Message)]
+#[rtype(result = "SegmentState")]
+pub struct GetSegmentState(pub String, pub u32); // rendition directory, segment index
+
+impl Handler<GetSegmentState> for HlsStateActor {
+    type Result = SegmentState;
+
+    fn handle(&mut self, msg: GetSegmentState, _: &mut Self::Context) -> Self::Result {
+        let GetSegmentState(rendition_dir, index) = msg;
+
+        if self
+            .complete_segments
+            .get(&rendition_dir)
+            .is_some_and(|complete| complete.contains(&index))
+        {
+            return SegmentState::Complete;
+        }
+        if self.writing_segments.get(&rendition_dir) == Some(&index) {
+            return SegmentState::Writing;
+        }
+
+        SegmentState::NotWritten {
+            next_index: self
+                .segment_indices
+                .get(&rendition_dir)
+                .copied()
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Points every rendition at the same segment, the renditions share one timeline.
+#[derive(Debug, Message)]
+#[rtype(result = "Result<()>")]
+pub struct SetSegmentIndex(pub u32);
+
+impl Handler<SetSegmentIndex> for HlsStateActor {
+    type Result = Result<()>;
+
+    fn handle(&mut self, msg: SetSegmentIndex, _: &mut Self::Context) -> Self::Result {
+        for index in self.segment_indices.values_mut() {
+            *index = msg.0;
+        }
+        // The seek flushes the pipeline, segments which were being written stay incomplete
+        self.writing_segments.clear();
         Ok(())
     }
 }
 
 #[derive(Debug, Message)]
 #[rtype(result = "Result<u32>")]
-pub struct GetSegmentIndex;
+pub struct GetSegmentIndex(pub String); // rendition directory
 
 impl Handler<GetSegmentIndex> for HlsStateActor {
     type Result = Result<u32>;
 
-    fn handle(&mut self, _: GetSegmentIndex, _: &mut Self::Context) -> Self::Result {
-        Ok(self
-            .segment_index
-            .load(std::sync::atomic::Ordering::Relaxed))
+    fn handle(&mut self, msg: GetSegmentIndex, _: &mut Self::Context) -> Self::Result {
+        Ok(*self.segment_indices.entry(msg.0).or_insert(0))
     }
 }
 
@@ -176,6 +266,89 @@ impl Handler<GetSegmentDuration> for HlsStateActor {
     type Result = Result<u64>;
 
     fn handle(&mut self, _: GetSegmentDuration, _: &mut Self::Context) -> Self::Result {
-        Ok(self.segment_duration.expect("segment_duration is not set"))
+        self.segment_duration
+            .ok_or_else(|| anyhow!("Segment duration is not known yet"))
+    }
+}
+
+#[derive(Debug, Message)]
+#[rtype(result = "()")]
+pub struct SetSubtitleRenditions(pub SubtitleRenditions);
+
+impl Handler<SetSubtitleRenditions> for HlsStateActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: SetSubtitleRenditions, _: &mut Self::Context) -> Self::Result {
+        self.subtitle_renditions = Some(msg.0);
+    }
+}
+
+#[derive(Debug, Message)]
+#[rtype(result = "Option<SubtitleRenditions>")]
+pub struct GetSubtitleRenditions;
+
+impl Handler<GetSubtitleRenditions> for HlsStateActor {
+    type Result = Option<SubtitleRenditions>;
+
+    fn handle(&mut self, _: GetSubtitleRenditions, _: &mut Self::Context) -> Self::Result {
+        self.subtitle_renditions.clone()
+    }
+}
+
+#[derive(Debug, Message)]
+#[rtype(result = "()")]
+pub struct SetRenditions {
+    pub video: Vec<Rendition>,
+    pub audio: Vec<AudioRendition>,
+    pub profile: Option<TranscodingProfile>,
+}
+
+impl Handler<SetRenditions> for HlsStateActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: SetRenditions, _: &mut Self::Context) -> Self::Result {
+        self.renditions = msg.video;
+        self.audio_renditions = msg.audio;
+        self.profile = msg.profile;
+        self.ready_renditions.clear();
+    }
+}
+
+/// Marks the playlist of a rendition as written. Returns the renditions and their profile once
+/// all of them are ready, which happens exactly once, so the master playlist is written a
+/// single time.
+#[derive(Debug, Message)]
+#[rtype(result = "Option<(Vec<Rendition>, Vec<AudioRendition>, Option<TranscodingProfile>)>")]
+pub struct MarkRenditionReady(pub String); // rendition name
+
+impl Handler<MarkRenditionReady> for HlsStateActor {
+    type Result = Option<(
+        Vec<Rendition>,
+        Vec<AudioRendition>,
+        Option<TranscodingProfile>,
+    )>;
+
+    fn handle(&mut self, msg: MarkRenditionReady, _: &mut Self::Context) -> Self::Result {
+        let newly_ready = self.ready_renditions.insert(msg.0);
+        // Muxed audio is part of the video renditions and has no playlist of its own
+        let all_ready = self
+            .renditions
+            .iter()
+            .map(|rendition| rendition.name.clone())
+            .chain(
+                self.audio_renditions
+                    .iter()
+                    .filter(|rendition| !rendition.muxed)
+                    .map(AudioRendition::name),
+            )
+            .all(|name| self.ready_renditions.contains(&name));
+
+        (newly_ready && all_ready).then(|| {
+            (
+                self.renditions.clone(),
+                self.audio_renditions.clone(),
+                self.profile.clone(),
+            )
+        })
     }
 }
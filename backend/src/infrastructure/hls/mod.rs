@@ -1,4 +1,7 @@
+pub mod file;
 pub mod hls_state_actor;
 pub mod model;
-pub mod playlist;
+pub mod session;
 pub mod stream;
+pub mod subtitle;
+pub mod webvtt;
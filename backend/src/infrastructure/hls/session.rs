@@ -0,0 +1,250 @@
+use actix::{Actor, Addr};
+use actix_files::file_extension_to_mime;
+use actix_web::http::header::ContentType;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{sync::RwLock, time::Instant};
+use uuid::Uuid;
+
+use super::hls_state_actor::HlsStateActor;
+use crate::{
+    domain::{
+        pipeline::{
+            profile::TranscodingProfile,
+            rendition::{AudioRendition, Rendition},
+        },
+        playback::method::PlaybackMethod,
+    },
+    infrastructure::event_dispatcher::event_bus::EventBus,
+};
+
+/// Every session writes into its own directory below this one, which is served under `/hls`.
+pub const HLS_OUTPUT_DIR: &str = "./tmp";
+/// Sessions whose player hasn't requested anything for this long are stopped, players which
+/// are closed without stopping the session would keep their pipeline running otherwise.
+pub const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+// How long the ids of removed sessions are remembered to answer players that they expired
+const EXPIRED_SESSION_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// A single video being streamed to a client. Sessions don't share any state, so several
+/// people can watch different videos at the same time.
+#[derive(Debug, Clone)]
+pub struct PlaybackSession {
+    pub id: String,
+    // Only the user who started the session may stream or control it
+    pub user_id: i64,
+    pub video_file_path: String,
+    pub method: PlaybackMethod,
+    // Each rendition is written to its own directory below the output directory
+    pub renditions: Vec<Rendition>,
+    pub audio_renditions: Vec<AudioRendition>,
+    // Index of the subtitle stream which is burnt into the video, only when transcoding
+    pub burn_in_subtitle: Option<u32>,
+    // Encoder settings, only used when transcoding
+    pub profile: TranscodingProfile,
+    pub output_dir: PathBuf,
+    pub hls_state_actor_addr: Addr<HlsStateActor>,
+}
+
+impl PlaybackSession {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        user_id: i64,
+        video_file_path: String,
+        method: PlaybackMethod,
+        renditions: Vec<Rendition>,
+        audio_renditions: Vec<AudioRendition>,
+        burn_in_subtitle: Option<u32>,
+        profile: TranscodingProfile,
+        event_bus: Arc<EventBus>,
+    ) -> Self {
+        let id = Uuid::new_v4().to_string();
+
+        Self {
+            output_dir: PathBuf::from(HLS_OUTPUT_DIR).join(&id),
+            hls_state_actor_addr: HlsStateActor::new(id.clone(), event_bus).start(),
+            user_id,
+            video_file_path,
+            method,
+            renditions,
+            audio_renditions,
+            burn_in_subtitle,
+            profile,
+            id,
+        }
+    }
+
+    /// Index of the audio track which is muxed into the video renditions.
+    pub fn muxed_audio_track(&self) -> u32 {
+        self.audio_renditions
+            .iter()
+            .find(|rendition| rendition.muxed)
+            .map(|rendition| rendition.track.index)
+            .unwrap_or_default()
+    }
+
+    /// Names of the directories below the output directory, one per rendition with a playlist.
+    pub fn rendition_names(&self) -> Vec<String> {
+        self.renditions
+            .iter()
+            .map(|rendition| rendition.name.clone())
+            .chain(
+                self.audio_renditions
+                    .iter()
+                    .filter(|rendition| !rendition.muxed)
+                    .map(AudioRendition::name),
+            )
+            .collect()
+    }
+
+    /// Direct play streams the original file with range requests, everything else goes
+    /// through the HLS master playlist of the session.
+    pub fn stream_url(&self) -> String {
+        match self.method {
+            PlaybackMethod::DirectPlay => format!("/video-player/{}/file", self.id),
+            _ => format!("/hls/{}/master.m3u8", self.id),
+        }
+    }
+
+    pub fn content_type(&self) -> String {
+        match self.method {
+            PlaybackMethod::DirectPlay => Path::new(&self.video_file_path)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(file_extension_to_mime)
+                .unwrap_or(ContentType::octet_stream().0)
+                .to_string(),
+            _ => "application/x-mpegURL".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PlaybackSessions {
+    sessions: Arc<RwLock<HashMap<String, PlaybackSession>>>,
+    // session id -> time the player last requested a segment or the file
+    last_requested: Arc<RwLock<HashMap<String, Instant>>>,
+    // Ids of removed sessions and when they were removed, so players still requesting their
+    // files can be told they expired
+    expired: Arc<RwLock<HashMap<String, Instant>>>,
+}
+
+impl PlaybackSessions {
+    pub async fn add(&self, session: PlaybackSession) {
+        let mut sessions = self.sessions.write().await;
+        self.last_requested
+            .write()
+            .await
+            .insert(session.id.clone(), Instant::now());
+        sessions.insert(session.id.clone(), session);
+    }
+
+    pub async fn remove(&self, id: &str) -> Option<PlaybackSession> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.remove(id);
+        self.last_requested.write().await.remove(id);
+        if session.is_some() {
+            let now = Instant::now();
+            let mut expired = self.expired.write().await;
+            prune_expired(&mut expired, now);
+            expired.insert(id.to_string(), now);
+        }
+        session
+    }
+
+    pub async fn is_expired(&self, id: &str) -> bool {
+        let expired = self.expired.read().await;
+        expired.contains_key(id)
+    }
+
+    /// Records that the player of the session requested something, which keeps it alive.
+    pub async fn touch(&self, id: &str) {
+        if let Some(last_requested) = self.last_requested.write().await.get_mut(id) {
+            *last_requested = Instant::now();
+        }
+    }
+
+    /// Ids of the sessions without any request in the last `timeout`.
+    pub async fn idle_sessions(&self, now: Instant, timeout: Duration) -> Vec<String> {
+        let last_requested = self.last_requested.read().await;
+        last_requested
+            .iter()
+            .filter(|(_, last_requested)| now.saturating_duration_since(**last_requested) > timeout)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    pub async fn get(&self, id: &str) -> Option<PlaybackSession> {
+        let sessions = self.sessions.read().await;
+        sessions.get(id).cloned()
+    }
+
+    pub async fn contains(&self, id: &str) -> bool {
+        let sessions = self.sessions.read().await;
+        sessions.contains_key(id)
+    }
+}
+
+fn prune_expired(expired: &mut HashMap<String, Instant>, now: Instant) {
+    expired.retain(|_, removed_at| {
+        now.saturating_duration_since(*removed_at) < EXPIRED_SESSION_RETENTION
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> PlaybackSession {
+        PlaybackSession::new(
+            1,
+            "/media/movie.mkv".to_string(),
+            PlaybackMethod::Remux,
+            vec![],
+            vec![],
+            None,
+            TranscodingProfile::default(),
+            Arc::new(EventBus::new(16)),
+        )
+    }
+
+    #[actix::test]
+    async fn test_idle_sessions() {
+        let sessions = PlaybackSessions::default();
+        let session = session();
+        let timeout = Duration::from_secs(60);
+        sessions.add(session.clone()).await;
+
+        let now = Instant::now();
+        assert!(sessions.idle_sessions(now, timeout).await.is_empty());
+        assert_eq!(
+            sessions.idle_sessions(now + timeout * 2, timeout).await,
+            vec![session.id.clone()]
+        );
+
+        sessions.remove(&session.id).await;
+        assert!(sessions
+            .idle_sessions(now + timeout * 2, timeout)
+            .await
+            .is_empty());
+        assert!(sessions.is_expired(&session.id).await);
+    }
+
+    #[test]
+    fn test_prune_expired() {
+        let now = Instant::now();
+        let mut expired = HashMap::from([
+            ("old".to_string(), now),
+            ("recent".to_string(), now + EXPIRED_SESSION_RETENTION),
+        ]);
+
+        prune_expired(&mut expired, now + EXPIRED_SESSION_RETENTION);
+
+        assert_eq!(expired.len(), 1);
+        assert!(expired.contains_key("recent"));
+    }
+}
@@ -8,7 +8,9 @@ use tokio::runtime::Runtime;
 use tracing::*;
 
 use super::{
-    hls_state_actor::{GetPipelineAddr, HlsStateActor},
+    hls_state_actor::{
+        FinishSegments, GetPipelineAddr, GetSubtitleRenditions, HlsStateActor, MarkRenditionReady,
+    },
     model::M3u8Tag,
 };
 use crate::domain::pipeline::event::PipelineEvent;
@@ -20,6 +22,7 @@ use crate::infrastructure::{
 
 #[derive(Clone)]
 pub struct HlsStream {
+    session_id: String,
     path_str: String,
     header: HashMap<M3u8Tag, String>,
     state: Addr<HlsStateActor>,
@@ -29,8 +32,14 @@ pub struct HlsStream {
 
 impl HlsStream {
     #[instrument(skip(state, event_bus))]
-    pub fn new(path_str: String, state: Addr<HlsStateActor>, event_bus: Arc<EventBus>) -> Self {
+    pub fn new(
+        session_id: String,
+        path_str: String,
+        state: Addr<HlsStateActor>,
+        event_bus: Arc<EventBus>,
+    ) -> Self {
         Self {
+            session_id,
             path_str,
             header: HashMap::new(),
             state,
@@ -44,8 +53,14 @@ impl HlsStream {
         WriteOutputStream::new(self.clone())
     }
 
+    /// Note: The buffer is parsed without checking whether it contains the complete header.
+    /// This relies on hlssink2 only sending the first buffer once it holds the whole header
+    /// and the first segment, which works in practice but is not documented by GStreamer.
+    ///
+    /// Returns whether the master playlist has been written, which happens once the playlists
+    /// of all renditions of the session exist.
     #[instrument(skip(self, buf))]
-    pub fn extract_header(&mut self, buf: &[u8]) -> Result<()> {
+    pub fn extract_header(&mut self, buf: &[u8]) -> Result<bool> {
         let header_str = std::str::from_utf8(buf).inspect_err(|&e| {
             error!("Failed to convert buffer to string: {}", e);
         })?;
@@ -61,20 +76,28 @@ impl HlsStream {
             }
         }
 
-        if !self.header.is_empty() {
-            let rt = Runtime::new().unwrap();
-            rt.block_on(async move {
-                match self.create_placeholder_m3u8().await {
-                    Ok(_) => info!("Created placeholder m3u8"),
-                    Err(e) => error!("Failed to create placeholder m3u8: {}", e),
-                }
-            });
+        if self.header.is_empty() {
+            return Ok(false);
         }
 
-        Ok(())
+        let rt = Runtime::new().unwrap();
+        let master_written = rt.block_on(async move {
+            match self.create_placeholder_m3u8().await {
+                Ok(master_written) => {
+                    info!("Created placeholder m3u8");
+                    master_written
+                }
+                Err(e) => {
+                    error!("Failed to create placeholder m3u8: {}", e);
+                    false
+                }
+            }
+        });
+
+        Ok(master_written)
     }
 
-    async fn create_placeholder_m3u8(&self) -> Result<()> {
+    async fn create_placeholder_m3u8(&self) -> Result<bool> {
         let duration = self.parse_segment_duration()?;
         self.state
             .send(SetSegmentDuration(duration))
@@ -92,8 +115,39 @@ impl HlsStream {
         let file_duration = pipeline_addr.send(QueryDuration).await?;
 
         let content = self.generate_m3u8_content(&self.header, file_duration, duration)?;
+        self.write_m3u8_file(&content)?;
+
+        let rendition_dir = self.output_dir()?;
+        let rendition_name = rendition_dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| anyhow!("Invalid rendition directory: {:?}", rendition_dir))?;
+        let Some((variants, audio, profile)) = self
+            .state
+            .send(MarkRenditionReady(rendition_name.to_string()))
+            .await?
+        else {
+            return Ok(false);
+        };
 
-        self.write_m3u8_file(&content)
+        // The master playlist is written even without subtitles so the player url is stable
+        let session_dir = rendition_dir
+            .parent()
+            .ok_or_else(|| anyhow!("Invalid rendition directory: {:?}", rendition_dir))?;
+        let renditions = self
+            .state
+            .send(GetSubtitleRenditions)
+            .await?
+            .unwrap_or_default();
+        renditions.write(
+            session_dir,
+            file_duration,
+            &variants,
+            &audio,
+            profile.as_ref(),
+        )?;
+
+        Ok(true)
     }
 
     fn parse_segment_duration(&self) -> Result<u64> {
@@ -167,6 +221,12 @@ impl HlsStream {
         Ok(m3u8_content)
     }
 
+    fn output_dir(&self) -> Result<&Path> {
+        Path::new(&self.path_str)
+            .parent()
+            .ok_or_else(|| anyhow!("Invalid playlist path: {}", self.path_str))
+    }
+
     fn write_m3u8_file(&self, content: &str) -> Result<()> {
         let path = Path::new(&self.path_str);
         let parent_dir = path
@@ -192,20 +252,32 @@ impl Write for HlsStream {
         let path = Path::new(&self.path_str);
 
         if !self.initialized {
-            if !path.exists() {
-                match self.extract_header(buf) {
-                    Ok(_) => self.initialized = true,
-                    Err(e) => return Err(std::io::Error::new(std::io::ErrorKind::Other, e)),
-                }
-            }
+            let master_written = if path.exists() {
+                false
+            } else {
+                self.extract_header(buf)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?
+            };
             self.initialized = true;
-            if let Err(e) =
-                self.event_bus
-                    .publish(DomainEvent::Pipeline(PipelineEvent::HlsStreamInitialized {
+
+            // Only the last rendition to become ready announces the stream
+            if master_written {
+                if let Err(e) = self.event_bus.publish(DomainEvent::Pipeline(
+                    PipelineEvent::HlsStreamInitialized {
+                        session_id: self.session_id.clone(),
                         path: self.path_str.clone(),
-                    }))
-            {
-                error!("Failed to publish hls stream initialized event: {}", e);
+                    },
+                )) {
+                    error!("Failed to publish hls stream initialized event: {}", e);
+                }
+            }
+        }
+
+        // hlssink2 ends the playlist once the stream is over, which closes the last segment
+        if String::from_utf8_lossy(buf).contains("#EXT-X-ENDLIST") {
+            if let Ok(rendition_dir) = self.output_dir() {
+                self.state
+                    .do_send(FinishSegments(rendition_dir.to_string_lossy().to_string()));
             }
         }
 
@@ -0,0 +1,299 @@
+use anyhow::*;
+use std::result::Result::Ok;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    thread,
+};
+use tracing::*;
+
+use super::webvtt::{parse_ass, parse_srt, render_webvtt};
+use crate::{
+    domain::{
+        pipeline::{
+            profile::TranscodingProfile,
+            rendition::{AudioRendition, Rendition},
+        },
+        subtitle::model::{Subtitle, SubtitleFormat},
+    },
+    infrastructure::video_pipeline::subtitle::extract_embedded_subtitle,
+};
+
+const SUBTITLE_GROUP_ID: &str = "subs";
+const AUDIO_GROUP_ID: &str = "audio";
+
+/// The subtitles of the video which is currently played, written next to the video
+/// playlist as WebVTT renditions of a master playlist.
+#[derive(Debug, Clone, Default)]
+pub struct SubtitleRenditions {
+    pub video_file_path: String,
+    pub subtitles: Vec<Subtitle>,
+}
+
+impl SubtitleRenditions {
+    pub fn new(video_file_path: String, subtitles: Vec<Subtitle>) -> Self {
+        Self {
+            video_file_path,
+            subtitles,
+        }
+    }
+
+    /// Writes `master.m3u8` with an entry for every video variant and alternate audio track,
+    /// together with a playlist and a WebVTT file for every subtitle. Embedded subtitles are
+    /// extracted in the background since that needs a pass over the whole container, the player
+    /// only requests them once a track is selected. `profile` is the one the variants are
+    /// encoded with and `None` when they are remuxed.
+    #[instrument(skip(self))]
+    pub fn write(
+        &self,
+        dir: &Path,
+        file_duration: u64,
+        variants: &[Rendition],
+        audio: &[AudioRendition],
+        profile: Option<&TranscodingProfile>,
+    ) -> Result<()> {
+        let duration_secs = file_duration as f64 / 1_000_000_000.0;
+
+        for (index, subtitle) in self.subtitles.iter().enumerate() {
+            let vtt_path = dir.join(format!("subtitles_{}.vtt", index));
+            fs::write(
+                dir.join(format!("subtitles_{}.m3u8", index)),
+                generate_subtitle_playlist(index, duration_secs),
+            )?;
+
+            if let Err(e) = self.write_webvtt(subtitle, vtt_path) {
+                error!("Failed to convert subtitle {}: {}", index, e);
+            }
+        }
+
+        fs::write(
+            dir.join("master.m3u8"),
+            self.generate_master_playlist(variants, audio, profile),
+        )?;
+
+        Ok(())
+    }
+
+    fn write_webvtt(&self, subtitle: &Subtitle, vtt_path: PathBuf) -> Result<()> {
+        if let Some(stream_index) = subtitle.stream_index {
+            let video_path = PathBuf::from(&self.video_file_path);
+            thread::spawn(
+                move || match extract_embedded_subtitle(&video_path, stream_index) {
+                    Ok(cues) => {
+                        if let Err(e) = fs::write(&vtt_path, render_webvtt(&cues)) {
+                            error!("Failed to write {:?}: {}", vtt_path, e);
+                        }
+                    }
+                    Err(e) => error!("Failed to extract subtitle {}: {}", stream_index, e),
+                },
+            );
+            return Ok(());
+        }
+
+        let file_path = subtitle
+            .file_path
+            .as_ref()
+            .ok_or_else(|| anyhow!("Subtitle has neither a file nor a stream"))?;
+
+        if subtitle.format == SubtitleFormat::WebVtt {
+            fs::copy(file_path, &vtt_path)?;
+            return Ok(());
+        }
+
+        let data = fs::read(file_path)?;
+        let content = String::from_utf8_lossy(&data);
+        let cues = match subtitle.format {
+            SubtitleFormat::Ass => parse_ass(&content),
+            _ => parse_srt(&content),
+        };
+        fs::write(&vtt_path, render_webvtt(&cues))?;
+
+        Ok(())
+    }
+
+    fn generate_master_playlist(
+        &self,
+        variants: &[Rendition],
+        audio: &[AudioRendition],
+        profile: Option<&TranscodingProfile>,
+    ) -> String {
+        let mut content = String::from("#EXTM3U\n#EXT-X-VERSION:3\n");
+
+        // A single track is simply the audio of the variants
+        let has_audio_group = audio.len() > 1;
+        if has_audio_group {
+            let mut names = HashSet::new();
+            for rendition in audio {
+                let language = rendition.track.language.as_deref();
+                let name = unique_name(&mut names, rendition.track.display_name());
+                // The muxed track is played from the variant stream, so it has no URI
+                let uri = if rendition.muxed {
+                    String::new()
+                } else {
+                    format!(",URI=\"{}/playlist.m3u8\"", rendition.name())
+                };
+
+                content.push_str(&format!(
+                    "#EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"{}\",NAME=\"{}\",{}DEFAULT={},AUTOSELECT=YES{}\n",
+                    AUDIO_GROUP_ID,
+                    name.replace('"', "'"),
+                    language
+                        .map(|language| format!("LANGUAGE=\"{}\",", language))
+                        .unwrap_or_default(),
+                    if rendition.muxed { "YES" } else { "NO" },
+                    uri
+                ));
+            }
+        }
+
+        let mut names = HashSet::new();
+
+        for (index, subtitle) in self.subtitles.iter().enumerate() {
+            let language = subtitle.language.as_deref();
+            let base_name = subtitle
+                .title
+                .clone()
+                .or_else(|| language.map(str::to_string))
+                .unwrap_or_else(|| format!("Subtitle {}", index + 1));
+
+            let name = unique_name(&mut names, base_name);
+
+            content.push_str(&format!(
+                "#EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID=\"{}\",NAME=\"{}\",{}DEFAULT=NO,AUTOSELECT=YES,URI=\"subtitles_{}.m3u8\"\n",
+                SUBTITLE_GROUP_ID,
+                name.replace('"', "'"),
+                language
+                    .map(|language| format!("LANGUAGE=\"{}\",", language))
+                    .unwrap_or_default(),
+                index
+            ));
+        }
+
+        let mut groups = String::new();
+        if has_audio_group {
+            groups.push_str(&format!(",AUDIO=\"{}\"", AUDIO_GROUP_ID));
+        }
+        if !self.subtitles.is_empty() {
+            groups.push_str(&format!(",SUBTITLES=\"{}\"", SUBTITLE_GROUP_ID));
+        }
+        for variant in variants {
+            content.push_str(&format!(
+                "#EXT-X-STREAM-INF:BANDWIDTH={}{}\n{}/playlist.m3u8\n",
+                variant.bandwidth(profile),
+                groups,
+                variant.name
+            ));
+        }
+
+        content
+    }
+}
+
+// NAME has to be unique within a group
+fn unique_name(names: &mut HashSet<String>, base_name: String) -> String {
+    let mut name = base_name.clone();
+    let mut counter = 2;
+    while !names.insert(name.clone()) {
+        name = format!("{} ({})", base_name, counter);
+        counter += 1;
+    }
+    name
+}
+
+// The whole file is a single segment, cue timings are relative to the start of the video
+fn generate_subtitle_playlist(index: usize, duration_secs: f64) -> String {
+    format!(
+        "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-TARGETDURATION:{}\n#EXT-X-MEDIA-SEQUENCE:0\n#EXT-X-PLAYLIST-TYPE:VOD\n#EXTINF:{:.3},\nsubtitles_{}.vtt\n#EXT-X-ENDLIST\n",
+        duration_secs.ceil() as u64,
+        duration_secs,
+        index
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::media_info::model::AudioTrack;
+
+    #[test]
+    fn test_generate_master_playlist() {
+        let renditions = SubtitleRenditions::new(
+            "/media/movie.mkv".to_string(),
+            vec![
+                Subtitle::sidecar(
+                    "/media/movie.en.srt".to_string(),
+                    SubtitleFormat::Srt,
+                    Some("en".to_string()),
+                ),
+                Subtitle::sidecar(
+                    "/media/movie.en.forced.srt".to_string(),
+                    SubtitleFormat::Srt,
+                    Some("en".to_string()),
+                ),
+            ],
+        );
+
+        assert_eq!(
+            renditions.generate_master_playlist(
+                &[Rendition::new(1080, 8000)],
+                &[],
+                Some(&TranscodingProfile::default())
+            ),
+            "#EXTM3U\n#EXT-X-VERSION:3\n\
+             #EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID=\"subs\",NAME=\"en\",LANGUAGE=\"en\",DEFAULT=NO,AUTOSELECT=YES,URI=\"subtitles_0.m3u8\"\n\
+             #EXT-X-MEDIA:TYPE=SUBTITLES,GROUP-ID=\"subs\",NAME=\"en (2)\",LANGUAGE=\"en\",DEFAULT=NO,AUTOSELECT=YES,URI=\"subtitles_1.m3u8\"\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=8128000,SUBTITLES=\"subs\"\n1080p/playlist.m3u8\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_master_playlist_without_subtitles() {
+        let renditions = SubtitleRenditions::new("/media/movie.mkv".to_string(), vec![]);
+
+        assert_eq!(
+            renditions.generate_master_playlist(
+                &[Rendition::new(720, 4000), Rendition::new(480, 1500)],
+                &[],
+                Some(&TranscodingProfile::default_profiles()[1])
+            ),
+            "#EXTM3U\n#EXT-X-VERSION:3\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=1596000\n720p/playlist.m3u8\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=1596000\n480p/playlist.m3u8\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_master_playlist_with_alternate_audio() {
+        let renditions = SubtitleRenditions::new("/media/anime.mkv".to_string(), vec![]);
+        let audio = AudioRendition::for_tracks(
+            &[
+                AudioTrack {
+                    index: 0,
+                    language: Some("ja".to_string()),
+                    ..Default::default()
+                },
+                AudioTrack {
+                    index: 1,
+                    language: Some("en".to_string()),
+                    title: Some("English Dub".to_string()),
+                    ..Default::default()
+                },
+            ],
+            Some(1),
+            true,
+        );
+
+        assert_eq!(
+            renditions.generate_master_playlist(
+                &[Rendition::new(720, 4000)],
+                &audio,
+                Some(&TranscodingProfile::default())
+            ),
+            "#EXTM3U\n#EXT-X-VERSION:3\n\
+             #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"audio\",NAME=\"ja\",LANGUAGE=\"ja\",DEFAULT=NO,AUTOSELECT=YES,URI=\"audio_0/playlist.m3u8\"\n\
+             #EXT-X-MEDIA:TYPE=AUDIO,GROUP-ID=\"audio\",NAME=\"English Dub\",LANGUAGE=\"en\",DEFAULT=YES,AUTOSELECT=YES\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=4128000,AUDIO=\"audio\"\n720p/playlist.m3u8\n"
+        );
+    }
+}
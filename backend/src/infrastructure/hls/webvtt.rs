@@ -0,0 +1,222 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubtitleCue {
+    pub start: Duration,
+    pub end: Duration,
+    pub text: String,
+}
+
+/// Parses a SubRip file, blocks without a valid timing line are skipped.
+pub fn parse_srt(content: &str) -> Vec<SubtitleCue> {
+    let content = content.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+
+    content
+        .split("\n\n")
+        .filter_map(|block| {
+            let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+            let (start, end) = lines.next()?.split_once("-->")?;
+
+            Some(SubtitleCue {
+                start: parse_timestamp(start.trim())?,
+                end: parse_timestamp(end.split_whitespace().next()?)?,
+                text: lines.collect::<Vec<_>>().join("\n"),
+            })
+        })
+        .filter(|cue| !cue.text.trim().is_empty())
+        .collect()
+}
+
+/// Parses the dialogue lines of an ASS/SSA file, styling and override tags are dropped
+/// since WebVTT can not represent them.
+pub fn parse_ass(content: &str) -> Vec<SubtitleCue> {
+    let mut in_events = false;
+    let mut format: Vec<String> = vec![];
+    let mut cues = vec![];
+
+    for line in content.trim_start_matches('\u{feff}').lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[events]");
+            continue;
+        }
+        if !in_events {
+            continue;
+        }
+
+        if let Some(fields) = line.strip_prefix("Format:") {
+            format = fields
+                .split(',')
+                .map(|field| field.trim().to_lowercase())
+                .collect();
+        } else if let Some(fields) = line.strip_prefix("Dialogue:") {
+            if let Some(cue) = parse_ass_dialogue(fields, &format) {
+                cues.push(cue);
+            }
+        }
+    }
+
+    cues
+}
+
+fn parse_ass_dialogue(fields: &str, format: &[String]) -> Option<SubtitleCue> {
+    // The text is always the last field and may contain commas itself
+    let values: Vec<&str> = fields.splitn(format.len(), ',').collect();
+    let field = |name: &str| {
+        format
+            .iter()
+            .position(|field| field == name)
+            .and_then(|index| values.get(index))
+            .map(|value| value.trim())
+    };
+
+    let text = strip_ass_tags(field("text")?);
+    if text.trim().is_empty() {
+        return None;
+    }
+
+    Some(SubtitleCue {
+        start: parse_timestamp(field("start")?)?,
+        end: parse_timestamp(field("end")?)?,
+        text,
+    })
+}
+
+/// Returns the text of an ASS event as stored in matroska blocks, which carry
+/// `ReadOrder,Layer,Style,Name,MarginL,MarginR,MarginV,Effect,Text` without timings.
+pub fn ass_event_text(event: &str) -> String {
+    strip_ass_tags(event.splitn(9, ',').last().unwrap_or_default())
+}
+
+fn strip_ass_tags(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut in_override = false;
+
+    for c in text.chars() {
+        match c {
+            '{' => in_override = true,
+            '}' if in_override => in_override = false,
+            _ if !in_override => result.push(c),
+            _ => (),
+        }
+    }
+
+    result
+        .replace("\\N", "\n")
+        .replace("\\n", "\n")
+        .replace("\\h", " ")
+}
+
+/// Parses `HH:MM:SS,mmm` (SubRip), `H:MM:SS.cc` (ASS) and `HH:MM:SS.mmm` (WebVTT) timestamps.
+fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let timestamp = timestamp.replace(',', ".");
+    let (clock, fraction) = timestamp.split_once('.').unwrap_or((&timestamp, "0"));
+
+    let mut seconds = 0u64;
+    for part in clock.split(':') {
+        seconds = seconds * 60 + part.trim().parse::<u64>().ok()?;
+    }
+
+    // Pad or truncate the fraction to milliseconds, e.g. 5 -> 500 and 05 -> 050
+    let millis = format!("{:0<3}", fraction.trim())
+        .get(..3)?
+        .parse::<u64>()
+        .ok()?;
+
+    Some(Duration::from_millis(seconds * 1000 + millis))
+}
+
+pub fn render_webvtt(cues: &[SubtitleCue]) -> String {
+    let mut content = String::from("WEBVTT\n\n");
+
+    for cue in cues {
+        content.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_timestamp(cue.start),
+            format_timestamp(cue.end),
+            // An empty line would end the cue early
+            cue.text
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .collect::<Vec<_>>()
+                .join("\n")
+                .replace("-->", "->")
+        ));
+    }
+
+    content
+}
+
+fn format_timestamp(duration: Duration) -> String {
+    let millis = duration.as_millis();
+
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_srt() {
+        let content = "\u{feff}1\r\n00:00:01,500 --> 00:00:03,000\r\nHello\r\nWorld\r\n\r\n2\r\n00:01:02,000 --> 00:01:04,250\r\n<i>Bye</i>\r\n";
+
+        assert_eq!(
+            parse_srt(content),
+            vec![
+                SubtitleCue {
+                    start: Duration::from_millis(1500),
+                    end: Duration::from_millis(3000),
+                    text: "Hello\nWorld".to_string(),
+                },
+                SubtitleCue {
+                    start: Duration::from_millis(62_000),
+                    end: Duration::from_millis(64_250),
+                    text: "<i>Bye</i>".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_ass() {
+        let content = "[Script Info]\nTitle: Test\n\n[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\nDialogue: 0,0:00:01.50,0:00:03.00,Default,,0,0,0,,{\\i1}Hello{\\i0}, there\\Nfriend\nComment: 0,0:00:04.00,0:00:05.00,Default,,0,0,0,,ignored\n";
+
+        assert_eq!(
+            parse_ass(content),
+            vec![SubtitleCue {
+                start: Duration::from_millis(1500),
+                end: Duration::from_millis(3000),
+                text: "Hello, there\nfriend".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_ass_event_text() {
+        assert_eq!(
+            ass_event_text("3,0,Default,,0,0,0,,{\\b1}Hi{\\b0},\\hyou"),
+            "Hi, you"
+        );
+    }
+
+    #[test]
+    fn test_render_webvtt() {
+        let cues = vec![SubtitleCue {
+            start: Duration::from_millis(3_723_004),
+            end: Duration::from_millis(3_725_000),
+            text: "Hello\n\nWorld".to_string(),
+        }];
+
+        assert_eq!(
+            render_webvtt(&cues),
+            "WEBVTT\n\n01:02:03.004 --> 01:02:05.000\nHello\nWorld\n\n"
+        );
+    }
+}
@@ -6,8 +6,8 @@ use quick_xml::Reader;
 use tracing::*;
 
 use crate::domain::{
-    episode::model::Episode, media_actor::model::MediaActor, season::model::Season,
-    tv_show::model::TvShow,
+    episode::model::Episode, media_actor::model::MediaActor, movie::model::Movie,
+    season::model::Season, tv_show::model::TvShow,
 };
 
 pub fn parse_tv_serie(nfo_path_str: &String) -> Result<TvShow> {
@@ -71,6 +71,10 @@ pub fn parse_tv_serie(nfo_path_str: &String) -> Result<TvShow> {
                         "country" => set_field(&mut tv_serie.country, &text, "country"),
                         "premiered" => set_field(&mut tv_serie.premiered, &text, "premiered"),
                         "runtime" => set_field(&mut tv_serie.runtime, &text, "runtime"),
+                        // Kodi writes the rating into mpaa, other scrapers use certification
+                        "mpaa" | "certification" => {
+                            set_field(&mut tv_serie.certification, &text, "certification")
+                        }
                         "uniqueid" => match type_attr.as_deref() {
                             Some("tmdb") => set_field(&mut tv_serie.tmdb_id, &text, "tmdb id"),
                             Some("imdb") => set_field(&mut tv_serie.imdb_id, &text, "imdb id"),
@@ -237,6 +241,137 @@ pub fn parse_episode(nfo_path_str: &String) -> Result<Episode> {
     Ok(episode)
 }
 
+pub fn parse_movie(nfo_path_str: &String) -> Result<Movie> {
+    let mut reader = Reader::from_file(nfo_path_str)?;
+    reader.config_mut().trim_text(true);
+
+    let mut movie = Movie::default();
+
+    let mut element_stack = Vec::new();
+    let mut curr_elem: Option<String> = None;
+    let mut is_in_actor = false;
+    let mut is_in_rating = false;
+
+    let mut buf = Vec::new();
+
+    let mut actor = MediaActor::default();
+    let mut type_attr = None;
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => {
+                let elem_name = String::from_utf8(e.name().as_ref().to_vec())?;
+
+                match elem_name.as_ref() {
+                    "actor" => is_in_actor = true,
+                    "rating" => is_in_rating = true,
+                    "uniqueid" => {
+                        for attr in e.attributes() {
+                            match attr {
+                                Ok(attr) => {
+                                    if attr.key == QName(b"type") {
+                                        type_attr =
+                                            Some(attr.unescape_value().unwrap().to_string());
+                                    }
+                                }
+                                Err(e) => {
+                                    error!("Error reading attribute: {}", e);
+                                }
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+
+                element_stack.push(elem_name.clone());
+                curr_elem = Some(elem_name);
+            }
+            Event::Text(e) => {
+                if let Some(ref elem_name) = curr_elem {
+                    let text = e.unescape().unwrap().to_string();
+                    match elem_name.as_ref() {
+                        "title" => set_field(&mut movie.title, &text, "title"),
+                        "originaltitle" => {
+                            set_field(&mut movie.original_title, &text, "originaltitle")
+                        }
+                        "sorttitle" => set_field(&mut movie.sort_title, &text, "sorttitle"),
+                        "tagline" => set_field(&mut movie.tagline, &text, "tagline"),
+                        "year" => set_field(&mut movie.year, &text, "year"),
+                        "plot" => set_field(&mut movie.plot, &text, "plot"),
+                        "country" => set_field(&mut movie.country, &text, "country"),
+                        "premiered" => set_field(&mut movie.premiered, &text, "premiered"),
+                        "runtime" => set_field(&mut movie.runtime, &text, "runtime"),
+                        "mpaa" | "certification" => {
+                            set_field(&mut movie.certification, &text, "certification")
+                        }
+                        "uniqueid" => match type_attr.as_deref() {
+                            Some("tmdb") => set_field(&mut movie.tmdb_id, &text, "tmdb id"),
+                            Some("imdb") => set_field(&mut movie.imdb_id, &text, "imdb id"),
+                            Some("wikidata") => {
+                                set_field(&mut movie.wikidata_id, &text, "wikidata id")
+                            }
+                            _ => {}
+                        },
+                        // Older Kodi exports put the ids in their own tags
+                        "tmdbid" if !is_in_actor => set_field(&mut movie.tmdb_id, &text, "tmdb id"),
+                        "imdbid" => set_field(&mut movie.imdb_id, &text, "imdb id"),
+                        "genre" => {
+                            debug!("genre: {}", text);
+                            movie.genres.push(text);
+                        }
+                        "studio" => {
+                            debug!("studio: {}", text);
+                            movie.studios.push(text);
+                        }
+                        "value" => {
+                            if is_in_rating {
+                                debug!("rating: {}", text);
+                                movie.rating = Some(text.parse::<f32>()?);
+                            }
+                        }
+                        "name" | "role" | "thumb" | "profile" | "tmdbid" => {
+                            if !is_in_actor {
+                                continue;
+                            }
+
+                            match elem_name.as_ref() {
+                                "name" => set_field(&mut actor.name, &text, "actor name"),
+                                "role" => set_field(&mut actor.role, &text, "actor role"),
+                                "thumb" => set_field(&mut actor.thumb, &text, "actor thumb"),
+                                "profile" => set_field(&mut actor.profile, &text, "actor profile"),
+                                "tmdbid" => set_field(&mut actor.tmdb_id, &text, "actor tmdb id"),
+                                _ => {}
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Event::End(e) => {
+                let elem_name = String::from_utf8(e.name().as_ref().to_vec())?;
+                match elem_name.as_ref() {
+                    "actor" => {
+                        debug!("Add new actor: {:?}", actor);
+                        is_in_actor = false;
+                        movie.actors.push(actor.clone());
+                        actor = MediaActor::default();
+                    }
+                    "rating" => is_in_rating = false,
+                    _ => {}
+                }
+
+                element_stack.pop();
+                curr_elem = element_stack.last().cloned();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    info!("movie: {:?}", movie);
+    Ok(movie)
+}
+
 fn set_field(field: &mut Option<String>, value: &str, field_name: &str) {
     *field = Some(value.to_string());
     debug!("{}: {}", field_name, value);
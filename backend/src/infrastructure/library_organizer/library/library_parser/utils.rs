@@ -2,10 +2,18 @@ use anyhow::*;
 use core::result::Result::Ok;
 use rayon::prelude::*;
 use regex::Regex;
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 use tracing::*;
 
-use crate::domain::{season::model::Season, tv_show::model::TvShow};
+use crate::domain::{
+    movie::model::Movie,
+    season::model::Season,
+    subtitle::model::{parse_subtitle_language, Subtitle, SubtitleFormat},
+    tv_show::model::TvShow,
+};
 
 #[instrument(skip(parse_fn))]
 pub fn parse_tv_series_nfo<F>(series_path: &PathBuf, parse_fn: F) -> Result<TvShow>
@@ -77,6 +85,105 @@ where
     Ok(seasons_map)
 }
 
+pub fn find_movie_nfo(
+    movie_dir: &Path,
+    video_stem: &str,
+    in_movie_folder: bool,
+) -> Option<PathBuf> {
+    // TODO: use static global file name
+    // A `movie.nfo` in the library root is shared by every loose video, so it describes none of them
+    let movie_nfo = in_movie_folder.then(|| movie_dir.join("movie.nfo"));
+
+    movie_nfo
+        .into_iter()
+        .chain([movie_dir.join(format!("{video_stem}.nfo"))])
+        .find(|path| path.exists())
+}
+
+/// Whether a file stem belongs to the video, e.g. `Heat (1995).en` for `Heat (1995)` but not
+/// `Heat (1995) Extended`.
+pub fn is_sidecar_stem(file_stem: &str, video_stem: &str) -> bool {
+    file_stem
+        .strip_prefix(video_stem)
+        .is_some_and(|suffix| suffix.is_empty() || suffix.starts_with('.'))
+}
+
+/// Kodi accepts either `movie.nfo` or `<video file stem>.nfo` next to the video file,
+/// `movie.nfo` wins when both exist and is only read inside a movie folder.
+#[instrument(skip(parse_fn))]
+pub fn parse_movie_nfo<F>(
+    movie_dir: &Path,
+    video_stem: &str,
+    in_movie_folder: bool,
+    parse_fn: F,
+) -> Result<Movie>
+where
+    F: Fn(&String) -> Result<Movie>,
+{
+    let nfo_path = find_movie_nfo(movie_dir, video_stem, in_movie_folder)
+        .ok_or_else(|| anyhow!("Movie nfo file does not exist"))?;
+
+    let nfo_path = nfo_path.to_string_lossy().to_string();
+    let mut movie = parse_fn(&nfo_path)?;
+    movie.nfo_path = Some(nfo_path);
+
+    Ok(movie)
+}
+
+#[instrument(skip(meta_files, movie, encode_fn))]
+pub fn parse_movie_meta_files<F>(
+    meta_files: &[PathBuf],
+    movie: &mut Movie,
+    video_stem: &str,
+    encode_fn: F,
+) where
+    F: Fn(&Option<PathBuf>) -> Option<String>,
+{
+    let poster_names = ["poster".to_string(), format!("{video_stem}-poster")];
+    let fanart_names = ["fanart".to_string(), format!("{video_stem}-fanart")];
+
+    meta_files.iter().for_each(|file| {
+        let file_stem = match file.file_stem() {
+            Some(file_stem) => file_stem.to_string_lossy().to_string(),
+            None => return,
+        };
+        let extension = match file.extension() {
+            Some(ext) => ext.to_string_lossy().to_lowercase(),
+            None => return,
+        };
+
+        match extension.as_ref() {
+            "jpg" | "jpeg" | "png" | "webp" => {
+                if poster_names.contains(&file_stem) {
+                    movie.poster_path = encode_fn(&Some(file.clone()));
+                } else if fanart_names.contains(&file_stem) {
+                    movie.fanart_path = encode_fn(&Some(file.clone()));
+                }
+            }
+            "srt" | "ass" | "ssa" | "vtt" => {
+                let Some(format) = SubtitleFormat::from_extension(&extension) else {
+                    return;
+                };
+                if !is_sidecar_stem(&file_stem, video_stem) {
+                    return;
+                }
+                let suffix = &file_stem[video_stem.len()..];
+                let file_path = file.to_string_lossy().to_string();
+
+                if movie.subtitle_file_path.is_none() {
+                    movie.subtitle_file_path = Some(file_path.clone());
+                }
+                movie.subtitles.push(Subtitle::sidecar(
+                    file_path,
+                    format,
+                    parse_subtitle_language(suffix),
+                ));
+            }
+            _ => (),
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,4 +249,131 @@ mod tests {
         assert_eq!(result.get(&1).unwrap().season_number, Some(1));
         assert_eq!(result.get(&2).unwrap().season_number, Some(2));
     }
+
+    #[test]
+    fn test_parse_movie_nfo_prefers_movie_nfo() {
+        let mock_parse = |_: &String| -> Result<Movie> { Ok(Movie::default()) };
+
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let movie_path = temp_dir.path().join("movie");
+        create_dir_all(&movie_path).expect("Failed to create movie dir");
+        write(movie_path.join("movie.nfo"), "").expect("Failed to write nfo file");
+        write(movie_path.join("Heat (1995).nfo"), "").expect("Failed to write nfo file");
+
+        let result = parse_movie_nfo(&movie_path, "Heat (1995)", true, mock_parse)
+            .expect("Failed to parse movie nfo");
+
+        assert_eq!(
+            result.nfo_path.unwrap(),
+            movie_path.join("movie.nfo").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_parse_movie_nfo_of_loose_file_ignores_movie_nfo() {
+        let mock_parse = |_: &String| -> Result<Movie> { Ok(Movie::default()) };
+
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        write(temp_dir.path().join("movie.nfo"), "").expect("Failed to write nfo file");
+
+        let result = parse_movie_nfo(temp_dir.path(), "Heat (1995)", false, mock_parse);
+        assert!(result.is_err());
+
+        write(temp_dir.path().join("Heat (1995).nfo"), "").expect("Failed to write nfo file");
+        let result = parse_movie_nfo(temp_dir.path(), "Heat (1995)", false, mock_parse)
+            .expect("Failed to parse movie nfo");
+
+        assert_eq!(
+            result.nfo_path.unwrap(),
+            temp_dir.path().join("Heat (1995).nfo").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_is_sidecar_stem() {
+        assert!(is_sidecar_stem("Aliens", "Aliens"));
+        assert!(is_sidecar_stem("Aliens.en", "Aliens"));
+        assert!(is_sidecar_stem("Aliens.pt-BR.forced", "Aliens"));
+        assert!(!is_sidecar_stem("Alien", "Aliens"));
+        assert!(!is_sidecar_stem("Aliens", "Alien"));
+        assert!(!is_sidecar_stem("Aliens-poster", "Alien"));
+    }
+
+    #[test]
+    fn test_parse_movie_nfo_with_video_named_nfo() {
+        let mock_parse = |_: &String| -> Result<Movie> { Ok(Movie::default()) };
+
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let movie_path = temp_dir.path().join("movie");
+        create_dir_all(&movie_path).expect("Failed to create movie dir");
+        write(movie_path.join("Heat (1995).nfo"), "").expect("Failed to write nfo file");
+
+        let result = parse_movie_nfo(&movie_path, "Heat (1995)", true, mock_parse)
+            .expect("Failed to parse movie nfo");
+
+        assert_eq!(
+            result.nfo_path.unwrap(),
+            movie_path.join("Heat (1995).nfo").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_parse_movie_nfo_without_nfo() {
+        let mock_parse = |_: &String| -> Result<Movie> { Ok(Movie::default()) };
+
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+
+        let result = parse_movie_nfo(temp_dir.path(), "Heat (1995)", true, mock_parse);
+
+        assert!(result.is_err());
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Movie nfo file does not exist"
+        );
+    }
+
+    #[test]
+    fn test_parse_movie_meta_files() {
+        let mock_encode = |path: &Option<PathBuf>| -> Option<String> {
+            path.as_ref().map(|p| p.to_string_lossy().to_string())
+        };
+
+        let meta_files = vec![
+            PathBuf::from("Heat (1995)-poster.jpg"),
+            PathBuf::from("fanart.png"),
+            PathBuf::from("Heat (1995).en.srt"),
+            PathBuf::from("Heat (1995).pt-BR.forced.ass"),
+            PathBuf::from("Other.de.srt"),
+            PathBuf::from("Heat (1995) Extended.srt"),
+            PathBuf::from("sample.txt"),
+        ];
+
+        let mut movie = Movie::default();
+        parse_movie_meta_files(&meta_files, &mut movie, "Heat (1995)", mock_encode);
+
+        assert_eq!(
+            movie.poster_path,
+            Some("Heat (1995)-poster.jpg".to_string())
+        );
+        assert_eq!(movie.fanart_path, Some("fanart.png".to_string()));
+        assert_eq!(
+            movie.subtitle_file_path,
+            Some("Heat (1995).en.srt".to_string())
+        );
+        assert_eq!(
+            movie.subtitles,
+            vec![
+                Subtitle::sidecar(
+                    "Heat (1995).en.srt".to_string(),
+                    SubtitleFormat::Srt,
+                    Some("en".to_string()),
+                ),
+                Subtitle::sidecar(
+                    "Heat (1995).pt-BR.forced.ass".to_string(),
+                    SubtitleFormat::Ass,
+                    Some("pt-BR".to_string()),
+                ),
+            ]
+        );
+    }
 }
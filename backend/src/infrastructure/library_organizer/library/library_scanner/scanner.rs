@@ -1,24 +1,123 @@
 use rayon::prelude::*;
 use std::{
+    collections::HashSet,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::atomic::{AtomicUsize, Ordering},
 };
 use tracing::*;
 use walkdir::WalkDir;
 
 use crate::{
-    domain::{media_library::model::Library, tv_show::model::TvShow},
+    domain::{
+        media_library::{
+            model::Library,
+            rescan::{diff_media_files, LibraryDiff, MediaFileState},
+        },
+        movie::model::Movie,
+        task::async_task::TaskContext,
+        tv_show::model::TvShow,
+    },
     infrastructure::{
-        event_dispatcher::{domain_event::DomainEvent, event_bus::EventBus, model::GeneralEvent},
-        library_organizer::library::processor::process_series,
+        artwork::store::ArtworkStore,
+        library_organizer::library::{
+            processor::{
+                collect_movie_file_states, collect_series_file_states, process_movie,
+                process_series,
+            },
+            utils::is_video_file,
+        },
     },
+    interfaces::http_api::controllers::api_models::LibraryCategory,
 };
 
-#[instrument(skip(event_bus))]
-pub fn scan_library(root_dir: &Path, event_bus: Arc<EventBus>) -> Library {
+/// The progress is reported once per parsed series or movie. When the task is cancelled
+/// the remaining entries are skipped, so the returned library is incomplete.
+#[instrument(skip(artwork_store, context))]
+pub fn scan_library(
+    root_dir: &Path,
+    category: LibraryCategory,
+    artwork_store: &ArtworkStore,
+    context: &TaskContext,
+) -> Library {
     debug!("Scanning library in: {:?}", root_dir);
 
-    let series_dirs: Vec<PathBuf> = WalkDir::new(root_dir)
+    let media_entries = collect_media_entries(root_dir, &category);
+    debug!("Found {} media entries", media_entries.len());
+
+    process_media_entries(&media_entries, &category, artwork_store, context)
+}
+
+/// Only fingerprints the files of the library and parses the entries which contain
+/// added or updated files, unchanged entries are not part of the returned library.
+/// When `entry_path` is given only that entry is rescanned, e.g. a single series directory.
+#[instrument(skip(stored, artwork_store, context))]
+pub fn rescan_library(
+    root_dir: &Path,
+    category: LibraryCategory,
+    entry_path: Option<&Path>,
+    mut stored: Vec<MediaFileState>,
+    artwork_store: &ArtworkStore,
+    context: &TaskContext,
+) -> (Library, LibraryDiff) {
+    debug!("Rescanning library in: {:?}", root_dir);
+
+    let media_entries = match entry_path {
+        Some(entry_path) => {
+            // Files outside of the entry are not scanned, so they must not be reported as removed
+            stored.retain(|state| Path::new(&state.video_file_path).starts_with(entry_path));
+            if is_media_entry(entry_path, &category) {
+                vec![entry_path.to_path_buf()]
+            } else {
+                vec![]
+            }
+        }
+        None => collect_media_entries(root_dir, &category),
+    };
+    debug!("Found {} media entries", media_entries.len());
+
+    let scanned: Vec<(PathBuf, MediaFileState)> = media_entries
+        .par_iter()
+        .flat_map(|entry| {
+            let states = match category {
+                LibraryCategory::Movie => collect_movie_file_states(entry),
+                LibraryCategory::TvShow | LibraryCategory::Animation => {
+                    collect_series_file_states(entry)
+                }
+            };
+            states
+                .into_iter()
+                .map(|state| (entry.clone(), state))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    debug!("Fingerprinted {} media files", scanned.len());
+
+    let scanned_states: Vec<MediaFileState> =
+        scanned.iter().map(|(_, state)| state.clone()).collect();
+    let diff = diff_media_files(&stored, &scanned_states);
+    debug!("Library diff: {:?}", diff.summary());
+
+    let changed_files: HashSet<&String> = diff.added.iter().chain(diff.updated.iter()).collect();
+    let changed_entries: Vec<PathBuf> = scanned
+        .iter()
+        .filter(|(_, state)| changed_files.contains(&state.video_file_path))
+        .map(|(entry, _)| entry.clone())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    debug!(
+        "Reprocessing {} changed media entries",
+        changed_entries.len()
+    );
+
+    (
+        process_media_entries(&changed_entries, &category, artwork_store, context),
+        diff,
+    )
+}
+
+fn collect_media_entries(root_dir: &Path, category: &LibraryCategory) -> Vec<PathBuf> {
+    WalkDir::new(root_dir)
         .min_depth(1)
         .max_depth(1)
         .into_iter()
@@ -26,25 +125,66 @@ pub fn scan_library(root_dir: &Path, event_bus: Arc<EventBus>) -> Library {
             let entry = entry.ok()?;
             let path = entry.path().to_path_buf();
 
-            if path.is_dir() {
+            if is_media_entry(&path, category) {
                 Some(path)
             } else {
                 None
             }
         })
-        .collect();
-    debug!("Found {} series directories", series_dirs.len());
+        .collect()
+}
 
-    if let Err(e) = event_bus.publish(DomainEvent::General(GeneralEvent::TaskProgressUpdated {
-        progress: 50.0,
-    })) {
-        error!("Failed to publish task progress updated event: {}", e);
-    }
+fn is_media_entry(path: &Path, category: &LibraryCategory) -> bool {
+    // Movie libraries may also keep loose video files in the root directory
+    path.is_dir() || (*category == LibraryCategory::Movie && is_video_file(path))
+}
+
+fn process_media_entries(
+    media_entries: &[PathBuf],
+    category: &LibraryCategory,
+    artwork_store: &ArtworkStore,
+    context: &TaskContext,
+) -> Library {
+    let processed = AtomicUsize::new(0);
+    let report_processed = || {
+        let processed = processed.fetch_add(1, Ordering::Relaxed) + 1;
+        context.report_steps(processed, media_entries.len());
+    };
 
-    let series_data: Vec<TvShow> = series_dirs.par_iter().map(process_series).collect();
-    debug!("Processed {} series", series_data.len());
+    match category {
+        LibraryCategory::Movie => {
+            let movie_data: Vec<Movie> = media_entries
+                .par_iter()
+                .filter(|_| !context.is_cancelled())
+                .filter_map(|entry| {
+                    let movie = process_movie(entry, artwork_store);
+                    report_processed();
+                    movie
+                })
+                .collect();
+            debug!("Processed {} movies", movie_data.len());
 
-    Library {
-        tv_show: series_data,
+            Library {
+                tv_show: vec![],
+                movie: movie_data,
+            }
+        }
+        LibraryCategory::TvShow | LibraryCategory::Animation => {
+            let series_data: Vec<TvShow> = media_entries
+                .par_iter()
+                .filter(|_| !context.is_cancelled())
+                .map(|entry| {
+                    let series = process_series(entry, artwork_store);
+                    report_processed();
+                    series
+                })
+                .collect();
+            debug!("Processed {} series", series_data.len());
+
+            Library {
+                tv_show: series_data,
+                movie: vec![],
+            }
+        }
     }
 }
@@ -1,20 +1,41 @@
 use regex::Regex;
-use std::path::PathBuf;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 use tracing::*;
 
 use crate::{
-    domain::{episode::model::Episode, season::model::Season, tv_show::model::TvShow},
-    infrastructure::library_organizer::library::{
-        library_parser::{
-            parsers::{parse_episode, parse_season, parse_tv_serie},
-            utils::{parse_meta_files, parse_seasons_nfo, parse_tv_series_nfo},
+    domain::{
+        episode::model::Episode,
+        media_info::model::MediaInfo,
+        media_library::rescan::MediaFileState,
+        movie::model::Movie,
+        season::model::Season,
+        subtitle::model::{parse_subtitle_language, Subtitle, SubtitleFormat},
+        tv_show::model::TvShow,
+    },
+    infrastructure::{
+        artwork::store::ArtworkStore,
+        library_organizer::library::{
+            library_parser::{
+                parsers::{parse_episode, parse_movie, parse_season, parse_tv_serie},
+                utils::{
+                    find_movie_nfo, is_sidecar_stem, parse_meta_files, parse_movie_meta_files,
+                    parse_movie_nfo, parse_seasons_nfo, parse_tv_series_nfo,
+                },
+            },
+            library_scanner::utils::{collect_files, partition_files},
+            utils::{file_fingerprint, is_video_file, store_optional_image},
+        },
+        video_pipeline::{
+            probe::{discover, media_info},
+            subtitle::probe_subtitle_streams,
         },
-        library_scanner::utils::{collect_files, partition_files},
-        utils::encode_optional_image,
     },
 };
 
-pub fn process_series(series_path: &PathBuf) -> TvShow {
+pub fn process_series(series_path: &PathBuf, artwork_store: &ArtworkStore) -> TvShow {
     // Collect all files in the series directory
     let series_files = match collect_files(series_path) {
         Ok(files) => files,
@@ -41,7 +62,10 @@ pub fn process_series(series_path: &PathBuf) -> TvShow {
     };
 
     // Parse tv serie meta files
-    parse_meta_files(&meta, &mut tv_serie, encode_optional_image);
+    parse_meta_files(&meta, &mut tv_serie, |path| {
+        store_optional_image(artwork_store, path)
+    });
+    tv_serie.metadata_modified_at = metadata_modified_at(&meta);
 
     // Parse season nfo files and build seasons
     let mut seasons_map = match parse_seasons_nfo(&meta, parse_season) {
@@ -60,7 +84,7 @@ pub fn process_series(series_path: &PathBuf) -> TvShow {
             None => return,
         };
 
-        let (season_number, episode_number) = match episode_pattern.captures(&file_name) {
+        let (season_number, episode_number, suffix) = match episode_pattern.captures(&file_name) {
             Some(caps) => {
                 // TODO: maybe we need to process the error here
                 let season_number = caps
@@ -71,7 +95,12 @@ pub fn process_series(series_path: &PathBuf) -> TvShow {
                     .get(2)
                     .and_then(|m| m.as_str().parse::<u8>().ok())
                     .unwrap_or_default();
-                (season_number, episode_number)
+                // Whatever follows the episode number, e.g. `.en` for sidecar subtitles
+                let suffix = caps
+                    .get(0)
+                    .map(|m| file_name[m.end()..].to_string())
+                    .unwrap_or_default();
+                (season_number, episode_number, suffix)
             }
             None => {
                 warn!("Error parsing episode file: {}", file_name);
@@ -104,10 +133,22 @@ pub fn process_series(series_path: &PathBuf) -> TvShow {
             "jpg" | "jpeg" | "png" | "webp" => {
                 let episode = season.episodes.get_mut(&episode_number).unwrap();
                 // TODO: what is the difference between thumb_image and thumbnail_image? fuck
-                episode.thumb_image = encode_optional_image(&Some(episode_dir.clone()));
+                episode.thumb_image =
+                    store_optional_image(artwork_store, &Some(episode_dir.clone()));
             }
-            "srt" | "ass" | "ssa" => {
-                // TODO: add subtitle to episode
+            "srt" | "ass" | "ssa" | "vtt" => {
+                let episode = season.episodes.get_mut(&episode_number).unwrap();
+                let file_path = episode_dir.to_string_lossy().to_string();
+                let format = SubtitleFormat::from_extension(&extension).unwrap_or_default();
+
+                if episode.subtitle_file_path.is_none() {
+                    episode.subtitle_file_path = Some(file_path.clone());
+                }
+                episode.subtitles.push(Subtitle::sidecar(
+                    file_path,
+                    format,
+                    parse_subtitle_language(&suffix),
+                ));
             }
             "nfo" => {
                 let existing_episode = season.episodes.get_mut(&episode_number).unwrap();
@@ -120,12 +161,225 @@ pub fn process_series(series_path: &PathBuf) -> TvShow {
                         }
                     };
                 existing_episode.merge(episode_from_nfo);
+                existing_episode.nfo_path = Some(episode_dir.to_string_lossy().to_string());
+                existing_episode.nfo_modified_at =
+                    file_fingerprint(episode_dir).map(|(_, modified_at)| modified_at);
             }
             _ => {}
         }
     });
 
+    // Fingerprint the video files so that the next rescan can skip unchanged episodes
+    seasons_map
+        .values_mut()
+        .flat_map(|season| season.episodes.values_mut())
+        .for_each(|episode| {
+            if let Some((file_size, file_modified_at)) =
+                file_fingerprint(Path::new(&episode.video_file_path))
+            {
+                episode.file_size = Some(file_size);
+                episode.file_modified_at = Some(file_modified_at);
+            }
+
+            let (media_info, subtitles) = probe_video_file(Path::new(&episode.video_file_path));
+            episode.subtitles.extend(subtitles);
+            // The probed duration is exact, the runtime of the nfo is often rounded or missing
+            if let Some(duration) = media_info.as_ref().and_then(|info| info.duration) {
+                episode.runtime = Some(duration.div_ceil(60).to_string());
+            }
+            episode.media_info = media_info;
+        });
+
     tv_serie.seasons = seasons_map;
 
     tv_serie
 }
+
+/// Builds a movie from either a movie folder or a loose video file in the library root.
+/// Returns `None` when no video file can be found, since a movie without one is not playable.
+pub fn process_movie(movie_path: &PathBuf, artwork_store: &ArtworkStore) -> Option<Movie> {
+    let (movie_dir, movie_files) = collect_movie_files(movie_path)?;
+    debug!("Found {} files", movie_files.len());
+
+    let video_file = match find_movie_video_file(&movie_files) {
+        Some(video_file) => video_file,
+        None => {
+            warn!("No video file found in: {:?}", movie_path);
+            return None;
+        }
+    };
+    let video_stem = video_file.file_stem()?.to_string_lossy().to_string();
+
+    let mut movie = match parse_movie_nfo(&movie_dir, &video_stem, movie_path.is_dir(), parse_movie)
+    {
+        Ok(movie) => movie,
+        Err(err) => {
+            warn!("Error parsing movie nfo file: {}", err);
+            Movie::default()
+        }
+    };
+
+    // Fall back to the folder or file name so that movies without nfo still show up
+    if movie.title.is_none() {
+        let fallback = if movie_path.is_dir() {
+            movie_path.file_name()
+        } else {
+            movie_path.file_stem()
+        };
+        movie.title = fallback.map(|name| name.to_string_lossy().to_string());
+    }
+
+    parse_movie_meta_files(&movie_files, &mut movie, &video_stem, |path| {
+        store_optional_image(artwork_store, path)
+    });
+
+    if let Some((file_size, file_modified_at)) = file_fingerprint(&video_file) {
+        movie.file_size = Some(file_size);
+        movie.file_modified_at = Some(file_modified_at);
+    }
+    movie.nfo_modified_at = movie
+        .nfo_path
+        .as_ref()
+        .and_then(|nfo_path| file_fingerprint(Path::new(nfo_path)))
+        .map(|(_, modified_at)| modified_at);
+    let (_, subtitles) = probe_video_file(&video_file);
+    movie.subtitles.extend(subtitles);
+    movie.video_file_path = video_file.to_string_lossy().to_string();
+
+    Some(movie)
+}
+
+/// Discovers the video file once for both its media info and its embedded subtitles.
+fn probe_video_file(video_file: &Path) -> (Option<MediaInfo>, Vec<Subtitle>) {
+    match discover(video_file) {
+        Ok(info) => (
+            Some(media_info(&info, video_file)),
+            probe_subtitle_streams(video_file, &info),
+        ),
+        Err(err) => {
+            warn!("Error probing {:?}: {}", video_file, err);
+            (None, vec![])
+        }
+    }
+}
+
+/// Collects the fingerprints of the episode files in a series directory without parsing
+/// any nfo or image, this is what the incremental rescan uses to find changed files.
+/// The nfo fingerprint of every episode also covers the series metadata, so that a changed
+/// `tvshow.nfo` or poster reprocesses the series.
+pub fn collect_series_file_states(series_path: &PathBuf) -> Vec<MediaFileState> {
+    let series_files = match collect_files(series_path) {
+        Ok(files) => files,
+        Err(err) => {
+            error!("Error collecting files: {}", err);
+            return vec![];
+        }
+    };
+
+    let episode_pattern = Regex::new(r"S(\d+)E(\d+)").expect("Invalid episode pattern");
+    let (meta, episodes) = partition_files(&series_files, &episode_pattern);
+    let series_modified_at = metadata_modified_at(&meta);
+
+    episodes
+        .iter()
+        .filter(|file| is_video_file(file))
+        .filter_map(|video_file| {
+            let nfo_file = video_file.with_extension("nfo");
+            media_file_state(video_file, nfo_file.exists().then_some(nfo_file.as_path()))
+        })
+        .map(|mut state| {
+            state.nfo_modified_at = state.nfo_modified_at.max(series_modified_at);
+            state
+        })
+        .collect()
+}
+
+/// Newest modification time of the series level files, e.g. `tvshow.nfo`, season nfo files
+/// and artwork.
+fn metadata_modified_at(meta: &[PathBuf]) -> Option<i64> {
+    meta.iter()
+        .filter_map(|file| file_fingerprint(file))
+        .map(|(_, modified_at)| modified_at)
+        .max()
+}
+
+pub fn collect_movie_file_states(movie_path: &PathBuf) -> Vec<MediaFileState> {
+    let (movie_dir, movie_files) = match collect_movie_files(movie_path) {
+        Some(files) => files,
+        None => return vec![],
+    };
+
+    let video_file = match find_movie_video_file(&movie_files) {
+        Some(video_file) => video_file,
+        None => return vec![],
+    };
+    let video_stem = match video_file.file_stem() {
+        Some(stem) => stem.to_string_lossy().to_string(),
+        None => return vec![],
+    };
+    let nfo_file = find_movie_nfo(&movie_dir, &video_stem, movie_path.is_dir());
+
+    media_file_state(&video_file, nfo_file.as_deref())
+        .into_iter()
+        .collect()
+}
+
+fn media_file_state(video_file: &Path, nfo_file: Option<&Path>) -> Option<MediaFileState> {
+    let (file_size, file_modified_at) = file_fingerprint(video_file)?;
+
+    Some(MediaFileState {
+        video_file_path: video_file.to_string_lossy().to_string(),
+        file_size: Some(file_size),
+        file_modified_at: Some(file_modified_at),
+        nfo_modified_at: nfo_file
+            .and_then(file_fingerprint)
+            .map(|(_, modified_at)| modified_at),
+    })
+}
+
+fn collect_movie_files(movie_path: &PathBuf) -> Option<(PathBuf, Vec<PathBuf>)> {
+    if movie_path.is_dir() {
+        return match collect_files(movie_path) {
+            Ok(files) => Some((movie_path.clone(), files)),
+            Err(err) => {
+                error!("Error collecting files: {}", err);
+                None
+            }
+        };
+    }
+
+    let movie_dir = movie_path.parent()?.to_path_buf();
+    let video_stem = movie_path.file_stem()?.to_string_lossy().to_string();
+    // Only pick up the sidecar files which belong to this video, e.g. `Aliens.en.srt` and
+    // `Aliens-poster.jpg` for `Aliens.mkv` but not `Alien.srt`
+    let artwork_stems = [
+        format!("{video_stem}-poster"),
+        format!("{video_stem}-fanart"),
+    ];
+    let files = fs::read_dir(&movie_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_stem()
+                    .map(|stem| {
+                        let stem = stem.to_string_lossy();
+                        is_sidecar_stem(&stem, &video_stem)
+                            || artwork_stems.contains(&stem.to_string())
+                    })
+                    .unwrap_or(false)
+        })
+        .collect();
+
+    Some((movie_dir, files))
+}
+
+fn find_movie_video_file(movie_files: &[PathBuf]) -> Option<PathBuf> {
+    // Extras and samples are usually much smaller than the feature itself
+    movie_files
+        .iter()
+        .filter(|file| is_video_file(file))
+        .max_by_key(|file| file.metadata().map(|m| m.len()).unwrap_or_default())
+        .cloned()
+}
@@ -1,12 +1,13 @@
-use base64::engine::general_purpose;
-use base64::Engine as _;
 use regex::Regex;
 use std::{
     fs,
     path::{Path, PathBuf},
+    time::UNIX_EPOCH,
 };
 use tracing::*;
 
+use crate::infrastructure::artwork::store::{artwork_url, ArtworkStore};
+
 #[instrument]
 pub fn extract_season_number(path: &Path) -> Option<u8> {
     trace!("Extracting season number from: {:?}", path);
@@ -45,28 +46,22 @@ pub fn extract_episode_number(path: &Path) -> Option<u32> {
     None
 }
 
-#[instrument]
-pub fn encode_optional_image(path: &Option<PathBuf>) -> Option<String> {
-    trace!("Encoding optional image: {:?}", path);
-
-    if let Some(image_path) = path {
-        if let Ok(image_data) = std::fs::read(image_path) {
-            let file_size = image_data.len();
-            trace!(
-                "Image data length: {} bytes ({:.2} MB)",
-                file_size,
-                file_size as f64 / 1_048_576.0
-            );
-
-            let base64_string = format!(
-                "data:image/png;base64,{}",
-                general_purpose::STANDARD.encode(image_data)
-            );
-            return Some(base64_string);
+/// Copies the image into the artwork store and returns the url it is served from.
+#[instrument(skip(artwork_store))]
+pub fn store_optional_image(
+    artwork_store: &ArtworkStore,
+    path: &Option<PathBuf>,
+) -> Option<String> {
+    trace!("Storing optional image: {:?}", path);
+
+    let image_path = path.as_ref()?;
+    match artwork_store.import(image_path) {
+        Ok(hash) => Some(artwork_url(&hash)),
+        Err(e) => {
+            warn!("Failed to store image {:?}: {:?}", image_path, e);
+            None
         }
     }
-
-    None
 }
 
 #[instrument]
@@ -126,3 +121,19 @@ pub fn find_associated_file(dir: &Path, base_name: &str, extensions: &[&str]) ->
 
     None
 }
+
+/// Returns the size and the modified time of a file, the time is a unix timestamp in seconds.
+#[instrument]
+pub fn file_fingerprint(path: &Path) -> Option<(i64, i64)> {
+    trace!("Reading file fingerprint: {:?}", path);
+
+    let metadata = fs::metadata(path).ok()?;
+    let modified_at = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs() as i64;
+
+    Some((metadata.len() as i64, modified_at))
+}
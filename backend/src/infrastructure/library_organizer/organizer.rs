@@ -1,14 +1,30 @@
 use actix::{Actor, Context, Handler, Message};
 use anyhow::Error;
-use std::{path::Path, sync::Arc};
+use std::path::Path;
 
-use super::library::library_scanner::scanner::scan_library;
+use super::library::library_scanner::scanner::{rescan_library, scan_library};
 use crate::{
-    domain::media_library::model::Library, infrastructure::event_dispatcher::event_bus::EventBus,
+    domain::{
+        media_library::{
+            model::Library,
+            rescan::{LibraryDiff, MediaFileState},
+        },
+        task::async_task::TaskContext,
+    },
+    infrastructure::artwork::store::ArtworkStore,
+    interfaces::http_api::controllers::api_models::LibraryCategory,
 };
 
-#[derive(Debug, Default)]
-pub struct ParserActor;
+#[derive(Debug)]
+pub struct ParserActor {
+    artwork_store: ArtworkStore,
+}
+
+impl ParserActor {
+    pub fn new(artwork_store: ArtworkStore) -> Self {
+        Self { artwork_store }
+    }
+}
 
 impl Actor for ParserActor {
     type Context = Context<Self>;
@@ -18,7 +34,8 @@ impl Actor for ParserActor {
 #[rtype(result = "Result<Library, Error>")]
 pub struct ScanLibrary {
     pub library_path: String,
-    pub event_bus: Arc<EventBus>,
+    pub category: LibraryCategory,
+    pub context: TaskContext,
 }
 
 impl Handler<ScanLibrary> for ParserActor {
@@ -26,8 +43,34 @@ impl Handler<ScanLibrary> for ParserActor {
 
     fn handle(&mut self, msg: ScanLibrary, _: &mut Self::Context) -> Self::Result {
         let root_dir = Path::new(&msg.library_path);
-        let library = scan_library(root_dir, msg.event_bus);
+        let library = scan_library(root_dir, msg.category, &self.artwork_store, &msg.context);
         // TODO: insert into database
         Ok(library)
     }
 }
+
+#[derive(Message)]
+#[rtype(result = "Result<(Library, LibraryDiff), Error>")]
+pub struct RescanLibrary {
+    pub library_path: String,
+    pub category: LibraryCategory,
+    pub entry_path: Option<String>,
+    pub stored: Vec<MediaFileState>,
+    pub context: TaskContext,
+}
+
+impl Handler<RescanLibrary> for ParserActor {
+    type Result = Result<(Library, LibraryDiff), Error>;
+
+    fn handle(&mut self, msg: RescanLibrary, _: &mut Self::Context) -> Self::Result {
+        let root_dir = Path::new(&msg.library_path);
+        Ok(rescan_library(
+            root_dir,
+            msg.category,
+            msg.entry_path.as_deref().map(Path::new),
+            msg.stored,
+            &self.artwork_store,
+            &msg.context,
+        ))
+    }
+}
@@ -0,0 +1,319 @@
+use anyhow::*;
+use notify::{Config, ErrorKind, Event, EventKind, PollWatcher, RecursiveMode, Watcher};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    result::Result::Ok,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, Mutex, RwLock},
+    time::{timeout_at, Instant},
+};
+use tracing::*;
+
+/// A top level entry of a watched library whose files changed, this is the series
+/// directory for tv shows and the movie directory or loose video file for movies.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LibraryEntryChange {
+    pub library_id: i64,
+    pub entry_path: PathBuf,
+}
+
+type WatcherEvent = notify::Result<Event>;
+
+#[derive(Clone)]
+pub struct LibraryWatcher {
+    roots: Arc<RwLock<HashMap<i64, PathBuf>>>,
+    watcher: Arc<Mutex<Box<dyn Watcher + Send>>>,
+    event_tx: mpsc::UnboundedSender<WatcherEvent>,
+    poll_interval: Duration,
+}
+
+impl LibraryWatcher {
+    /// Creates the watcher together with the receiver of the debounced entry changes.
+    /// Uses the native backend (inotify on linux) and falls back to polling when it is not available.
+    #[instrument]
+    pub fn new(
+        debounce: Duration,
+        poll_interval: Duration,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<LibraryEntryChange>)> {
+        let (event_tx, event_rx) = mpsc::unbounded_channel::<WatcherEvent>();
+        let (change_tx, change_rx) = mpsc::unbounded_channel::<LibraryEntryChange>();
+        let roots = Arc::new(RwLock::new(HashMap::<i64, PathBuf>::new()));
+
+        let watcher = match create_native_watcher(event_tx.clone()) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                warn!(
+                    "Native file watcher unavailable, falling back to polling: {}",
+                    e
+                );
+                create_poll_watcher(event_tx.clone(), poll_interval)?
+            }
+        };
+
+        tokio::spawn(debounce_events(
+            event_rx,
+            roots.clone(),
+            debounce,
+            change_tx,
+        ));
+
+        Ok((
+            Self {
+                roots,
+                watcher: Arc::new(Mutex::new(watcher)),
+                event_tx,
+                poll_interval,
+            },
+            change_rx,
+        ))
+    }
+
+    #[instrument(skip(self))]
+    pub async fn watch(&self, library_id: i64, directory: &Path) -> Result<()> {
+        let mut watcher = self.watcher.lock().await;
+
+        let current = self.roots.read().await.get(&library_id).cloned();
+        if let Some(current) = current {
+            if current == directory {
+                return Ok(());
+            }
+            if let Err(e) = watcher.unwatch(&current) {
+                warn!("Failed to unwatch {:?}: {}", current, e);
+            }
+            self.roots.write().await.remove(&library_id);
+        }
+
+        if let Err(e) = watcher.watch(directory, RecursiveMode::Recursive) {
+            if !matches!(e.kind, ErrorKind::MaxFilesWatch) {
+                return Err(anyhow!("Failed to watch {:?}: {}", directory, e));
+            }
+
+            // Large libraries can exhaust the inotify watch limit, polling has no such limit
+            warn!("File watch limit reached, falling back to polling");
+            let mut poll_watcher = create_poll_watcher(self.event_tx.clone(), self.poll_interval)?;
+            for (id, root) in self.roots.read().await.iter() {
+                if *id != library_id {
+                    poll_watcher.watch(root, RecursiveMode::Recursive)?;
+                }
+            }
+            poll_watcher.watch(directory, RecursiveMode::Recursive)?;
+            *watcher = poll_watcher;
+        }
+
+        debug!("Watching library {} in {:?}", library_id, directory);
+        self.roots
+            .write()
+            .await
+            .insert(library_id, directory.to_path_buf());
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn unwatch(&self, library_id: i64) -> Result<()> {
+        let mut watcher = self.watcher.lock().await;
+
+        let root = match self.roots.write().await.remove(&library_id) {
+            Some(root) => root,
+            None => return Ok(()),
+        };
+
+        debug!("Unwatching library {} in {:?}", library_id, root);
+        watcher
+            .unwatch(&root)
+            .map_err(|e| anyhow!("Failed to unwatch {:?}: {}", root, e))
+    }
+}
+
+fn create_native_watcher(
+    event_tx: mpsc::UnboundedSender<WatcherEvent>,
+) -> notify::Result<Box<dyn Watcher + Send>> {
+    let watcher = notify::recommended_watcher(move |event: WatcherEvent| {
+        let _ = event_tx.send(event);
+    })?;
+
+    Ok(Box::new(watcher))
+}
+
+fn create_poll_watcher(
+    event_tx: mpsc::UnboundedSender<WatcherEvent>,
+    poll_interval: Duration,
+) -> Result<Box<dyn Watcher + Send>> {
+    let watcher = PollWatcher::new(
+        move |event: WatcherEvent| {
+            let _ = event_tx.send(event);
+        },
+        Config::default().with_poll_interval(poll_interval),
+    )?;
+
+    Ok(Box::new(watcher))
+}
+
+async fn debounce_events(
+    mut event_rx: mpsc::UnboundedReceiver<WatcherEvent>,
+    roots: Arc<RwLock<HashMap<i64, PathBuf>>>,
+    delay: Duration,
+    change_tx: mpsc::UnboundedSender<LibraryEntryChange>,
+) {
+    let mut debouncer = Debouncer::new(delay);
+
+    loop {
+        let received = match debouncer.next_deadline() {
+            Some(deadline) => match timeout_at(deadline, event_rx.recv()).await {
+                Ok(received) => received,
+                Err(_) => {
+                    for change in debouncer.drain_ready(Instant::now()) {
+                        debug!("Library entry changed: {:?}", change);
+                        if change_tx.send(change).is_err() {
+                            return;
+                        }
+                    }
+                    continue;
+                }
+            },
+            None => event_rx.recv().await,
+        };
+
+        let event = match received {
+            Some(Ok(event)) => event,
+            Some(Err(e)) => {
+                warn!("File watcher error: {}", e);
+                continue;
+            }
+            None => break,
+        };
+
+        if matches!(event.kind, EventKind::Access(_)) {
+            continue;
+        }
+
+        let roots = roots.read().await;
+        for path in &event.paths {
+            if let Some(change) = resolve_entry(&roots, path) {
+                debouncer.push(change, Instant::now());
+            }
+        }
+    }
+
+    debug!("File watcher stopped");
+}
+
+/// Maps a changed path to the top level entry of the library which contains it,
+/// changes to the library root itself are ignored.
+fn resolve_entry(roots: &HashMap<i64, PathBuf>, path: &Path) -> Option<LibraryEntryChange> {
+    roots.iter().find_map(|(library_id, root)| {
+        let relative = path.strip_prefix(root).ok()?;
+        let entry = relative.components().next()?;
+
+        Some(LibraryEntryChange {
+            library_id: *library_id,
+            entry_path: root.join(entry),
+        })
+    })
+}
+
+/// Collects bursts of changes per entry and only releases an entry once it has been
+/// quiet for the whole delay, e.g. while a season is still being copied.
+struct Debouncer {
+    delay: Duration,
+    pending: HashMap<LibraryEntryChange, Instant>,
+}
+
+impl Debouncer {
+    fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: HashMap::new(),
+        }
+    }
+
+    fn push(&mut self, change: LibraryEntryChange, now: Instant) {
+        self.pending.insert(change, now + self.delay);
+    }
+
+    fn next_deadline(&self) -> Option<Instant> {
+        self.pending.values().min().copied()
+    }
+
+    fn drain_ready(&mut self, now: Instant) -> Vec<LibraryEntryChange> {
+        let ready: Vec<LibraryEntryChange> = self
+            .pending
+            .iter()
+            .filter(|(_, deadline)| **deadline <= now)
+            .map(|(change, _)| change.clone())
+            .collect();
+
+        for change in &ready {
+            self.pending.remove(change);
+        }
+
+        ready
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_roots() -> HashMap<i64, PathBuf> {
+        HashMap::from([
+            (1, PathBuf::from("/media/tv")),
+            (2, PathBuf::from("/media/movies")),
+        ])
+    }
+
+    #[test]
+    fn test_resolve_entry_returns_top_level_directory() {
+        let roots = create_roots();
+
+        let change = resolve_entry(
+            &roots,
+            Path::new("/media/tv/Show/Season 01/Show - S01E01.mkv"),
+        );
+
+        assert_eq!(
+            change,
+            Some(LibraryEntryChange {
+                library_id: 1,
+                entry_path: PathBuf::from("/media/tv/Show"),
+            })
+        );
+    }
+
+    #[test]
+    fn test_resolve_entry_ignores_root_and_unknown_paths() {
+        let roots = create_roots();
+
+        assert_eq!(resolve_entry(&roots, Path::new("/media/tv")), None);
+        assert_eq!(
+            resolve_entry(&roots, Path::new("/other/Show/file.mkv")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_debouncer_waits_for_quiet_period() {
+        let mut debouncer = Debouncer::new(Duration::from_secs(5));
+        let change = LibraryEntryChange {
+            library_id: 1,
+            entry_path: PathBuf::from("/media/tv/Show"),
+        };
+        let start = Instant::now();
+
+        debouncer.push(change.clone(), start);
+        debouncer.push(change.clone(), start + Duration::from_secs(3));
+
+        assert!(debouncer
+            .drain_ready(start + Duration::from_secs(5))
+            .is_empty());
+        assert_eq!(
+            debouncer.drain_ready(start + Duration::from_secs(8)),
+            vec![change]
+        );
+        assert_eq!(debouncer.next_deadline(), None);
+    }
+}
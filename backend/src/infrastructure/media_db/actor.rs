@@ -7,40 +7,85 @@ use ts_rs::TS;
 use crate::{
     define_actor_message_handler,
     domain::{
-        episode::model::Episode,
+        episode::model::{Episode, EpisodeVideo},
+        library::model::LibraryStatus,
         media_actor::model::MediaActor,
+        media_item::model::MediaFilter,
         media_library::{
             constant::SENTINEL_LIBRARY_ID,
             model::{LibraryBrief, LibraryPoster},
+            rescan::MediaFileState,
+            schedule::{LibraryScanSchedule, ScanSchedule},
         },
+        movie::model::Movie,
+        playback::model::{PlayableMedia, PlaybackProgress},
+        search::model::SearchResult,
         season::model::Season,
+        subtitle::model::Subtitle,
+        task::async_task::{TaskRecord, TaskStatus},
         tv_show::model::TvShow,
+        user::{
+            access::AccessScope,
+            api_key::{ApiKey, ApiKeyGrant, ApiKeyScope},
+            model::{User, UserCredentials},
+        },
     },
     infrastructure::media_db::{
         category::query::check_category_exists,
         database::Database,
         episode::{
             create::save_episode,
-            query::{query_episodes, query_media_episodes},
+            query::{
+                query_episodes, query_episodes_without_thumbnail, query_library_episode_videos,
+                query_media_episodes,
+            },
+            update::{update_episode, update_episode_thumbnail},
         },
         genre::create::save_genre,
         library::{
             create::save_library,
-            delete::delete_library,
-            query::{query_library, query_library_posters},
-            update::{populate_library_metadata, update_library},
+            delete::{delete_library, delete_scan_schedule, soft_delete_media_files},
+            query::{
+                query_library, query_library_media_files, query_library_posters,
+                query_scan_schedules,
+            },
+            update::{
+                populate_library_metadata, save_scan_schedule, update_library,
+                update_library_status,
+            },
         },
         media_actor::create::save_actor,
         media_item::query::{
             query_library_media, query_library_media_episodes, query_library_medias,
-            query_media_by_id,
+            query_media_by_id, query_tv_show_visible, query_video_file_path,
+        },
+        movie::{
+            create::save_movie,
+            query::{query_library_movie, query_library_movies, query_movie_visible},
+            update::update_movie,
         },
+        playback::{
+            create::save_playback_progress,
+            query::{query_continue_watching, query_next_up},
+        },
+        search::query::query_search,
         season::{create::save_season, query::query_seasons},
         studio::create::save_studio,
+        subtitle::query::query_subtitles,
+        task::{create::save_task_record, query::query_task_records},
         tv_show::create::save_tv_show,
+        user::{
+            create::{save_api_key, save_session, save_user},
+            delete::{delete_expired_sessions, delete_session},
+            query::{
+                query_api_key_grant, query_api_keys, query_session_user, query_user_count,
+                query_user_credentials, query_user_library_ids, query_users,
+            },
+            update::{revoke_api_key, update_api_key_last_used, update_user_access},
+        },
     },
     interfaces::{
-        dtos::{EpisodeDto, MediaItemDto, SeasonDto},
+        dtos::{EpisodeDto, MediaItemDto, MediaPageDto, SeasonDto, WatchingEpisodeDto},
         http_api::controllers::api_models::{LibraryCategory, SaveLibraryPayload},
     },
     shared::util_traits::map_rows,
@@ -220,6 +265,209 @@ define_actor_message_handler!(
     error_return = ()
 );
 
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "i64")]
+pub struct SaveMovie {
+    pub movie: Movie,
+    pub library_id: i64,
+}
+
+impl Display for SaveMovie {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SaveMovie({:?}, library_id: {})",
+            self.movie.title, self.library_id
+        )
+    }
+}
+
+define_actor_message_handler!(
+    message_type = SaveMovie,
+    return_type = i64,
+    db_call = |pool, query_manager, msg: SaveMovie| save_movie(
+        pool,
+        query_manager,
+        msg.movie,
+        msg.library_id
+    ),
+    success_return = |res| res,
+    error_return = -1
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "MediaPageDto")]
+pub struct QueryLibraryMovies {
+    pub library_id: i64,
+    pub filter: MediaFilter,
+    pub max_certification_level: Option<i64>,
+}
+
+impl Display for QueryLibraryMovies {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GetLibraryMovies({})", self.library_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryLibraryMovies,
+    return_type = MediaPageDto,
+    db_call = |pool, query_manager, msg: QueryLibraryMovies| query_library_movies(
+        pool,
+        query_manager,
+        map_rows,
+        msg.library_id,
+        msg.filter,
+        msg.max_certification_level
+    ),
+    success_return = |res| res,
+    error_return = MediaPageDto::default()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Option<MediaItemDto>")]
+pub struct QueryLibraryMovie {
+    pub library_id: i64,
+    pub movie_id: i64,
+}
+
+impl Display for QueryLibraryMovie {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "GetLibraryMovie(library_id: {}, movie_id: {})",
+            self.library_id, self.movie_id
+        )
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryLibraryMovie,
+    return_type = Option<MediaItemDto>,
+    db_call = |pool, query_manager, msg: QueryLibraryMovie| query_library_movie(
+        pool,
+        query_manager,
+        msg.library_id,
+        msg.movie_id
+    ),
+    success_return = |res| res,
+    error_return = None
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "()")]
+pub struct UpdateEpisode {
+    pub episode: Episode,
+}
+
+impl Display for UpdateEpisode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UpdateEpisode({})", self.episode.video_file_path)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = UpdateEpisode,
+    return_type = (),
+    db_call =
+        |pool, query_manager, msg: UpdateEpisode| update_episode(pool, query_manager, msg.episode),
+    success_return = |_| (),
+    error_return = ()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "()")]
+pub struct UpdateMovie {
+    pub movie: Movie,
+}
+
+impl Display for UpdateMovie {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UpdateMovie({})", self.movie.video_file_path)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = UpdateMovie,
+    return_type = (),
+    db_call = |pool, query_manager, msg: UpdateMovie| update_movie(pool, query_manager, msg.movie),
+    success_return = |_| (),
+    error_return = ()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Vec<MediaFileState>")]
+pub struct QueryLibraryMediaFiles {
+    pub library_id: i64,
+}
+
+impl Display for QueryLibraryMediaFiles {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "GetLibraryMediaFiles({})", self.library_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryLibraryMediaFiles,
+    return_type = Vec<MediaFileState>,
+    db_call = |pool, query_manager, msg: QueryLibraryMediaFiles| query_library_media_files(pool, query_manager, map_rows, msg.library_id),
+    success_return = |res| res,
+    error_return = Vec::<MediaFileState>::new()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "()")]
+pub struct SoftDeleteMediaFiles {
+    pub video_file_paths: Vec<String>,
+}
+
+impl Display for SoftDeleteMediaFiles {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SoftDeleteMediaFiles({} files)",
+            self.video_file_paths.len()
+        )
+    }
+}
+
+define_actor_message_handler!(
+    message_type = SoftDeleteMediaFiles,
+    return_type = (),
+    db_call = |pool, query_manager, msg: SoftDeleteMediaFiles| soft_delete_media_files(
+        pool,
+        query_manager,
+        msg.video_file_paths
+    ),
+    success_return = |_| (),
+    error_return = ()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Vec<Subtitle>")]
+pub struct QuerySubtitles {
+    pub video_file_path: String,
+}
+
+impl Display for QuerySubtitles {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QuerySubtitles({})", self.video_file_path)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QuerySubtitles,
+    return_type = Vec<Subtitle>,
+    db_call = |pool, query_manager, msg: QuerySubtitles| query_subtitles(
+        pool,
+        query_manager,
+        map_rows,
+        msg.video_file_path
+    ),
+    success_return = |res| res,
+    error_return = Vec::<Subtitle>::new()
+);
+
 #[derive(Debug, Serialize, Deserialize, TS, Message)]
 #[rtype(result = "Vec<MediaItemDto>")]
 pub struct QueryMediaItemsByMediaLibraryId(pub i64);
@@ -270,9 +518,11 @@ define_actor_message_handler!(
 );
 
 #[derive(Debug, Serialize, Deserialize, TS, Message)]
-#[rtype(result = "Vec<MediaItemDto>")]
+#[rtype(result = "MediaPageDto")]
 pub struct QueryLibraryMedias {
     pub library_id: i64,
+    pub filter: MediaFilter,
+    pub max_certification_level: Option<i64>,
 }
 
 impl Display for QueryLibraryMedias {
@@ -283,10 +533,17 @@ impl Display for QueryLibraryMedias {
 
 define_actor_message_handler!(
     message_type = QueryLibraryMedias,
-    return_type = Vec<MediaItemDto>,
-    db_call = |pool, query_manager, msg: QueryLibraryMedias| query_library_medias(pool, query_manager, map_rows, msg.library_id),
+    return_type = MediaPageDto,
+    db_call = |pool, query_manager, msg: QueryLibraryMedias| query_library_medias(
+        pool,
+        query_manager,
+        map_rows,
+        msg.library_id,
+        msg.filter,
+        msg.max_certification_level
+    ),
     success_return = |res| res,
-    error_return = Vec::<MediaItemDto>::new()
+    error_return = MediaPageDto::default()
 );
 
 #[derive(Debug, Serialize, Deserialize, TS, Message)]
@@ -294,6 +551,8 @@ define_actor_message_handler!(
 pub struct QueryLibraryMediaEpisodes {
     pub library_id: i64,
     pub media_id: i64,
+    /// The user whose playback progress is attached to the episodes
+    pub user_id: i64,
 }
 
 impl Display for QueryLibraryMediaEpisodes {
@@ -315,6 +574,7 @@ define_actor_message_handler!(
         map_rows,
         msg.library_id,
         msg.media_id,
+        msg.user_id,
     ),
     success_return = |res| res,
     error_return = Vec::<EpisodeDto>::new()
@@ -348,6 +608,8 @@ define_actor_message_handler!(
 #[rtype(result = "Vec<EpisodeDto>")]
 pub struct QueryMediaEpisodes {
     pub media_id: i64,
+    /// The user whose playback progress is attached to the episodes
+    pub user_id: i64,
 }
 
 impl Display for QueryMediaEpisodes {
@@ -364,6 +626,7 @@ define_actor_message_handler!(
         query_manager,
         map_rows,
         msg.media_id,
+        msg.user_id,
     ),
     success_return = |res| res,
     error_return = Vec::<EpisodeDto>::new()
@@ -524,6 +787,117 @@ define_actor_message_handler!(
     error_return = ()
 );
 
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "()")]
+pub struct UpdateLibraryStatus {
+    pub library_id: i64,
+    pub status: LibraryStatus,
+    pub error: Option<String>,
+}
+
+impl Display for UpdateLibraryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "UpdateLibraryStatus({}, {:?})",
+            self.library_id, self.status
+        )
+    }
+}
+
+define_actor_message_handler!(
+    message_type = UpdateLibraryStatus,
+    return_type = (),
+    db_call = |pool, query_manager, msg: UpdateLibraryStatus| update_library_status(
+        pool,
+        query_manager,
+        msg.library_id,
+        msg.status,
+        msg.error
+    ),
+    success_return = |_| (),
+    error_return = ()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Vec<LibraryScanSchedule>")]
+pub struct QueryScanSchedules {
+    pub library_id: Option<i64>,
+}
+
+impl Display for QueryScanSchedules {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryScanSchedules({:?})", self.library_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryScanSchedules,
+    return_type = Vec<LibraryScanSchedule>,
+    db_call = |pool, query_manager, msg: QueryScanSchedules| query_scan_schedules(
+        pool,
+        query_manager,
+        map_rows,
+        msg.library_id
+    ),
+    success_return = |res| res,
+    error_return = Vec::<LibraryScanSchedule>::new()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "()")]
+pub struct SaveScanSchedule {
+    pub library_id: i64,
+    pub schedule: ScanSchedule,
+}
+
+impl Display for SaveScanSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SaveScanSchedule({}, {:?})",
+            self.library_id, self.schedule
+        )
+    }
+}
+
+define_actor_message_handler!(
+    message_type = SaveScanSchedule,
+    return_type = (),
+    db_call = |pool, query_manager, msg: SaveScanSchedule| save_scan_schedule(
+        pool,
+        query_manager,
+        msg.library_id,
+        msg.schedule
+    ),
+    success_return = |_| (),
+    error_return = ()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "()")]
+pub struct DeleteScanSchedule {
+    pub library_id: i64,
+}
+
+impl Display for DeleteScanSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DeleteScanSchedule({})", self.library_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = DeleteScanSchedule,
+    return_type = (),
+    db_call = |pool, query_manager, msg: DeleteScanSchedule| delete_scan_schedule(
+        pool,
+        query_manager,
+        msg.library_id
+    ),
+    success_return = |_| (),
+    error_return = ()
+);
+
 #[derive(Debug, Serialize, Deserialize, TS, Message)]
 #[rtype(result = "bool")]
 pub struct ValidateCategory {
@@ -547,3 +921,696 @@ define_actor_message_handler!(
     success_return = |_| true,
     error_return = false
 );
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "()")]
+pub struct SavePlaybackProgress {
+    pub user_id: i64,
+    pub progress: PlaybackProgress,
+}
+
+impl Display for SavePlaybackProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "SavePlaybackProgress(user {}, episode {} at {}s)",
+            self.user_id, self.progress.episode_id, self.progress.position
+        )
+    }
+}
+
+define_actor_message_handler!(
+    message_type = SavePlaybackProgress,
+    return_type = (),
+    db_call = |pool, query_manager, msg: SavePlaybackProgress| save_playback_progress(
+        pool,
+        query_manager,
+        msg.user_id,
+        msg.progress
+    ),
+    success_return = |_| (),
+    error_return = ()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Vec<WatchingEpisodeDto>")]
+pub struct QueryContinueWatching {
+    pub limit: i64,
+    pub scope: AccessScope,
+    pub user_id: i64,
+}
+
+impl Display for QueryContinueWatching {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryContinueWatching(limit: {})", self.limit)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryContinueWatching,
+    return_type = Vec<WatchingEpisodeDto>,
+    db_call = |pool, query_manager, msg: QueryContinueWatching| query_continue_watching(
+        pool,
+        query_manager,
+        map_rows,
+        msg.limit,
+        msg.scope,
+        msg.user_id
+    ),
+    success_return = |res| res,
+    error_return = Vec::<WatchingEpisodeDto>::new()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Vec<WatchingEpisodeDto>")]
+pub struct QueryNextUp {
+    pub limit: i64,
+    pub scope: AccessScope,
+    pub user_id: i64,
+}
+
+impl Display for QueryNextUp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryNextUp(limit: {})", self.limit)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryNextUp,
+    return_type = Vec<WatchingEpisodeDto>,
+    db_call = |pool, query_manager, msg: QueryNextUp| query_next_up(
+        pool,
+        query_manager,
+        map_rows,
+        msg.limit,
+        msg.scope,
+        msg.user_id
+    ),
+    success_return = |res| res,
+    error_return = Vec::<WatchingEpisodeDto>::new()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Vec<SearchResult>")]
+pub struct QuerySearch {
+    pub fts_query: String,
+    pub limit: i64,
+    pub scope: AccessScope,
+}
+
+impl Display for QuerySearch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QuerySearch({}, limit: {})", self.fts_query, self.limit)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QuerySearch,
+    return_type = Vec<SearchResult>,
+    db_call = |pool, query_manager, msg: QuerySearch| query_search(
+        pool,
+        query_manager,
+        map_rows,
+        msg.fts_query,
+        msg.limit,
+        msg.scope
+    ),
+    success_return = |res| res,
+    error_return = Vec::<SearchResult>::new()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Vec<EpisodeVideo>")]
+pub struct QueryEpisodesWithoutThumbnail {
+    pub library_id: i64,
+}
+
+impl Display for QueryEpisodesWithoutThumbnail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryEpisodesWithoutThumbnail({})", self.library_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryEpisodesWithoutThumbnail,
+    return_type = Vec<EpisodeVideo>,
+    db_call = |pool, query_manager, msg: QueryEpisodesWithoutThumbnail| query_episodes_without_thumbnail(
+        pool,
+        query_manager,
+        map_rows,
+        msg.library_id
+    ),
+    success_return = |res| res,
+    error_return = Vec::<EpisodeVideo>::new()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "()")]
+pub struct SaveEpisodeThumbnail {
+    pub episode_id: i64,
+    pub thumb_image: String,
+}
+
+impl Display for SaveEpisodeThumbnail {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SaveEpisodeThumbnail(episode {})", self.episode_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = SaveEpisodeThumbnail,
+    return_type = (),
+    db_call = |pool, query_manager, msg: SaveEpisodeThumbnail| update_episode_thumbnail(
+        pool,
+        query_manager,
+        msg.episode_id,
+        msg.thumb_image
+    ),
+    success_return = |_| (),
+    error_return = ()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Vec<EpisodeVideo>")]
+pub struct QueryLibraryEpisodeVideos {
+    pub library_id: i64,
+}
+
+impl Display for QueryLibraryEpisodeVideos {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryLibraryEpisodeVideos({})", self.library_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryLibraryEpisodeVideos,
+    return_type = Vec<EpisodeVideo>,
+    db_call = |pool, query_manager, msg: QueryLibraryEpisodeVideos| query_library_episode_videos(
+        pool,
+        query_manager,
+        map_rows,
+        msg.library_id
+    ),
+    success_return = |res| res,
+    error_return = Vec::<EpisodeVideo>::new()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "i64")]
+pub struct SaveUser {
+    pub username: String,
+    pub password_hash: String,
+    pub is_admin: bool,
+}
+
+impl Display for SaveUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SaveUser({})", self.username)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = SaveUser,
+    return_type = i64,
+    db_call = |pool, query_manager, msg: SaveUser| save_user(
+        pool,
+        query_manager,
+        msg.username,
+        msg.password_hash,
+        msg.is_admin
+    ),
+    success_return = |res| res,
+    error_return = -1
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Vec<User>")]
+pub struct QueryUsers;
+
+impl Display for QueryUsers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryUsers")
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryUsers,
+    return_type = Vec<User>,
+    db_call = |pool, query_manager, _msg: QueryUsers| query_users(pool, query_manager, map_rows),
+    success_return = |res| res,
+    error_return = Vec::<User>::new()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Option<UserCredentials>")]
+pub struct QueryUserCredentials {
+    pub username: String,
+}
+
+impl Display for QueryUserCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryUserCredentials({})", self.username)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryUserCredentials,
+    return_type = Option<UserCredentials>,
+    db_call = |pool, query_manager, msg: QueryUserCredentials| query_user_credentials(
+        pool,
+        query_manager,
+        map_rows,
+        msg.username
+    ),
+    success_return = |res| res,
+    error_return = None
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "i64")]
+pub struct QueryUserCount;
+
+impl Display for QueryUserCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryUserCount")
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryUserCount,
+    return_type = i64,
+    db_call = |pool, query_manager, _msg: QueryUserCount| query_user_count(pool, query_manager),
+    success_return = |res| res,
+    error_return = -1
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "bool")]
+pub struct SaveSession {
+    pub session_id: String,
+    pub user_id: i64,
+    pub ttl_seconds: i64,
+}
+
+impl Display for SaveSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SaveSession(user {})", self.user_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = SaveSession,
+    return_type = bool,
+    db_call = |pool, query_manager, msg: SaveSession| save_session(
+        pool,
+        query_manager,
+        msg.session_id,
+        msg.user_id,
+        msg.ttl_seconds
+    ),
+    success_return = |_| true,
+    error_return = false
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Option<User>")]
+pub struct QuerySessionUser {
+    pub session_id: String,
+}
+
+impl Display for QuerySessionUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QuerySessionUser")
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QuerySessionUser,
+    return_type = Option<User>,
+    db_call = |pool, query_manager, msg: QuerySessionUser| query_session_user(
+        pool,
+        query_manager,
+        map_rows,
+        msg.session_id
+    ),
+    success_return = |res| res,
+    error_return = None
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "()")]
+pub struct DeleteSession {
+    pub session_id: String,
+}
+
+impl Display for DeleteSession {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DeleteSession")
+    }
+}
+
+define_actor_message_handler!(
+    message_type = DeleteSession,
+    return_type = (),
+    db_call = |pool, query_manager, msg: DeleteSession| delete_session(
+        pool,
+        query_manager,
+        msg.session_id
+    ),
+    success_return = |_| (),
+    error_return = ()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "()")]
+pub struct DeleteExpiredSessions;
+
+impl Display for DeleteExpiredSessions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DeleteExpiredSessions")
+    }
+}
+
+define_actor_message_handler!(
+    message_type = DeleteExpiredSessions,
+    return_type = (),
+    db_call = |pool, query_manager, _msg: DeleteExpiredSessions| delete_expired_sessions(
+        pool,
+        query_manager
+    ),
+    success_return = |_| (),
+    error_return = ()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Vec<i64>")]
+pub struct QueryUserLibraryIds {
+    pub user_id: i64,
+}
+
+impl Display for QueryUserLibraryIds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryUserLibraryIds(user {})", self.user_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryUserLibraryIds,
+    return_type = Vec<i64>,
+    db_call = |pool, query_manager, msg: QueryUserLibraryIds| query_user_library_ids(
+        pool,
+        query_manager,
+        msg.user_id
+    ),
+    success_return = |res| res,
+    error_return = Vec::<i64>::new()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "bool")]
+pub struct SaveUserAccess {
+    pub user_id: i64,
+    pub library_ids: Vec<i64>,
+    pub max_certification: Option<String>,
+}
+
+impl Display for SaveUserAccess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SaveUserAccess(user {})", self.user_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = SaveUserAccess,
+    return_type = bool,
+    db_call = |pool, query_manager, msg: SaveUserAccess| update_user_access(
+        pool,
+        query_manager,
+        msg.user_id,
+        msg.library_ids,
+        msg.max_certification
+    ),
+    success_return = |_| true,
+    error_return = false
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "i64")]
+pub struct SaveApiKey {
+    pub user_id: i64,
+    pub name: String,
+    pub scope: ApiKeyScope,
+    pub key_hash: String,
+}
+
+impl Display for SaveApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SaveApiKey(user {}, {})", self.user_id, self.name)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = SaveApiKey,
+    return_type = i64,
+    db_call = |pool, query_manager, msg: SaveApiKey| save_api_key(
+        pool,
+        query_manager,
+        msg.user_id,
+        msg.name,
+        msg.scope,
+        msg.key_hash
+    ),
+    success_return = |res| res,
+    error_return = -1
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Vec<ApiKey>")]
+pub struct QueryApiKeys {
+    pub user_id: i64,
+}
+
+impl Display for QueryApiKeys {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryApiKeys(user {})", self.user_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryApiKeys,
+    return_type = Vec<ApiKey>,
+    db_call = |pool, query_manager, msg: QueryApiKeys| query_api_keys(
+        pool,
+        query_manager,
+        map_rows,
+        msg.user_id
+    ),
+    success_return = |res| res,
+    error_return = Vec::<ApiKey>::new()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Option<ApiKeyGrant>")]
+pub struct QueryApiKeyGrant {
+    pub key_hash: String,
+}
+
+impl Display for QueryApiKeyGrant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryApiKeyGrant")
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryApiKeyGrant,
+    return_type = Option<ApiKeyGrant>,
+    db_call = |pool, query_manager, msg: QueryApiKeyGrant| query_api_key_grant(
+        pool,
+        query_manager,
+        map_rows,
+        msg.key_hash
+    ),
+    success_return = |res| res,
+    error_return = None
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "()")]
+pub struct UpdateApiKeyLastUsed {
+    pub api_key_id: i64,
+}
+
+impl Display for UpdateApiKeyLastUsed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "UpdateApiKeyLastUsed({})", self.api_key_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = UpdateApiKeyLastUsed,
+    return_type = (),
+    db_call = |pool, query_manager, msg: UpdateApiKeyLastUsed| update_api_key_last_used(
+        pool,
+        query_manager,
+        msg.api_key_id
+    ),
+    success_return = |_| (),
+    error_return = ()
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "bool")]
+pub struct RevokeApiKey {
+    pub api_key_id: i64,
+    pub user_id: Option<i64>,
+}
+
+impl Display for RevokeApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RevokeApiKey({})", self.api_key_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = RevokeApiKey,
+    return_type = bool,
+    db_call = |pool, query_manager, msg: RevokeApiKey| revoke_api_key(
+        pool,
+        query_manager,
+        msg.api_key_id,
+        msg.user_id
+    ),
+    success_return = |res| res,
+    error_return = false
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "bool")]
+pub struct QueryTvShowVisible {
+    pub tv_show_id: i64,
+    pub scope: AccessScope,
+}
+
+impl Display for QueryTvShowVisible {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryTvShowVisible({})", self.tv_show_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryTvShowVisible,
+    return_type = bool,
+    db_call = |pool, query_manager, msg: QueryTvShowVisible| query_tv_show_visible(
+        pool,
+        query_manager,
+        msg.tv_show_id,
+        msg.scope
+    ),
+    success_return = |res| res,
+    error_return = false
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "bool")]
+pub struct QueryMovieVisible {
+    pub movie_id: i64,
+    pub scope: AccessScope,
+}
+
+impl Display for QueryMovieVisible {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryMovieVisible({})", self.movie_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryMovieVisible,
+    return_type = bool,
+    db_call = |pool, query_manager, msg: QueryMovieVisible| query_movie_visible(
+        pool,
+        query_manager,
+        msg.movie_id,
+        msg.scope
+    ),
+    success_return = |res| res,
+    error_return = false
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Option<String>")]
+pub struct QueryVideoFilePath {
+    pub media: PlayableMedia,
+    pub scope: AccessScope,
+}
+
+impl Display for QueryVideoFilePath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryVideoFilePath({:?})", self.media)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryVideoFilePath,
+    return_type = Option<String>,
+    db_call = |pool, query_manager, msg: QueryVideoFilePath| query_video_file_path(
+        pool,
+        query_manager,
+        msg.media,
+        msg.scope
+    ),
+    success_return = |res| res,
+    error_return = None
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "bool")]
+pub struct SaveTaskRecord {
+    pub record: TaskRecord,
+}
+
+impl Display for SaveTaskRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SaveTaskRecord({:?})", self.record.task_id)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = SaveTaskRecord,
+    return_type = bool,
+    db_call = |pool, query_manager, msg: SaveTaskRecord| save_task_record(
+        pool,
+        query_manager,
+        msg.record
+    ),
+    success_return = |_| true,
+    error_return = false
+);
+
+#[derive(Debug, Serialize, Deserialize, TS, Message)]
+#[rtype(result = "Vec<TaskRecord>")]
+pub struct QueryTaskRecords {
+    pub status: Option<TaskStatus>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl Display for QueryTaskRecords {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "QueryTaskRecords({:?})", self.status)
+    }
+}
+
+define_actor_message_handler!(
+    message_type = QueryTaskRecords,
+    return_type = Vec<TaskRecord>,
+    db_call = |pool, query_manager, msg: QueryTaskRecords| query_task_records(
+        pool,
+        query_manager,
+        map_rows,
+        msg.status,
+        msg.limit,
+        msg.offset
+    ),
+    success_return = |res| res,
+    error_return = Vec::<TaskRecord>::new()
+);
@@ -5,7 +5,11 @@ use sqlx::{Acquire, SqlitePool};
 use tracing::*;
 
 use crate::{
-    domain::episode::model::Episode, infrastructure::media_db::query_manager::QueryManager,
+    domain::episode::model::Episode,
+    infrastructure::media_db::{
+        media_info::create::save_episode_media_info, query_manager::QueryManager,
+        subtitle::create::save_episode_subtitles,
+    },
 };
 
 #[instrument(skip(conn_pool, query_manager, episode))]
@@ -19,6 +23,7 @@ pub async fn save_episode(
     let mut tx = conn.begin().await?;
 
     let query = query_manager.get_query("episode", "save_episode").await?;
+    let video_file_path = episode.video_file_path.clone();
 
     sqlx::query(&query)
         .bind(season_id)
@@ -32,9 +37,101 @@ pub async fn save_episode(
         .bind(episode.thumb_image)
         .bind(episode.episode_number)
         .bind(episode.runtime)
+        .bind(episode.file_size)
+        .bind(episode.file_modified_at)
+        .bind(episode.nfo_modified_at)
         .execute(&mut *tx)
         .await?;
+
+    save_episode_subtitles(
+        &mut tx,
+        query_manager.clone(),
+        &video_file_path,
+        &episode.subtitles,
+    )
+    .await?;
+    save_episode_media_info(
+        &mut tx,
+        query_manager,
+        &video_file_path,
+        episode.media_info.as_ref(),
+    )
+    .await?;
     tx.commit().await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use sqlx::{sqlite::SqlitePoolOptions, Row};
+
+    use super::*;
+    use crate::infrastructure::media_db::{
+        library::delete::soft_delete_media_files, query_manager::FileQueryManager,
+    };
+
+    async fn setup() -> (SqlitePool, Arc<dyn QueryManager>) {
+        let sql_root_path = concat!(env!("CARGO_MANIFEST_DIR"), "/sql");
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let schema = std::fs::read_to_string(format!("{}/schema.sql", sql_root_path)).unwrap();
+        sqlx::raw_sql(&schema).execute(&pool).await.unwrap();
+        sqlx::raw_sql(
+            "INSERT INTO tv_shows (id, title) VALUES (1, 'Show');
+             INSERT INTO seasons (id, tv_show_id, season_number) VALUES (1, 1, 1);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let query_manager = FileQueryManager::new(sql_root_path).await.unwrap();
+        query_manager.reload().await.unwrap();
+
+        (pool, Arc::new(query_manager))
+    }
+
+    fn episode(video_file_path: &str, file_size: i64) -> Episode {
+        Episode {
+            title: Some("Pilot".to_string()),
+            video_file_path: video_file_path.to_string(),
+            episode_number: Some("1".to_string()),
+            file_size: Some(file_size),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rescan_keeps_episode_of_renamed_file() {
+        let (pool, query_manager) = setup().await;
+
+        save_episode(
+            &pool,
+            query_manager.clone(),
+            1,
+            episode("/a/S01E01.mkv", 10),
+        )
+        .await
+        .unwrap();
+        // A rescan saves the file found under the new name and then soft deletes the old path.
+        save_episode(&pool, query_manager.clone(), 1, episode("/a/Pilot.mkv", 20))
+            .await
+            .unwrap();
+        soft_delete_media_files(&pool, query_manager, vec!["/a/S01E01.mkv".to_string()])
+            .await
+            .unwrap();
+
+        let rows = sqlx::query("SELECT id, video_file_path, file_size, deleted_at FROM episodes")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].get::<i64, _>("id"), 1);
+        assert_eq!(rows[0].get::<String, _>("video_file_path"), "/a/Pilot.mkv");
+        assert_eq!(rows[0].get::<Option<i64>, _>("file_size"), Some(20));
+        assert_eq!(rows[0].get::<Option<String>, _>("deleted_at"), None);
+    }
+}
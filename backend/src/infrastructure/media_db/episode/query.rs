@@ -3,7 +3,13 @@ use sqlx::{sqlite::SqliteRow, Acquire, SqlitePool};
 use std::sync::Arc;
 use tracing::*;
 
-use crate::{infrastructure::media_db::query_manager::QueryManager, interfaces::dtos::EpisodeDto};
+use crate::{
+    domain::episode::model::EpisodeVideo,
+    infrastructure::media_db::{
+        media_info::query::attach_media_tracks, query_manager::QueryManager,
+    },
+    interfaces::dtos::EpisodeDto,
+};
 
 #[instrument(skip(conn_pool, query_manager, mapper))]
 pub async fn query_episodes(
@@ -12,6 +18,7 @@ pub async fn query_episodes(
     mapper: impl Fn(Vec<SqliteRow>) -> Vec<EpisodeDto>,
     library_id: i64,
     media_id: i64,
+    user_id: i64,
 ) -> Result<Vec<EpisodeDto>> {
     let mut conn = conn_pool.acquire().await?;
     let mut tx = conn.begin().await?;
@@ -24,10 +31,14 @@ pub async fn query_episodes(
     let episodes = sqlx::query(&query)
         .bind(media_id)
         .bind(library_id)
+        .bind(user_id)
         .fetch_all(&mut *tx)
         .await?;
 
-    Ok(mapper(episodes))
+    let mut episodes = mapper(episodes);
+    attach_media_tracks(&mut tx, query_manager, media_id, &mut episodes).await?;
+
+    Ok(episodes)
 }
 
 #[instrument(skip(conn_pool, query_manager, mapper))]
@@ -36,6 +47,7 @@ pub async fn query_media_episodes(
     query_manager: Arc<dyn QueryManager>,
     mapper: impl Fn(Vec<SqliteRow>) -> Vec<EpisodeDto>,
     media_id: i64,
+    user_id: i64,
 ) -> Result<Vec<EpisodeDto>> {
     let mut conn = conn_pool.acquire().await?;
     let mut tx = conn.begin().await?;
@@ -46,8 +58,50 @@ pub async fn query_media_episodes(
 
     let episodes = sqlx::query(&query)
         .bind(media_id)
+        .bind(user_id)
         .fetch_all(&mut *tx)
         .await?;
 
+    let mut episodes = mapper(episodes);
+    attach_media_tracks(&mut tx, query_manager, media_id, &mut episodes).await?;
+
+    Ok(episodes)
+}
+
+#[instrument(skip(conn_pool, query_manager, mapper))]
+pub async fn query_episodes_without_thumbnail(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<EpisodeVideo>,
+    library_id: i64,
+) -> Result<Vec<EpisodeVideo>> {
+    let query = query_manager
+        .get_query("episode", "find_library_episodes_without_thumbnail")
+        .await?;
+
+    let episodes = sqlx::query(&query)
+        .bind(library_id)
+        .fetch_all(conn_pool)
+        .await?;
+
+    Ok(mapper(episodes))
+}
+
+#[instrument(skip(conn_pool, query_manager, mapper))]
+pub async fn query_library_episode_videos(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<EpisodeVideo>,
+    library_id: i64,
+) -> Result<Vec<EpisodeVideo>> {
+    let query = query_manager
+        .get_query("episode", "find_library_episode_videos")
+        .await?;
+
+    let episodes = sqlx::query(&query)
+        .bind(library_id)
+        .fetch_all(conn_pool)
+        .await?;
+
     Ok(mapper(episodes))
 }
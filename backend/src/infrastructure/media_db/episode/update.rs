@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use anyhow::*;
+use sqlx::{Acquire, SqlitePool};
+use tracing::*;
+
+use crate::{
+    domain::episode::model::Episode,
+    infrastructure::media_db::{
+        media_info::create::save_episode_media_info, query_manager::QueryManager,
+        subtitle::create::save_episode_subtitles,
+    },
+};
+
+/// Refreshes the metadata of an episode by its video file path and restores it
+/// if it was soft deleted by a previous rescan.
+#[instrument(skip(conn_pool, query_manager, episode))]
+pub async fn update_episode(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    episode: Episode,
+) -> Result<()> {
+    let mut conn = conn_pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    let query = query_manager
+        .get_query("episode", "update_episode_by_video_file_path")
+        .await?;
+
+    sqlx::query(&query)
+        .bind(episode.title)
+        .bind(episode.original_title)
+        .bind(episode.plot)
+        .bind(episode.nfo_path)
+        .bind(episode.subtitle_file_path)
+        .bind(episode.thumb_image_url)
+        .bind(episode.thumb_image)
+        .bind(episode.runtime)
+        .bind(episode.file_size)
+        .bind(episode.file_modified_at)
+        .bind(episode.nfo_modified_at)
+        .bind(&episode.video_file_path)
+        .execute(&mut *tx)
+        .await?;
+
+    save_episode_subtitles(
+        &mut tx,
+        query_manager.clone(),
+        &episode.video_file_path,
+        &episode.subtitles,
+    )
+    .await?;
+    save_episode_media_info(
+        &mut tx,
+        query_manager,
+        &episode.video_file_path,
+        episode.media_info.as_ref(),
+    )
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Sets a generated thumbnail unless the episode got a thumb image from a rescan meanwhile.
+#[instrument(skip(conn_pool, query_manager))]
+pub async fn update_episode_thumbnail(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    episode_id: i64,
+    thumb_image: String,
+) -> Result<()> {
+    let query = query_manager
+        .get_query("episode", "update_episode_thumbnail")
+        .await?;
+
+    sqlx::query(&query)
+        .bind(thumb_image)
+        .bind(episode_id)
+        .execute(conn_pool)
+        .await?;
+
+    Ok(())
+}
@@ -16,8 +16,73 @@ pub async fn delete_library(
     let mut tx = conn.begin().await?;
 
     let query = query_manager.get_query("library", "delete_library").await?;
+    let schedule_query = query_manager
+        .get_query("library", "delete_scan_schedule")
+        .await?;
 
     sqlx::query(&query).bind(id).execute(&mut *tx).await?;
+    sqlx::query(&schedule_query)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[instrument(skip(conn_pool, query_manager))]
+pub async fn delete_scan_schedule(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    library_id: i64,
+) -> Result<()> {
+    let mut conn = conn_pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    let query = query_manager
+        .get_query("library", "delete_scan_schedule")
+        .await?;
+
+    sqlx::query(&query)
+        .bind(library_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Soft deletes the episodes and movies which are backed by the given video files,
+/// the rows are kept so that a file which comes back can be restored with its metadata.
+#[instrument(skip(conn_pool, query_manager, video_file_paths))]
+pub async fn soft_delete_media_files(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    video_file_paths: Vec<String>,
+) -> Result<()> {
+    let mut conn = conn_pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    let episode_query = query_manager
+        .get_query("episode", "soft_delete_episode_by_video_file_path")
+        .await?;
+    let movie_query = query_manager
+        .get_query("movie", "soft_delete_movie_by_video_file_path")
+        .await?;
+
+    for video_file_path in video_file_paths {
+        debug!("Soft deleting media file: {}", video_file_path);
+        sqlx::query(&episode_query)
+            .bind(&video_file_path)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(&movie_query)
+            .bind(&video_file_path)
+            .execute(&mut *tx)
+            .await?;
+    }
 
     tx.commit().await?;
 
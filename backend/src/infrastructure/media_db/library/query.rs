@@ -4,7 +4,11 @@ use std::sync::Arc;
 use tracing::*;
 
 use crate::{
-    domain::media_library::model::{LibraryBrief, LibraryPoster},
+    domain::media_library::{
+        model::{LibraryBrief, LibraryPoster},
+        rescan::MediaFileState,
+        schedule::LibraryScanSchedule,
+    },
     infrastructure::media_db::query_manager::QueryManager,
 };
 
@@ -60,3 +64,57 @@ pub async fn query_library_posters(
 
     Ok(mapper(raw_library_posters))
 }
+
+#[instrument(skip(conn_pool, query_manager, mapper))]
+pub async fn query_library_media_files(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<MediaFileState>,
+    library_id: i64,
+) -> Result<Vec<MediaFileState>> {
+    let mut conn = conn_pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    let query = query_manager
+        .get_query("library", "find_media_files_by_library_id")
+        .await?;
+
+    let raw_media_files = sqlx::query(&query)
+        .bind(library_id)
+        .bind(library_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+    Ok(mapper(raw_media_files))
+}
+
+#[instrument(skip(conn_pool, query_manager, mapper))]
+pub async fn query_scan_schedules(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<LibraryScanSchedule>,
+    library_id: Option<i64>,
+) -> Result<Vec<LibraryScanSchedule>> {
+    let mut conn = conn_pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    let raw_schedules = match library_id {
+        Some(library_id) => {
+            let query = query_manager
+                .get_query("library", "find_scan_schedule_by_library_id")
+                .await?;
+            sqlx::query(&query)
+                .bind(library_id)
+                .fetch_all(&mut *tx)
+                .await?
+        }
+        None => {
+            let query = query_manager
+                .get_query("library", "find_all_scan_schedules")
+                .await?;
+            sqlx::query(&query).fetch_all(&mut *tx).await?
+        }
+    };
+
+    Ok(mapper(raw_schedules))
+}
@@ -4,9 +4,16 @@ use std::sync::Arc;
 use tracing::*;
 
 use super::wrapper::LibraryDatabase;
-use crate::interfaces::{
-    dtos::LibraryDto,
-    http_api::controllers::api_models::{SaveLibraryPayload, UpdateLibraryPayload},
+use crate::{
+    domain::{
+        library::model::LibraryStatus,
+        media_library::schedule::{LibraryScanSchedule, ScanSchedule},
+        user::access::AccessScope,
+    },
+    interfaces::{
+        dtos::LibraryDto,
+        http_api::controllers::api_models::{SaveLibraryPayload, UpdateLibraryPayload},
+    },
 };
 
 #[derive(Clone)]
@@ -40,6 +47,45 @@ impl<D: LibraryDatabase> LibraryRepository<D> {
         Ok(results.into_iter().next().unwrap())
     }
 
+    /// The libraries the scope may see.
+    #[instrument(skip(self))]
+    pub async fn get_visible_libraries(&self, scope: &AccessScope) -> Result<Vec<LibraryDto>> {
+        let libraries = self.get_libraries().await?;
+        let Some(user_id) = scope.user_id else {
+            return Ok(libraries);
+        };
+
+        let granted_library_ids = self.database.query_user_library_ids(user_id).await?;
+        Ok(libraries
+            .into_iter()
+            .filter(|library| scope.can_see_library(library.id, &granted_library_ids))
+            .collect())
+    }
+
+    /// Hidden libraries are reported as missing so their ids do not leak.
+    #[instrument(skip(self))]
+    pub async fn get_visible_library_by_id(
+        &self,
+        id: i64,
+        scope: &AccessScope,
+    ) -> Result<LibraryDto> {
+        if !self.is_library_visible(id, scope).await? {
+            return Err(anyhow::anyhow!("Media library not found"));
+        }
+
+        self.get_library_by_id(id).await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn is_library_visible(&self, id: i64, scope: &AccessScope) -> Result<bool> {
+        let Some(user_id) = scope.user_id else {
+            return Ok(true);
+        };
+
+        let granted_library_ids = self.database.query_user_library_ids(user_id).await?;
+        Ok(scope.can_see_library(id, &granted_library_ids))
+    }
+
     #[instrument(skip(self))]
     pub async fn get_library_internal(&self, id: Option<i64>) -> Result<Vec<LibraryDto>> {
         let media_library_briefs = self.database.query_library(id).await?;
@@ -61,6 +107,20 @@ impl<D: LibraryDatabase> LibraryRepository<D> {
                         vec![]
                     }
                 };
+                let scan_schedule = match self
+                    .database
+                    .query_scan_schedules(Some(media_library_brief.id))
+                    .await
+                {
+                    Ok(schedules) => schedules.into_iter().next().map(|s| s.schedule),
+                    Err(e) => {
+                        error!(
+                            "Error getting scan schedule for library {}: {:?}",
+                            media_library_brief.id, e
+                        );
+                        None
+                    }
+                };
 
                 LibraryDto {
                     id: media_library_brief.id,
@@ -72,6 +132,7 @@ impl<D: LibraryDatabase> LibraryRepository<D> {
                     last_scanned: media_library_brief.last_scanned,
                     current_status: media_library_brief.current_status,
                     auto_scan: media_library_brief.auto_scan,
+                    scan_schedule,
                     error: media_library_brief.error,
                     storage_used: media_library_brief.storage_used,
                     health_score: media_library_brief.health_score,
@@ -111,6 +172,39 @@ impl<D: LibraryDatabase> LibraryRepository<D> {
     pub async fn delete_library(&self, id: i64) -> Result<()> {
         self.database.delete_library(id).await
     }
+
+    #[instrument(skip(self))]
+    pub async fn update_library_status(
+        &self,
+        library_id: i64,
+        status: LibraryStatus,
+        error: Option<String>,
+    ) -> Result<()> {
+        self.database
+            .update_library_status(library_id, status, error)
+            .await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_scan_schedules(&self) -> Result<Vec<LibraryScanSchedule>> {
+        self.database.query_scan_schedules(None).await
+    }
+
+    /// Saves the schedule of a library, `None` removes the existing one.
+    #[instrument(skip(self))]
+    pub async fn set_scan_schedule(
+        &self,
+        library_id: i64,
+        schedule: Option<ScanSchedule>,
+    ) -> Result<()> {
+        match schedule {
+            Some(schedule) => {
+                schedule.validate()?;
+                self.database.save_scan_schedule(library_id, schedule).await
+            }
+            None => self.database.delete_scan_schedule(library_id).await,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -191,6 +285,11 @@ mod tests {
             .with(eq(1))
             .returning(|_| Box::pin(ready(Ok(vec![LibraryPoster::default()]))));
 
+        mock_database
+            .expect_query_scan_schedules()
+            .with(eq(Some(1)))
+            .returning(|_| Box::pin(ready(Ok(vec![]))));
+
         let repository = LibraryRepository::new(Arc::new(mock_database));
         let result = repository.get_library_internal(Some(1)).await;
 
@@ -207,6 +306,7 @@ mod tests {
                 last_scanned: Some("2021-01-01".to_string()),
                 current_status: LibraryStatus::Active,
                 auto_scan: true,
+                scan_schedule: None,
                 error: None,
                 storage_used: 1,
                 health_score: 1,
@@ -265,6 +365,11 @@ mod tests {
             .with(eq(1))
             .returning(|_| Box::pin(ready(Err(anyhow::anyhow!("Error getting library posters")))));
 
+        mock_database
+            .expect_query_scan_schedules()
+            .with(eq(Some(1)))
+            .returning(|_| Box::pin(ready(Ok(vec![]))));
+
         let repository = LibraryRepository::new(Arc::new(mock_database));
         let result = repository.get_library_internal(Some(1)).await;
 
@@ -281,6 +386,7 @@ mod tests {
                 last_scanned: Some("2021-01-01".to_string()),
                 current_status: LibraryStatus::Active,
                 auto_scan: true,
+                scan_schedule: None,
                 error: None,
                 storage_used: 1,
                 health_score: 1,
@@ -378,4 +484,64 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(result.unwrap_err().to_string(), "Error deleting library");
     }
+
+    #[tokio::test]
+    async fn test_set_scan_schedule_success() {
+        let schedule = ScanSchedule::Interval { hours: 6 };
+        let mut mock_database = MockLibraryDatabase::new();
+        mock_database
+            .expect_save_scan_schedule()
+            .with(eq(1), eq(schedule.clone()))
+            .returning(|_, _| Box::pin(ready(Ok(()))));
+
+        let repository = LibraryRepository::new(Arc::new(mock_database));
+        let result = repository.set_scan_schedule(1, Some(schedule)).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_set_scan_schedule_invalid() {
+        let mock_database = MockLibraryDatabase::new();
+
+        let repository = LibraryRepository::new(Arc::new(mock_database));
+        let result = repository
+            .set_scan_schedule(1, Some(ScanSchedule::Interval { hours: 0 }))
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_remove_scan_schedule_success() {
+        let mut mock_database = MockLibraryDatabase::new();
+        mock_database
+            .expect_delete_scan_schedule()
+            .with(eq(1))
+            .returning(|_| Box::pin(ready(Ok(()))));
+
+        let repository = LibraryRepository::new(Arc::new(mock_database));
+        let result = repository.set_scan_schedule(1, None).await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_get_visible_library_by_id_hidden() {
+        let mut mock_database = MockLibraryDatabase::new();
+        mock_database
+            .expect_query_user_library_ids()
+            .with(eq(7))
+            .returning(|_| Box::pin(ready(Ok(vec![2]))));
+
+        let repository = LibraryRepository::new(Arc::new(mock_database));
+        let scope = AccessScope {
+            user_id: Some(7),
+            max_certification_level: None,
+        };
+        let result = repository.get_visible_library_by_id(1, &scope).await;
+
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().to_string(), "Media library not found");
+    }
 }
@@ -3,7 +3,8 @@ use sqlx::{Acquire, SqlitePool};
 use std::sync::Arc;
 
 use crate::{
-    domain::library::model::LibraryStatus, infrastructure::media_db::query_manager::QueryManager,
+    domain::{library::model::LibraryStatus, media_library::schedule::ScanSchedule},
+    infrastructure::media_db::query_manager::QueryManager,
     interfaces::http_api::controllers::api_models::LibraryCategory,
 };
 
@@ -64,3 +65,65 @@ pub async fn populate_library_metadata(
     tx.commit().await?;
     Ok(())
 }
+
+/// Updates the status of a library, `last_scanned` is only touched when a scan finished.
+pub async fn update_library_status(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    library_id: i64,
+    status: LibraryStatus,
+    error: Option<String>,
+) -> Result<()> {
+    let mut conn = pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    let query = query_manager
+        .get_query("library", "update_library_status")
+        .await?;
+
+    let last_scanned = match status {
+        LibraryStatus::Active => Some(chrono::Local::now().to_rfc3339()),
+        _ => None,
+    };
+
+    sqlx::query(&query)
+        .bind(status.to_id())
+        .bind(error)
+        .bind(last_scanned)
+        .bind(library_id)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn save_scan_schedule(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    library_id: i64,
+    schedule: ScanSchedule,
+) -> Result<()> {
+    let mut conn = pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    let query = query_manager
+        .get_query("library", "save_scan_schedule")
+        .await?;
+
+    let (interval_hours, daily_hour, daily_minute) = match schedule {
+        ScanSchedule::Daily { hour, minute } => (None, Some(hour), Some(minute)),
+        ScanSchedule::Interval { hours } => (Some(hours), None, None),
+    };
+
+    sqlx::query(&query)
+        .bind(library_id)
+        .bind(interval_hours)
+        .bind(daily_hour)
+        .bind(daily_minute)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(())
+}
@@ -4,11 +4,18 @@ use async_trait::async_trait;
 use mockall::automock;
 
 use crate::{
-    domain::media_library::model::{LibraryBrief, LibraryPoster},
+    domain::{
+        library::model::LibraryStatus,
+        media_library::{
+            model::{LibraryBrief, LibraryPoster},
+            schedule::{LibraryScanSchedule, ScanSchedule},
+        },
+    },
     infrastructure::media_db::{
         actor::{
-            DeleteLibrary, PopulateLibraryMetadata, QueryLibrary, QueryLibraryPosters, SaveLibrary,
-            UpdateLibrary, ValidateCategory,
+            DeleteLibrary, DeleteScanSchedule, PopulateLibraryMetadata, QueryLibrary,
+            QueryLibraryPosters, QueryScanSchedules, QueryUserLibraryIds, SaveLibrary,
+            SaveScanSchedule, UpdateLibrary, UpdateLibraryStatus, ValidateCategory,
         },
         database::Database,
     },
@@ -25,6 +32,19 @@ pub trait LibraryDatabase: Send + Sync {
     async fn save_library(&self, payload: SaveLibraryPayload) -> Result<i64>;
     async fn delete_library(&self, id: i64) -> Result<()>;
     async fn populate_library_metadata(&self, library_id: i64, item_count: usize) -> Result<()>;
+    async fn update_library_status(
+        &self,
+        library_id: i64,
+        status: LibraryStatus,
+        error: Option<String>,
+    ) -> Result<()>;
+    async fn query_scan_schedules(
+        &self,
+        library_id: Option<i64>,
+    ) -> Result<Vec<LibraryScanSchedule>>;
+    async fn save_scan_schedule(&self, library_id: i64, schedule: ScanSchedule) -> Result<()>;
+    async fn delete_scan_schedule(&self, library_id: i64) -> Result<()>;
+    async fn query_user_library_ids(&self, user_id: i64) -> Result<Vec<i64>>;
 }
 
 #[derive(Clone)]
@@ -96,4 +116,54 @@ impl LibraryDatabase for LibraryDatabaseWrapper {
             .await
             .map_err(|e| anyhow!("Error deleting library: {}", e))
     }
+
+    async fn update_library_status(
+        &self,
+        library_id: i64,
+        status: LibraryStatus,
+        error: Option<String>,
+    ) -> Result<()> {
+        self.addr
+            .send(UpdateLibraryStatus {
+                library_id,
+                status,
+                error,
+            })
+            .await
+            .map_err(|e| anyhow!("Error updating library status: {}", e))
+    }
+
+    async fn query_scan_schedules(
+        &self,
+        library_id: Option<i64>,
+    ) -> Result<Vec<LibraryScanSchedule>> {
+        self.addr
+            .send(QueryScanSchedules { library_id })
+            .await
+            .map_err(|e| anyhow!("Error getting scan schedules: {}", e))
+    }
+
+    async fn save_scan_schedule(&self, library_id: i64, schedule: ScanSchedule) -> Result<()> {
+        self.addr
+            .send(SaveScanSchedule {
+                library_id,
+                schedule,
+            })
+            .await
+            .map_err(|e| anyhow!("Error saving scan schedule: {}", e))
+    }
+
+    async fn delete_scan_schedule(&self, library_id: i64) -> Result<()> {
+        self.addr
+            .send(DeleteScanSchedule { library_id })
+            .await
+            .map_err(|e| anyhow!("Error deleting scan schedule: {}", e))
+    }
+
+    async fn query_user_library_ids(&self, user_id: i64) -> Result<Vec<i64>> {
+        self.addr
+            .send(QueryUserLibraryIds { user_id })
+            .await
+            .map_err(|e| anyhow!("Error getting libraries of user: {}", e))
+    }
 }
@@ -0,0 +1,99 @@
+use anyhow::*;
+use sqlx::SqliteConnection;
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::media_info::model::MediaInfo, infrastructure::media_db::query_manager::QueryManager,
+};
+
+/// Replaces the media info and the tracks of the episode with the given video file, this
+/// runs inside the transaction which saves or updates the episode itself. Without media
+/// info the stale one of a previous scan is removed.
+#[instrument(skip(conn, query_manager, media_info))]
+pub async fn save_episode_media_info(
+    conn: &mut SqliteConnection,
+    query_manager: Arc<dyn QueryManager>,
+    video_file_path: &str,
+    media_info: Option<&MediaInfo>,
+) -> Result<()> {
+    let Some(media_info) = media_info else {
+        let query = query_manager
+            .get_query("media_info", "delete_episode_media_info")
+            .await?;
+        sqlx::query(&query)
+            .bind(video_file_path)
+            .execute(&mut *conn)
+            .await?;
+        return Ok(());
+    };
+
+    let query = query_manager
+        .get_query("media_info", "save_episode_media_info")
+        .await?;
+    sqlx::query(&query)
+        .bind(&media_info.container)
+        .bind(media_info.duration.map(|duration| duration as i64))
+        .bind(media_info.bitrate.map(|bitrate| bitrate as i64))
+        .bind(&media_info.video_codec)
+        .bind(media_info.width)
+        .bind(media_info.height)
+        .bind(media_info.frame_rate)
+        .bind(media_info.hdr_format.map(|format| format.as_str()))
+        .bind(video_file_path)
+        .execute(&mut *conn)
+        .await?;
+
+    let delete_query = query_manager
+        .get_query("media_info", "delete_episode_audio_tracks")
+        .await?;
+    sqlx::query(&delete_query)
+        .bind(video_file_path)
+        .execute(&mut *conn)
+        .await?;
+
+    let save_query = query_manager
+        .get_query("media_info", "save_episode_audio_track")
+        .await?;
+    for track in &media_info.audio_tracks {
+        sqlx::query(&save_query)
+            .bind(track.index)
+            .bind(&track.language)
+            .bind(&track.title)
+            .bind(&track.codec)
+            .bind(track.channels)
+            .bind(video_file_path)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    let delete_query = query_manager
+        .get_query("media_info", "delete_episode_subtitle_tracks")
+        .await?;
+    sqlx::query(&delete_query)
+        .bind(video_file_path)
+        .execute(&mut *conn)
+        .await?;
+
+    let save_query = query_manager
+        .get_query("media_info", "save_episode_subtitle_track")
+        .await?;
+    for track in &media_info.subtitle_tracks {
+        sqlx::query(&save_query)
+            .bind(track.index)
+            .bind(&track.language)
+            .bind(&track.title)
+            .bind(&track.codec)
+            .bind(track.image_based)
+            .bind(video_file_path)
+            .execute(&mut *conn)
+            .await?;
+    }
+    debug!(
+        "Saved media info with {} audio and {} subtitle tracks",
+        media_info.audio_tracks.len(),
+        media_info.subtitle_tracks.len()
+    );
+
+    Ok(())
+}
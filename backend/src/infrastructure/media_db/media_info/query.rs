@@ -0,0 +1,59 @@
+use anyhow::*;
+use sqlx::SqliteConnection;
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::media_info::{
+        mapping::{audio_track_from_row, subtitle_track_from_row},
+        model::MediaInfo,
+    },
+    infrastructure::media_db::query_manager::QueryManager,
+    interfaces::dtos::EpisodeDto,
+};
+
+/// Fills the tracks of the probed episodes of a tv show, the episode queries only join
+/// the single row of `media_info`.
+#[instrument(skip(conn, query_manager, episodes))]
+pub async fn attach_media_tracks(
+    conn: &mut SqliteConnection,
+    query_manager: Arc<dyn QueryManager>,
+    media_id: i64,
+    episodes: &mut [EpisodeDto],
+) -> Result<()> {
+    let query = query_manager
+        .get_query("media_info", "find_audio_tracks_by_media_id")
+        .await?;
+    let audio_tracks = sqlx::query(&query)
+        .bind(media_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let query = query_manager
+        .get_query("media_info", "find_subtitle_tracks_by_media_id")
+        .await?;
+    let subtitle_tracks = sqlx::query(&query)
+        .bind(media_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    for (episode_id, track) in audio_tracks.into_iter().map(audio_track_from_row) {
+        if let Some(media_info) = media_info_of(episodes, episode_id) {
+            media_info.audio_tracks.push(track);
+        }
+    }
+    for (episode_id, track) in subtitle_tracks.into_iter().map(subtitle_track_from_row) {
+        if let Some(media_info) = media_info_of(episodes, episode_id) {
+            media_info.subtitle_tracks.push(track);
+        }
+    }
+
+    Ok(())
+}
+
+fn media_info_of(episodes: &mut [EpisodeDto], episode_id: i64) -> Option<&mut MediaInfo> {
+    episodes
+        .iter_mut()
+        .find(|episode| episode.id == episode_id)
+        .and_then(|episode| episode.media_info.as_mut())
+}
@@ -1,31 +1,119 @@
 use anyhow::*;
-use sqlx::sqlite::SqliteRow;
-use sqlx::{Acquire, SqlitePool};
+use sqlx::query::Query;
+use sqlx::sqlite::{SqliteArguments, SqliteRow};
+use sqlx::{Acquire, Row, Sqlite, SqlitePool};
 use std::sync::Arc;
 use tracing::*;
 
 use crate::domain::media_actor::model::MediaActor;
+use crate::domain::media_item::model::MediaFilter;
+use crate::domain::playback::model::PlayableMedia;
+use crate::domain::user::access::AccessScope;
 use crate::infrastructure::media_db::query_manager::QueryManager;
-use crate::interfaces::dtos::MediaItemDto;
+use crate::interfaces::dtos::{MediaItemDto, MediaPageDto};
 use crate::shared::util_traits::map_rows;
 
+/// The video file of an episode or movie, `None` when it doesn't exist or the scope may not
+/// see it.
+#[instrument(skip(conn_pool, query_manager))]
+pub async fn query_video_file_path(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    media: PlayableMedia,
+    scope: AccessScope,
+) -> Result<Option<String>> {
+    let (query, id) = match media {
+        PlayableMedia::Episode(episode_id) => (
+            query_manager
+                .get_query("episode", "find_visible_episode_video_file_path")
+                .await?,
+            episode_id,
+        ),
+        PlayableMedia::Movie(movie_id) => (
+            query_manager
+                .get_query("movie", "find_visible_movie_video_file_path")
+                .await?,
+            movie_id,
+        ),
+    };
+
+    let video_file_path = sqlx::query_scalar::<_, String>(&query)
+        .bind(id)
+        .bind(scope.user_id)
+        .bind(scope.max_certification_level)
+        .fetch_optional(conn_pool)
+        .await?;
+
+    Ok(video_file_path)
+}
+
 #[instrument(skip(conn_pool, query_manager, mapper))]
 pub async fn query_library_medias(
     conn_pool: &SqlitePool,
     query_manager: Arc<dyn QueryManager>,
     mapper: impl Fn(Vec<SqliteRow>) -> Vec<MediaItemDto>,
     library_id: i64,
-) -> Result<Vec<MediaItemDto>> {
+    filter: MediaFilter,
+    max_certification_level: Option<i64>,
+) -> Result<MediaPageDto> {
     let mut conn = conn_pool.acquire().await?;
     let mut tx = conn.begin().await?;
 
-    let query = query_manager.get_query("media", "find_all_media").await?;
-    let raw_media = sqlx::query(&query)
-        .bind(library_id)
-        .fetch_all(&mut *tx)
+    let query = query_manager
+        .get_query("media", "find_library_media_page")
         .await?;
+    let raw_media = bind_media_filter(
+        sqlx::query(&query),
+        library_id,
+        &filter,
+        max_certification_level,
+    )
+    .bind(filter.sort.as_str())
+    .bind(filter.order.as_str())
+    .bind(filter.limit)
+    .bind(filter.offset)
+    .fetch_all(&mut *tx)
+    .await?;
 
-    Ok(mapper(raw_media))
+    let query = query_manager
+        .get_query("media", "count_library_media")
+        .await?;
+    let total = bind_media_filter(
+        sqlx::query(&query),
+        library_id,
+        &filter,
+        max_certification_level,
+    )
+    .fetch_one(&mut *tx)
+    .await?
+    .get::<i64, _>("total");
+
+    Ok(MediaPageDto {
+        items: mapper(raw_media),
+        total,
+        offset: filter.offset,
+        limit: filter.limit,
+    })
+}
+
+/// Binds the filters of the named `*_page` and `count_*` queries of tv shows and movies,
+/// which share their parameters. Page queries take the sorting and the page after them.
+pub fn bind_media_filter<'q>(
+    query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    library_id: i64,
+    filter: &MediaFilter,
+    max_certification_level: Option<i64>,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+    query
+        .bind(library_id)
+        .bind(filter.genre.clone())
+        .bind(filter.studio.clone())
+        .bind(filter.actor.clone())
+        .bind(filter.year_from)
+        .bind(filter.year_to)
+        .bind(filter.rating_min)
+        .bind(filter.country.clone())
+        .bind(max_certification_level)
 }
 
 #[instrument(skip(conn_pool, query_manager, mapper))]
@@ -104,3 +192,26 @@ pub async fn query_media_by_id(
 
     Ok(media_item)
 }
+
+/// Whether the tv show exists and the scope may see it.
+#[instrument(skip(conn_pool, query_manager))]
+pub async fn query_tv_show_visible(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    tv_show_id: i64,
+    scope: AccessScope,
+) -> Result<bool> {
+    let query = query_manager
+        .get_query("media", "count_visible_tv_show")
+        .await?;
+
+    let total = sqlx::query(&query)
+        .bind(tv_show_id)
+        .bind(scope.user_id)
+        .bind(scope.max_certification_level)
+        .fetch_one(conn_pool)
+        .await?
+        .get::<i64, _>("total");
+
+    Ok(total > 0)
+}
@@ -4,12 +4,22 @@ use std::result::Result::Ok;
 use std::sync::Arc;
 use tracing::*;
 
+use crate::domain::media_item::model::MediaFilter;
+use crate::domain::media_library::rescan::MediaFileState;
+use crate::domain::playback::model::{PlayableMedia, PlaybackProgress};
+use crate::domain::search::model::{fts_query, SearchResult};
+use crate::domain::subtitle::model::Subtitle;
+use crate::domain::user::access::AccessScope;
 use crate::infrastructure::media_db::actor::{
-    QueryLibraryMedia, QueryLibraryMediaEpisodes, QueryLibraryMedias, QueryMediaById,
-    QueryMediaEpisodes,
+    QueryContinueWatching, QueryLibrary, QueryLibraryMedia, QueryLibraryMediaEpisodes,
+    QueryLibraryMediaFiles, QueryLibraryMedias, QueryLibraryMovie, QueryLibraryMovies,
+    QueryMediaById, QueryMediaEpisodes, QueryMovieVisible, QueryNextUp, QuerySearch,
+    QuerySubtitles, QueryTvShowVisible, QueryUserLibraryIds, QueryVideoFilePath,
+    SavePlaybackProgress,
 };
 use crate::infrastructure::media_db::database::Database;
-use crate::interfaces::dtos::{EpisodeDto, MediaItemDto};
+use crate::interfaces::dtos::{EpisodeDto, MediaItemDto, MediaPageDto, WatchingEpisodeDto};
+use crate::interfaces::http_api::controllers::api_models::LibraryCategory;
 
 #[derive(Clone)]
 pub struct MediaRepository {
@@ -22,18 +32,141 @@ impl MediaRepository {
     }
 
     #[instrument(skip(self))]
-    pub async fn get_library_medias(&self, library_id: i64) -> Result<Vec<MediaItemDto>> {
-        debug!("Getting all media");
+    async fn is_movie_library(&self, library_id: i64) -> Result<bool> {
+        let library = self
+            .database_addr
+            .send(QueryLibrary {
+                id: Some(library_id),
+            })
+            .await?;
+
+        match library.first() {
+            Some(library) => Ok(library.category == LibraryCategory::Movie),
+            None => Err(anyhow::anyhow!("Library not found")),
+        }
+    }
+
+    /// Hidden libraries and media are reported as missing so their ids do not leak.
+    #[instrument(skip(self))]
+    async fn ensure_library_visible(&self, library_id: i64, scope: &AccessScope) -> Result<()> {
+        let Some(user_id) = scope.user_id else {
+            return Ok(());
+        };
+
+        let granted_library_ids = self
+            .database_addr
+            .send(QueryUserLibraryIds { user_id })
+            .await?;
+        if !scope.can_see_library(library_id, &granted_library_ids) {
+            return Err(anyhow::anyhow!("Library not found"));
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn ensure_tv_show_visible(&self, tv_show_id: i64, scope: &AccessScope) -> Result<()> {
+        if *scope == AccessScope::unrestricted() {
+            return Ok(());
+        }
+
+        let visible = self
+            .database_addr
+            .send(QueryTvShowVisible {
+                tv_show_id,
+                scope: scope.clone(),
+            })
+            .await?;
+        if !visible {
+            return Err(anyhow::anyhow!("Media item not found"));
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn ensure_movie_visible(&self, movie_id: i64, scope: &AccessScope) -> Result<()> {
+        if *scope == AccessScope::unrestricted() {
+            return Ok(());
+        }
+
+        let visible = self
+            .database_addr
+            .send(QueryMovieVisible {
+                movie_id,
+                scope: scope.clone(),
+            })
+            .await?;
+        if !visible {
+            return Err(anyhow::anyhow!("Movie not found"));
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_library_medias(
+        &self,
+        library_id: i64,
+        filter: MediaFilter,
+        scope: &AccessScope,
+    ) -> Result<MediaPageDto> {
+        self.ensure_library_visible(library_id, scope).await?;
+        let max_certification_level = scope.max_certification_level;
+
+        if self.is_movie_library(library_id).await? {
+            debug!("Getting a page of movies");
+            let movies = self
+                .database_addr
+                .send(QueryLibraryMovies {
+                    library_id,
+                    filter,
+                    max_certification_level,
+                })
+                .await?;
+
+            return Ok(movies);
+        }
+
+        debug!("Getting a page of media");
         let media_items = self
             .database_addr
-            .send(QueryLibraryMedias { library_id })
+            .send(QueryLibraryMedias {
+                library_id,
+                filter,
+                max_certification_level,
+            })
             .await?;
 
         Ok(media_items)
     }
 
     #[instrument(skip(self))]
-    pub async fn get_library_media(&self, library_id: i64, media_id: i64) -> Result<MediaItemDto> {
+    pub async fn get_library_media(
+        &self,
+        library_id: i64,
+        media_id: i64,
+        scope: &AccessScope,
+    ) -> Result<MediaItemDto> {
+        self.ensure_library_visible(library_id, scope).await?;
+
+        if self.is_movie_library(library_id).await? {
+            self.ensure_movie_visible(media_id, scope).await?;
+            debug!("Getting movie for id: {}", media_id);
+            return match self
+                .database_addr
+                .send(QueryLibraryMovie {
+                    library_id,
+                    movie_id: media_id,
+                })
+                .await?
+            {
+                Some(movie) => Ok(movie),
+                None => Err(anyhow::anyhow!("Movie not found")),
+            };
+        }
+
+        self.ensure_tv_show_visible(media_id, scope).await?;
         debug!("Getting media for id: {}", media_id);
         let media = self
             .database_addr
@@ -55,17 +188,22 @@ impl MediaRepository {
         &self,
         library_id: i64,
         media_id: i64,
+        user_id: i64,
+        scope: &AccessScope,
     ) -> Result<Vec<EpisodeDto>> {
         debug!(
             "Getting media episodes for id: {} in library: {}",
             media_id, library_id
         );
+        self.ensure_library_visible(library_id, scope).await?;
+        self.ensure_tv_show_visible(media_id, scope).await?;
 
         let episodes = self
             .database_addr
             .send(QueryLibraryMediaEpisodes {
                 library_id,
                 media_id,
+                user_id,
             })
             .await?;
 
@@ -73,8 +211,13 @@ impl MediaRepository {
     }
 
     #[instrument(skip(self))]
-    pub async fn get_media_by_id(&self, media_id: i64) -> Result<MediaItemDto> {
+    pub async fn get_media_by_id(
+        &self,
+        media_id: i64,
+        scope: &AccessScope,
+    ) -> Result<MediaItemDto> {
         debug!("Getting media for id: {}", media_id);
+        self.ensure_tv_show_visible(media_id, scope).await?;
         match self.database_addr.send(QueryMediaById { media_id }).await? {
             Some(media) => Ok(media),
             None => Err(anyhow::anyhow!("Media item not found")),
@@ -82,14 +225,145 @@ impl MediaRepository {
     }
 
     #[instrument(skip(self))]
-    pub async fn get_media_episodes(&self, media_id: i64) -> Result<Vec<EpisodeDto>> {
+    pub async fn get_media_episodes(
+        &self,
+        media_id: i64,
+        user_id: i64,
+        scope: &AccessScope,
+    ) -> Result<Vec<EpisodeDto>> {
         debug!("Getting media episodes for id: {}", media_id);
+        self.ensure_tv_show_visible(media_id, scope).await?;
+
+        let episodes = self
+            .database_addr
+            .send(QueryMediaEpisodes { media_id, user_id })
+            .await?;
+
+        Ok(episodes)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_library_media_files(&self, library_id: i64) -> Result<Vec<MediaFileState>> {
+        debug!("Getting media files for library: {}", library_id);
 
+        let media_files = self
+            .database_addr
+            .send(QueryLibraryMediaFiles { library_id })
+            .await?;
+
+        Ok(media_files)
+    }
+
+    /// The video file to play, hidden media is reported as missing like everywhere else.
+    #[instrument(skip(self))]
+    pub async fn get_video_file_path(
+        &self,
+        media: PlayableMedia,
+        scope: &AccessScope,
+    ) -> Result<String> {
+        self.database_addr
+            .send(QueryVideoFilePath {
+                media,
+                scope: scope.clone(),
+            })
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Media item not found"))
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_subtitles(&self, video_file_path: &str) -> Result<Vec<Subtitle>> {
+        debug!("Getting subtitles for video: {}", video_file_path);
+
+        let subtitles = self
+            .database_addr
+            .send(QuerySubtitles {
+                video_file_path: video_file_path.to_string(),
+            })
+            .await?;
+
+        Ok(subtitles)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn save_playback_progress(
+        &self,
+        user_id: i64,
+        progress: PlaybackProgress,
+    ) -> Result<()> {
+        debug!(
+            "Saving playback progress of user {} for episode: {}",
+            user_id, progress.episode_id
+        );
+
+        self.database_addr
+            .send(SavePlaybackProgress { user_id, progress })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Episodes which the user started but not finished, most recently watched first.
+    #[instrument(skip(self))]
+    pub async fn get_continue_watching(
+        &self,
+        limit: i64,
+        user_id: i64,
+        scope: &AccessScope,
+    ) -> Result<Vec<WatchingEpisodeDto>> {
+        let episodes = self
+            .database_addr
+            .send(QueryContinueWatching {
+                limit,
+                scope: scope.clone(),
+                user_id,
+            })
+            .await?;
+
+        Ok(episodes)
+    }
+
+    /// The episode following the last one the user finished of every tv show.
+    #[instrument(skip(self))]
+    pub async fn get_next_up(
+        &self,
+        limit: i64,
+        user_id: i64,
+        scope: &AccessScope,
+    ) -> Result<Vec<WatchingEpisodeDto>> {
         let episodes = self
             .database_addr
-            .send(QueryMediaEpisodes { media_id })
+            .send(QueryNextUp {
+                limit,
+                scope: scope.clone(),
+                user_id,
+            })
             .await?;
 
         Ok(episodes)
     }
+
+    /// Tv shows, episodes, movies, actors, genres and studios matching the words of the query,
+    /// best match first.
+    #[instrument(skip(self))]
+    pub async fn search(
+        &self,
+        query: &str,
+        limit: i64,
+        scope: &AccessScope,
+    ) -> Result<Vec<SearchResult>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(vec![]);
+        };
+
+        let results = self
+            .database_addr
+            .send(QuerySearch {
+                fts_query,
+                limit,
+                scope: scope.clone(),
+            })
+            .await?;
+
+        Ok(results)
+    }
 }
@@ -5,8 +5,15 @@ pub mod episode;
 pub mod genre;
 pub mod library;
 pub mod media_actor;
+pub mod media_info;
 pub mod media_item;
+pub mod movie;
+pub mod playback;
 pub mod query_manager;
+pub mod search;
 pub mod season;
 pub mod studio;
+pub mod subtitle;
+pub mod task;
 pub mod tv_show;
+pub mod user;
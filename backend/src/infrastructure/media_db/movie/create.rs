@@ -0,0 +1,148 @@
+use anyhow::*;
+use sqlx::{Acquire, Row, SqlitePool};
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::{movie::model::Movie, user::access::certification_level},
+    infrastructure::media_db::{
+        query_manager::QueryManager, subtitle::create::save_movie_subtitles,
+    },
+};
+
+/// Saves the movie together with its genres, studios and actors in a single transaction,
+/// a movie has no nested seasons so there is no need to split it into several messages.
+#[instrument(skip(conn_pool, query_manager, movie))]
+pub async fn save_movie(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    movie: Movie,
+    library_id: i64,
+) -> Result<i64> {
+    let mut conn = conn_pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    let save_movie_query = query_manager.get_query("movie", "save_movie").await?;
+    let certification_level = movie.certification.as_deref().and_then(certification_level);
+
+    let movie_id: i64 = sqlx::query_scalar(&save_movie_query)
+        .bind(movie.title)
+        .bind(movie.original_title)
+        .bind(movie.sort_title)
+        .bind(movie.tagline)
+        .bind(movie.nfo_path)
+        .bind(movie.poster_path)
+        .bind(movie.fanart_path)
+        .bind(&movie.video_file_path)
+        .bind(movie.subtitle_file_path)
+        .bind(movie.country)
+        .bind(movie.year)
+        .bind(movie.premiered)
+        .bind(movie.rating)
+        .bind(movie.certification)
+        .bind(certification_level)
+        .bind(movie.runtime)
+        .bind(movie.plot)
+        .bind(movie.tmdb_id)
+        .bind(movie.imdb_id)
+        .bind(movie.wikidata_id)
+        .bind(movie.file_size)
+        .bind(movie.file_modified_at)
+        .bind(movie.nfo_modified_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    let save_library_movie_query = query_manager
+        .get_query("movie", "save_library_movie")
+        .await?;
+
+    sqlx::query(&save_library_movie_query)
+        .bind(library_id)
+        .bind(movie_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let save_genre_query = query_manager.get_query("genre", "save_genre").await?;
+    let save_movie_genre_query = query_manager.get_query("movie", "save_movie_genre").await?;
+    for genre in movie.genres {
+        debug!("Saving genre: {}", genre);
+        let genre_id: i64 = sqlx::query_scalar(&save_genre_query)
+            .bind(genre)
+            .fetch_one(&mut *tx)
+            .await?;
+        sqlx::query(&save_movie_genre_query)
+            .bind(movie_id)
+            .bind(genre_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let save_studio_query = query_manager.get_query("studio", "save_studio").await?;
+    let save_movie_studio_query = query_manager
+        .get_query("movie", "save_movie_studio")
+        .await?;
+    for studio in movie.studios {
+        debug!("Saving studio: {}", studio);
+        let studio_id: i64 = sqlx::query_scalar(&save_studio_query)
+            .bind(studio)
+            .fetch_one(&mut *tx)
+            .await?;
+        sqlx::query(&save_movie_studio_query)
+            .bind(movie_id)
+            .bind(studio_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let save_actor_query = query_manager.get_query("actor", "save_actor").await?;
+    let save_movie_actor_query = query_manager.get_query("movie", "save_movie_actor").await?;
+    let find_actor_id_query = query_manager
+        .get_query("actor", "find_actor_id_by_name")
+        .await?;
+    for actor in movie.actors {
+        debug!("Saving actor: {:?}", actor.name);
+        if actor.name.is_none() {
+            warn!("Skipping actor without name");
+            continue;
+        }
+        let actor_name = actor.name.clone();
+        let actor_id: Option<i64> = sqlx::query(&save_actor_query)
+            .bind(actor.name)
+            .bind(actor.role)
+            .bind(actor.thumb)
+            .bind(actor.profile)
+            .bind(actor.tmdb_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .map(|row| row.get(0));
+
+        // The insert is ignored when the actor already exists, so look it up by name
+        let actor_id: i64 = match actor_id {
+            Some(actor_id) => actor_id,
+            None => {
+                sqlx::query_scalar(&find_actor_id_query)
+                    .bind(actor_name)
+                    .fetch_one(&mut *tx)
+                    .await?
+            }
+        };
+
+        sqlx::query(&save_movie_actor_query)
+            .bind(movie_id)
+            .bind(actor_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    save_movie_subtitles(
+        &mut tx,
+        query_manager,
+        &movie.video_file_path,
+        &movie.subtitles,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(movie_id)
+}
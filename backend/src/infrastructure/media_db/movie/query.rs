@@ -0,0 +1,118 @@
+use anyhow::*;
+use sqlx::{sqlite::SqliteRow, Acquire, Row, SqlitePool};
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::{
+        media_actor::model::MediaActor, media_item::model::MediaFilter, user::access::AccessScope,
+    },
+    infrastructure::media_db::{media_item::query::bind_media_filter, query_manager::QueryManager},
+    interfaces::dtos::{MediaItemDto, MediaPageDto},
+    shared::util_traits::map_rows,
+};
+
+#[instrument(skip(conn_pool, query_manager, mapper))]
+pub async fn query_library_movies(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<MediaItemDto>,
+    library_id: i64,
+    filter: MediaFilter,
+    max_certification_level: Option<i64>,
+) -> Result<MediaPageDto> {
+    let mut conn = conn_pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    let query = query_manager
+        .get_query("movie", "find_library_movies_page")
+        .await?;
+    let raw_movies = bind_media_filter(
+        sqlx::query(&query),
+        library_id,
+        &filter,
+        max_certification_level,
+    )
+    .bind(filter.sort.as_str())
+    .bind(filter.order.as_str())
+    .bind(filter.limit)
+    .bind(filter.offset)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let query = query_manager
+        .get_query("movie", "count_library_movies")
+        .await?;
+    let total = bind_media_filter(
+        sqlx::query(&query),
+        library_id,
+        &filter,
+        max_certification_level,
+    )
+    .fetch_one(&mut *tx)
+    .await?
+    .get::<i64, _>("total");
+
+    Ok(MediaPageDto {
+        items: mapper(raw_movies),
+        total,
+        offset: filter.offset,
+        limit: filter.limit,
+    })
+}
+
+#[instrument(skip(conn_pool, query_manager))]
+pub async fn query_library_movie(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    library_id: i64,
+    movie_id: i64,
+) -> Result<Option<MediaItemDto>> {
+    let mut conn = conn_pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    let query = query_manager.get_query("movie", "find_movie_by_id").await?;
+    let rows = sqlx::query(&query)
+        .bind(library_id)
+        .bind(movie_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+    let mut movie = map_rows::<MediaItemDto>(rows).into_iter().next();
+
+    if let Some(ref mut movie) = movie {
+        let actors_query = query_manager
+            .get_query("movie", "find_actors_by_movie_id")
+            .await?;
+        let actor_rows = sqlx::query(&actors_query)
+            .bind(movie_id)
+            .fetch_all(&mut *tx)
+            .await?;
+        movie.actors = map_rows::<MediaActor>(actor_rows);
+    }
+
+    Ok(movie)
+}
+
+/// Whether the movie exists and the scope may see it.
+#[instrument(skip(conn_pool, query_manager))]
+pub async fn query_movie_visible(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    movie_id: i64,
+    scope: AccessScope,
+) -> Result<bool> {
+    let query = query_manager
+        .get_query("movie", "count_visible_movie")
+        .await?;
+
+    let total = sqlx::query(&query)
+        .bind(movie_id)
+        .bind(scope.user_id)
+        .bind(scope.max_certification_level)
+        .fetch_one(conn_pool)
+        .await?
+        .get::<i64, _>("total");
+
+    Ok(total > 0)
+}
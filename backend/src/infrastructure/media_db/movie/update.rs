@@ -0,0 +1,67 @@
+use anyhow::*;
+use sqlx::{Acquire, SqlitePool};
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::{movie::model::Movie, user::access::certification_level},
+    infrastructure::media_db::{
+        query_manager::QueryManager, subtitle::create::save_movie_subtitles,
+    },
+};
+
+/// Refreshes the metadata of a movie by its video file path and restores it
+/// if it was soft deleted by a previous rescan.
+#[instrument(skip(conn_pool, query_manager, movie))]
+pub async fn update_movie(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    movie: Movie,
+) -> Result<()> {
+    let mut conn = conn_pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    let query = query_manager
+        .get_query("movie", "update_movie_by_video_file_path")
+        .await?;
+    let certification_level = movie.certification.as_deref().and_then(certification_level);
+
+    sqlx::query(&query)
+        .bind(movie.title)
+        .bind(movie.original_title)
+        .bind(movie.sort_title)
+        .bind(movie.tagline)
+        .bind(movie.nfo_path)
+        .bind(movie.poster_path)
+        .bind(movie.fanart_path)
+        .bind(movie.subtitle_file_path)
+        .bind(movie.country)
+        .bind(movie.year)
+        .bind(movie.premiered)
+        .bind(movie.rating)
+        .bind(movie.certification)
+        .bind(certification_level)
+        .bind(movie.runtime)
+        .bind(movie.plot)
+        .bind(movie.tmdb_id)
+        .bind(movie.imdb_id)
+        .bind(movie.wikidata_id)
+        .bind(movie.file_size)
+        .bind(movie.file_modified_at)
+        .bind(movie.nfo_modified_at)
+        .bind(&movie.video_file_path)
+        .execute(&mut *tx)
+        .await?;
+
+    save_movie_subtitles(
+        &mut tx,
+        query_manager,
+        &movie.video_file_path,
+        &movie.subtitles,
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
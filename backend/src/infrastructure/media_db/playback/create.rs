@@ -0,0 +1,32 @@
+use anyhow::*;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::playback::model::PlaybackProgress,
+    infrastructure::media_db::query_manager::QueryManager,
+};
+
+#[instrument(skip(pool, query_manager))]
+pub async fn save_playback_progress(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    user_id: i64,
+    progress: PlaybackProgress,
+) -> Result<()> {
+    let query = query_manager
+        .get_query("playback", "save_playback_progress")
+        .await?;
+
+    sqlx::query(&query)
+        .bind(user_id)
+        .bind(progress.episode_id)
+        .bind(progress.position)
+        .bind(progress.duration)
+        .bind(progress.completed)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use anyhow::*;
+use sqlx::{sqlite::SqliteRow, SqlitePool};
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::user::access::AccessScope, infrastructure::media_db::query_manager::QueryManager,
+    interfaces::dtos::WatchingEpisodeDto,
+};
+
+#[instrument(skip(pool, query_manager, mapper))]
+pub async fn query_continue_watching(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<WatchingEpisodeDto>,
+    limit: i64,
+    scope: AccessScope,
+    user_id: i64,
+) -> Result<Vec<WatchingEpisodeDto>> {
+    let query = query_manager
+        .get_query("playback", "find_continue_watching")
+        .await?;
+
+    let raw_episodes = sqlx::query(&query)
+        .bind(limit)
+        .bind(scope.user_id)
+        .bind(scope.max_certification_level)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(mapper(raw_episodes))
+}
+
+#[instrument(skip(pool, query_manager, mapper))]
+pub async fn query_next_up(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<WatchingEpisodeDto>,
+    limit: i64,
+    scope: AccessScope,
+    user_id: i64,
+) -> Result<Vec<WatchingEpisodeDto>> {
+    let query = query_manager.get_query("playback", "find_next_up").await?;
+
+    let raw_episodes = sqlx::query(&query)
+        .bind(limit)
+        .bind(scope.user_id)
+        .bind(scope.max_certification_level)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(mapper(raw_episodes))
+}
@@ -0,0 +1,125 @@
+use anyhow::*;
+use sqlx::{sqlite::SqliteRow, SqlitePool};
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::{search::model::SearchResult, user::access::AccessScope},
+    infrastructure::media_db::query_manager::QueryManager,
+};
+
+#[instrument(skip(pool, query_manager, mapper))]
+pub async fn query_search(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<SearchResult>,
+    fts_query: String,
+    limit: i64,
+    scope: AccessScope,
+) -> Result<Vec<SearchResult>> {
+    let query = query_manager.get_query("search", "search_media").await?;
+
+    let raw_results = sqlx::query(&query)
+        .bind(fts_query)
+        .bind(limit)
+        .bind(scope.user_id)
+        .bind(scope.max_certification_level)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(mapper(raw_results))
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+    use crate::{
+        domain::search::model::{fts_query, SearchResultKind},
+        infrastructure::media_db::query_manager::FileQueryManager,
+        shared::util_traits::map_rows,
+    };
+
+    async fn setup() -> (SqlitePool, Arc<dyn QueryManager>) {
+        let sql_root_path = concat!(env!("CARGO_MANIFEST_DIR"), "/sql");
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let schema = std::fs::read_to_string(format!("{}/schema.sql", sql_root_path)).unwrap();
+        sqlx::raw_sql(&schema).execute(&pool).await.unwrap();
+        sqlx::raw_sql(
+            "INSERT INTO library (id, name, directory) VALUES (1, 'Movies', '/movies');
+             INSERT INTO users (id, username, password_hash) VALUES (2, 'viewer', 'hash');
+             INSERT INTO movies (id, title, video_file_path, plot, certification_level)
+                 VALUES (1, 'Spirited Away', '/movies/a.mkv', 'A girl in the spirit world', 1),
+                        (2, 'Spirited Horror', '/movies/b.mkv', NULL, 5);
+             INSERT INTO library_movies (library_id, movie_id) VALUES (1, 1), (1, 2);",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let query_manager = FileQueryManager::new(sql_root_path).await.unwrap();
+        query_manager.reload().await.unwrap();
+
+        (pool, Arc::new(query_manager))
+    }
+
+    async fn search(
+        pool: &SqlitePool,
+        query_manager: Arc<dyn QueryManager>,
+        query: &str,
+        scope: AccessScope,
+    ) -> Vec<SearchResult> {
+        query_search(
+            pool,
+            query_manager,
+            map_rows,
+            fts_query(query).unwrap(),
+            10,
+            scope,
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_search_finds_movies_within_scope() {
+        let (pool, query_manager) = setup().await;
+
+        let results = search(
+            &pool,
+            query_manager.clone(),
+            "spirit",
+            AccessScope::unrestricted(),
+        )
+        .await;
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|result| result.kind == SearchResultKind::Movie));
+
+        let scope = AccessScope {
+            user_id: Some(2),
+            max_certification_level: Some(3),
+        };
+        assert!(
+            search(&pool, query_manager.clone(), "spirit", scope.clone())
+                .await
+                .is_empty()
+        );
+
+        sqlx::query("INSERT INTO user_libraries (user_id, library_id) VALUES (2, 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let results = search(&pool, query_manager, "spirit", scope).await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, 1);
+        assert_eq!(results[0].media_id, Some(1));
+        assert_eq!(results[0].title, "Spirited Away");
+    }
+}
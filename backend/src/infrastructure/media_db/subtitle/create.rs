@@ -0,0 +1,76 @@
+use anyhow::*;
+use sqlx::SqliteConnection;
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::subtitle::model::Subtitle, infrastructure::media_db::query_manager::QueryManager,
+};
+
+/// Replaces the subtitles of the episode with the given video file, this runs inside
+/// the transaction which saves or updates the episode itself.
+#[instrument(skip(conn, query_manager, subtitles))]
+pub async fn save_episode_subtitles(
+    conn: &mut SqliteConnection,
+    query_manager: Arc<dyn QueryManager>,
+    video_file_path: &str,
+    subtitles: &[Subtitle],
+) -> Result<()> {
+    replace_subtitles(
+        conn,
+        query_manager,
+        ("delete_episode_subtitles", "save_episode_subtitle"),
+        video_file_path,
+        subtitles,
+    )
+    .await
+}
+
+#[instrument(skip(conn, query_manager, subtitles))]
+pub async fn save_movie_subtitles(
+    conn: &mut SqliteConnection,
+    query_manager: Arc<dyn QueryManager>,
+    video_file_path: &str,
+    subtitles: &[Subtitle],
+) -> Result<()> {
+    replace_subtitles(
+        conn,
+        query_manager,
+        ("delete_movie_subtitles", "save_movie_subtitle"),
+        video_file_path,
+        subtitles,
+    )
+    .await
+}
+
+async fn replace_subtitles(
+    conn: &mut SqliteConnection,
+    query_manager: Arc<dyn QueryManager>,
+    (delete_query_name, save_query_name): (&str, &str),
+    video_file_path: &str,
+    subtitles: &[Subtitle],
+) -> Result<()> {
+    let delete_query = query_manager
+        .get_query("subtitle", delete_query_name)
+        .await?;
+    sqlx::query(&delete_query)
+        .bind(video_file_path)
+        .execute(&mut *conn)
+        .await?;
+
+    let save_query = query_manager.get_query("subtitle", save_query_name).await?;
+    for subtitle in subtitles {
+        debug!("Saving subtitle: {:?}", subtitle);
+        sqlx::query(&save_query)
+            .bind(&subtitle.language)
+            .bind(&subtitle.title)
+            .bind(subtitle.format.as_str())
+            .bind(&subtitle.file_path)
+            .bind(subtitle.stream_index)
+            .bind(video_file_path)
+            .execute(&mut *conn)
+            .await?;
+    }
+
+    Ok(())
+}
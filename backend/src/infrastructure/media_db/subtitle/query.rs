@@ -0,0 +1,28 @@
+use anyhow::*;
+use sqlx::{sqlite::SqliteRow, SqlitePool};
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::subtitle::model::Subtitle, infrastructure::media_db::query_manager::QueryManager,
+};
+
+#[instrument(skip(conn_pool, query_manager, mapper))]
+pub async fn query_subtitles(
+    conn_pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<Subtitle>,
+    video_file_path: String,
+) -> Result<Vec<Subtitle>> {
+    let query = query_manager
+        .get_query("subtitle", "find_subtitles_by_video_file_path")
+        .await?;
+
+    let raw_subtitles = sqlx::query(&query)
+        .bind(&video_file_path)
+        .bind(&video_file_path)
+        .fetch_all(conn_pool)
+        .await?;
+
+    Ok(mapper(raw_subtitles))
+}
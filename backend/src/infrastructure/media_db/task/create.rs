@@ -0,0 +1,36 @@
+use anyhow::*;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::task::async_task::TaskRecord, infrastructure::media_db::query_manager::QueryManager,
+};
+
+#[instrument(skip(pool, query_manager))]
+pub async fn save_task_record(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    record: TaskRecord,
+) -> Result<()> {
+    let query = query_manager.get_query("task", "save_task_record").await?;
+
+    sqlx::query(&query)
+        .bind(record.task_id.0.to_string())
+        .bind(record.task_type.as_str())
+        .bind(record.status.as_str())
+        .bind(record.error)
+        .bind(record.started_at)
+        .bind(record.finished_at)
+        .execute(pool)
+        .await?;
+
+    // Old records are dropped whenever a new one comes in, so the history stays small
+    let query = query_manager
+        .get_query("task", "delete_expired_task_records")
+        .await?;
+
+    sqlx::query(&query).execute(pool).await?;
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anyhow::*;
+use sqlx::{sqlite::SqliteRow, SqlitePool};
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::task::async_task::{TaskRecord, TaskStatus},
+    infrastructure::media_db::query_manager::QueryManager,
+};
+
+#[instrument(skip(pool, query_manager, mapper))]
+pub async fn query_task_records(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<TaskRecord>,
+    status: Option<TaskStatus>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<TaskRecord>> {
+    let query = query_manager.get_query("task", "find_task_records").await?;
+
+    let raw_records = sqlx::query(&query)
+        .bind(status.map(|status| status.as_str()))
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(mapper(raw_records))
+}
@@ -0,0 +1,48 @@
+use actix::Addr;
+use anyhow::*;
+use std::sync::Arc;
+use tracing::*;
+
+use crate::domain::task::async_task::{TaskRecord, TaskStatus};
+use crate::infrastructure::media_db::actor::{QueryTaskRecords, SaveTaskRecord};
+use crate::infrastructure::media_db::database::Database;
+
+#[derive(Clone)]
+pub struct TaskRepository {
+    database_addr: Addr<Database>,
+}
+
+impl TaskRepository {
+    pub fn new(database_addr: Addr<Database>) -> Arc<Self> {
+        Arc::new(Self { database_addr })
+    }
+
+    #[instrument(skip(self))]
+    pub async fn save_task_record(&self, record: TaskRecord) -> Result<()> {
+        debug!("Saving task record: {:?}", record.task_id);
+
+        match self.database_addr.send(SaveTaskRecord { record }).await? {
+            true => Ok(()),
+            false => Err(anyhow!("Failed to save task record")),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_task_records(
+        &self,
+        status: Option<TaskStatus>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<TaskRecord>> {
+        let records = self
+            .database_addr
+            .send(QueryTaskRecords {
+                status,
+                limit,
+                offset,
+            })
+            .await?;
+
+        Ok(records)
+    }
+}
@@ -4,7 +4,8 @@ use std::sync::Arc;
 use tracing::*;
 
 use crate::{
-    domain::tv_show::model::TvShow, infrastructure::media_db::query_manager::QueryManager,
+    domain::{tv_show::model::TvShow, user::access::certification_level},
+    infrastructure::media_db::query_manager::QueryManager,
 };
 
 #[instrument(skip(conn_pool, query_manager, tv_show))]
@@ -18,6 +19,11 @@ pub async fn save_tv_show(
     let mut tx = conn.begin().await?;
 
     let save_tv_show_query = query_manager.get_query("tv_show", "save_tv_show").await?;
+    // Stored next to the label so access checks can compare certifications in SQL
+    let certification_level = tv_show
+        .certification
+        .as_deref()
+        .and_then(certification_level);
 
     let tv_show_id: i64 = sqlx::query_scalar(&save_tv_show_query)
         .bind(tv_show.title)
@@ -29,12 +35,15 @@ pub async fn save_tv_show(
         .bind(tv_show.year)
         .bind(tv_show.premiered)
         .bind(tv_show.rating)
+        .bind(tv_show.certification)
+        .bind(certification_level)
         .bind(tv_show.runtime)
         .bind(tv_show.plot)
         .bind(tv_show.tmdb_id)
         .bind(tv_show.imdb_id)
         .bind(tv_show.wikidata_id)
         .bind(tv_show.tvdb_id)
+        .bind(tv_show.metadata_modified_at)
         .fetch_one(&mut *tx)
         .await?;
 
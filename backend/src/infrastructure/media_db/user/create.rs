@@ -0,0 +1,70 @@
+use anyhow::*;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::user::api_key::ApiKeyScope, infrastructure::media_db::query_manager::QueryManager,
+};
+
+#[instrument(skip(pool, query_manager, password_hash))]
+pub async fn save_user(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    username: String,
+    password_hash: String,
+    is_admin: bool,
+) -> Result<i64> {
+    let query = query_manager.get_query("user", "save_user").await?;
+
+    let user_id = sqlx::query_scalar::<_, i64>(&query)
+        .bind(username)
+        .bind(password_hash)
+        .bind(is_admin)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(user_id)
+}
+
+#[instrument(skip(pool, query_manager, session_id))]
+pub async fn save_session(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    session_id: String,
+    user_id: i64,
+    ttl_seconds: i64,
+) -> Result<()> {
+    let query = query_manager.get_query("user", "save_session").await?;
+
+    sqlx::query(&query)
+        .bind(session_id)
+        .bind(user_id)
+        .bind(ttl_seconds)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+#[instrument(skip(pool, query_manager, key_hash))]
+pub async fn save_api_key(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    user_id: i64,
+    name: String,
+    scope: ApiKeyScope,
+    key_hash: String,
+) -> Result<i64> {
+    let query = query_manager.get_query("user", "save_api_key").await?;
+
+    let api_key_id = sqlx::query_scalar::<_, i64>(&query)
+        .bind(user_id)
+        .bind(name)
+        .bind(scope.as_str())
+        .bind(key_hash)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(api_key_id)
+}
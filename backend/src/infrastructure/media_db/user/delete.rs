@@ -0,0 +1,33 @@
+use anyhow::*;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+use tracing::*;
+
+use crate::infrastructure::media_db::query_manager::QueryManager;
+
+#[instrument(skip(pool, query_manager, session_id))]
+pub async fn delete_session(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    session_id: String,
+) -> Result<()> {
+    let query = query_manager.get_query("user", "delete_session").await?;
+
+    sqlx::query(&query).bind(session_id).execute(pool).await?;
+
+    Ok(())
+}
+
+#[instrument(skip(pool, query_manager))]
+pub async fn delete_expired_sessions(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+) -> Result<()> {
+    let query = query_manager
+        .get_query("user", "delete_expired_sessions")
+        .await?;
+
+    sqlx::query(&query).execute(pool).await?;
+
+    Ok(())
+}
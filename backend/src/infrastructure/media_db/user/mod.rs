@@ -0,0 +1,5 @@
+pub mod create;
+pub mod delete;
+pub mod query;
+pub mod repository;
+pub mod update;
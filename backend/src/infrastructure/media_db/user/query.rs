@@ -0,0 +1,117 @@
+use anyhow::*;
+use sqlx::{sqlite::SqliteRow, Row, SqlitePool};
+use std::sync::Arc;
+use tracing::*;
+
+use crate::{
+    domain::user::{
+        api_key::{ApiKey, ApiKeyGrant},
+        model::{User, UserCredentials},
+    },
+    infrastructure::media_db::query_manager::QueryManager,
+};
+
+#[instrument(skip(pool, query_manager, mapper))]
+pub async fn query_users(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<User>,
+) -> Result<Vec<User>> {
+    let query = query_manager.get_query("user", "find_users").await?;
+
+    let raw_users = sqlx::query(&query).fetch_all(pool).await?;
+
+    Ok(mapper(raw_users))
+}
+
+#[instrument(skip(pool, query_manager, mapper))]
+pub async fn query_user_credentials(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<UserCredentials>,
+    username: String,
+) -> Result<Option<UserCredentials>> {
+    let query = query_manager
+        .get_query("user", "find_user_credentials_by_username")
+        .await?;
+
+    let raw_users = sqlx::query(&query).bind(username).fetch_all(pool).await?;
+
+    Ok(mapper(raw_users).into_iter().next())
+}
+
+#[instrument(skip(pool, query_manager))]
+pub async fn query_user_count(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+) -> Result<i64> {
+    let query = query_manager.get_query("user", "count_users").await?;
+
+    let row = sqlx::query(&query).fetch_one(pool).await?;
+
+    Ok(row.get::<i64, _>("total"))
+}
+
+#[instrument(skip(pool, query_manager, mapper, session_id))]
+pub async fn query_session_user(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<User>,
+    session_id: String,
+) -> Result<Option<User>> {
+    let query = query_manager.get_query("user", "find_session_user").await?;
+
+    let raw_users = sqlx::query(&query).bind(session_id).fetch_all(pool).await?;
+
+    Ok(mapper(raw_users).into_iter().next())
+}
+
+#[instrument(skip(pool, query_manager))]
+pub async fn query_user_library_ids(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    user_id: i64,
+) -> Result<Vec<i64>> {
+    let query = query_manager
+        .get_query("user", "find_user_library_ids")
+        .await?;
+
+    let library_ids = sqlx::query_scalar::<_, i64>(&query)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(library_ids)
+}
+
+#[instrument(skip(pool, query_manager, mapper))]
+pub async fn query_api_keys(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<ApiKey>,
+    user_id: i64,
+) -> Result<Vec<ApiKey>> {
+    let query = query_manager
+        .get_query("user", "find_api_keys_by_user")
+        .await?;
+
+    let raw_api_keys = sqlx::query(&query).bind(user_id).fetch_all(pool).await?;
+
+    Ok(mapper(raw_api_keys))
+}
+
+#[instrument(skip(pool, query_manager, mapper, key_hash))]
+pub async fn query_api_key_grant(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    mapper: impl Fn(Vec<SqliteRow>) -> Vec<ApiKeyGrant>,
+    key_hash: String,
+) -> Result<Option<ApiKeyGrant>> {
+    let query = query_manager
+        .get_query("user", "find_api_key_grant")
+        .await?;
+
+    let raw_grants = sqlx::query(&query).bind(key_hash).fetch_all(pool).await?;
+
+    Ok(mapper(raw_grants).into_iter().next())
+}
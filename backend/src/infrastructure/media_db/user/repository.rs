@@ -0,0 +1,223 @@
+use actix::Addr;
+use anyhow::*;
+use std::sync::Arc;
+use tracing::*;
+
+use crate::domain::user::{
+    api_key::{ApiKey, ApiKeyGrant, ApiKeyScope},
+    model::{User, UserCredentials},
+};
+use crate::infrastructure::media_db::actor::{
+    DeleteExpiredSessions, DeleteSession, QueryApiKeyGrant, QueryApiKeys, QuerySessionUser,
+    QueryUserCount, QueryUserCredentials, QueryUserLibraryIds, QueryUsers, RevokeApiKey,
+    SaveApiKey, SaveSession, SaveUser, SaveUserAccess, UpdateApiKeyLastUsed,
+};
+use crate::infrastructure::media_db::database::Database;
+
+#[derive(Clone)]
+pub struct UserRepository {
+    database_addr: Addr<Database>,
+}
+
+impl UserRepository {
+    pub fn new(database_addr: Addr<Database>) -> Arc<Self> {
+        Arc::new(Self { database_addr })
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_users(&self) -> Result<Vec<User>> {
+        let users = self.database_addr.send(QueryUsers).await?;
+
+        Ok(users)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn count_users(&self) -> Result<i64> {
+        match self.database_addr.send(QueryUserCount).await? {
+            -1 => Err(anyhow!("Failed to count users")),
+            count => Ok(count),
+        }
+    }
+
+    #[instrument(skip(self, password_hash))]
+    pub async fn create_user(
+        &self,
+        username: String,
+        password_hash: String,
+        is_admin: bool,
+    ) -> Result<i64> {
+        debug!("Creating user: {}", username);
+
+        match self
+            .database_addr
+            .send(SaveUser {
+                username,
+                password_hash,
+                is_admin,
+            })
+            .await?
+        {
+            -1 => Err(anyhow!("Failed to create user, the username may be taken")),
+            user_id => Ok(user_id),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_user_credentials(&self, username: String) -> Result<Option<UserCredentials>> {
+        let credentials = self
+            .database_addr
+            .send(QueryUserCredentials { username })
+            .await?;
+
+        Ok(credentials)
+    }
+
+    #[instrument(skip(self, session_id))]
+    pub async fn create_session(
+        &self,
+        session_id: String,
+        user_id: i64,
+        ttl_seconds: i64,
+    ) -> Result<()> {
+        let saved = self
+            .database_addr
+            .send(SaveSession {
+                session_id,
+                user_id,
+                ttl_seconds,
+            })
+            .await?;
+        if !saved {
+            return Err(anyhow!("Failed to save session"));
+        }
+
+        Ok(())
+    }
+
+    /// The user of a session which has not expired.
+    #[instrument(skip(self, session_id))]
+    pub async fn get_session_user(&self, session_id: String) -> Result<Option<User>> {
+        let user = self
+            .database_addr
+            .send(QuerySessionUser { session_id })
+            .await?;
+
+        Ok(user)
+    }
+
+    #[instrument(skip(self, session_id))]
+    pub async fn delete_session(&self, session_id: String) -> Result<()> {
+        self.database_addr
+            .send(DeleteSession { session_id })
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete_expired_sessions(&self) -> Result<()> {
+        self.database_addr.send(DeleteExpiredSessions).await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_user_library_ids(&self, user_id: i64) -> Result<Vec<i64>> {
+        let library_ids = self
+            .database_addr
+            .send(QueryUserLibraryIds { user_id })
+            .await?;
+
+        Ok(library_ids)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn update_user_access(
+        &self,
+        user_id: i64,
+        library_ids: Vec<i64>,
+        max_certification: Option<String>,
+    ) -> Result<()> {
+        let saved = self
+            .database_addr
+            .send(SaveUserAccess {
+                user_id,
+                library_ids,
+                max_certification,
+            })
+            .await?;
+
+        if !saved {
+            return Err(anyhow!("Failed to update access of user {}", user_id));
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, key_hash))]
+    pub async fn create_api_key(
+        &self,
+        user_id: i64,
+        name: String,
+        scope: ApiKeyScope,
+        key_hash: String,
+    ) -> Result<i64> {
+        match self
+            .database_addr
+            .send(SaveApiKey {
+                user_id,
+                name,
+                scope,
+                key_hash,
+            })
+            .await?
+        {
+            -1 => Err(anyhow!("Failed to create API key")),
+            api_key_id => Ok(api_key_id),
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_api_keys(&self, user_id: i64) -> Result<Vec<ApiKey>> {
+        let api_keys = self.database_addr.send(QueryApiKeys { user_id }).await?;
+
+        Ok(api_keys)
+    }
+
+    /// The owner and the scope of a key which was not revoked.
+    #[instrument(skip(self, key_hash))]
+    pub async fn get_api_key_grant(&self, key_hash: String) -> Result<Option<ApiKeyGrant>> {
+        let grant = self
+            .database_addr
+            .send(QueryApiKeyGrant { key_hash })
+            .await?;
+
+        Ok(grant)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn update_api_key_last_used(&self, api_key_id: i64) -> Result<()> {
+        self.database_addr
+            .send(UpdateApiKeyLastUsed { api_key_id })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Revokes a key of the user, or any key when `user_id` is `None`.
+    #[instrument(skip(self))]
+    pub async fn revoke_api_key(&self, api_key_id: i64, user_id: Option<i64>) -> Result<()> {
+        let revoked = self
+            .database_addr
+            .send(RevokeApiKey {
+                api_key_id,
+                user_id,
+            })
+            .await?;
+        if !revoked {
+            return Err(anyhow!("API key {} not found", api_key_id));
+        }
+
+        Ok(())
+    }
+}
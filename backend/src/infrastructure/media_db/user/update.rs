@@ -0,0 +1,85 @@
+use anyhow::*;
+use sqlx::{Acquire, SqlitePool};
+use std::sync::Arc;
+use tracing::*;
+
+use crate::infrastructure::media_db::query_manager::QueryManager;
+
+/// Replaces the granted libraries and the certification cap of the user in one
+/// transaction, so a failed update never leaves the user with a partial grant.
+#[instrument(skip(pool, query_manager))]
+pub async fn update_user_access(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    user_id: i64,
+    library_ids: Vec<i64>,
+    max_certification: Option<String>,
+) -> Result<()> {
+    let mut conn = pool.acquire().await?;
+    let mut tx = conn.begin().await?;
+
+    let query = query_manager
+        .get_query("user", "update_user_max_certification")
+        .await?;
+    let updated = sqlx::query(&query)
+        .bind(max_certification)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    if updated.rows_affected() == 0 {
+        return Err(anyhow!("User {} not found", user_id));
+    }
+
+    let query = query_manager
+        .get_query("user", "delete_user_libraries")
+        .await?;
+    sqlx::query(&query).bind(user_id).execute(&mut *tx).await?;
+
+    let query = query_manager.get_query("user", "save_user_library").await?;
+    for library_id in library_ids {
+        sqlx::query(&query)
+            .bind(user_id)
+            .bind(library_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[instrument(skip(pool, query_manager))]
+pub async fn update_api_key_last_used(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    api_key_id: i64,
+) -> Result<()> {
+    let query = query_manager
+        .get_query("user", "update_api_key_last_used")
+        .await?;
+
+    sqlx::query(&query).bind(api_key_id).execute(pool).await?;
+
+    Ok(())
+}
+
+/// Revokes the key, only when it belongs to the user unless `user_id` is `None`. Returns
+/// whether a key was revoked.
+#[instrument(skip(pool, query_manager))]
+pub async fn revoke_api_key(
+    pool: &SqlitePool,
+    query_manager: Arc<dyn QueryManager>,
+    api_key_id: i64,
+    user_id: Option<i64>,
+) -> Result<bool> {
+    let query = query_manager.get_query("user", "revoke_api_key").await?;
+
+    let revoked = sqlx::query(&query)
+        .bind(api_key_id)
+        .bind(user_id)
+        .execute(pool)
+        .await?;
+
+    Ok(revoked.rows_affected() > 0)
+}
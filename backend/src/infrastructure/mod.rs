@@ -1,8 +1,12 @@
+pub mod artwork;
 pub mod async_task_pool;
+pub mod auth;
 pub mod event_dispatcher;
 pub mod file;
 pub mod hls;
 pub mod library_organizer;
+pub mod library_watcher;
 pub mod media_db;
 pub mod time;
+pub mod trickplay;
 pub mod video_pipeline;
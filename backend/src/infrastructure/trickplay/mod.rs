@@ -0,0 +1,2 @@
+pub mod sprite;
+pub mod store;
@@ -0,0 +1,139 @@
+use anyhow::*;
+use image::{
+    codecs::jpeg::JpegEncoder,
+    imageops::{self, FilterType},
+    RgbImage,
+};
+
+use crate::domain::trickplay::model::{Trickplay, TrickplayLayout};
+
+const SHEET_QUALITY: u8 = 80;
+
+/// Tiles the frames of a video onto sprite sheets as they are decoded, so only the sheet
+/// which is being filled is kept uncompressed.
+#[derive(Debug)]
+pub struct SpriteSheets {
+    layout: TrickplayLayout,
+    tile_height: Option<u32>,
+    current: Option<RgbImage>,
+    sheets: Vec<Vec<u8>>,
+    frame_count: u32,
+}
+
+impl SpriteSheets {
+    pub fn new(layout: TrickplayLayout) -> Self {
+        Self {
+            layout,
+            tile_height: None,
+            current: None,
+            sheets: vec![],
+            frame_count: 0,
+        }
+    }
+
+    /// Adds the next frame, its height sets the height of every tile.
+    pub fn push(&mut self, frame: &RgbImage) -> Result<()> {
+        let tile_width = self.layout.tile_width;
+        let tile_height = *self.tile_height.get_or_insert(frame.height());
+        let (_, x, y) = self.layout.tile_position(self.frame_count, tile_height);
+
+        let sheet = self.current.get_or_insert_with(|| {
+            RgbImage::new(
+                tile_width * self.layout.columns,
+                tile_height * self.layout.rows,
+            )
+        });
+        if frame.dimensions() == (tile_width, tile_height) {
+            imageops::replace(sheet, frame, x as i64, y as i64);
+        } else {
+            let frame = imageops::resize(frame, tile_width, tile_height, FilterType::Triangle);
+            imageops::replace(sheet, &frame, x as i64, y as i64);
+        }
+        self.frame_count += 1;
+
+        if self
+            .frame_count
+            .is_multiple_of(self.layout.frames_per_sheet())
+        {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<Trickplay> {
+        let tile_height = self
+            .tile_height
+            .ok_or_else(|| anyhow!("No frame was decoded"))?;
+        self.flush()?;
+
+        Ok(Trickplay {
+            index: self.layout.index(self.frame_count, tile_height),
+            sheets: self.sheets,
+        })
+    }
+
+    // The last sheet is cut below its last row of tiles
+    fn flush(&mut self) -> Result<()> {
+        let Some(sheet) = self.current.take() else {
+            return Ok(());
+        };
+        let (Some(tile_height), Some(last_frame)) =
+            (self.tile_height, self.frame_count.checked_sub(1))
+        else {
+            return Ok(());
+        };
+        let (_, _, last_y) = self.layout.tile_position(last_frame, tile_height);
+        let sheet =
+            imageops::crop_imm(&sheet, 0, 0, sheet.width(), last_y + tile_height).to_image();
+
+        let mut data = vec![];
+        JpegEncoder::new_with_quality(&mut data, SHEET_QUALITY)
+            .encode_image(&sheet)
+            .context("Failed to encode sprite sheet")?;
+        self.sheets.push(data);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgb;
+
+    use super::*;
+
+    #[test]
+    fn test_sprite_sheets() {
+        let layout = TrickplayLayout {
+            tile_width: 16,
+            columns: 2,
+            rows: 2,
+            ..Default::default()
+        };
+        let mut sprite_sheets = SpriteSheets::new(layout);
+        for _ in 0..5 {
+            sprite_sheets
+                .push(&RgbImage::from_pixel(16, 9, Rgb([200, 30, 30])))
+                .unwrap();
+        }
+
+        let trickplay = sprite_sheets.finish().unwrap();
+        let first = image::load_from_memory(&trickplay.sheets[0]).unwrap();
+        let last = image::load_from_memory(&trickplay.sheets[1]).unwrap();
+
+        assert_eq!(trickplay.sheets.len(), 2);
+        assert_eq!((first.width(), first.height()), (32, 18));
+        assert_eq!((last.width(), last.height()), (32, 9));
+        assert!(trickplay
+            .index
+            .ends_with("00:00:40.000 --> 00:00:50.000\nsprite_1.jpg#xywh=0,0,16,9\n\n"));
+    }
+
+    #[test]
+    fn test_sprite_sheets_without_frames() {
+        assert!(SpriteSheets::new(TrickplayLayout::default())
+            .finish()
+            .is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::*;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::*;
+use uuid::Uuid;
+
+use crate::domain::trickplay::model::{is_trickplay_file, sheet_name, Trickplay, TRICKPLAY_INDEX};
+
+/// Keeps the sprite sheets and the index of every episode in a directory named by the id
+/// of the episode.
+#[derive(Debug, Clone)]
+pub struct TrickplayStore {
+    root: PathBuf,
+}
+
+impl TrickplayStore {
+    #[instrument]
+    pub fn new(root: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&root)
+            .with_context(|| format!("Failed to create trickplay directory {:?}", root))?;
+
+        Ok(Self { root })
+    }
+
+    pub fn exists(&self, episode_id: i64) -> bool {
+        self.episode_dir(episode_id).join(TRICKPLAY_INDEX).exists()
+    }
+
+    /// Replaces the trickplay of the episode, the files are written to a temporary directory
+    /// first so the index never points at sheets which are missing.
+    #[instrument(skip(self, trickplay))]
+    pub fn save(&self, episode_id: i64, trickplay: &Trickplay) -> Result<()> {
+        let target = self.episode_dir(episode_id);
+        let temp_dir = self
+            .root
+            .join(format!("{}.{}.tmp", episode_id, Uuid::new_v4()));
+        fs::create_dir_all(&temp_dir)
+            .with_context(|| format!("Failed to create {:?}", temp_dir))?;
+
+        let result = write_trickplay(&temp_dir, trickplay).and_then(|_| {
+            if target.exists() {
+                fs::remove_dir_all(&target)
+                    .with_context(|| format!("Failed to remove {:?}", target))?;
+            }
+            fs::rename(&temp_dir, &target).with_context(|| format!("Failed to write {:?}", target))
+        });
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&temp_dir);
+        }
+
+        result
+    }
+
+    #[instrument(skip(self))]
+    pub fn find(&self, episode_id: i64, file: &str) -> Option<PathBuf> {
+        if !is_trickplay_file(file) {
+            return None;
+        }
+
+        let path = self.episode_dir(episode_id).join(file);
+        path.exists().then_some(path)
+    }
+
+    fn episode_dir(&self, episode_id: i64) -> PathBuf {
+        self.root.join(episode_id.to_string())
+    }
+}
+
+fn write_trickplay(dir: &Path, trickplay: &Trickplay) -> Result<()> {
+    for (sheet, data) in trickplay.sheets.iter().enumerate() {
+        let path = dir.join(sheet_name(sheet as u32));
+        fs::write(&path, data).with_context(|| format!("Failed to write {:?}", path))?;
+    }
+    let path = dir.join(TRICKPLAY_INDEX);
+    fs::write(&path, &trickplay.index).with_context(|| format!("Failed to write {:?}", path))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn test_save_and_find() {
+        let store_dir = TempDir::new().unwrap();
+        let store = TrickplayStore::new(store_dir.path().to_path_buf()).unwrap();
+        let trickplay = Trickplay {
+            sheets: vec![vec![1, 2, 3], vec![4, 5]],
+            index: "WEBVTT\n\n".to_string(),
+        };
+
+        assert!(!store.exists(7));
+        store.save(7, &trickplay).unwrap();
+        store.save(7, &trickplay).unwrap();
+
+        assert!(store.exists(7));
+        assert_eq!(
+            fs::read(store.find(7, "sprite_1.jpg").unwrap()).unwrap(),
+            vec![4, 5]
+        );
+        assert!(store.find(7, "sprite_2.jpg").is_none());
+        assert!(store.find(7, "../7/index.vtt").is_none());
+        assert!(store.find(8, TRICKPLAY_INDEX).is_none());
+    }
+}
@@ -5,11 +5,25 @@ use gstreamer::{Caps, Element};
 use std::fmt::Debug;
 use tracing::{debug, instrument};
 
-use crate::{domain::pipeline::ports::StreamBranch, shared::utils::ElementFactoryTrait};
+use crate::{
+    domain::pipeline::{
+        ports::StreamBranch,
+        profile::{AudioCodec, RateControl, TranscodingProfile, VideoCodec},
+        rendition::Rendition,
+    },
+    shared::utils::ElementFactoryTrait,
+};
+
+// Bitrate of the video when no rendition is given, in kbps
+const DEFAULT_VIDEO_BITRATE: u32 = 8000;
 
 #[derive(Debug)]
 pub struct VideoBranch {
     queue: Element,
+    // subtitleoverlay, only present when subtitles are burnt in
+    overlay: Option<Element>,
+    scaler: Option<Element>,
+    capsfilter: Option<Element>,
     converter: Element,
     encoder: Element,
     parser: Element,
@@ -20,14 +34,19 @@ impl StreamBranch for VideoBranch {
     fn new(factory: &(impl ElementFactoryTrait + Debug)) -> Result<Self> {
         let queue = factory.make("queue")?;
         let converter = factory.make("videoconvert")?;
-        let encoder = generate_encoder()?;
-
-        let parser = factory.make("h264parse")?;
+        let (encoder, parser) = generate_encoder(
+            factory,
+            &TranscodingProfile::default(),
+            DEFAULT_VIDEO_BITRATE,
+        )?;
 
         debug!("VideoBranch created with elements: videoconvert, x264enc, h264parse");
 
         Ok(Self {
             queue,
+            overlay: None,
+            scaler: None,
+            capsfilter: None,
             converter,
             encoder,
             parser,
@@ -39,18 +58,129 @@ impl StreamBranch for VideoBranch {
     }
 
     fn get_elements(&self) -> Vec<&Element> {
-        vec![&self.queue, &self.converter, &self.encoder, &self.parser]
+        [
+            Some(&self.queue),
+            self.overlay.as_ref(),
+            self.scaler.as_ref(),
+            self.capsfilter.as_ref(),
+            Some(&self.converter),
+            Some(&self.encoder),
+            Some(&self.parser),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    fn get_subtitle_entry(&self) -> Option<Element> {
+        self.overlay.clone()
+    }
+}
+
+impl VideoBranch {
+    /// Scales the video down to the height of the rendition and encodes it as the profile
+    /// says, videoscale keeps the aspect ratio since only the height is fixed.
+    #[instrument]
+    pub fn for_rendition(
+        factory: &(impl ElementFactoryTrait + Debug),
+        rendition: &Rendition,
+        profile: &TranscodingProfile,
+    ) -> Result<Self> {
+        let queue = factory.make("queue")?;
+        let scaler = factory.make("videoscale")?;
+        let capsfilter = factory.make("capsfilter")?;
+        capsfilter.set_property(
+            "caps",
+            Caps::builder("video/x-raw")
+                .field("height", rendition.height as i32)
+                .build(),
+        );
+        let converter = factory.make("videoconvert")?;
+        let (encoder, parser) = generate_encoder(factory, profile, rendition.video_bitrate)?;
+
+        debug!(
+            "VideoBranch created for rendition {} with profile {}",
+            rendition.name, profile.name
+        );
+
+        Ok(Self {
+            queue,
+            overlay: None,
+            scaler: Some(scaler),
+            capsfilter: Some(capsfilter),
+            converter,
+            encoder,
+            parser,
+        })
+    }
+
+    /// Composites a subtitle stream onto the video before it is scaled and encoded, which
+    /// is the only way to show bitmap subtitles like PGS or VobSub. subtitleoverlay renders
+    /// text subtitles as well.
+    #[instrument]
+    pub fn with_subtitle_overlay(
+        mut self,
+        factory: &(impl ElementFactoryTrait + Debug),
+    ) -> Result<Self> {
+        self.overlay = Some(factory.make("subtitleoverlay")?);
+        debug!("Subtitle overlay added to VideoBranch");
+
+        Ok(self)
     }
 }
 
+/// Creates the video encoder of the profile and the parser which goes after it. Only H.264
+/// tries hardware encoders, HEVC always uses x265.
+fn generate_encoder(
+    factory: &(impl ElementFactoryTrait + Debug),
+    profile: &TranscodingProfile,
+    rendition_bitrate: u32,
+) -> Result<(Element, Element)> {
+    let bitrate = profile.video_bitrate(rendition_bitrate);
+    let crf = match profile.rate_control {
+        Some(RateControl::Crf(crf)) => Some(crf),
+        _ => None,
+    };
+    let preset = profile.preset.as_deref();
+
+    let (encoder, parser) = match profile.video_codec {
+        VideoCodec::H264 => (
+            generate_h264_encoder(profile, rendition_bitrate)?,
+            factory.make("h264parse")?,
+        ),
+        VideoCodec::Hevc => {
+            let mut builder = ElementFactory::make("x265enc");
+            if let Some(preset) = preset {
+                builder = builder.property_from_str("speed-preset", preset);
+            }
+            builder = match (bitrate, crf) {
+                (Some(bitrate), _) => builder.property("bitrate", bitrate),
+                (None, Some(crf)) => builder.property("option-string", format!("crf={}", crf)),
+                (None, None) => builder,
+            };
+            let encoder = builder
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to create HEVC encoder: {}", e))?;
+            (encoder, factory.make("h265parse")?)
+        }
+    };
+
+    Ok((encoder, parser))
+}
+
 #[cfg(target_os = "linux")]
-fn generate_encoder() -> Result<Element> {
+fn generate_h264_encoder(profile: &TranscodingProfile, rendition_bitrate: u32) -> Result<Element> {
     use std::sync::OnceLock;
     use tracing::info;
 
     // Cache the encoder detection result to avoid repeated detection
     static ENCODER_TYPE: OnceLock<&'static str> = OnceLock::new();
 
+    // The hardware encoders are only driven by bitrate, constant quality needs x264
+    let Some(bitrate) = profile.video_bitrate(rendition_bitrate) else {
+        return generate_x264_encoder(profile, None);
+    };
+
     let encoder_type = ENCODER_TYPE.get_or_init(|| {
         // Try to use VAAPI hardware encoding for Intel and AMD
         if ElementFactory::make("vaapih264enc").build().is_ok() {
@@ -77,10 +207,12 @@ fn generate_encoder() -> Result<Element> {
 
     match *encoder_type {
         "vaapih264enc" => ElementFactory::make("vaapih264enc")
+            .property("bitrate", bitrate)
             .build()
             .map_err(|e| anyhow::anyhow!("Failed to create VAAPI encoder: {}", e)),
 
         "nvh264enc" => ElementFactory::make("nvh264enc")
+            .property("bitrate", bitrate)
             .build()
             .map_err(|e| anyhow::anyhow!("Failed to create NVIDIA encoder: {}", e)),
 
@@ -88,24 +220,46 @@ fn generate_encoder() -> Result<Element> {
             .build()
             .map_err(|e| anyhow::anyhow!("Failed to create V4L2 encoder: {}", e)),
 
-        _ => ElementFactory::make("x264enc")
-            .property_from_str("speed-preset", "superfast")
-            // .property("tune", "zerolatency")
-            .property("bitrate", 8000u32)
-            .build()
-            .map_err(|e| anyhow::anyhow!("Failed to create software encoder: {}", e)),
+        _ => generate_x264_encoder(profile, Some(bitrate)),
     }
 }
 
+#[cfg(target_os = "linux")]
+fn generate_x264_encoder(profile: &TranscodingProfile, bitrate: Option<u32>) -> Result<Element> {
+    let builder = ElementFactory::make("x264enc").property_from_str(
+        "speed-preset",
+        profile.preset.as_deref().unwrap_or("superfast"),
+    );
+    // .property("tune", "zerolatency")
+    let builder = match (bitrate, profile.rate_control) {
+        (Some(bitrate), _) => builder.property("bitrate", bitrate),
+        // Constant quality mode takes the CRF as quantizer
+        (None, Some(RateControl::Crf(crf))) => builder
+            .property_from_str("pass", "qual")
+            .property("quantizer", crf),
+        (None, _) => builder,
+    };
+
+    builder
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to create software encoder: {}", e))
+}
+
 #[cfg(target_os = "macos")]
-fn generate_encoder() -> Result<Element> {
+fn generate_h264_encoder(profile: &TranscodingProfile, rendition_bitrate: u32) -> Result<Element> {
     let encoder = ElementFactory::make("vtenc_h264")
         .property("max-keyframe-interval", 30)
         .property("max-keyframe-interval-duration", 2_000_000_000u64)
         .property("realtime", true)
         .property("allow-frame-reordering", false)
         .build()?;
-    encoder.set_property("bitrate", 8000u32);
+    // VideoToolbox has no constant quality mode, so it falls back to the rendition bitrate
+    encoder.set_property(
+        "bitrate",
+        profile
+            .video_bitrate(rendition_bitrate)
+            .unwrap_or(rendition_bitrate),
+    );
 
     Ok(encoder)
 }
@@ -123,20 +277,67 @@ unsafe impl Send for AudioBranch {}
 impl StreamBranch for AudioBranch {
     #[instrument]
     fn new(factory: &(impl ElementFactoryTrait + Debug)) -> Result<Self> {
+        Self::for_profile(factory, &TranscodingProfile::default())
+    }
+
+    fn get_entry(&self) -> Element {
+        self.queue.clone()
+    }
+
+    fn get_elements(&self) -> Vec<&Element> {
+        vec![
+            &self.queue,
+            &self.converter,
+            &self.resampler,
+            &self.capsfilter,
+            &self.encoder,
+            // &self.payloader,
+        ]
+    }
+}
+
+impl AudioBranch {
+    /// Encodes the audio with the codec and bitrate of the profile, the capsfilter
+    /// downmixes it when the profile limits the channels.
+    #[instrument]
+    pub fn for_profile(
+        factory: &(impl ElementFactoryTrait + Debug),
+        profile: &TranscodingProfile,
+    ) -> Result<Self> {
         let queue = factory.make("queue")?;
         let converter = factory.make("audioconvert")?;
         let resampler = factory.make("audioresample")?;
         let capsfilter = factory.make("capsfilter")?;
 
-        // TODO: maybe we have better way to set capsfilter
-        let cap = Caps::builder("audio/x-raw");
-        capsfilter.set_property("caps", cap.build());
+        let mut caps = Caps::builder("audio/x-raw");
+        if let Some(channels) = profile.audio_channels {
+            caps = caps.field("channels", channels as i32);
+        }
+        capsfilter.set_property("caps", caps.build());
 
         // let encoder = factory.make("opusenc")?;
-        let encoder = factory.make("avenc_aac")?;
+        let encoder = match profile.audio_codec {
+            AudioCodec::Aac => factory.make("avenc_aac")?,
+            AudioCodec::Mp3 => factory.make("lamemp3enc")?,
+            AudioCodec::Ac3 => factory.make("avenc_ac3")?,
+        };
+        match profile.audio_codec {
+            // libav takes bits per second
+            AudioCodec::Aac | AudioCodec::Ac3 => {
+                encoder.set_property("bitrate", profile.audio_bitrate as i64 * 1000)
+            }
+            AudioCodec::Mp3 => {
+                encoder.set_property_from_str("target", "bitrate");
+                encoder.set_property("bitrate", profile.audio_bitrate as i32);
+                encoder.set_property("cbr", true);
+            }
+        }
         // let payloader = factory.make("rtpopuspay")?;
 
-        debug!("AudioBranch created with elements: audioconvert, audioresample, capsfilter, opusenc, rtpopuspay");
+        debug!(
+            "AudioBranch created with {:?} at {} kbps",
+            profile.audio_codec, profile.audio_bitrate
+        );
 
         Ok(Self {
             queue,
@@ -147,20 +348,59 @@ impl StreamBranch for AudioBranch {
             // payloader,
         })
     }
+}
+
+/// Copies an H.264 stream into the HLS segments without decoding it.
+#[derive(Debug)]
+pub struct RemuxVideoBranch {
+    queue: Element,
+    parser: Element,
+}
+unsafe impl Send for RemuxVideoBranch {}
+impl StreamBranch for RemuxVideoBranch {
+    #[instrument]
+    fn new(factory: &(impl ElementFactoryTrait + Debug)) -> Result<Self> {
+        let queue = factory.make("queue")?;
+        let parser = factory.make("h264parse")?;
+
+        debug!("RemuxVideoBranch created with elements: queue, h264parse");
+
+        Ok(Self { queue, parser })
+    }
 
     fn get_entry(&self) -> Element {
         self.queue.clone()
     }
 
     fn get_elements(&self) -> Vec<&Element> {
-        vec![
-            &self.queue,
-            &self.converter,
-            &self.resampler,
-            &self.capsfilter,
-            &self.encoder,
-            // &self.payloader,
-        ]
+        vec![&self.queue, &self.parser]
+    }
+}
+
+/// Copies an AAC stream into the HLS segments, aacparse converts it to ADTS for mpegtsmux.
+#[derive(Debug)]
+pub struct RemuxAudioBranch {
+    queue: Element,
+    parser: Element,
+}
+unsafe impl Send for RemuxAudioBranch {}
+impl StreamBranch for RemuxAudioBranch {
+    #[instrument]
+    fn new(factory: &(impl ElementFactoryTrait + Debug)) -> Result<Self> {
+        let queue = factory.make("queue")?;
+        let parser = factory.make("aacparse")?;
+
+        debug!("RemuxAudioBranch created with elements: queue, aacparse");
+
+        Ok(Self { queue, parser })
+    }
+
+    fn get_entry(&self) -> Element {
+        self.queue.clone()
+    }
+
+    fn get_elements(&self) -> Vec<&Element> {
+        vec![&self.queue, &self.parser]
     }
 }
 
@@ -247,4 +487,47 @@ mod tests {
             "Error message should match"
         );
     }
+
+    #[test]
+    fn test_remux_branches_new_success() {
+        gstreamer::init().unwrap();
+
+        let mut mock_factory = MockElementFactory::new();
+        mock_factory
+            .expect_make()
+            .returning(|name| Ok(ElementFactory::make(name).build().unwrap()));
+
+        let video_branch = RemuxVideoBranch::new(&mock_factory).unwrap();
+        assert_eq!(video_branch.get_elements().len(), 2);
+
+        let audio_branch = RemuxAudioBranch::new(&mock_factory).unwrap();
+        assert_eq!(audio_branch.get_elements().len(), 2);
+    }
+
+    #[test]
+    fn test_video_branch_for_rendition() {
+        gstreamer::init().unwrap();
+
+        let mut mock_factory = MockElementFactory::new();
+        mock_factory
+            .expect_make()
+            .returning(|name| Ok(ElementFactory::make(name).build().unwrap()));
+
+        let branch = VideoBranch::for_rendition(
+            &mock_factory,
+            &Rendition::new(720, 4000),
+            &TranscodingProfile::default(),
+        )
+        .unwrap();
+        assert_eq!(branch.get_elements().len(), 6);
+        assert!(branch.get_subtitle_entry().is_none());
+
+        let branch = branch.with_subtitle_overlay(&mock_factory).unwrap();
+        assert_eq!(branch.get_elements().len(), 7);
+        assert_eq!(
+            branch.get_elements()[1].factory().unwrap().name(),
+            "subtitleoverlay"
+        );
+        assert!(branch.get_subtitle_entry().is_some());
+    }
 }
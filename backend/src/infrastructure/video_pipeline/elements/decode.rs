@@ -1,10 +1,17 @@
 use anyhow::Result;
 use gstreamer::Element;
-use gstreamer::{prelude::*, Pad};
-use std::fmt::Debug;
+use gstreamer::{prelude::*, Pad, Stream, StreamCollection, StreamType};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+};
 use tracing::*;
 
-use crate::domain::pipeline::ports::{DecodebinSignal, Decoder};
+use crate::domain::pipeline::ports::{DecodebinSignal, Decoder, DecoderSinks};
 use crate::shared::utils::ElementFactoryTrait;
 
 #[derive(Debug)]
@@ -22,25 +29,30 @@ impl Decoder for Decodebin {
     }
 
     #[instrument]
-    fn handle_signal(&mut self, signal: DecodebinSignal, video_sink: Element, audio_sink: Element) {
+    fn handle_signal(&mut self, signal: DecodebinSignal, sinks: DecoderSinks) {
         match signal {
             DecodebinSignal::ConnectPadAdded => {
-                let video_sink_clone = video_sink.clone();
-                let audio_sink_clone = audio_sink.clone();
-                let self_weak = self.element.downgrade();
-
-                self.element.connect_pad_added(move |_dbin, src_pad| {
-                    if let Some(self_element) = self_weak.upgrade() {
-                        let decodebin = Decodebin {
-                            element: self_element,
-                        };
-                        if let Err(e) = decodebin.pad_added_handler(
-                            src_pad,
-                            &video_sink_clone,
-                            &audio_sink_clone,
-                        ) {
-                            error!("Failed to handle pad added: {}", e);
-                        }
+                let sinks = Arc::new(sinks);
+                // stream id -> index of the audio track
+                let audio_tracks = Arc::new(Mutex::new(HashMap::new()));
+
+                // decodebin3 only exposes one stream per type by default, every audio track
+                // with a sink has to be selected explicitly
+                self.element.connect("select-stream", false, {
+                    let sinks = sinks.clone();
+                    let audio_tracks = audio_tracks.clone();
+                    move |args| {
+                        let collection = args[1].get::<StreamCollection>().ok()?;
+                        let stream = args[2].get::<Stream>().ok()?;
+                        let selected =
+                            Decodebin::select_stream(&collection, &stream, &sinks, &audio_tracks);
+                        Some((selected as i32).to_value())
+                    }
+                });
+
+                self.element.connect_pad_added(move |_, src_pad| {
+                    if let Err(e) = Decodebin::pad_added_handler(src_pad, &sinks, &audio_tracks) {
+                        error!("Failed to handle pad added: {}", e);
                     }
                 });
             }
@@ -71,26 +83,190 @@ impl Decodebin {
         Ok(())
     }
 
+    /// Selects the first video stream, the audio streams which have a sink and the subtitle
+    /// which is burnt in. Other subtitles are never decoded since they are served as WebVTT.
+    fn select_stream(
+        collection: &StreamCollection,
+        stream: &Stream,
+        sinks: &DecoderSinks,
+        audio_tracks: &Mutex<HashMap<String, u32>>,
+    ) -> bool {
+        let stream_type = stream.stream_type();
+        let mut streams_of_type = collection
+            .iter()
+            .filter(|s| s.stream_type().intersects(stream_type));
+
+        if stream_type.contains(StreamType::VIDEO) {
+            return streams_of_type
+                .take(1)
+                .any(|s| s.stream_id() == stream.stream_id());
+        }
+
+        if stream_type.contains(StreamType::AUDIO) {
+            let Some(index) = streams_of_type
+                .position(|s| s.stream_id() == stream.stream_id())
+                .map(|index| index as u32)
+            else {
+                return false;
+            };
+            if let Some(stream_id) = stream.stream_id() {
+                audio_tracks
+                    .lock()
+                    .unwrap()
+                    .insert(stream_id.to_string(), index);
+            }
+
+            return sinks.audio.iter().any(|(track, _)| *track == index);
+        }
+
+        if stream_type.contains(StreamType::TEXT) {
+            let index = streams_of_type
+                .position(|s| s.stream_id() == stream.stream_id())
+                .map(|index| index as u32);
+            return sinks
+                .subtitle
+                .as_ref()
+                .is_some_and(|(track, _)| index == Some(*track));
+        }
+
+        false
+    }
+
     #[instrument]
     fn pad_added_handler(
-        &self,
         src_pad: &Pad,
-        video_sink: &Element,
-        audio_sink: &Element,
+        sinks: &DecoderSinks,
+        audio_tracks: &Mutex<HashMap<String, u32>>,
     ) -> Result<()> {
         let pad_type = src_pad.name();
         debug!("Prepare to connect pad: {}", pad_type);
 
         if pad_type.starts_with("video_") {
-            Self::connect_pad(video_sink, src_pad, "video")?;
+            Self::connect_pad(&sinks.video, src_pad, "video")?;
         } else if pad_type.starts_with("audio_") {
-            Self::connect_pad(audio_sink, src_pad, "audio")?;
+            let track = src_pad
+                .stream()
+                .and_then(|stream| stream.stream_id())
+                .and_then(|stream_id| {
+                    audio_tracks
+                        .lock()
+                        .unwrap()
+                        .get(stream_id.as_str())
+                        .copied()
+                });
+            // Without stream info the pad can only be the single selected track
+            let sink = match track {
+                Some(track) => sinks.audio.iter().find(|(index, _)| *index == track),
+                None => sinks.audio.first(),
+            };
+
+            match sink {
+                Some((track, sink)) => {
+                    Self::connect_pad(sink, src_pad, &format!("audio {}", track))?
+                }
+                None => debug!("Skipping audio pad {} without sink", pad_type),
+            }
+        } else if pad_type.starts_with("text_") {
+            // Only the subtitle which is burnt in gets selected
+            match &sinks.subtitle {
+                Some((track, sink)) => {
+                    Self::connect_pad(sink, src_pad, &format!("subtitle {}", track))?
+                }
+                None => debug!("Skipping subtitle pad {} without sink", pad_type),
+            }
         }
 
         Ok(())
     }
 }
 
+/// Splits the container into parsed but still encoded streams, used when the streams are
+/// remuxed instead of transcoded.
+#[derive(Debug)]
+pub struct Parsebin {
+    element: Element,
+}
+unsafe impl Send for Parsebin {}
+impl Decoder for Parsebin {
+    #[instrument]
+    fn new(factory: &(impl ElementFactoryTrait + Debug)) -> Result<Self> {
+        let element = factory.make("parsebin")?;
+        debug!("Created parsebin");
+
+        Ok(Self { element })
+    }
+
+    #[instrument]
+    fn handle_signal(&mut self, signal: DecodebinSignal, sinks: DecoderSinks) {
+        match signal {
+            DecodebinSignal::ConnectPadAdded => {
+                // parsebin exposes the streams in container order, so counting the audio
+                // pads gives the index of their track
+                let audio_pads = AtomicU32::new(0);
+                self.element.connect_pad_added(move |_, src_pad| {
+                    if let Err(e) = Parsebin::pad_added_handler(src_pad, &sinks, &audio_pads) {
+                        error!("Failed to handle pad added: {}", e);
+                    }
+                });
+            }
+        }
+    }
+
+    fn get_element(&self) -> &Element {
+        &self.element
+    }
+}
+
+impl Parsebin {
+    // Unlike decodebin3 the pads of parsebin are named src_%u, so the stream type is
+    // taken from the caps. Only the first video stream and the audio tracks with a sink
+    // are linked, remuxed subtitles can't be burnt in.
+    #[instrument]
+    fn pad_added_handler(
+        src_pad: &Pad,
+        sinks: &DecoderSinks,
+        audio_pads: &AtomicU32,
+    ) -> Result<()> {
+        let caps = src_pad
+            .current_caps()
+            .unwrap_or_else(|| src_pad.query_caps(None));
+        let media_type = caps
+            .structure(0)
+            .map(|structure| structure.name().to_string())
+            .unwrap_or_default();
+        debug!(
+            "Prepare to connect pad {} with {}",
+            src_pad.name(),
+            media_type
+        );
+
+        let (sink, pad_type) = if media_type.starts_with("video/") {
+            (&sinks.video, "video")
+        } else if media_type.starts_with("audio/") {
+            let track = audio_pads.fetch_add(1, Ordering::SeqCst);
+            match sinks.audio.iter().find(|(index, _)| *index == track) {
+                Some((_, sink)) => (sink, "audio"),
+                None => {
+                    debug!("Skipping audio track {}", track);
+                    return Ok(());
+                }
+            }
+        } else {
+            return Ok(());
+        };
+
+        let sink_pad = sink
+            .static_pad("sink")
+            .ok_or(anyhow::anyhow!("No sink pad"))?;
+        if sink_pad.is_linked() {
+            debug!("Skipping additional {} stream", pad_type);
+            return Ok(());
+        }
+
+        Decodebin::connect_pad(sink, src_pad, pad_type)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,6 +298,44 @@ mod tests {
         assert!(decodebin.element.is::<Element>());
     }
 
+    #[test]
+    fn test_select_stream() {
+        gstreamer::init().unwrap();
+        let stream = |id: &str, stream_type: StreamType| {
+            Stream::new(Some(id), None, stream_type, gstreamer::StreamFlags::empty())
+        };
+        let video = stream("video", StreamType::VIDEO);
+        let japanese = stream("ja", StreamType::AUDIO);
+        let english = stream("en", StreamType::AUDIO);
+        let subtitle = stream("sub", StreamType::TEXT);
+        let collection = StreamCollection::builder(None)
+            .stream(video.clone())
+            .stream(japanese.clone())
+            .stream(english.clone())
+            .stream(subtitle.clone())
+            .build();
+
+        let sink = || gstreamer::ElementFactory::make("queue").build().unwrap();
+        let mut sinks = DecoderSinks {
+            video: sink(),
+            audio: vec![(1, sink())],
+            subtitle: None,
+        };
+        let audio_tracks = Mutex::new(HashMap::new());
+
+        let select = |sinks: &DecoderSinks, stream: &Stream| {
+            Decodebin::select_stream(&collection, stream, sinks, &audio_tracks)
+        };
+        assert!(select(&sinks, &video));
+        assert!(!select(&sinks, &japanese));
+        assert!(select(&sinks, &english));
+        assert!(!select(&sinks, &subtitle));
+        assert_eq!(audio_tracks.lock().unwrap().get("en"), Some(&1));
+
+        sinks.subtitle = Some((0, sink()));
+        assert!(select(&sinks, &subtitle));
+    }
+
     #[test]
     fn test_new_failure() {
         gstreamer::init().unwrap();
@@ -20,11 +20,20 @@ pub struct HlsSinkImpl {
 unsafe impl Send for HlsSinkImpl {}
 impl HlsSink for HlsSinkImpl {
     #[instrument]
-    fn new(hls_state_actor_addr: Addr<HlsStateActor>) -> Result<Self> {
+    fn new(output_dir: &Path, hls_state_actor_addr: Addr<HlsStateActor>) -> Result<Self> {
         // TODO: figure out if we can use hlssink3 for hls on linux
         let element = ElementFactory::make("hlssink2")
-            .property("location", "./tmp/segment_%05d.ts")
-            .property("playlist-location", "./tmp/event.m3u8")
+            .property(
+                "location",
+                output_dir
+                    .join("segment_%05d.ts")
+                    .to_string_lossy()
+                    .to_string(),
+            )
+            .property(
+                "playlist-location",
+                output_dir.join("event.m3u8").to_string_lossy().to_string(),
+            )
             // .property("target-duration", 10u32)
             // .property_from_str("playlist-type", "2")
             .property("max-files", 100000u32)
@@ -50,11 +59,13 @@ impl HlsSink for HlsSinkImpl {
 
                 let path = Path::new(&path_str);
                 let parent_path = path.parent().expect("Failed to get parent path");
+                let rendition_dir = parent_path.to_string_lossy().to_string();
 
-                let index = match Runtime::new()
-                    .unwrap()
-                    .block_on(async { hls_state_actor_addr.send(GetSegmentIndex).await })
-                {
+                let index = match Runtime::new().unwrap().block_on(async {
+                    hls_state_actor_addr
+                        .send(GetSegmentIndex(rendition_dir.clone()))
+                        .await
+                }) {
                     Ok(Ok(index)) => index,
                     Ok(Err(e)) => {
                         error!("Failed to get segment index: {}", e);
@@ -83,7 +94,9 @@ impl HlsSink for HlsSinkImpl {
                 ) {
                     Ok(stream) => {
                         match Runtime::new().unwrap().block_on(async {
-                            hls_state_actor_addr.send(IncrementSegmentIndex).await
+                            hls_state_actor_addr
+                                .send(IncrementSegmentIndex(rendition_dir.clone()))
+                                .await
                         }) {
                             Ok(_) => (),
                             Err(e) => error!("Failed to increment segment index: {}", e),
@@ -100,23 +113,6 @@ impl HlsSink for HlsSinkImpl {
             }
         });
 
-        // element.connect("get-playlist-stream", false, move |args| {
-        //     debug!("get-playlist-stream signal: {:?}", args);
-
-        //     let path_str = match args[1].get::<String>() {
-        //         Ok(path) => path,
-        //         Err(e) => {
-        //             error!("Failed to get path: {}", e);
-        //             return None;
-        //         }
-        //     };
-        //     info!("path_str: {}", path_str);
-        //     // let stream = PlaylistStream::new(path_str).get_write_stream();
-        //     let stream = get_playlist_stream(path_str).get_write_stream();
-
-        //     Some(stream.to_value())
-        // });
-
         element.connect("get-playlist-stream", false, {
             let hls_state_actor_addr = hls_state_actor_addr.clone();
             move |args| {
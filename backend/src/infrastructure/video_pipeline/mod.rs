@@ -1,3 +1,7 @@
 pub mod actor;
 pub mod elements;
 pub mod pipeline;
+pub mod probe;
+pub mod snapshot;
+pub mod subtitle;
+pub mod trickplay;
@@ -2,13 +2,12 @@ use anyhow::*;
 use async_trait::async_trait;
 use gstreamer::{
     glib::WeakRef, prelude::*, query, BusSyncReply, ClockTime, DebugGraphDetails,
-    Element as GstElement, Format, GenericFormattedValue, Message as GstMessage, MessageView,
-    Pipeline as GstPipeline, SeekFlags, State,
+    Element as GstElement, ElementFactory as GstElementFactory, Format, GenericFormattedValue,
+    Message as GstMessage, MessageView, Pipeline as GstPipeline, SeekFlags, State,
 };
 use std::{
     result::Result::Ok,
     sync::{Arc, Mutex, RwLock},
-    time::Duration,
 };
 use tokio::sync::mpsc::UnboundedReceiver;
 use tracing::*;
@@ -17,20 +16,38 @@ use crate::{
     domain::pipeline::{
         event::PipelineEvent,
         model::{Duration as DomainDuration, PipelineState, Position},
-        ports::{DecodebinSignal, Decoder, HlsSink, PipelinePort, Source, StreamBranch},
+        ports::{
+            DecodebinSignal, Decoder, DecoderSinks, HlsSink, PipelinePort, Source, StreamBranch,
+        },
     },
     infrastructure::event_dispatcher::{domain_event::DomainEvent, event_bus::EventBus},
-    init::app_state::{get_pipeline_segment_duration, set_segment_index},
 };
 
+/// The audio of a pipeline, the chosen track is muxed into every video rendition.
+pub struct PipelineAudio {
+    pub track: u32,
+    pub branch: Arc<dyn StreamBranch + Send>,
+    pub alternates: Vec<AlternateAudio>,
+}
+
+/// Another audio track of the file, encoded into an audio only rendition.
+pub struct AlternateAudio {
+    pub track: u32,
+    pub branch: Arc<dyn StreamBranch + Send>,
+    pub hls_sink: Arc<dyn HlsSink + Send>,
+}
+
 // TODO: 1. Avoid using Box<dyn Source>, use Arc<dyn Source> instead
 // TODO: 2. Each element should have more general type instead of using WebRtcElement or other specific elements
 pub struct Pipeline {
     pub source: Arc<dyn Source + Send>,
     decoder: Arc<dyn Decoder + Send>,
-    video_branch: Arc<dyn StreamBranch + Send>,
-    audio_branch: Arc<dyn StreamBranch + Send>,
-    hls_sink: Arc<dyn HlsSink + Send>,
+    // One video branch and hls sink per rendition, the audio is encoded once and shared
+    video_branches: Vec<Arc<dyn StreamBranch + Send>>,
+    hls_sinks: Vec<Arc<dyn HlsSink + Send>>,
+    audio: PipelineAudio,
+    // Index of the subtitle stream burnt into the video branches
+    burn_in_subtitle: Option<u32>,
     gst_pipeline: Option<GstPipeline>,
 
     state: Arc<RwLock<State>>,
@@ -41,17 +58,19 @@ impl Pipeline {
     pub fn new(
         source: Arc<dyn Source + Send>,
         decoder: Arc<dyn Decoder + Send>,
-        video_branch: Arc<dyn StreamBranch + Send>,
-        audio_branch: Arc<dyn StreamBranch + Send>,
-        hls_sink: Arc<dyn HlsSink + Send>,
+        video_branches: Vec<Arc<dyn StreamBranch + Send>>,
+        hls_sinks: Vec<Arc<dyn HlsSink + Send>>,
+        audio: PipelineAudio,
+        burn_in_subtitle: Option<u32>,
         event_bus: Arc<EventBus>,
     ) -> Self {
         Self {
             source,
             decoder,
-            video_branch,
-            audio_branch,
-            hls_sink,
+            video_branches,
+            hls_sinks,
+            audio,
+            burn_in_subtitle,
             gst_pipeline: None,
             state: Arc::new(RwLock::new(State::Null)),
             event_bus,
@@ -93,61 +112,139 @@ impl PipelinePort for Pipeline {
 
         let decoder = self.decoder.get_element();
 
-        let video_branch_elements = self.video_branch.get_elements();
-        let audio_branch_elements = self.audio_branch.get_elements();
-
-        // let mpegtsmux = ElementFactory::make("mpegtsmux")
-        //     .build()
-        //     .map_err(|e| anyhow::anyhow!("Failed to create mpegtsmux element: {}", e))?;
-
-        let hls_sink = self.hls_sink.get_element();
+        // The decoded video is split into one branch per rendition, while the encoded audio
+        // is split to feed every hls sink
+        let video_tee = GstElementFactory::make("tee")
+            .name("video_tee")
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to create video tee: {}", e))?;
+        let audio_tee = GstElementFactory::make("tee")
+            .name("audio_tee")
+            .build()
+            .map_err(|e| anyhow::anyhow!("Failed to create audio tee: {}", e))?;
         debug!("Generate source, decoder, branches and sink elements");
 
+        if self.video_branches.len() != self.hls_sinks.len() {
+            return Err(anyhow::anyhow!(
+                "Every video branch needs its own hls sink, got {} branches and {} sinks",
+                self.video_branches.len(),
+                self.hls_sinks.len()
+            ));
+        }
+
         let gst_pipeline = GstPipeline::with_name("normal_pipeline");
         debug!("Pipeline created");
 
-        let mut elements = vec![source, decoder];
-        elements.extend(video_branch_elements);
-        elements.extend(audio_branch_elements);
-        elements.push(hls_sink);
-        // elements.push(&mpegtsmux);
+        let mut elements = vec![source, decoder, &video_tee, &audio_tee];
+        elements.extend(self.audio.branch.get_elements());
+        for (video_branch, hls_sink) in self.video_branches.iter().zip(&self.hls_sinks) {
+            elements.extend(video_branch.get_elements());
+            elements.push(hls_sink.get_element());
+        }
+        for alternate in &self.audio.alternates {
+            elements.extend(alternate.branch.get_elements());
+            elements.push(alternate.hls_sink.get_element());
+        }
         gst_pipeline.add_many(&elements)?;
         debug!("Elements added to pipeline");
 
         GstElement::link_many([source, decoder])?;
 
-        // TODO: fuck we should make sure the order of video and audio sink, otherwise using enum to store that shit
-        let video_sink = &self.video_branch.get_entry();
-        let audio_sink = &self.audio_branch.get_entry();
+        // The subtitle is split like the audio, every video branch overlays it on its own.
+        // Its pad is linked before the branch, so linking the branch only finds the video pad
+        let subtitle_sink = match self.burn_in_subtitle {
+            Some(track) => {
+                let subtitle_tee = GstElementFactory::make("tee")
+                    .name("subtitle_tee")
+                    .build()
+                    .map_err(|e| anyhow::anyhow!("Failed to create subtitle tee: {}", e))?;
+                gst_pipeline.add(&subtitle_tee)?;
+
+                for overlay in self
+                    .video_branches
+                    .iter()
+                    .filter_map(|video_branch| video_branch.get_subtitle_entry())
+                {
+                    let subtitle_queue = GstElementFactory::make("queue")
+                        .build()
+                        .map_err(|e| anyhow::anyhow!("Failed to create subtitle queue: {}", e))?;
+                    gst_pipeline.add(&subtitle_queue)?;
+                    subtitle_tee.link(&subtitle_queue)?;
+                    subtitle_queue.link_pads(Some("src"), &overlay, Some("subtitle_sink"))?;
+                }
+                debug!("Subtitle {} linked to the video branches", track);
+
+                Some((track, subtitle_tee))
+            }
+            None => None,
+        };
 
+        let mut audio_sinks = vec![(self.audio.track, self.audio.branch.get_entry())];
+        audio_sinks.extend(
+            self.audio
+                .alternates
+                .iter()
+                .map(|alternate| (alternate.track, alternate.branch.get_entry())),
+        );
         Arc::get_mut(&mut self.decoder).unwrap().handle_signal(
             DecodebinSignal::ConnectPadAdded,
-            video_sink.clone(),
-            audio_sink.clone(),
+            DecoderSinks {
+                video: video_tee.clone(),
+                audio: audio_sinks,
+                subtitle: subtitle_sink,
+            },
         );
         debug!("Signal connect-pad-added of decoder connected");
 
-        // TODO: only fool use index to get video and audio branch, fuck
-        let video_branch_elements = self.video_branch.get_elements();
-        // video_branch_elements.push(sink);
-        GstElement::link_many(&video_branch_elements)?;
-        debug!("Video branch elements linked");
-
-        let video_branch_elements = self.video_branch.get_elements();
-        let video_parser = video_branch_elements.last().unwrap();
-        let video_src_pad = video_parser.static_pad("src").unwrap();
-        let hlssink_video_pad = hls_sink.request_pad_simple("video").unwrap();
-        video_src_pad.link(&hlssink_video_pad)?;
-
-        let audio_branch_elements = self.audio_branch.get_elements();
+        let audio_branch_elements = self.audio.branch.get_elements();
         GstElement::link_many(&audio_branch_elements)?;
+        audio_branch_elements.last().unwrap().link(&audio_tee)?;
         debug!("Audio branch elements linked");
 
-        let audio_branch_elements = self.audio_branch.get_elements();
-        let audio_parser = audio_branch_elements.last().unwrap();
-        let audio_src_pad = audio_parser.static_pad("src").unwrap();
-        let hlssink_audio_pad = hls_sink.request_pad_simple("audio").unwrap();
-        audio_src_pad.link(&hlssink_audio_pad)?;
+        for (video_branch, hls_sink) in self.video_branches.iter().zip(&self.hls_sinks) {
+            let hls_sink = hls_sink.get_element();
+
+            let video_branch_elements = video_branch.get_elements();
+            video_tee.link(&video_branch.get_entry())?;
+            GstElement::link_many(&video_branch_elements)?;
+
+            let video_parser = video_branch_elements.last().unwrap();
+            let video_src_pad = video_parser.static_pad("src").unwrap();
+            let hlssink_video_pad = hls_sink.request_pad_simple("video").unwrap();
+            video_src_pad.link(&hlssink_video_pad)?;
+
+            // Each sink needs its own queue so a slow rendition doesn't stall the others
+            let audio_queue = GstElementFactory::make("queue")
+                .build()
+                .map_err(|e| anyhow::anyhow!("Failed to create audio queue: {}", e))?;
+            gst_pipeline.add(&audio_queue)?;
+            audio_tee.link(&audio_queue)?;
+            let audio_src_pad = audio_queue.static_pad("src").unwrap();
+            let hlssink_audio_pad = hls_sink.request_pad_simple("audio").unwrap();
+            audio_src_pad.link(&hlssink_audio_pad)?;
+        }
+        debug!("{} video branches linked", self.video_branches.len());
+
+        for alternate in &self.audio.alternates {
+            let audio_branch_elements = alternate.branch.get_elements();
+            GstElement::link_many(&audio_branch_elements)?;
+
+            let audio_src_pad = audio_branch_elements
+                .last()
+                .unwrap()
+                .static_pad("src")
+                .unwrap();
+            let hlssink_audio_pad = alternate
+                .hls_sink
+                .get_element()
+                .request_pad_simple("audio")
+                .unwrap();
+            audio_src_pad.link(&hlssink_audio_pad)?;
+        }
+        debug!(
+            "{} alternate audio tracks linked",
+            self.audio.alternates.len()
+        );
 
         let bus = gst_pipeline.bus().ok_or(anyhow::anyhow!("Bus not found"))?;
 
@@ -228,39 +325,20 @@ impl PipelinePort for Pipeline {
         }
     }
 
+    /// Moves the pipeline to the given position, the caller is responsible for pointing the
+    /// hls sink at the segment which belongs to the position before.
     #[instrument(skip(self))]
     fn seek(&self, position: Position) -> Result<()> {
-        // if !self.is_valid_position(position)? {
-        //     error!("Invalid position: {:?} ns", position);
-        //     return Err(anyhow::anyhow!("Invalid position: {:?} ns", position));
-        // }
-        // if self.query_pipeline_state()? != State::Playing {
-        //     error!("Pipeline is not playing");
-        //     return Err(anyhow::anyhow!("Pipeline is not playing"));
-        // }
-
         let gst_pipeline = self
             .gst_pipeline
             .as_ref()
             .ok_or(anyhow::anyhow!("Pipeline not built"))?;
 
-        let duration = get_pipeline_segment_duration();
-        info!("Query duration: {:?} ns", duration);
-
-        let position_ns = ClockTime::try_from(Duration::from_secs(position.as_nanos()))?;
-        let position_ns = position_ns.nseconds();
-        info!("Seeked position: {:?} ns", position_ns);
-
-        // let start_index = (position_ns / duration) + 30;
-        let start_index = position_ns / duration;
-        info!("Start index: {:?}", start_index);
-        set_segment_index(start_index as u32);
-
-        let position = ClockTime::try_from(Duration::from_secs(position.as_nanos()))?;
-        match gst_pipeline.seek_simple(SeekFlags::KEY_UNIT, position) {
-            Ok(_) => info!("Seek to position {:?} success", position),
-            Err(e) => error!("Seek to position {:?} failed: {}", position, e),
-        }
+        let position = ClockTime::from_nseconds(position.as_nanos());
+        gst_pipeline
+            .seek_simple(SeekFlags::FLUSH | SeekFlags::KEY_UNIT, position)
+            .map_err(|e| anyhow::anyhow!("Seek to position {} failed: {}", position, e))?;
+        info!("Seek to position {} success", position);
 
         Ok(())
     }
@@ -0,0 +1,246 @@
+use anyhow::*;
+use gstreamer::{ClockTime, StructureRef};
+use gstreamer_pbutils::{prelude::*, Discoverer, DiscovererInfo};
+use std::path::Path;
+use tracing::*;
+
+use crate::domain::media_info::model::{AudioTrack, HdrFormat, MediaInfo, SubtitleTrack};
+
+const DISCOVER_TIMEOUT_SECONDS: u64 = 10;
+
+/// Runs the GStreamer discoverer over the file, this reads the container headers
+/// and blocks for up to a few seconds.
+pub fn discover(path: &Path) -> Result<DiscovererInfo> {
+    gstreamer::init()?;
+
+    let uri = gstreamer::glib::filename_to_uri(path, None)
+        .map_err(|e| anyhow!("Invalid video path {:?}: {}", path, e))?;
+    let discoverer = Discoverer::new(ClockTime::from_seconds(DISCOVER_TIMEOUT_SECONDS))
+        .map_err(|e| anyhow!("Failed to create discoverer: {}", e))?;
+
+    discoverer
+        .discover_uri(&uri)
+        .map_err(|e| anyhow!("Failed to discover {:?}: {}", path, e))
+}
+
+#[instrument]
+pub fn probe_media(path: &Path) -> Result<MediaInfo> {
+    let info = discover(path)?;
+    Ok(media_info(&info, path))
+}
+
+/// Reads the media info out of a discoverer result, so callers which need more of the result
+/// only discover the file once.
+#[instrument(skip(info))]
+pub fn media_info(info: &DiscovererInfo, path: &Path) -> MediaInfo {
+    let container = info
+        .stream_info()
+        .and_then(|stream| stream.caps())
+        .and_then(|caps| caps.structure(0).and_then(container_name));
+    let video_stream = info.video_streams().into_iter().next();
+    let video_codec = video_stream
+        .as_ref()
+        .and_then(|stream| stream.caps())
+        .and_then(|caps| caps.structure(0).and_then(codec_name));
+    let frame_rate = video_stream.as_ref().and_then(|stream| {
+        let frame_rate = stream.framerate();
+        (frame_rate.numer() > 0 && frame_rate.denom() > 0)
+            .then(|| frame_rate.numer() as f64 / frame_rate.denom() as f64)
+    });
+    let hdr_format = video_stream
+        .as_ref()
+        .and_then(|stream| stream.caps())
+        .and_then(|caps| {
+            caps.structure(0)
+                .and_then(|structure| structure.get::<String>("colorimetry").ok())
+        })
+        .and_then(|colorimetry| hdr_format(&colorimetry));
+    let audio_tracks = info
+        .audio_streams()
+        .iter()
+        .enumerate()
+        .map(|(index, stream)| AudioTrack {
+            index: index as u32,
+            language: stream.language().map(|language| language.to_string()),
+            title: stream.tags().and_then(|tags| {
+                tags.get::<gstreamer::tags::Title>()
+                    .map(|title| title.get().to_string())
+            }),
+            codec: stream
+                .caps()
+                .and_then(|caps| caps.structure(0).and_then(codec_name)),
+            channels: Some(stream.channels()).filter(|channels| *channels > 0),
+        })
+        .collect();
+    let subtitle_tracks = info
+        .subtitle_streams()
+        .iter()
+        .enumerate()
+        .map(|(index, stream)| {
+            let caps = stream.caps();
+            let structure = caps.as_ref().and_then(|caps| caps.structure(0));
+            SubtitleTrack {
+                index: index as u32,
+                language: stream.language().map(|language| language.to_string()),
+                title: stream.tags().and_then(|tags| {
+                    tags.get::<gstreamer::tags::Title>()
+                        .map(|title| title.get().to_string())
+                }),
+                codec: structure.and_then(codec_name),
+                image_based: structure
+                    .is_some_and(|structure| structure.name().starts_with("subpicture/")),
+            }
+        })
+        .collect();
+
+    let duration = info.duration().map(|duration| duration.seconds());
+
+    let media_info = MediaInfo {
+        container,
+        duration,
+        bitrate: bitrate(info, path, duration),
+        video_codec,
+        width: video_stream.as_ref().map(|stream| stream.width()),
+        height: video_stream.as_ref().map(|stream| stream.height()),
+        frame_rate,
+        hdr_format,
+        audio_tracks,
+        subtitle_tracks,
+    };
+    debug!("Probed {:?}: {:?}", path, media_info);
+
+    media_info
+}
+
+/// Containers rarely store the bitrate of the streams, so it is estimated from the file size
+/// unless every stream has one.
+fn bitrate(info: &DiscovererInfo, path: &Path, duration: Option<u64>) -> Option<u64> {
+    let video_bitrates = info
+        .video_streams()
+        .iter()
+        .map(|stream| stream.bitrate())
+        .collect::<Vec<_>>();
+    let audio_bitrates = info
+        .audio_streams()
+        .iter()
+        .map(|stream| stream.bitrate())
+        .collect::<Vec<_>>();
+    let stream_bitrates = video_bitrates.iter().chain(&audio_bitrates);
+    if !video_bitrates.is_empty() && stream_bitrates.clone().all(|bitrate| *bitrate > 0) {
+        return Some(stream_bitrates.map(|bitrate| *bitrate as u64).sum());
+    }
+
+    let file_size = std::fs::metadata(path).ok()?.len();
+    duration
+        .filter(|duration| *duration > 0)
+        .map(|duration| file_size * 8 / duration)
+}
+
+/// Maps the colorimetry of the video caps to its HDR format, GStreamer either writes the name
+/// like `bt2100-pq` or the numeric `range:matrix:transfer:primaries` form.
+fn hdr_format(colorimetry: &str) -> Option<HdrFormat> {
+    // Numeric values of GstVideoTransferFunction
+    const TRANSFER_SMPTE2084: &str = "14";
+    const TRANSFER_ARIB_STD_B67: &str = "15";
+
+    match colorimetry {
+        "bt2100-pq" => return Some(HdrFormat::Hdr10),
+        "bt2100-hlg" => return Some(HdrFormat::Hlg),
+        _ => {}
+    }
+
+    match colorimetry.split(':').nth(2) {
+        Some(TRANSFER_SMPTE2084) => Some(HdrFormat::Hdr10),
+        Some(TRANSFER_ARIB_STD_B67) => Some(HdrFormat::Hlg),
+        _ => None,
+    }
+}
+
+fn container_name(structure: &StructureRef) -> Option<String> {
+    let name = match structure.name().as_str() {
+        "video/quicktime" => "mp4",
+        "video/x-matroska" => "mkv",
+        "video/webm" => "webm",
+        "video/mpegts" => "ts",
+        "video/x-msvideo" => "avi",
+        other => {
+            debug!("Unknown container {}", other);
+            return None;
+        }
+    };
+
+    Some(name.to_string())
+}
+
+fn codec_name(structure: &StructureRef) -> Option<String> {
+    let name = match structure.name().as_str() {
+        "video/x-h264" => "h264",
+        "video/x-h265" => "hevc",
+        "video/x-vp8" => "vp8",
+        "video/x-vp9" => "vp9",
+        "video/x-av1" => "av1",
+        "audio/mpeg" => match structure.get::<i32>("mpegversion").unwrap_or(1) {
+            1 => "mp3",
+            _ => "aac",
+        },
+        "audio/x-ac3" => "ac3",
+        "audio/x-eac3" => "eac3",
+        "audio/x-opus" => "opus",
+        "audio/x-vorbis" => "vorbis",
+        "audio/x-flac" => "flac",
+        "audio/x-dts" => "dts",
+        "audio/x-true-hd" => "truehd",
+        "text/x-raw" | "application/x-subtitle" => "text",
+        "application/x-ass" | "application/x-ssa" => "ass",
+        "subpicture/x-pgs" => "pgs",
+        "subpicture/x-dvd" => "vobsub",
+        "subpicture/x-dvb" => "dvbsub",
+        other => {
+            debug!("Unknown codec {}", other);
+            return None;
+        }
+    };
+
+    Some(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gstreamer::Structure;
+
+    #[test]
+    fn test_codec_name() {
+        gstreamer::init().unwrap();
+
+        let aac = Structure::builder("audio/mpeg")
+            .field("mpegversion", 4i32)
+            .build();
+        let mp3 = Structure::builder("audio/mpeg")
+            .field("mpegversion", 1i32)
+            .field("layer", 3i32)
+            .build();
+
+        assert_eq!(codec_name(&aac), Some("aac".to_string()));
+        assert_eq!(codec_name(&mp3), Some("mp3".to_string()));
+        assert_eq!(
+            codec_name(&Structure::new_empty("video/x-h265")),
+            Some("hevc".to_string())
+        );
+        assert_eq!(
+            codec_name(&Structure::new_empty("subpicture/x-pgs")),
+            Some("pgs".to_string())
+        );
+        assert_eq!(codec_name(&Structure::new_empty("video/x-divx")), None);
+    }
+
+    #[test]
+    fn test_hdr_format() {
+        assert_eq!(hdr_format("bt2100-pq"), Some(HdrFormat::Hdr10));
+        assert_eq!(hdr_format("bt2100-hlg"), Some(HdrFormat::Hlg));
+        assert_eq!(hdr_format("2:9:14:9"), Some(HdrFormat::Hdr10));
+        assert_eq!(hdr_format("2:9:15:9"), Some(HdrFormat::Hlg));
+        assert_eq!(hdr_format("bt709"), None);
+        assert_eq!(hdr_format("1:3:5:1"), None);
+    }
+}
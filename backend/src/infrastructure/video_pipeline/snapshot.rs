@@ -0,0 +1,140 @@
+use anyhow::*;
+use gstreamer::{
+    prelude::*, Caps, ClockTime, Element, ElementFactory, Fraction, MessageType, MessageView,
+    Pipeline, SeekFlags, State,
+};
+use gstreamer_app::AppSink;
+use std::{path::Path, time::Duration};
+use tracing::*;
+
+use super::subtitle::link_to_fakesink;
+
+const STATE_CHANGE_TIMEOUT_SECONDS: u64 = 10;
+
+/// Decodes the single frame at the given position and encodes it as JPEG scaled to the
+/// given width. The pipeline only prerolls, so nothing but the frame is decoded.
+#[instrument]
+pub fn extract_snapshot(video_path: &Path, position: Duration, width: u32) -> Result<Vec<u8>> {
+    gstreamer::init()?;
+
+    let uri = gstreamer::glib::filename_to_uri(video_path, None)
+        .map_err(|e| anyhow!("Invalid video path {:?}: {}", video_path, e))?;
+
+    let pipeline = Pipeline::new();
+    let decoder = ElementFactory::make("uridecodebin")
+        .property("uri", uri.as_str())
+        .build()?;
+    let convert = ElementFactory::make("videoconvert").build()?;
+    let scale = ElementFactory::make("videoscale").build()?;
+    // Only the width is fixed, videoscale picks the height which keeps the aspect ratio
+    let caps = ElementFactory::make("capsfilter")
+        .property(
+            "caps",
+            Caps::builder("video/x-raw")
+                .field("width", width as i32)
+                .field("pixel-aspect-ratio", Fraction::new(1, 1))
+                .build(),
+        )
+        .build()?;
+    let encoder = ElementFactory::make("jpegenc").build()?;
+    let appsink = AppSink::builder().sync(false).build();
+
+    pipeline.add_many([
+        &decoder,
+        &convert,
+        &scale,
+        &caps,
+        &encoder,
+        appsink.upcast_ref::<Element>(),
+    ])?;
+    Element::link_many([
+        &convert,
+        &scale,
+        &caps,
+        &encoder,
+        appsink.upcast_ref::<Element>(),
+    ])?;
+
+    connect_video_pad(&decoder, &pipeline, &convert);
+
+    let result = pipeline
+        .set_state(State::Paused)
+        .map_err(|e| anyhow!("Failed to pause snapshot pipeline: {}", e))
+        .and_then(|_| wait_for_preroll(&pipeline))
+        .and_then(|_| {
+            pipeline
+                .seek_simple(
+                    SeekFlags::FLUSH | SeekFlags::KEY_UNIT,
+                    ClockTime::from_nseconds(position.as_nanos() as u64),
+                )
+                .map_err(|e| anyhow!("Failed to seek to {:?}: {}", position, e))
+        })
+        .and_then(|_| wait_for_preroll(&pipeline))
+        .and_then(|_| pull_jpeg(&appsink));
+    pipeline.set_state(State::Null)?;
+
+    result
+}
+
+/// Links the first video pad of a `uridecodebin` to the given element, the other streams
+/// are dropped.
+pub(super) fn connect_video_pad(decoder: &Element, pipeline: &Pipeline, video_sink: &Element) {
+    let pipeline_weak = pipeline.downgrade();
+    let video_sink_weak = video_sink.downgrade();
+    decoder.connect_pad_added(move |_, pad| {
+        let (Some(pipeline), Some(video_sink)) =
+            (pipeline_weak.upgrade(), video_sink_weak.upgrade())
+        else {
+            return;
+        };
+
+        let is_video = pad
+            .current_caps()
+            .and_then(|caps| caps.structure(0).map(|s| s.name().starts_with("video/")))
+            .unwrap_or(false);
+        let result = match video_sink.static_pad("sink") {
+            Some(sink_pad) if is_video && !sink_pad.is_linked() => pad
+                .link(&sink_pad)
+                .map(|_| ())
+                .map_err(|e| anyhow!("{:?}", e)),
+            _ => link_to_fakesink(&pipeline, pad),
+        };
+
+        if let Err(e) = result {
+            error!("Failed to link decoder pad {}: {}", pad.name(), e);
+        }
+    });
+}
+
+fn wait_for_preroll(pipeline: &Pipeline) -> Result<()> {
+    let bus = pipeline
+        .bus()
+        .ok_or_else(|| anyhow!("Pipeline has no bus"))?;
+    let message = bus
+        .timed_pop_filtered(
+            ClockTime::from_seconds(STATE_CHANGE_TIMEOUT_SECONDS),
+            &[MessageType::AsyncDone, MessageType::Error],
+        )
+        .ok_or_else(|| anyhow!("Snapshot pipeline did not preroll in time"))?;
+
+    match message.view() {
+        MessageView::Error(err) => Err(anyhow!(
+            "Failed to take snapshot: {} ({:?})",
+            err.error(),
+            err.debug()
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn pull_jpeg(appsink: &AppSink) -> Result<Vec<u8>> {
+    let sample = appsink
+        .try_pull_preroll(ClockTime::from_seconds(STATE_CHANGE_TIMEOUT_SECONDS))
+        .ok_or_else(|| anyhow!("Snapshot pipeline produced no frame"))?;
+    let buffer = sample
+        .buffer()
+        .ok_or_else(|| anyhow!("Snapshot sample has no buffer"))?;
+    let map = buffer.map_readable()?;
+
+    Ok(map.as_slice().to_vec())
+}
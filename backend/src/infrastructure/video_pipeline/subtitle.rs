@@ -0,0 +1,202 @@
+use anyhow::*;
+use gstreamer::{prelude::*, ClockTime, Element, ElementFactory, MessageView, Pipeline, State};
+use gstreamer_app::AppSink;
+use gstreamer_pbutils::{prelude::*, DiscovererInfo};
+use std::{path::Path, time::Duration};
+use tracing::*;
+
+use crate::{
+    domain::subtitle::model::{Subtitle, SubtitleFormat},
+    infrastructure::hls::webvtt::{ass_event_text, SubtitleCue},
+};
+
+const PULL_TIMEOUT_MILLIS: u64 = 100;
+
+/// Embedded subtitles are only extracted from matroska files for now, which covers
+/// almost every video that ships text subtitles.
+pub fn supports_embedded_subtitles(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("mkv"))
+}
+
+/// Lists the text subtitle streams of the discovered video file. Bitmap subtitles like PGS
+/// or VobSub are skipped since they can not be served as WebVTT.
+#[instrument(skip(info))]
+pub fn probe_subtitle_streams(path: &Path, info: &DiscovererInfo) -> Vec<Subtitle> {
+    if !supports_embedded_subtitles(path) {
+        return vec![];
+    }
+
+    let subtitles = info
+        .subtitle_streams()
+        .iter()
+        .enumerate()
+        .filter_map(|(index, stream)| {
+            let caps = stream.caps()?;
+            let format = match caps.structure(0)?.name().as_str() {
+                "text/x-raw" | "application/x-subtitle" => SubtitleFormat::Srt,
+                "application/x-ass" | "application/x-ssa" => SubtitleFormat::Ass,
+                name => {
+                    debug!("Skipping unsupported subtitle stream {}: {}", index, name);
+                    return None;
+                }
+            };
+            let title = stream.tags().and_then(|tags| {
+                tags.get::<gstreamer::tags::Title>()
+                    .map(|title| title.get().to_string())
+            });
+
+            Some(Subtitle {
+                language: stream.language().map(|language| language.to_string()),
+                title,
+                format,
+                file_path: None,
+                stream_index: Some(index as i64),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    debug!("Found {} embedded subtitles in {:?}", subtitles.len(), path);
+
+    subtitles
+}
+
+/// Demuxes a single subtitle stream and collects its cues, the other streams are
+/// dropped without being decoded so this finishes well before realtime.
+#[instrument]
+pub fn extract_embedded_subtitle(video_path: &Path, stream_index: i64) -> Result<Vec<SubtitleCue>> {
+    gstreamer::init()?;
+
+    let pipeline = Pipeline::new();
+    let source = ElementFactory::make("filesrc")
+        .property("location", video_path.to_string_lossy().to_string())
+        .build()?;
+    let demuxer = ElementFactory::make("matroskademux").build()?;
+    let appsink = AppSink::builder().sync(false).build();
+    appsink.set_property("async", false);
+
+    pipeline.add_many([&source, &demuxer, appsink.upcast_ref::<Element>()])?;
+    source.link(&demuxer)?;
+
+    let target_pad = format!("subtitle_{}", stream_index);
+    let pipeline_weak = pipeline.downgrade();
+    let appsink_weak = appsink.downgrade();
+    demuxer.connect_pad_added(move |_, pad| {
+        let (Some(pipeline), Some(appsink)) = (pipeline_weak.upgrade(), appsink_weak.upgrade())
+        else {
+            return;
+        };
+
+        let result = if pad.name() == target_pad {
+            appsink
+                .static_pad("sink")
+                .ok_or_else(|| anyhow!("appsink has no sink pad"))
+                .and_then(|sink_pad| {
+                    pad.link(&sink_pad)?;
+                    Ok(())
+                })
+        } else {
+            link_to_fakesink(&pipeline, pad)
+        };
+
+        if let Err(e) = result {
+            error!("Failed to link demuxer pad {}: {}", pad.name(), e);
+        }
+    });
+
+    // Without the requested stream the appsink would wait for data forever
+    let appsink_weak = appsink.downgrade();
+    demuxer.connect_no_more_pads(move |_| {
+        if let Some(appsink) = appsink_weak.upgrade() {
+            let linked = appsink
+                .static_pad("sink")
+                .is_some_and(|pad| pad.is_linked());
+            if !linked {
+                warn!("Subtitle stream {} does not exist", stream_index);
+                appsink.send_event(gstreamer::event::Eos::new());
+            }
+        }
+    });
+
+    pipeline.set_state(State::Playing)?;
+    let result = pull_cues(&pipeline, &appsink);
+    pipeline.set_state(State::Null)?;
+
+    result
+}
+
+pub(super) fn link_to_fakesink(pipeline: &Pipeline, pad: &gstreamer::Pad) -> Result<()> {
+    let fakesink = ElementFactory::make("fakesink")
+        .property("sync", false)
+        .property("async", false)
+        .build()?;
+    pipeline.add(&fakesink)?;
+    fakesink.sync_state_with_parent()?;
+
+    let sink_pad = fakesink
+        .static_pad("sink")
+        .ok_or_else(|| anyhow!("fakesink has no sink pad"))?;
+    pad.link(&sink_pad)?;
+
+    Ok(())
+}
+
+fn pull_cues(pipeline: &Pipeline, appsink: &AppSink) -> Result<Vec<SubtitleCue>> {
+    let bus = pipeline
+        .bus()
+        .ok_or_else(|| anyhow!("Pipeline has no bus"))?;
+    let mut cues = vec![];
+
+    loop {
+        if let Some(sample) = appsink.try_pull_sample(ClockTime::from_mseconds(PULL_TIMEOUT_MILLIS))
+        {
+            if let Some(cue) = sample_to_cue(&sample) {
+                cues.push(cue);
+            }
+            continue;
+        }
+
+        if appsink.is_eos() {
+            break;
+        }
+
+        while let Some(message) = bus.pop() {
+            if let MessageView::Error(err) = message.view() {
+                return Err(anyhow!(
+                    "Failed to extract subtitle: {} ({:?})",
+                    err.error(),
+                    err.debug()
+                ));
+            }
+        }
+    }
+
+    Ok(cues)
+}
+
+fn sample_to_cue(sample: &gstreamer::Sample) -> Option<SubtitleCue> {
+    let buffer = sample.buffer()?;
+    let start = buffer.pts()?;
+    let duration = buffer.duration().unwrap_or(ClockTime::ZERO);
+    let map = buffer.map_readable().ok()?;
+    let text = String::from_utf8_lossy(map.as_slice())
+        .trim_end_matches('\0')
+        .to_string();
+
+    let is_ass = sample
+        .caps()
+        .and_then(|caps| caps.structure(0).map(|s| s.name().as_str().to_string()))
+        .is_some_and(|name| name == "application/x-ass" || name == "application/x-ssa");
+    let text = if is_ass { ass_event_text(&text) } else { text };
+
+    if text.trim().is_empty() {
+        return None;
+    }
+
+    Some(SubtitleCue {
+        start: Duration::from_nanos(start.nseconds()),
+        end: Duration::from_nanos((start + duration).nseconds()),
+        text,
+    })
+}
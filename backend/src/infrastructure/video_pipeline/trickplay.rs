@@ -0,0 +1,136 @@
+use anyhow::*;
+use gstreamer::{
+    prelude::*, Caps, ClockTime, Element, ElementFactory, Fraction, MessageView, Pipeline, Sample,
+    State,
+};
+use gstreamer_app::AppSink;
+use image::RgbImage;
+use std::path::Path;
+use tracing::*;
+
+use super::snapshot::connect_video_pad;
+use crate::{
+    domain::trickplay::model::{Trickplay, TrickplayLayout},
+    infrastructure::trickplay::sprite::SpriteSheets,
+};
+
+const PULL_TIMEOUT_MILLIS: u64 = 100;
+
+/// Decodes the whole video and keeps one frame per interval of the layout, videorate drops
+/// the others before they are scaled. This runs as fast as the file can be decoded.
+#[instrument]
+pub fn generate_trickplay(video_path: &Path, layout: TrickplayLayout) -> Result<Trickplay> {
+    gstreamer::init()?;
+
+    let uri = gstreamer::glib::filename_to_uri(video_path, None)
+        .map_err(|e| anyhow!("Invalid video path {:?}: {}", video_path, e))?;
+
+    let pipeline = Pipeline::new();
+    let decoder = ElementFactory::make("uridecodebin")
+        .property("uri", uri.as_str())
+        .build()?;
+    let rate = ElementFactory::make("videorate").build()?;
+    let convert = ElementFactory::make("videoconvert").build()?;
+    let scale = ElementFactory::make("videoscale").build()?;
+    let caps = ElementFactory::make("capsfilter")
+        .property(
+            "caps",
+            Caps::builder("video/x-raw")
+                .field("format", "RGB")
+                .field("width", layout.tile_width as i32)
+                .field("pixel-aspect-ratio", Fraction::new(1, 1))
+                .field(
+                    "framerate",
+                    Fraction::new(1, layout.interval_seconds as i32),
+                )
+                .build(),
+        )
+        .build()?;
+    let appsink = AppSink::builder().sync(false).build();
+
+    pipeline.add_many([
+        &decoder,
+        &rate,
+        &convert,
+        &scale,
+        &caps,
+        appsink.upcast_ref::<Element>(),
+    ])?;
+    Element::link_many([
+        &rate,
+        &convert,
+        &scale,
+        &caps,
+        appsink.upcast_ref::<Element>(),
+    ])?;
+    connect_video_pad(&decoder, &pipeline, &rate);
+
+    pipeline.set_state(State::Playing)?;
+    let result = pull_frames(&pipeline, &appsink, SpriteSheets::new(layout));
+    pipeline.set_state(State::Null)?;
+
+    result?.finish()
+}
+
+fn pull_frames(
+    pipeline: &Pipeline,
+    appsink: &AppSink,
+    mut sprite_sheets: SpriteSheets,
+) -> Result<SpriteSheets> {
+    let bus = pipeline
+        .bus()
+        .ok_or_else(|| anyhow!("Pipeline has no bus"))?;
+
+    loop {
+        if let Some(sample) = appsink.try_pull_sample(ClockTime::from_mseconds(PULL_TIMEOUT_MILLIS))
+        {
+            sprite_sheets.push(&sample_to_image(&sample)?)?;
+            continue;
+        }
+
+        if appsink.is_eos() {
+            break;
+        }
+
+        while let Some(message) = bus.pop() {
+            if let MessageView::Error(err) = message.view() {
+                return Err(anyhow!(
+                    "Failed to generate trickplay: {} ({:?})",
+                    err.error(),
+                    err.debug()
+                ));
+            }
+        }
+    }
+
+    Ok(sprite_sheets)
+}
+
+// Rows of raw RGB frames are padded to a multiple of four bytes
+fn sample_to_image(sample: &Sample) -> Result<RgbImage> {
+    let structure = sample
+        .caps()
+        .and_then(|caps| caps.structure(0))
+        .ok_or_else(|| anyhow!("Frame has no caps"))?;
+    let width = structure.get::<i32>("width")? as usize;
+    let height = structure.get::<i32>("height")? as usize;
+    let buffer = sample
+        .buffer()
+        .ok_or_else(|| anyhow!("Frame has no buffer"))?;
+    let map = buffer.map_readable()?;
+
+    let stride = (width * 3).next_multiple_of(4);
+    if map.len() < stride * (height - 1) + width * 3 {
+        return Err(anyhow!("Frame buffer is too small"));
+    }
+    let pixels = map
+        .as_slice()
+        .chunks(stride)
+        .take(height)
+        .flat_map(|row| &row[..width * 3])
+        .copied()
+        .collect::<Vec<_>>();
+
+    RgbImage::from_raw(width as u32, height as u32, pixels)
+        .ok_or_else(|| anyhow!("Invalid frame size {}x{}", width, height))
+}
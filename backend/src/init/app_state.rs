@@ -1,85 +1,34 @@
 use actix::Addr;
 use getset::Getters;
-use once_cell::sync::Lazy;
-use std::sync::atomic::{AtomicU32, Ordering};
-use std::sync::{Arc, Mutex};
+use std::{sync::Arc, time::Duration};
 
 use crate::application::file_service::FileService;
 use crate::application::pipeline_service::PipelineService;
+use crate::infrastructure::artwork::store::ArtworkStore;
+use crate::infrastructure::async_task_pool::scheduler::ScanScheduler;
 use crate::infrastructure::async_task_pool::task_pool::TaskPool;
+use crate::infrastructure::auth::token::SessionSigner;
 use crate::infrastructure::event_dispatcher::event_bus::EventBus;
-use crate::infrastructure::hls::hls_state_actor::HlsStateActor;
 use crate::infrastructure::library_organizer::organizer::ParserActor;
+use crate::infrastructure::library_watcher::watcher::LibraryWatcher;
 use crate::infrastructure::media_db::database::Database;
-use crate::infrastructure::video_pipeline::pipeline::Pipeline;
+use crate::infrastructure::trickplay::store::TrickplayStore;
 use crate::interfaces::ws::utils::WsConnections;
 
 use super::repository_manager::Repositories;
 
-// TODO: all of these functionalities should be moved to the hls module
-
-static PIPELINE_ADDR: Lazy<Mutex<Option<Addr<Pipeline>>>> = Lazy::new(|| Mutex::new(None));
-
-pub fn get_pipeline_addr() -> Addr<Pipeline> {
-    match PIPELINE_ADDR.lock() {
-        Ok(pipeline_addr) => pipeline_addr.clone().unwrap(),
-        Err(e) => panic!("Failed to lock pipeline address: {}", e),
-    }
-}
-
-static GLOBAL_SEGMENT_INDEX: Lazy<AtomicU32> = Lazy::new(|| AtomicU32::new(0));
-
-pub fn set_segment_index(value: u32) {
-    GLOBAL_SEGMENT_INDEX.store(value, Ordering::Relaxed);
-}
-
-// TODO: optimize this, we should query the duration from the pipeline
-static PIPELINE_DURATION: Lazy<Mutex<Option<u64>>> = Lazy::new(|| Mutex::new(None));
-
-pub fn set_pipeline_duration(duration: u64) {
-    let mut pipeline_duration = match PIPELINE_DURATION.lock() {
-        Ok(pipeline_duration) => pipeline_duration,
-        Err(e) => panic!("Failed to lock pipeline duration: {}", e),
-    };
-    *pipeline_duration = Some(duration);
-}
-
-static PIPELINE_SEGMENT_DURATION: Lazy<Mutex<Option<u64>>> = Lazy::new(|| Mutex::new(None));
-
-pub fn set_pipeline_segment_duration(duration: u64) {
-    let mut pipeline_segment_duration = match PIPELINE_SEGMENT_DURATION.lock() {
-        Ok(pipeline_segment_duration) => pipeline_segment_duration,
-        Err(e) => panic!("Failed to lock pipeline segment duration: {}", e),
-    };
-    *pipeline_segment_duration = Some(duration);
-}
-
-pub fn get_pipeline_segment_duration() -> u64 {
-    match PIPELINE_SEGMENT_DURATION.lock() {
-        Ok(pipeline_segment_duration) => pipeline_segment_duration.unwrap(),
-        Err(e) => panic!("Failed to lock pipeline segment duration: {}", e),
-    }
-}
-
 #[derive(Clone, Getters)]
 #[getset(get = "pub")]
 pub struct MediaProcessingContext {
     pipeline_service: PipelineService,
     parser_addr: Addr<ParserActor>,
-    #[allow(unused)]
-    hls_state_actor_addr: Addr<HlsStateActor>,
 }
 
 impl MediaProcessingContext {
-    pub fn new(
-        pipeline_service: PipelineService,
-        parser_addr: Addr<ParserActor>,
-        hls_state_actor_addr: Addr<HlsStateActor>,
-    ) -> Self {
+    pub fn new(pipeline_service: PipelineService, parser_addr: Addr<ParserActor>) -> Self {
         Self {
             pipeline_service,
             parser_addr,
-            hls_state_actor_addr,
         }
     }
 }
@@ -90,6 +39,8 @@ pub struct StorageContext {
     database_addr: Addr<Database>,
     #[allow(unused)]
     file_service: FileService,
+    artwork_store: ArtworkStore,
+    trickplay_store: TrickplayStore,
     repositories: Repositories,
 }
 
@@ -97,11 +48,15 @@ impl StorageContext {
     pub fn new(
         database_addr: Addr<Database>,
         file_service: FileService,
+        artwork_store: ArtworkStore,
+        trickplay_store: TrickplayStore,
         repositories: Repositories,
     ) -> Self {
         Self {
             database_addr,
             file_service,
+            artwork_store,
+            trickplay_store,
             repositories,
         }
     }
@@ -123,14 +78,42 @@ impl CommunicationContext {
 #[getset(get = "pub")]
 pub struct InfrastructureContext {
     task_pool: TaskPool,
+    scan_scheduler: ScanScheduler,
     event_bus: Arc<EventBus>,
+    library_watcher: LibraryWatcher,
 }
 
 impl InfrastructureContext {
-    pub fn new(task_pool: TaskPool, event_bus: Arc<EventBus>) -> Self {
+    pub fn new(
+        task_pool: TaskPool,
+        scan_scheduler: ScanScheduler,
+        event_bus: Arc<EventBus>,
+        library_watcher: LibraryWatcher,
+    ) -> Self {
         Self {
             task_pool,
+            scan_scheduler,
             event_bus,
+            library_watcher,
+        }
+    }
+}
+
+#[derive(Clone, Getters)]
+#[getset(get = "pub")]
+pub struct SecurityContext {
+    session_signer: SessionSigner,
+    session_ttl: Duration,
+    // Sends the session cookie over HTTPS only, which breaks logins over plain HTTP
+    secure_cookies: bool,
+}
+
+impl SecurityContext {
+    pub fn new(session_signer: SessionSigner, session_ttl: Duration, secure_cookies: bool) -> Self {
+        Self {
+            session_signer,
+            session_ttl,
+            secure_cookies,
         }
     }
 }
@@ -142,6 +125,7 @@ pub struct AppState {
     storage: StorageContext,
     communication: CommunicationContext,
     infrastructure: InfrastructureContext,
+    security: SecurityContext,
 }
 
 impl AppState {
@@ -150,12 +134,14 @@ impl AppState {
         storage_context: StorageContext,
         communication_context: CommunicationContext,
         infrastructure_context: InfrastructureContext,
+        security_context: SecurityContext,
     ) -> Self {
         Self {
             media: media_context,
             storage: storage_context,
             communication: communication_context,
             infrastructure: infrastructure_context,
+            security: security_context,
         }
     }
 }
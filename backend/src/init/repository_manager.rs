@@ -6,12 +6,16 @@ use crate::infrastructure::media_db::{
     database::Database,
     library::{repository::LibraryRepository, wrapper::LibraryDatabaseWrapper},
     media_item::repository::MediaRepository,
+    task::repository::TaskRepository,
+    user::repository::UserRepository,
 };
 
 #[derive(Clone)]
 pub struct Repositories {
     pub library: Arc<LibraryRepository<LibraryDatabaseWrapper>>,
     pub media: Arc<MediaRepository>,
+    pub task: Arc<TaskRepository>,
+    pub user: Arc<UserRepository>,
 }
 
 pub struct RepositoryManager {
@@ -29,6 +33,8 @@ impl RepositoryManager {
         Ok(Repositories {
             library: LibraryRepository::new(Arc::new(library_database_wrapper)),
             media: MediaRepository::new(self.database_addr.clone()),
+            task: TaskRepository::new(self.database_addr.clone()),
+            user: UserRepository::new(self.database_addr.clone()),
         })
     }
 }
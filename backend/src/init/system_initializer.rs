@@ -6,28 +6,38 @@ use std::{
     env,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 use tracing::*;
 use tracing_appender::non_blocking::WorkerGuard;
 use tracing_subscriber::{filter::LevelFilter, fmt, prelude::*};
 
 use crate::{
-    application::{file_service::FileService, pipeline_service::PipelineService},
+    application::{
+        auth_service::ensure_admin_user, file_service::FileService,
+        library_schedule_service::start_library_schedule_service,
+        library_watcher_service::start_library_watcher_service, pipeline_service::PipelineService,
+        task_service::start_task_event_service, video_preview_service::start_video_preview_service,
+    },
+    domain::pipeline::{profile::TranscodingProfile, rendition::Rendition},
     infrastructure::{
-        async_task_pool::task_pool::TaskPool,
+        artwork::store::ArtworkStore,
+        async_task_pool::{scheduler::ScanScheduler, task_pool::TaskPool},
+        auth::token::SessionSigner,
         event_dispatcher::event_bus::EventBus,
         file::repository_impl::FileRepositoryImpl,
-        hls::hls_state_actor::HlsStateActor,
         library_organizer::organizer::ParserActor,
+        library_watcher::watcher::LibraryWatcher,
         media_db::{
             database::Database,
             query_manager::{FileQueryManager, QueryManager},
         },
+        trickplay::store::TrickplayStore,
     },
     init::{
         app_state::{
             AppState, CommunicationContext, InfrastructureContext, MediaProcessingContext,
-            StorageContext,
+            SecurityContext, StorageContext,
         },
         repository_manager::RepositoryManager,
     },
@@ -37,8 +47,23 @@ use crate::{
 #[derive(Debug)]
 pub struct SystemConfig {
     pub database_url: String,
+    pub artwork_dir: PathBuf,
+    pub trickplay_dir: PathBuf,
     pub task_pool_size: usize,
+    pub scheduler_tick_interval: Duration,
     pub event_bus_capacity: usize,
+    pub watcher_debounce: Duration,
+    pub watcher_poll_interval: Duration,
+    pub thumbnail_interval: Duration,
+    pub trickplay_interval: Duration,
+    pub renditions: Vec<Rendition>,
+    pub transcoding_profiles: Vec<TranscodingProfile>,
+    pub auth_secret: Option<String>,
+    pub session_ttl: Duration,
+    pub secure_cookies: bool,
+    pub admin_username: String,
+    pub admin_password: Option<String>,
+    pub cors_origins: Vec<String>,
 }
 
 impl Default for SystemConfig {
@@ -46,8 +71,64 @@ impl Default for SystemConfig {
         // TODO: move all configs to env vars
         Self {
             database_url: env::var("DATABASE_URL").expect("DATABASE_URL must be set"),
+            artwork_dir: env::var("ARTWORK_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from("./artwork")),
+            trickplay_dir: env::var("TRICKPLAY_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from("./trickplay")),
             task_pool_size: 100,
+            scheduler_tick_interval: Duration::from_secs(60),
             event_bus_capacity: 100,
+            watcher_debounce: Duration::from_secs(5),
+            watcher_poll_interval: Duration::from_secs(60),
+            // Pause between two generated thumbnails
+            thumbnail_interval: Duration::from_secs(2),
+            // Pause between two episodes whose trickplay is generated
+            trickplay_interval: Duration::from_secs(10),
+            // e.g. HLS_RENDITIONS=1080:8000,720:4000,480:1500
+            renditions: env::var("HLS_RENDITIONS")
+                .map(|ladder| {
+                    Rendition::parse_ladder(&ladder)
+                        .expect("HLS_RENDITIONS must be height:kbps,...")
+                })
+                .unwrap_or_else(|_| Rendition::default_ladder()),
+            // Path of a JSON file with a list of profiles, e.g. TRANSCODING_PROFILES=./profiles.json
+            transcoding_profiles: env::var("TRANSCODING_PROFILES")
+                .map(|path| {
+                    TranscodingProfile::load(Path::new(&path))
+                        .expect("TRANSCODING_PROFILES must point to valid profiles")
+                })
+                .unwrap_or_else(|_| TranscodingProfile::default_profiles()),
+            // Key which signs the session tokens, sessions do not survive a restart without it
+            auth_secret: env::var("AUTH_SECRET").ok(),
+            session_ttl: env::var("SESSION_TTL_HOURS")
+                .map(|hours| {
+                    Duration::from_secs(
+                        hours
+                            .parse::<u64>()
+                            .expect("SESSION_TTL_HOURS must be a number")
+                            * 60
+                            * 60,
+                    )
+                })
+                .unwrap_or_else(|_| Duration::from_secs(30 * 24 * 60 * 60)),
+            secure_cookies: env::var("SECURE_COOKIES")
+                .map(|value| value == "true" || value == "1")
+                .unwrap_or(false),
+            // The first admin is only created while there is no user at all
+            admin_username: env::var("ADMIN_USERNAME").unwrap_or_else(|_| "admin".to_string()),
+            admin_password: env::var("ADMIN_PASSWORD").ok(),
+            // e.g. CORS_ORIGINS=https://cirno.example.com,http://localhost:5173
+            cors_origins: env::var("CORS_ORIGINS")
+                .map(|origins| {
+                    origins
+                        .split(',')
+                        .map(|origin| origin.trim().to_string())
+                        .filter(|origin| !origin.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
         }
     }
 }
@@ -188,12 +269,16 @@ impl SystemInitializer {
         let event_bus = EventBusBuilder::new(self.config.event_bus_capacity).build();
         event_bus.start();
 
-        info!("Initializing parser");
-        let parser_addr = ParserActor.start();
+        info!("Initializing artwork store");
+        let artwork_store = ArtworkStore::new(self.config.artwork_dir)
+            .context("Failed to initialize artwork store")?;
 
-        info!("Initializing hls state actor");
-        let hls_state_actor = HlsStateActor::new(event_bus.clone());
-        let hls_state_actor_addr = hls_state_actor.start();
+        info!("Initializing trickplay store");
+        let trickplay_store = TrickplayStore::new(self.config.trickplay_dir)
+            .context("Failed to initialize trickplay store")?;
+
+        info!("Initializing parser");
+        let parser_addr = ParserActor::new(artwork_store.clone()).start();
 
         info!("Initializing repository manager");
         let repository_manager = RepositoryManager::new(database_addr.clone());
@@ -204,25 +289,97 @@ impl SystemInitializer {
         info!("Initializing task pool");
         let task_pool = TaskPool::new(self.config.task_pool_size, event_bus.clone());
 
+        info!("Initializing scan scheduler");
+        let (scan_scheduler, due_libraries) =
+            ScanScheduler::new(self.config.scheduler_tick_interval);
+
+        info!("Initializing library watcher");
+        let (library_watcher, library_changes) = LibraryWatcher::new(
+            self.config.watcher_debounce,
+            self.config.watcher_poll_interval,
+        )
+        .context("Failed to initialize library watcher")?;
+
         info!("Initializing websocket connections");
         let ws_connections = WsConnections::default();
 
         info!("Initializing pipeline service");
-        let pipeline_service =
-            PipelineService::new(event_bus.clone(), hls_state_actor_addr.clone())
-                .context("Failed to initialize pipeline service")?;
+        let pipeline_service = PipelineService::new(
+            event_bus.clone(),
+            self.config.renditions.clone(),
+            self.config.transcoding_profiles.clone(),
+        )
+        .context("Failed to initialize pipeline service")?;
 
         info!("Initializing file service");
         let file_service = FileService::new(Arc::new(FileRepositoryImpl {}));
 
+        info!("Initializing session signer");
+        let session_signer = match &self.config.auth_secret {
+            Some(secret) => SessionSigner::new(secret.as_bytes()),
+            None => {
+                warn!("AUTH_SECRET is not set, sessions will be invalid after a restart");
+                SessionSigner::random()
+            }
+        };
+
         info!("Assembling application state");
         let app_state = AppState::new(
-            MediaProcessingContext::new(pipeline_service, parser_addr, hls_state_actor_addr),
-            StorageContext::new(database_addr, file_service, repositories),
+            MediaProcessingContext::new(pipeline_service, parser_addr),
+            StorageContext::new(
+                database_addr,
+                file_service,
+                artwork_store,
+                trickplay_store,
+                repositories,
+            ),
             CommunicationContext::new(ws_connections.clone()),
-            InfrastructureContext::new(task_pool.clone(), event_bus.clone()),
+            InfrastructureContext::new(
+                task_pool.clone(),
+                scan_scheduler,
+                event_bus.clone(),
+                library_watcher,
+            ),
+            SecurityContext::new(
+                session_signer,
+                self.config.session_ttl,
+                self.config.secure_cookies,
+            ),
         );
 
+        info!("Ensuring admin user");
+        ensure_admin_user(
+            &app_state,
+            self.config.admin_username,
+            self.config.admin_password,
+        )
+        .await
+        .context("Failed to ensure admin user")?;
+
+        info!("Starting task event service");
+        start_task_event_service(app_state.clone())
+            .await
+            .context("Failed to start task event service")?;
+
+        info!("Starting library watcher service");
+        start_library_watcher_service(app_state.clone(), library_changes)
+            .await
+            .context("Failed to start library watcher service")?;
+
+        info!("Starting library schedule service");
+        start_library_schedule_service(app_state.clone(), due_libraries)
+            .await
+            .context("Failed to start library schedule service")?;
+
+        info!("Starting video preview service");
+        start_video_preview_service(
+            app_state.clone(),
+            self.config.thumbnail_interval,
+            self.config.trickplay_interval,
+        )
+        .await
+        .context("Failed to start video preview service")?;
+
         Ok(app_state)
     }
 }
@@ -2,8 +2,13 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
 use crate::domain::{
-    library::model::LibraryStatus, media_actor::model::MediaActor,
-    media_library::model::LibraryPoster,
+    library::model::LibraryStatus,
+    media_actor::model::MediaActor,
+    media_info::model::MediaInfo,
+    media_library::{model::LibraryPoster, schedule::ScanSchedule},
+    playback::{method::PlaybackMethod, model::PlaybackProgress},
+    task::async_task::{TaskId, TaskStatus, TaskType},
+    user::{api_key::ApiKey, model::User},
 };
 
 use super::http_api::controllers::api_models::LibraryCategory;
@@ -25,6 +30,18 @@ pub struct MediaItemDto {
     pub genres: Vec<String>,
     pub studios: Vec<String>,
     pub actors: Vec<MediaActor>,
+    // Only movies point at a single playable file, tv shows go through their episodes
+    pub video_file_path: Option<String>,
+}
+
+/// A page of the media of a library, `total` counts every item matching the filters.
+#[derive(Debug, Default, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct MediaPageDto {
+    pub items: Vec<MediaItemDto>,
+    pub total: i64,
+    pub offset: i64,
+    pub limit: i64,
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, TS)]
@@ -39,6 +56,7 @@ pub struct LibraryDto {
     pub last_scanned: Option<String>,
     pub current_status: LibraryStatus,
     pub auto_scan: bool,
+    pub scan_schedule: Option<ScanSchedule>,
     pub error: Option<String>,
     pub storage_used: i64,
     pub health_score: i64,
@@ -70,4 +88,54 @@ pub struct EpisodeDto {
     pub runtime: Option<i64>,
     pub season_number: Option<i64>,
     pub season_title: Option<String>,
+    pub progress: Option<PlaybackProgress>,
+    // Tracks are only filled when listing the episodes of a tv show
+    pub media_info: Option<MediaInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct WatchingEpisodeDto {
+    pub media_id: i64,
+    pub media_title: String,
+    pub poster_path: Option<String>,
+    pub episode: EpisodeDto,
+}
+
+/// A started login session, browsers get the token as a cookie as well while other clients
+/// send it in an `Authorization: Bearer` header.
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct SessionDto {
+    pub token: String,
+    pub user: User,
+}
+
+/// A created API key, the key is only returned here and can't be looked up later.
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct CreatedApiKeyDto {
+    pub key: String,
+    pub api_key: ApiKey,
+}
+
+/// The state of an async task, clients without a WebSocket connection poll it.
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct TaskDto {
+    pub task_id: TaskId,
+    pub task_type: TaskType,
+    pub status: TaskStatus,
+    pub progress: f32,
+}
+
+/// A started playback session, the player loads the stream from `stream_url`.
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct PlaybackSessionDto {
+    pub session_id: String,
+    pub task_id: TaskId,
+    pub method: PlaybackMethod,
+    pub stream_url: String,
+    pub content_type: String,
 }
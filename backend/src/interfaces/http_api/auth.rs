@@ -0,0 +1,138 @@
+use actix_web::{
+    body::MessageBody,
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    error::{ErrorForbidden, ErrorInternalServerError, ErrorUnauthorized},
+    http::header::AUTHORIZATION,
+    middleware::Next,
+    web::Data,
+    Error, FromRequest, HttpMessage, HttpRequest,
+};
+use std::future::{ready, Ready};
+use tracing::*;
+
+use crate::{
+    application::auth_service::{authenticate_api_key_service, authenticate_service},
+    domain::user::{api_key::ApiKeyScope, model::User},
+    init::app_state::AppState,
+    interfaces::http_api::controllers::consts::{
+        API_KEY_HEADER, SESSION_COOKIE, WS_CLIENT_KEY_HEADER,
+    },
+};
+
+/// The session token of the request, API clients send it as a bearer token and browsers as
+/// the session cookie.
+pub fn extract_session_token(req: &HttpRequest) -> Option<String> {
+    let bearer = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string());
+
+    bearer.or_else(|| {
+        req.cookie(SESSION_COOKIE)
+            .map(|cookie| cookie.value().to_string())
+    })
+}
+
+/// The user of a session token, or of an API key when the request carries one, whose
+/// scope must cover the request.
+async fn authenticate_request(req: &ServiceRequest, app_state: &AppState) -> Result<User, Error> {
+    if let Some(key) = req.headers().get(API_KEY_HEADER) {
+        let key = key
+            .to_str()
+            .map_err(|_| ErrorUnauthorized("API key is invalid"))?;
+        let grant = match authenticate_api_key_service(key, app_state).await {
+            Ok(Some(grant)) => grant,
+            Ok(None) => return Err(ErrorUnauthorized("API key is invalid or revoked")),
+            Err(e) => {
+                error!("Failed to authenticate API key: {:?}", e);
+                return Err(ErrorInternalServerError("Failed to authenticate request"));
+            }
+        };
+
+        let required = ApiKeyScope::required_for(req.method().as_str(), req.path());
+        if !grant.scope.allows(required) {
+            warn!(
+                "API key {} with scope {:?} used for {} {}",
+                grant.api_key_id,
+                grant.scope,
+                req.method(),
+                req.path()
+            );
+            return Err(ErrorForbidden("API key scope does not allow this request"));
+        }
+
+        return Ok(grant.user);
+    }
+
+    let token =
+        extract_session_token(req.request()).ok_or_else(|| ErrorUnauthorized("Not logged in"))?;
+
+    match authenticate_service(&token, app_state).await {
+        Ok(Some(user)) => Ok(user),
+        Ok(None) => Err(ErrorUnauthorized("Session is invalid or expired")),
+        Err(e) => {
+            error!("Failed to authenticate request: {:?}", e);
+            Err(ErrorInternalServerError("Failed to authenticate request"))
+        }
+    }
+}
+
+/// Rejects requests without a valid session or API key and makes the user available to
+/// the handlers through [`AuthenticatedUser`]. A WebSocket client key sent along must belong
+/// to a connection of the same user, otherwise events of a request would reach someone else.
+pub async fn require_user(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let app_state = req
+        .app_data::<Data<AppState>>()
+        .cloned()
+        .ok_or_else(|| ErrorInternalServerError("Missing app state"))?;
+    let user = authenticate_request(&req, &app_state).await?;
+
+    if let Some(key) = req.headers().get(WS_CLIENT_KEY_HEADER) {
+        let owner = match key.to_str() {
+            Ok(key) => {
+                app_state
+                    .communication()
+                    .ws_connections()
+                    .user_id(key)
+                    .await
+            }
+            Err(_) => None,
+        };
+        if owner != Some(user.id) {
+            warn!(
+                "User {} sent a WebSocket client key it does not own",
+                user.id
+            );
+            return Err(ErrorForbidden(
+                "WebSocket connection belongs to another user",
+            ));
+        }
+    }
+
+    req.extensions_mut().insert(user);
+    next.call(req).await
+}
+
+/// The user of a request which passed [`require_user`].
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser(pub User);
+
+impl FromRequest for AuthenticatedUser {
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        ready(
+            req.extensions()
+                .get::<User>()
+                .cloned()
+                .map(AuthenticatedUser)
+                .ok_or_else(|| ErrorUnauthorized("Not logged in")),
+        )
+    }
+}
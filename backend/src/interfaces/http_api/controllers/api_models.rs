@@ -1,6 +1,15 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+use super::consts::{DEFAULT_MEDIA_PAGE_SIZE, MAX_MEDIA_PAGE_SIZE};
+use crate::domain::{
+    media_item::model::{MediaFilter, MediaSort, SortOrder},
+    media_library::schedule::ScanSchedule,
+    playback::{method::ClientCapabilities, model::PlayableMedia},
+    task::async_task::TaskStatus,
+    user::api_key::ApiKeyScope,
+};
+
 //------------------------------------------------------------------------------
 // Create Media Library API Models
 //------------------------------------------------------------------------------
@@ -21,6 +30,10 @@ pub struct UpdateLibraryPayload {
     pub name: String,
     pub directory: String,
     pub category: LibraryCategory,
+    // Replaces the background scan schedule, a missing schedule disables it
+    #[serde(default)]
+    #[ts(optional)]
+    pub scan_schedule: Option<ScanSchedule>,
 }
 
 #[derive(Debug, Deserialize, Serialize, TS, Clone, PartialEq, Default)]
@@ -71,6 +84,239 @@ pub struct GetMediaItemsQuery {
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 #[ts(export)]
-pub struct PlayVideoWithPathPayload {
-    pub path: String,
+pub struct PlayVideoPayload {
+    // Exactly one of the episode and the movie to play
+    #[serde(default)]
+    #[ts(optional)]
+    pub episode_id: Option<i64>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub movie_id: Option<i64>,
+    // Seconds to start playback from, e.g. the saved playback progress
+    #[serde(default)]
+    #[ts(optional)]
+    pub start_position: Option<u64>,
+    // Without capabilities the video is always transcoded
+    #[serde(default)]
+    #[ts(optional)]
+    pub capabilities: Option<ClientCapabilities>,
+    // Index of the audio track as listed by the probe endpoint, the default track otherwise
+    #[serde(default)]
+    #[ts(optional)]
+    pub audio_track: Option<u32>,
+    // Index of a subtitle stream as listed by the probe endpoint, needed for bitmap subtitles
+    // which can't be served as WebVTT
+    #[serde(default)]
+    #[ts(optional)]
+    pub burn_in_subtitle: Option<u32>,
+    // Name of a transcoding profile, the default profile otherwise. Only used when the video
+    // is transcoded, leave out the capabilities to always transcode
+    #[serde(default)]
+    #[ts(optional)]
+    pub profile: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct ProbeQuery {
+    // Exactly one of the episode and the movie to probe
+    pub episode_id: Option<i64>,
+    pub movie_id: Option<i64>,
+}
+
+impl PlayVideoPayload {
+    pub fn media(&self) -> Result<PlayableMedia, String> {
+        playable_media(self.episode_id, self.movie_id)
+    }
+}
+
+impl ProbeQuery {
+    pub fn media(&self) -> Result<PlayableMedia, String> {
+        playable_media(self.episode_id, self.movie_id)
+    }
+}
+
+fn playable_media(episode_id: Option<i64>, movie_id: Option<i64>) -> Result<PlayableMedia, String> {
+    match (episode_id, movie_id) {
+        (Some(episode_id), None) => Ok(PlayableMedia::Episode(episode_id)),
+        (None, Some(movie_id)) => Ok(PlayableMedia::Movie(movie_id)),
+        _ => Err("Either an episode or a movie id is required".to_string()),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct SeekPayload {
+    // Seconds from the start of the video
+    pub position: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct SavePlaybackProgressPayload {
+    pub episode_id: i64,
+    // Seconds from the start of the video
+    pub position: i64,
+    pub duration: Option<i64>,
+}
+
+//------------------------------------------------------------------------------
+// Watching API Models
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct WatchingQuery {
+    pub limit: Option<i64>,
+}
+
+//------------------------------------------------------------------------------
+// Media List API Models
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Default, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct MediaListQuery {
+    pub genre: Option<String>,
+    pub studio: Option<String>,
+    pub actor: Option<String>,
+    pub year_from: Option<i64>,
+    pub year_to: Option<i64>,
+    pub rating_min: Option<f64>,
+    pub country: Option<String>,
+    pub sort: Option<MediaSort>,
+    pub order: Option<SortOrder>,
+    pub offset: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+impl From<MediaListQuery> for MediaFilter {
+    fn from(query: MediaListQuery) -> Self {
+        MediaFilter {
+            genre: query.genre,
+            studio: query.studio,
+            actor: query.actor,
+            year_from: query.year_from,
+            year_to: query.year_to,
+            rating_min: query.rating_min,
+            country: query.country,
+            sort: query.sort.unwrap_or_default(),
+            order: query.order.unwrap_or_default(),
+            limit: query
+                .limit
+                .unwrap_or(DEFAULT_MEDIA_PAGE_SIZE)
+                .clamp(1, MAX_MEDIA_PAGE_SIZE),
+            offset: query.offset.unwrap_or(0).max(0),
+        }
+    }
+}
+
+//------------------------------------------------------------------------------
+// Auth API Models
+//------------------------------------------------------------------------------
+
+#[derive(Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct LoginPayload {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct CreateUserPayload {
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub is_admin: bool,
+}
+
+/// Replaces what a user who is not an admin may see.
+#[derive(Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct UserAccessPayload {
+    pub library_ids: Vec<i64>,
+    /// The highest certification the user may watch, e.g. `TV-14`, uncapped when `None`
+    pub max_certification: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct CreateApiKeyPayload {
+    pub name: String,
+    pub scope: ApiKeyScope,
+}
+
+//------------------------------------------------------------------------------
+// Search API Models
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct SearchQuery {
+    pub q: String,
+    pub limit: Option<i64>,
+}
+
+//------------------------------------------------------------------------------
+// Task API Models
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct TaskHistoryQuery {
+    pub status: Option<TaskStatus>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+//------------------------------------------------------------------------------
+// Artwork API Models
+//------------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct ArtworkQuery {
+    // Width of the resized variant, rounded up to a variant width. The original is served
+    // when missing
+    pub w: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_list_query_into_filter() {
+        let filter = MediaFilter::from(MediaListQuery {
+            genre: Some("Drama".to_string()),
+            sort: Some(MediaSort::Year),
+            offset: Some(-5),
+            limit: Some(10_000),
+            ..Default::default()
+        });
+
+        assert_eq!(filter.genre.as_deref(), Some("Drama"));
+        assert_eq!(filter.sort, MediaSort::Year);
+        assert_eq!(filter.order, SortOrder::Asc);
+        assert_eq!(filter.offset, 0);
+        assert_eq!(filter.limit, MAX_MEDIA_PAGE_SIZE);
+        assert_eq!(
+            MediaFilter::from(MediaListQuery::default()).limit,
+            DEFAULT_MEDIA_PAGE_SIZE
+        );
+    }
+
+    #[test]
+    fn test_playable_media() {
+        let query = |episode_id, movie_id| ProbeQuery {
+            episode_id,
+            movie_id,
+        };
+
+        assert_eq!(query(Some(3), None).media(), Ok(PlayableMedia::Episode(3)));
+        assert_eq!(query(None, Some(4)).media(), Ok(PlayableMedia::Movie(4)));
+        assert!(query(None, None).media().is_err());
+        assert!(query(Some(3), Some(4)).media().is_err());
+    }
 }
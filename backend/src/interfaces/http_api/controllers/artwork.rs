@@ -0,0 +1,97 @@
+use actix_web::{
+    http::header::{self, HeaderValue},
+    web::{Data, Path, Query},
+    HttpRequest, HttpResponse, Responder,
+};
+use std::result::Result::Ok;
+use tracing::*;
+
+use super::api_models::ArtworkQuery;
+use crate::{
+    application::artwork_service::get_artwork_service,
+    infrastructure::artwork::store::variant_width, init::app_state::AppState,
+};
+
+// Artworks are addressed by the hash of their content, so they never change
+const ARTWORK_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+#[instrument(skip(req, app_state))]
+pub async fn get_artwork_controller(
+    hash: Path<String>,
+    query: Query<ArtworkQuery>,
+    req: HttpRequest,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    let hash = hash.into_inner();
+    // Widths between the variant widths share the variant and its etag
+    let width = match query.w.map(variant_width).transpose() {
+        Ok(width) => width,
+        Err(e) => return HttpResponse::BadRequest().json(e.to_string()),
+    };
+    let etag = match width {
+        Some(width) => format!("\"{}-w{}\"", hash, width),
+        None => format!("\"{}\"", hash),
+    };
+
+    let artwork = match get_artwork_service(hash, width, app_state).await {
+        Ok(Some(artwork)) => artwork,
+        Ok(None) => return HttpResponse::NotFound().json("Artwork not found"),
+        Err(e) => {
+            error!("Failed to get artwork: {:?}", e);
+            return HttpResponse::BadRequest().json(e.to_string());
+        }
+    };
+
+    if req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .is_some_and(|value| matches_etag(value, &etag))
+    {
+        return HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .insert_header((header::CACHE_CONTROL, ARTWORK_CACHE_CONTROL))
+            .finish();
+    }
+
+    match tokio::fs::read(&artwork.path).await {
+        Ok(data) => HttpResponse::Ok()
+            .content_type(artwork.mime_type())
+            .insert_header((header::ETAG, etag))
+            .insert_header((header::CACHE_CONTROL, ARTWORK_CACHE_CONTROL))
+            .body(data),
+        Err(e) => {
+            error!("Failed to read artwork {:?}: {:?}", artwork.path, e);
+            HttpResponse::InternalServerError().json("Failed to read artwork")
+        }
+    }
+}
+
+fn matches_etag(if_none_match: &HeaderValue, etag: &str) -> bool {
+    if_none_match.to_str().is_ok_and(|value| {
+        value
+            .split(',')
+            .map(str::trim)
+            .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_etag() {
+        let etag = "\"abc-w300\"";
+
+        assert!(matches_etag(
+            &HeaderValue::from_static("\"abc-w300\""),
+            etag
+        ));
+        assert!(matches_etag(
+            &HeaderValue::from_static("\"abc\", W/\"abc-w300\""),
+            etag
+        ));
+        assert!(matches_etag(&HeaderValue::from_static("*"), etag));
+        assert!(!matches_etag(&HeaderValue::from_static("\"abc\""), etag));
+    }
+}
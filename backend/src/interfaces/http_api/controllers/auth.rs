@@ -0,0 +1,120 @@
+use actix_web::{
+    cookie::{time::Duration as CookieDuration, Cookie, SameSite},
+    web::{Data, Json, Path},
+    HttpRequest, HttpResponse, Responder,
+};
+use std::result::Result::Ok;
+use tracing::*;
+
+use super::{
+    api_models::{CreateApiKeyPayload, LoginPayload},
+    consts::SESSION_COOKIE,
+};
+use crate::{
+    application::auth_service::{
+        create_api_key_service, get_api_keys_service, login_service, logout_service,
+        revoke_api_key_service,
+    },
+    handle_controller_result,
+    init::app_state::AppState,
+    interfaces::{
+        dtos::SessionDto,
+        http_api::auth::{extract_session_token, AuthenticatedUser},
+    },
+};
+
+fn session_cookie(token: String, max_age: CookieDuration, secure: bool) -> Cookie<'static> {
+    Cookie::build(SESSION_COOKIE, token)
+        .path("/")
+        .http_only(true)
+        .same_site(SameSite::Strict)
+        .secure(secure)
+        .max_age(max_age)
+        .finish()
+}
+
+pub async fn login_controller(
+    payload: Json<LoginPayload>,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    let security = app_state.security().clone();
+
+    match login_service(payload.into_inner(), app_state).await {
+        Ok(Some((token, user))) => {
+            let max_age = CookieDuration::seconds(security.session_ttl().as_secs() as i64);
+            HttpResponse::Ok()
+                .cookie(session_cookie(
+                    token.clone(),
+                    max_age,
+                    *security.secure_cookies(),
+                ))
+                .json(SessionDto { token, user })
+        }
+        Ok(None) => HttpResponse::Unauthorized().json("Invalid username or password"),
+        Err(e) => {
+            error!("Failed to log in: {:?}", e);
+            HttpResponse::InternalServerError().json(e.to_string())
+        }
+    }
+}
+
+pub async fn logout_controller(req: HttpRequest, app_state: Data<AppState>) -> impl Responder {
+    let secure_cookies = *app_state.security().secure_cookies();
+    if let Some(token) = extract_session_token(&req) {
+        if let Err(e) = logout_service(&token, app_state).await {
+            error!("Failed to log out: {:?}", e);
+            return HttpResponse::InternalServerError().json(e.to_string());
+        }
+    }
+
+    // An expired cookie with the same attributes makes the browser drop it
+    HttpResponse::Ok()
+        .cookie(session_cookie(
+            String::new(),
+            CookieDuration::ZERO,
+            secure_cookies,
+        ))
+        .finish()
+}
+
+pub async fn get_current_user_controller(user: AuthenticatedUser) -> impl Responder {
+    HttpResponse::Ok().json(user.0)
+}
+
+#[instrument(skip(app_state))]
+pub async fn get_api_keys_controller(
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    handle_controller_result!(
+        get_api_keys_service(user.0, app_state).await,
+        HttpResponse::Ok(),
+        HttpResponse::InternalServerError()
+    )
+}
+
+#[instrument(skip(payload, app_state))]
+pub async fn create_api_key_controller(
+    user: AuthenticatedUser,
+    payload: Json<CreateApiKeyPayload>,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    handle_controller_result!(
+        create_api_key_service(user.0, payload.into_inner(), app_state).await,
+        HttpResponse::Ok(),
+        HttpResponse::BadRequest()
+    )
+}
+
+#[instrument(skip(app_state))]
+pub async fn revoke_api_key_controller(
+    api_key_id: Path<i64>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    handle_controller_result!(
+        revoke_api_key_service(api_key_id.into_inner(), user.0, app_state).await,
+        HttpResponse::Ok(),
+        HttpResponse::NotFound()
+    )
+}
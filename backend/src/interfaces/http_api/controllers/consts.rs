@@ -1,2 +1,23 @@
 /// This header key's value is used to identify different WebSocket client connections
 pub const WS_CLIENT_KEY_HEADER: &str = "X-WS-CLIENT-KEY";
+
+/// How many episodes the continue watching and next up lists return unless asked otherwise
+pub const DEFAULT_WATCHING_LIMIT: i64 = 20;
+
+/// How many search results are returned unless asked otherwise, and at most
+pub const DEFAULT_SEARCH_LIMIT: i64 = 50;
+pub const MAX_SEARCH_LIMIT: i64 = 200;
+
+/// How many media a page of a library lists unless asked otherwise, and at most
+pub const DEFAULT_MEDIA_PAGE_SIZE: i64 = 50;
+pub const MAX_MEDIA_PAGE_SIZE: i64 = 200;
+
+/// How many finished tasks the task history lists unless asked otherwise, and at most
+pub const DEFAULT_TASK_HISTORY_LIMIT: i64 = 50;
+pub const MAX_TASK_HISTORY_LIMIT: i64 = 200;
+
+/// The cookie which carries the session token of browsers
+pub const SESSION_COOKIE: &str = "cirno_session";
+
+/// The header headless clients send their API key in
+pub const API_KEY_HEADER: &str = "X-Api-Key";
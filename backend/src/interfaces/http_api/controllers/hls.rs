@@ -0,0 +1,84 @@
+use actix_files::NamedFile;
+use actix_web::{
+    http::header::{ContentType, HeaderValue, CACHE_CONTROL},
+    web::{Data, Path},
+    HttpRequest, HttpResponse, Responder,
+};
+use tracing::*;
+
+use crate::{
+    infrastructure::hls::file::HlsFile, init::app_state::AppState,
+    interfaces::http_api::auth::AuthenticatedUser,
+};
+
+/// Serves the playlists, segments and subtitles of a session. Segments are only served once
+/// they are complete, segments which haven't been generated yet are produced on demand.
+#[instrument(skip(req, app_state))]
+pub async fn get_hls_file_controller(
+    path: Path<(String, String)>,
+    user: AuthenticatedUser,
+    req: HttpRequest,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    let (session_id, file) = path.into_inner();
+    let pipeline_service = app_state.media().pipeline_service();
+
+    if pipeline_service
+        .get_session(&session_id, user.0.id)
+        .await
+        .is_none()
+    {
+        return if pipeline_service.is_session_expired(&session_id).await {
+            HttpResponse::Gone().json("Playback session expired")
+        } else {
+            HttpResponse::NotFound().json("Playback session not found")
+        };
+    }
+
+    let hls_file = match HlsFile::parse(&file) {
+        Ok(hls_file) => hls_file,
+        Err(e) => return HttpResponse::NotFound().json(e.to_string()),
+    };
+
+    let result = match &hls_file {
+        HlsFile::Segment { rendition, index } => {
+            pipeline_service.touch_session(&session_id).await;
+            pipeline_service
+                .wait_for_segment(&session_id, rendition, *index)
+                .await
+        }
+        HlsFile::Playlist(path) | HlsFile::Subtitle(path) => {
+            pipeline_service.wait_for_file(&session_id, path).await
+        }
+    };
+    let file_path = match result {
+        Ok(file_path) => file_path,
+        Err(e) => {
+            error!("Failed to get {}: {:?}", file, e);
+            return HttpResponse::NotFound().json("File not found");
+        }
+    };
+
+    let named_file = match NamedFile::open_async(&file_path).await {
+        Ok(named_file) => named_file,
+        Err(e) => {
+            error!("Failed to open {:?}: {:?}", file_path, e);
+            return HttpResponse::NotFound().json("File not found");
+        }
+    };
+
+    let mut response = named_file
+        .set_content_type(
+            hls_file
+                .content_type()
+                .parse()
+                .unwrap_or(ContentType::octet_stream().0),
+        )
+        .into_response(&req);
+    response.headers_mut().insert(
+        CACHE_CONTROL,
+        HeaderValue::from_static(hls_file.cache_control()),
+    );
+
+    response
+}
@@ -7,23 +7,37 @@ use tracing::*;
 
 use super::api_models::{SaveLibraryPayload, UpdateLibraryPayload};
 use crate::{
-    application::library_service::create_library_service,
-    domain::media_library::library::{delete_library, get_libraries, get_library_by_id},
+    application::library_service::{
+        create_library_service, delete_library_service, rescan_library_service,
+        update_library_service,
+    },
+    domain::{
+        media_library::library::{get_visible_libraries, get_visible_library_by_id},
+        user::access::AccessScope,
+    },
     handle_controller_result,
     init::app_state::AppState,
-    interfaces::http_api::controllers::consts::WS_CLIENT_KEY_HEADER,
+    interfaces::http_api::auth::AuthenticatedUser,
+    shared::utils::extract_ws_client_key,
 };
 
+/// Only admins may add, scan, change or remove libraries, a restricted user could otherwise
+/// scan any directory of the server or change the libraries hidden from them.
+fn forbid_non_admin(user: &AuthenticatedUser) -> Option<HttpResponse> {
+    (!user.0.is_admin).then(|| HttpResponse::Forbidden().json("Only admins can manage libraries"))
+}
+
 pub async fn create_library_controller(
     payload: Json<SaveLibraryPayload>,
+    user: AuthenticatedUser,
     req: HttpRequest,
     app_state: Data<AppState>,
 ) -> impl Responder {
-    let ws_client_key = match req.headers().get(WS_CLIENT_KEY_HEADER) {
-        // TODO: handle the case where the key is not a string
-        Some(key) => key.to_str().unwrap().to_string(),
-        None => return HttpResponse::Unauthorized().json("Unauthorized"),
-    };
+    if let Some(response) = forbid_non_admin(&user) {
+        return response;
+    }
+
+    let ws_client_key = extract_ws_client_key(&req);
     let payload = payload.into_inner();
 
     debug!(
@@ -38,30 +52,59 @@ pub async fn create_library_controller(
     )
 }
 
+pub async fn rescan_library_controller(
+    library_id: Path<i64>,
+    user: AuthenticatedUser,
+    req: HttpRequest,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    if let Some(response) = forbid_non_admin(&user) {
+        return response;
+    }
+
+    let ws_client_key = extract_ws_client_key(&req);
+    let library_id = library_id.into_inner();
+
+    debug!("Rescanning library for id: {}", library_id);
+
+    handle_controller_result!(
+        rescan_library_service(library_id, ws_client_key, app_state).await,
+        HttpResponse::Ok(),
+        HttpResponse::InternalServerError()
+    )
+}
+
 #[instrument(skip(app_state))]
 pub async fn update_library_controller(
     library_id: Path<i64>,
     payload: Json<UpdateLibraryPayload>,
+    user: AuthenticatedUser,
     app_state: Data<AppState>,
 ) -> impl Responder {
+    if let Some(response) = forbid_non_admin(&user) {
+        return response;
+    }
+
     debug!("Updating library for id: {}", library_id);
     handle_controller_result!(
-        app_state
-            .storage()
-            .repositories()
-            .library
-            .update_library(library_id.into_inner(), payload.into_inner())
-            .await,
+        update_library_service(library_id.into_inner(), payload.into_inner(), app_state).await,
         HttpResponse::Ok(),
         HttpResponse::InternalServerError()
     )
 }
 
 #[instrument(skip(app_state))]
-pub async fn get_libraries_controller(app_state: Data<AppState>) -> impl Responder {
+pub async fn get_libraries_controller(
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
     debug!("Getting all libraries");
     handle_controller_result!(
-        get_libraries(app_state.storage().repositories().library.clone()).await,
+        get_visible_libraries(
+            &AccessScope::for_user(&user.0),
+            app_state.storage().repositories().library.clone()
+        )
+        .await,
         HttpResponse::Ok(),
         HttpResponse::NotFound()
     )
@@ -70,12 +113,14 @@ pub async fn get_libraries_controller(app_state: Data<AppState>) -> impl Respond
 #[instrument(skip(app_state))]
 pub async fn get_library_by_id_controller(
     id: Path<i64>,
+    user: AuthenticatedUser,
     app_state: Data<AppState>,
 ) -> impl Responder {
     debug!("Getting library for id: {}", id);
     handle_controller_result!(
-        get_library_by_id(
+        get_visible_library_by_id(
             id.into_inner(),
+            &AccessScope::for_user(&user.0),
             app_state.storage().repositories().library.clone()
         )
         .await,
@@ -85,17 +130,47 @@ pub async fn get_library_by_id_controller(
 }
 
 #[instrument(skip(app_state))]
-pub async fn delete_library_controller(id: Path<i64>, app_state: Data<AppState>) -> impl Responder {
+pub async fn delete_library_controller(
+    id: Path<i64>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    if let Some(response) = forbid_non_admin(&user) {
+        return response;
+    }
+
     let library_id = id.into_inner();
     debug!("Deleting library for id: {}", library_id);
 
     handle_controller_result!(
-        delete_library(
-            library_id,
-            app_state.storage().repositories().library.clone()
-        )
-        .await,
+        delete_library_service(library_id, app_state).await,
         HttpResponse::Ok(),
         HttpResponse::InternalServerError()
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::StatusCode;
+
+    use super::*;
+    use crate::domain::user::model::User;
+
+    fn user(is_admin: bool) -> AuthenticatedUser {
+        AuthenticatedUser(User {
+            id: 2,
+            username: "guest".to_string(),
+            is_admin,
+            max_certification: None,
+            created_at: None,
+        })
+    }
+
+    #[test]
+    fn test_non_admin_cannot_manage_libraries() {
+        let response = forbid_non_admin(&user(false)).expect("non-admin must be rejected");
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        assert!(forbid_non_admin(&user(true)).is_none());
+    }
+}
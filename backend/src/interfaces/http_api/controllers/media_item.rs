@@ -1,15 +1,24 @@
 use actix_web::{
-    web::{Data, Path},
+    web::{Data, Path, Query},
     HttpResponse, Responder,
 };
 use std::result::Result::Ok;
 use tracing::*;
 
-use crate::{handle_controller_result, init::app_state::AppState};
+use super::{
+    api_models::{MediaListQuery, WatchingQuery},
+    consts::DEFAULT_WATCHING_LIMIT,
+};
+use crate::{
+    domain::user::access::AccessScope, handle_controller_result, init::app_state::AppState,
+    interfaces::http_api::auth::AuthenticatedUser,
+};
 
 #[instrument(skip(app_state))]
 pub async fn get_library_medias_controller(
     library_id: Path<i64>,
+    query: Query<MediaListQuery>,
+    user: AuthenticatedUser,
     app_state: Data<AppState>,
 ) -> impl Responder {
     handle_controller_result!(
@@ -17,7 +26,11 @@ pub async fn get_library_medias_controller(
             .storage()
             .repositories()
             .media
-            .get_library_medias(library_id.into_inner())
+            .get_library_medias(
+                library_id.into_inner(),
+                query.into_inner().into(),
+                &AccessScope::for_user(&user.0)
+            )
             .await,
         HttpResponse::Ok(),
         HttpResponse::InternalServerError()
@@ -27,6 +40,7 @@ pub async fn get_library_medias_controller(
 #[instrument(skip(app_state))]
 pub async fn get_library_media_controller(
     path: Path<(i64, i64)>,
+    user: AuthenticatedUser,
     app_state: Data<AppState>,
 ) -> impl Responder {
     let (library_id, media_id) = path.into_inner();
@@ -35,7 +49,7 @@ pub async fn get_library_media_controller(
             .storage()
             .repositories()
             .media
-            .get_library_media(library_id, media_id)
+            .get_library_media(library_id, media_id, &AccessScope::for_user(&user.0))
             .await,
         HttpResponse::Ok(),
         HttpResponse::InternalServerError()
@@ -45,6 +59,7 @@ pub async fn get_library_media_controller(
 #[instrument(skip(app_state))]
 pub async fn get_library_media_episodes_controller(
     path: Path<(i64, i64)>,
+    user: AuthenticatedUser,
     app_state: Data<AppState>,
 ) -> impl Responder {
     let (library_id, media_id) = path.into_inner();
@@ -54,7 +69,12 @@ pub async fn get_library_media_episodes_controller(
             .storage()
             .repositories()
             .media
-            .get_library_media_episodes(library_id, media_id)
+            .get_library_media_episodes(
+                library_id,
+                media_id,
+                user.0.id,
+                &AccessScope::for_user(&user.0)
+            )
             .await,
         HttpResponse::Ok(),
         HttpResponse::InternalServerError()
@@ -62,14 +82,18 @@ pub async fn get_library_media_episodes_controller(
 }
 
 #[instrument(skip(app_state))]
-pub async fn get_media_controller(path: Path<i64>, app_state: Data<AppState>) -> impl Responder {
+pub async fn get_media_controller(
+    path: Path<i64>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
     let media_id = path.into_inner();
     handle_controller_result!(
         app_state
             .storage()
             .repositories()
             .media
-            .get_media_by_id(media_id)
+            .get_media_by_id(media_id, &AccessScope::for_user(&user.0))
             .await,
         HttpResponse::Ok(),
         HttpResponse::InternalServerError()
@@ -79,6 +103,7 @@ pub async fn get_media_controller(path: Path<i64>, app_state: Data<AppState>) ->
 #[instrument(skip(app_state))]
 pub async fn get_media_episodes_controller(
     path: Path<i64>,
+    user: AuthenticatedUser,
     app_state: Data<AppState>,
 ) -> impl Responder {
     let media_id = path.into_inner();
@@ -87,7 +112,51 @@ pub async fn get_media_episodes_controller(
             .storage()
             .repositories()
             .media
-            .get_media_episodes(media_id)
+            .get_media_episodes(media_id, user.0.id, &AccessScope::for_user(&user.0))
+            .await,
+        HttpResponse::Ok(),
+        HttpResponse::InternalServerError()
+    )
+}
+
+#[instrument(skip(app_state))]
+pub async fn get_continue_watching_controller(
+    query: Query<WatchingQuery>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    handle_controller_result!(
+        app_state
+            .storage()
+            .repositories()
+            .media
+            .get_continue_watching(
+                query.limit.unwrap_or(DEFAULT_WATCHING_LIMIT),
+                user.0.id,
+                &AccessScope::for_user(&user.0)
+            )
+            .await,
+        HttpResponse::Ok(),
+        HttpResponse::InternalServerError()
+    )
+}
+
+#[instrument(skip(app_state))]
+pub async fn get_next_up_controller(
+    query: Query<WatchingQuery>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    handle_controller_result!(
+        app_state
+            .storage()
+            .repositories()
+            .media
+            .get_next_up(
+                query.limit.unwrap_or(DEFAULT_WATCHING_LIMIT),
+                user.0.id,
+                &AccessScope::for_user(&user.0)
+            )
             .await,
         HttpResponse::Ok(),
         HttpResponse::InternalServerError()
@@ -1,5 +1,12 @@
 pub mod api_models;
+pub mod artwork;
+pub mod auth;
 pub mod consts;
+pub mod hls;
 pub mod library;
 pub mod media_item;
+pub mod search;
+pub mod task;
+pub mod trickplay;
+pub mod user;
 pub mod video_player;
@@ -0,0 +1,38 @@
+use actix_web::{
+    web::{Data, Query},
+    HttpResponse, Responder,
+};
+use std::result::Result::Ok;
+use tracing::*;
+
+use super::{
+    api_models::SearchQuery,
+    consts::{DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT},
+};
+use crate::{
+    domain::user::access::AccessScope, handle_controller_result, init::app_state::AppState,
+    interfaces::http_api::auth::AuthenticatedUser,
+};
+
+#[instrument(skip(app_state))]
+pub async fn search_controller(
+    query: Query<SearchQuery>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    handle_controller_result!(
+        app_state
+            .storage()
+            .repositories()
+            .media
+            .search(&query.q, limit, &AccessScope::for_user(&user.0))
+            .await,
+        HttpResponse::Ok(),
+        HttpResponse::InternalServerError()
+    )
+}
@@ -0,0 +1,92 @@
+use actix_web::{
+    web::{Data, Path, Query},
+    HttpResponse, Responder,
+};
+use std::result::Result::Ok;
+use tracing::*;
+use uuid::Uuid;
+
+use super::{
+    api_models::TaskHistoryQuery,
+    consts::{DEFAULT_TASK_HISTORY_LIMIT, MAX_TASK_HISTORY_LIMIT},
+};
+use crate::{
+    application::task_service::{
+        cancel_task_service, get_task_history_service, get_task_service, get_tasks_service,
+    },
+    domain::task::async_task::TaskId,
+    handle_controller_result,
+    init::app_state::AppState,
+    interfaces::http_api::auth::AuthenticatedUser,
+};
+
+/// Lists the tasks of every client, so only admins may see them.
+#[instrument(skip(app_state))]
+pub async fn get_tasks_controller(
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    if !user.0.is_admin {
+        return HttpResponse::Forbidden().json("Only admins can list tasks");
+    }
+
+    handle_controller_result!(
+        get_tasks_service(app_state).await,
+        HttpResponse::Ok(),
+        HttpResponse::InternalServerError()
+    )
+}
+
+/// Admins see every task, other users only the ones they started, e.g. their playbacks.
+#[instrument(skip(app_state))]
+pub async fn get_task_controller(
+    task_id: Path<Uuid>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    handle_controller_result!(
+        get_task_service(TaskId(task_id.into_inner()), &user.0, app_state).await,
+        HttpResponse::Ok(),
+        HttpResponse::NotFound()
+    )
+}
+
+/// Cancels a task, which fails when the task is unknown, has already finished or was
+/// started by someone else and the user isn't an admin.
+#[instrument(skip(app_state))]
+pub async fn cancel_task_controller(
+    task_id: Path<Uuid>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    handle_controller_result!(
+        cancel_task_service(TaskId(task_id.into_inner()), &user.0, app_state).await,
+        HttpResponse::Ok(),
+        HttpResponse::BadRequest()
+    )
+}
+
+/// Lists the finished tasks of every client, so only admins may see them.
+#[instrument(skip(app_state))]
+pub async fn get_task_history_controller(
+    query: Query<TaskHistoryQuery>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    if !user.0.is_admin {
+        return HttpResponse::Forbidden().json("Only admins can list the task history");
+    }
+
+    let query = query.into_inner();
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_TASK_HISTORY_LIMIT)
+        .clamp(1, MAX_TASK_HISTORY_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+
+    handle_controller_result!(
+        get_task_history_service(query.status, limit, offset, app_state).await,
+        HttpResponse::Ok(),
+        HttpResponse::InternalServerError()
+    )
+}
@@ -0,0 +1,53 @@
+use actix_files::NamedFile;
+use actix_web::{
+    http::header::ContentType,
+    web::{Data, Path},
+    HttpRequest, HttpResponse, Responder,
+};
+use tracing::*;
+
+use crate::{
+    application::trickplay_service::get_trickplay_file_service,
+    domain::trickplay::model::TRICKPLAY_INDEX, init::app_state::AppState,
+    interfaces::http_api::auth::AuthenticatedUser,
+};
+
+/// Serves the WebVTT thumbnail index of an episode and the sprite sheets it points at.
+#[instrument(skip(req, app_state))]
+pub async fn get_trickplay_file_controller(
+    path: Path<(i64, i64, String)>,
+    user: AuthenticatedUser,
+    req: HttpRequest,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    let (media_id, episode_id, file) = path.into_inner();
+    let content_type = if file == TRICKPLAY_INDEX {
+        "text/vtt"
+    } else {
+        "image/jpeg"
+    };
+
+    let file_path =
+        match get_trickplay_file_service(media_id, episode_id, file, user.0, app_state).await {
+            Ok(Some(file_path)) => file_path,
+            Ok(None) => return HttpResponse::NotFound().json("Trickplay not found"),
+            Err(e) => {
+                error!("Failed to get trickplay: {:?}", e);
+                return HttpResponse::InternalServerError().json(e.to_string());
+            }
+        };
+
+    match NamedFile::open_async(&file_path).await {
+        Ok(named_file) => named_file
+            .set_content_type(
+                content_type
+                    .parse()
+                    .unwrap_or(ContentType::octet_stream().0),
+            )
+            .into_response(&req),
+        Err(e) => {
+            error!("Failed to open {:?}: {:?}", file_path, e);
+            HttpResponse::NotFound().json("Trickplay not found")
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use actix_web::{
+    web::{Data, Json, Path},
+    HttpResponse, Responder,
+};
+use std::result::Result::Ok;
+use tracing::*;
+
+use super::api_models::{CreateUserPayload, UserAccessPayload};
+use crate::{
+    application::auth_service::{
+        create_user_service, get_user_library_ids_service, get_users_service,
+        update_user_access_service,
+    },
+    handle_controller_result,
+    init::app_state::AppState,
+    interfaces::http_api::auth::AuthenticatedUser,
+};
+
+#[instrument(skip(app_state))]
+pub async fn get_users_controller(
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    if !user.0.is_admin {
+        return HttpResponse::Forbidden().json("Only admins can list users");
+    }
+
+    handle_controller_result!(
+        get_users_service(app_state).await,
+        HttpResponse::Ok(),
+        HttpResponse::InternalServerError()
+    )
+}
+
+#[instrument(skip(payload, app_state))]
+pub async fn create_user_controller(
+    user: AuthenticatedUser,
+    payload: Json<CreateUserPayload>,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    if !user.0.is_admin {
+        return HttpResponse::Forbidden().json("Only admins can create users");
+    }
+
+    handle_controller_result!(
+        create_user_service(payload.into_inner(), app_state).await,
+        HttpResponse::Ok(),
+        HttpResponse::BadRequest()
+    )
+}
+
+#[instrument(skip(app_state))]
+pub async fn get_user_libraries_controller(
+    user_id: Path<i64>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    if !user.0.is_admin {
+        return HttpResponse::Forbidden().json("Only admins can see the access of users");
+    }
+
+    handle_controller_result!(
+        get_user_library_ids_service(user_id.into_inner(), app_state).await,
+        HttpResponse::Ok(),
+        HttpResponse::InternalServerError()
+    )
+}
+
+#[instrument(skip(payload, app_state))]
+pub async fn update_user_access_controller(
+    user_id: Path<i64>,
+    user: AuthenticatedUser,
+    payload: Json<UserAccessPayload>,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    if !user.0.is_admin {
+        return HttpResponse::Forbidden().json("Only admins can change the access of users");
+    }
+
+    handle_controller_result!(
+        update_user_access_service(user_id.into_inner(), payload.into_inner(), app_state).await,
+        HttpResponse::Ok(),
+        HttpResponse::BadRequest()
+    )
+}
@@ -1,40 +1,108 @@
+use actix_files::NamedFile;
 use actix_web::{
-    web::{Data, Json},
+    web::{Data, Json, Path, Query},
     HttpRequest, HttpResponse, Responder,
 };
 use std::sync::Arc;
 use tracing::*;
 
-use super::api_models::PlayVideoWithPathPayload;
-use crate::{init::app_state::AppState, shared::utils::extract_ws_client_key};
+use super::api_models::{PlayVideoPayload, ProbeQuery, SavePlaybackProgressPayload, SeekPayload};
+use crate::{
+    application::pipeline_service::{probe, PlaybackRequest},
+    domain::{
+        pipeline::model::Position,
+        playback::{
+            method::PlaybackMethod,
+            model::{PlayableMedia, PlaybackProgress},
+        },
+        user::access::AccessScope,
+    },
+    handle_controller_result,
+    init::app_state::AppState,
+    interfaces::{dtos::PlaybackSessionDto, http_api::auth::AuthenticatedUser},
+    shared::utils::extract_ws_client_key,
+};
 
 #[instrument(skip(req, app_state))]
-pub async fn play_video_with_path_controller(
-    payload: Json<PlayVideoWithPathPayload>,
+pub async fn play_video_controller(
+    payload: Json<PlayVideoPayload>,
+    user: AuthenticatedUser,
     req: HttpRequest,
     app_state: Data<AppState>,
 ) -> impl Responder {
-    let ws_client_key = match extract_ws_client_key(&req) {
-        Ok(key) => key,
-        Err(e) => return HttpResponse::Unauthorized().json(e.to_string()),
+    let ws_client_key = extract_ws_client_key(&req);
+
+    let media = match payload.media() {
+        Ok(media) => media,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+    // The file is only looked up within the user's scope, so hidden media can't be played
+    let path = match app_state
+        .storage()
+        .repositories()
+        .media
+        .get_video_file_path(media, &AccessScope::for_user(&user.0))
+        .await
+    {
+        Ok(path) => path,
+        Err(e) => return HttpResponse::NotFound().json(e.to_string()),
     };
 
+    let start_position = match payload.start_position.map(Position::from_secs).transpose() {
+        Ok(start_position) => start_position,
+        Err(e) => return HttpResponse::BadRequest().json(e.to_string()),
+    };
+
+    // Playback still works without subtitles, so a failed lookup is only logged
+    let subtitles = app_state
+        .storage()
+        .repositories()
+        .media
+        .get_subtitles(&path)
+        .await
+        .unwrap_or_else(|e| {
+            error!("Failed to get subtitles for {}: {:?}", path, e);
+            vec![]
+        });
+
     let pipeline_service = app_state.media().pipeline_service();
+    let profile = match pipeline_service.profile(payload.profile.as_deref()) {
+        Some(profile) => profile.clone(),
+        None => return HttpResponse::BadRequest().json("Unknown transcoding profile"),
+    };
     let file_service = app_state.storage().file_service();
     let task_pool = app_state.infrastructure().task_pool();
     let ws_connections = app_state.communication().ws_connections();
 
+    let payload = payload.into_inner();
+    let request = PlaybackRequest {
+        user_id: user.0.id,
+        path,
+        start_position,
+        capabilities: payload.capabilities.unwrap_or_default(),
+        audio_track: payload.audio_track,
+        burn_in_subtitle: payload.burn_in_subtitle,
+        profile,
+        subtitles,
+    };
+
     match pipeline_service
         .start_playback(
-            &payload.path,
+            request,
             Arc::new(file_service.clone()),
             Arc::new(task_pool.clone()),
-            ws_client_key.clone(),
+            ws_client_key,
             ws_connections.clone(),
         )
         .await
     {
-        Ok(task_id) => HttpResponse::Ok().json(task_id),
+        Ok((session, task_id)) => HttpResponse::Ok().json(PlaybackSessionDto {
+            stream_url: session.stream_url(),
+            content_type: session.content_type(),
+            method: session.method,
+            session_id: session.id,
+            task_id,
+        }),
         Err(e) => {
             error!("Failed to start playback: {:?}", e);
             HttpResponse::InternalServerError().json("Failed to start playback")
@@ -42,9 +110,115 @@ pub async fn play_video_with_path_controller(
     }
 }
 
+/// Lists the streams of a video file, e.g. to let the viewer choose an audio track.
+#[instrument(skip(app_state))]
+pub async fn probe_video_controller(
+    query: Query<ProbeQuery>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    let media = match query.media() {
+        Ok(media) => media,
+        Err(e) => return HttpResponse::BadRequest().json(e),
+    };
+    let path = match app_state
+        .storage()
+        .repositories()
+        .media
+        .get_video_file_path(media, &AccessScope::for_user(&user.0))
+        .await
+    {
+        Ok(path) => path,
+        Err(e) => return HttpResponse::NotFound().json(e.to_string()),
+    };
+
+    handle_controller_result!(
+        probe(&path).await,
+        HttpResponse::Ok(),
+        HttpResponse::NotFound()
+    )
+}
+
+/// Lists the transcoding profiles a client can pick from, the first one is the default.
+#[instrument(skip(app_state))]
+pub async fn get_transcoding_profiles_controller(app_state: Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(app_state.media().pipeline_service().profiles())
+}
+
+/// Serves the original file of a direct play session, NamedFile answers range requests
+/// so the client can seek without downloading the whole file.
+#[instrument(skip(req, app_state))]
+pub async fn get_video_file_controller(
+    session_id: Path<String>,
+    user: AuthenticatedUser,
+    req: HttpRequest,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    let pipeline_service = app_state.media().pipeline_service();
+    let session = match pipeline_service.get_session(&session_id, user.0.id).await {
+        Some(session) if session.method == PlaybackMethod::DirectPlay => session,
+        _ => return HttpResponse::NotFound().json("Playback session not found"),
+    };
+    pipeline_service.touch_session(&session.id).await;
+
+    match NamedFile::open_async(&session.video_file_path).await {
+        Ok(file) => file.into_response(&req),
+        Err(e) => {
+            error!("Failed to open {}: {:?}", session.video_file_path, e);
+            HttpResponse::NotFound().json("Video file not found")
+        }
+    }
+}
+
+#[instrument(skip(app_state))]
+pub async fn seek_video_player_controller(
+    session_id: Path<String>,
+    payload: Json<SeekPayload>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    let position = match Position::from_secs(payload.position) {
+        Ok(position) => position,
+        Err(e) => return HttpResponse::BadRequest().json(e.to_string()),
+    };
+
+    let pipeline_service = app_state.media().pipeline_service();
+    if pipeline_service
+        .get_session(&session_id, user.0.id)
+        .await
+        .is_none()
+    {
+        return HttpResponse::NotFound().json("Playback session not found");
+    }
+
+    match pipeline_service
+        .seek_to_position(&session_id, position)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().json("Pipeline seeked"),
+        Err(e) => {
+            error!("Failed to seek pipeline: {:?}", e);
+            HttpResponse::InternalServerError().json("Failed to seek pipeline")
+        }
+    }
+}
+
 #[instrument(skip(app_state))]
-pub async fn stop_video_player_controller(app_state: Data<AppState>) -> impl Responder {
-    match app_state.media().pipeline_service().stop_and_clean().await {
+pub async fn stop_video_player_controller(
+    session_id: Path<String>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    let pipeline_service = app_state.media().pipeline_service();
+    if pipeline_service
+        .get_session(&session_id, user.0.id)
+        .await
+        .is_none()
+    {
+        return HttpResponse::NotFound().json("Playback session not found");
+    }
+
+    match pipeline_service.stop_and_clean(&session_id).await {
         Ok(_) => HttpResponse::Ok().json("Pipeline stopped"),
         Err(e) => {
             error!("Failed to stop pipeline: {:?}", e);
@@ -52,3 +226,32 @@ pub async fn stop_video_player_controller(app_state: Data<AppState>) -> impl Res
         }
     }
 }
+
+#[instrument(skip(app_state))]
+pub async fn save_playback_progress_controller(
+    payload: Json<SavePlaybackProgressPayload>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    let payload = payload.into_inner();
+    let media_repository = &app_state.storage().repositories().media;
+    // Progress of hidden or unknown episodes is rejected like playing them
+    if let Err(e) = media_repository
+        .get_video_file_path(
+            PlayableMedia::Episode(payload.episode_id),
+            &AccessScope::for_user(&user.0),
+        )
+        .await
+    {
+        return HttpResponse::NotFound().json(e.to_string());
+    }
+
+    let progress = PlaybackProgress::new(payload.episode_id, payload.position, payload.duration);
+    handle_controller_result!(
+        media_repository
+            .save_playback_progress(user.0.id, progress)
+            .await,
+        HttpResponse::Ok(),
+        HttpResponse::InternalServerError()
+    )
+}
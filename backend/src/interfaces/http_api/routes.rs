@@ -1,26 +1,59 @@
 use actix_web::{
-    delete, get, post, put,
-    web::{scope, Data, Json, Path, ServiceConfig},
+    delete, get,
+    middleware::from_fn,
+    post, put,
+    web::{scope, Data, Json, Path, Query, ServiceConfig},
     HttpRequest, Responder,
 };
+use uuid::Uuid;
 
 use crate::{
     init::app_state::AppState,
-    interfaces::http_api::controllers::{
-        api_models::SaveLibraryPayload,
-        library::{
-            create_library_controller, delete_library_controller, get_libraries_controller,
-            get_library_by_id_controller, update_library_controller,
+    interfaces::http_api::{
+        auth::{require_user, AuthenticatedUser},
+        controllers::{
+            api_models::{
+                ArtworkQuery, CreateApiKeyPayload, CreateUserPayload, LoginPayload, MediaListQuery,
+                SaveLibraryPayload, SavePlaybackProgressPayload, SearchQuery, TaskHistoryQuery,
+                UserAccessPayload, WatchingQuery,
+            },
+            artwork::get_artwork_controller,
+            auth::{
+                create_api_key_controller, get_api_keys_controller, get_current_user_controller,
+                login_controller, logout_controller, revoke_api_key_controller,
+            },
+            hls::get_hls_file_controller,
+            library::{
+                create_library_controller, delete_library_controller, get_libraries_controller,
+                get_library_by_id_controller, rescan_library_controller, update_library_controller,
+            },
+            media_item::{
+                get_continue_watching_controller, get_library_media_controller,
+                get_library_media_episodes_controller, get_library_medias_controller,
+                get_media_controller, get_media_episodes_controller, get_next_up_controller,
+            },
+            search::search_controller,
+            task::{
+                cancel_task_controller, get_task_controller, get_task_history_controller,
+                get_tasks_controller,
+            },
+            trickplay::get_trickplay_file_controller,
+            user::{
+                create_user_controller, get_user_libraries_controller, get_users_controller,
+                update_user_access_controller,
+            },
+            video_player::{
+                get_transcoding_profiles_controller, get_video_file_controller,
+                play_video_controller, probe_video_controller, save_playback_progress_controller,
+                seek_video_player_controller, stop_video_player_controller,
+            },
         },
-        media_item::{
-            get_library_media_controller, get_library_media_episodes_controller,
-            get_library_medias_controller, get_media_controller, get_media_episodes_controller,
-        },
-        video_player::{play_video_with_path_controller, stop_video_player_controller},
     },
 };
 
-use super::controllers::api_models::{PlayVideoWithPathPayload, UpdateLibraryPayload};
+use super::controllers::api_models::{
+    PlayVideoPayload, ProbeQuery, SeekPayload, UpdateLibraryPayload,
+};
 
 // TODO: 1. move data models to database/models.rs
 // TODO: 2. return error messages in the response
@@ -33,25 +66,29 @@ use super::controllers::api_models::{PlayVideoWithPathPayload, UpdateLibraryPayl
 #[get("/{library_id}/media")]
 async fn get_library_medias_route(
     library_id: Path<i64>,
+    query: Query<MediaListQuery>,
+    user: AuthenticatedUser,
     app_state: Data<AppState>,
 ) -> impl Responder {
-    get_library_medias_controller(library_id, app_state).await
+    get_library_medias_controller(library_id, query, user, app_state).await
 }
 
 #[get("/{library_id}/media/{media_id}")]
 async fn get_library_media_route(
     path: Path<(i64, i64)>,
+    user: AuthenticatedUser,
     app_state: Data<AppState>,
 ) -> impl Responder {
-    get_library_media_controller(path, app_state).await
+    get_library_media_controller(path, user, app_state).await
 }
 
 #[get("/{library_id}/media/{media_id}/episodes")]
 async fn get_library_media_episodes_route(
     path: Path<(i64, i64)>,
+    user: AuthenticatedUser,
     app_state: Data<AppState>,
 ) -> impl Responder {
-    get_library_media_episodes_controller(path, app_state).await
+    get_library_media_episodes_controller(path, user, app_state).await
 }
 
 // TODO: fetch at most 10 media items randomly?
@@ -59,43 +96,65 @@ async fn get_library_media_episodes_route(
 #[post("/")]
 async fn create_library_route(
     payload: Json<SaveLibraryPayload>,
+    user: AuthenticatedUser,
+    req: HttpRequest,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    create_library_controller(payload, user, req, app_state).await
+}
+
+#[post("/{library_id}/rescan")]
+async fn rescan_library_route(
+    library_id: Path<i64>,
+    user: AuthenticatedUser,
     req: HttpRequest,
     app_state: Data<AppState>,
 ) -> impl Responder {
-    create_library_controller(payload, req, app_state).await
+    rescan_library_controller(library_id, user, req, app_state).await
 }
 
 #[put("/{library_id}")]
 async fn update_library_route(
     library_id: Path<i64>,
     payload: Json<UpdateLibraryPayload>,
+    user: AuthenticatedUser,
     app_state: Data<AppState>,
 ) -> impl Responder {
-    update_library_controller(library_id, payload, app_state).await
+    update_library_controller(library_id, payload, user, app_state).await
 }
 
 #[get("/")]
-async fn get_libraries_route(app_state: Data<AppState>) -> impl Responder {
-    get_libraries_controller(app_state).await
+async fn get_libraries_route(user: AuthenticatedUser, app_state: Data<AppState>) -> impl Responder {
+    get_libraries_controller(user, app_state).await
 }
 
 #[get("/{id}")]
-async fn get_library_route(id: Path<i64>, app_state: Data<AppState>) -> impl Responder {
-    get_library_by_id_controller(id, app_state).await
+async fn get_library_route(
+    id: Path<i64>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    get_library_by_id_controller(id, user, app_state).await
 }
 
 #[delete("/{id}")]
-async fn delete_library_route(id: Path<i64>, app_state: Data<AppState>) -> impl Responder {
-    delete_library_controller(id, app_state).await
+async fn delete_library_route(
+    id: Path<i64>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    delete_library_controller(id, user, app_state).await
 }
 
 pub fn init_library_routes(cfg: &mut ServiceConfig) {
     cfg.service(
         scope("/library")
+            .wrap(from_fn(require_user))
             .service(get_library_medias_route)
             .service(get_library_media_route)
             .service(get_library_media_episodes_route)
             .service(create_library_route)
+            .service(rescan_library_route)
             .service(update_library_route)
             .service(get_libraries_route)
             .service(get_library_route)
@@ -107,21 +166,62 @@ pub fn init_library_routes(cfg: &mut ServiceConfig) {
 // Media Routes
 // --------------------------------
 
+#[get("/continue-watching")]
+async fn get_continue_watching_route(
+    query: Query<WatchingQuery>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    get_continue_watching_controller(query, user, app_state).await
+}
+
+#[get("/next-up")]
+async fn get_next_up_route(
+    query: Query<WatchingQuery>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    get_next_up_controller(query, user, app_state).await
+}
+
 #[get("/{media_id}")]
-async fn get_media_route(path: Path<i64>, app_state: Data<AppState>) -> impl Responder {
-    get_media_controller(path, app_state).await
+async fn get_media_route(
+    path: Path<i64>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    get_media_controller(path, user, app_state).await
 }
 
 #[get("/{media_id}/episodes")]
-async fn get_media_episodes_route(path: Path<i64>, app_state: Data<AppState>) -> impl Responder {
-    get_media_episodes_controller(path, app_state).await
+async fn get_media_episodes_route(
+    path: Path<i64>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    get_media_episodes_controller(path, user, app_state).await
+}
+
+#[get("/{media_id}/episodes/{episode_id}/trickplay/{file}")]
+async fn get_trickplay_file_route(
+    path: Path<(i64, i64, String)>,
+    user: AuthenticatedUser,
+    req: HttpRequest,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    get_trickplay_file_controller(path, user, req, app_state).await
 }
 
 pub fn init_media_routes(cfg: &mut ServiceConfig) {
     cfg.service(
         scope("/media")
+            .wrap(from_fn(require_user))
+            // Registered before /{media_id} which would otherwise match them
+            .service(get_continue_watching_route)
+            .service(get_next_up_route)
             .service(get_media_route)
-            .service(get_media_episodes_route),
+            .service(get_media_episodes_route)
+            .service(get_trickplay_file_route),
     );
 }
 
@@ -130,23 +230,292 @@ pub fn init_media_routes(cfg: &mut ServiceConfig) {
 // --------------------------------
 
 #[post("/play")]
-async fn play_video_with_path(
-    payload: Json<PlayVideoWithPathPayload>,
+async fn play_video_route(
+    payload: Json<PlayVideoPayload>,
+    user: AuthenticatedUser,
+    req: HttpRequest,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    play_video_controller(payload, user, req, app_state).await
+}
+
+#[get("/probe")]
+async fn probe_video_route(
+    query: Query<ProbeQuery>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    probe_video_controller(query, user, app_state).await
+}
+
+#[get("/profiles")]
+async fn get_transcoding_profiles_route(app_state: Data<AppState>) -> impl Responder {
+    get_transcoding_profiles_controller(app_state).await
+}
+
+#[get("/{session_id}/file")]
+async fn get_video_file_route(
+    session_id: Path<String>,
+    user: AuthenticatedUser,
     req: HttpRequest,
     app_state: Data<AppState>,
 ) -> impl Responder {
-    play_video_with_path_controller(payload, req, app_state).await
+    get_video_file_controller(session_id, user, req, app_state).await
+}
+
+#[post("/{session_id}/seek")]
+async fn seek_video_player_route(
+    session_id: Path<String>,
+    payload: Json<SeekPayload>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    seek_video_player_controller(session_id, payload, user, app_state).await
 }
 
-#[post("/stop")]
-async fn stop_video_player_route(app_state: Data<AppState>) -> impl Responder {
-    stop_video_player_controller(app_state).await
+#[post("/{session_id}/stop")]
+async fn stop_video_player_route(
+    session_id: Path<String>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    stop_video_player_controller(session_id, user, app_state).await
+}
+
+#[post("/progress")]
+async fn save_playback_progress_route(
+    payload: Json<SavePlaybackProgressPayload>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    save_playback_progress_controller(payload, user, app_state).await
 }
 
 pub fn init_video_player_routes(cfg: &mut ServiceConfig) {
     cfg.service(
         scope("/video-player")
-            .service(play_video_with_path)
-            .service(stop_video_player_route),
+            .wrap(from_fn(require_user))
+            .service(play_video_route)
+            .service(probe_video_route)
+            .service(get_transcoding_profiles_route)
+            .service(get_video_file_route)
+            .service(seek_video_player_route)
+            .service(stop_video_player_route)
+            .service(save_playback_progress_route),
+    );
+}
+
+// --------------------------------
+// Artwork Routes
+// --------------------------------
+
+#[get("/{hash}")]
+async fn get_artwork_route(
+    hash: Path<String>,
+    query: Query<ArtworkQuery>,
+    req: HttpRequest,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    get_artwork_controller(hash, query, req, app_state).await
+}
+
+pub fn init_artwork_routes(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("/artwork")
+            .wrap(from_fn(require_user))
+            .service(get_artwork_route),
+    );
+}
+
+// --------------------------------
+// Search Routes
+// --------------------------------
+
+#[get("")]
+async fn search_route(
+    query: Query<SearchQuery>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    search_controller(query, user, app_state).await
+}
+
+pub fn init_search_routes(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("/search")
+            .wrap(from_fn(require_user))
+            .service(search_route),
+    );
+}
+
+// --------------------------------
+// Auth Routes
+// --------------------------------
+
+#[post("/login")]
+async fn login_route(payload: Json<LoginPayload>, app_state: Data<AppState>) -> impl Responder {
+    login_controller(payload, app_state).await
+}
+
+#[post("/logout")]
+async fn logout_route(req: HttpRequest, app_state: Data<AppState>) -> impl Responder {
+    logout_controller(req, app_state).await
+}
+
+#[get("/me", wrap = "from_fn(require_user)")]
+async fn get_current_user_route(user: AuthenticatedUser) -> impl Responder {
+    get_current_user_controller(user).await
+}
+
+#[get("/api-keys", wrap = "from_fn(require_user)")]
+async fn get_api_keys_route(user: AuthenticatedUser, app_state: Data<AppState>) -> impl Responder {
+    get_api_keys_controller(user, app_state).await
+}
+
+#[post("/api-keys", wrap = "from_fn(require_user)")]
+async fn create_api_key_route(
+    user: AuthenticatedUser,
+    payload: Json<CreateApiKeyPayload>,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    create_api_key_controller(user, payload, app_state).await
+}
+
+#[delete("/api-keys/{api_key_id}", wrap = "from_fn(require_user)")]
+async fn revoke_api_key_route(
+    api_key_id: Path<i64>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    revoke_api_key_controller(api_key_id, user, app_state).await
+}
+
+pub fn init_auth_routes(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("/auth")
+            .service(login_route)
+            .service(logout_route)
+            .service(get_current_user_route)
+            .service(get_api_keys_route)
+            .service(create_api_key_route)
+            .service(revoke_api_key_route),
+    );
+}
+
+// --------------------------------
+// User Routes
+// --------------------------------
+
+#[get("/")]
+async fn get_users_route(user: AuthenticatedUser, app_state: Data<AppState>) -> impl Responder {
+    get_users_controller(user, app_state).await
+}
+
+#[post("/")]
+async fn create_user_route(
+    user: AuthenticatedUser,
+    payload: Json<CreateUserPayload>,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    create_user_controller(user, payload, app_state).await
+}
+
+#[get("/{user_id}/libraries")]
+async fn get_user_libraries_route(
+    user_id: Path<i64>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    get_user_libraries_controller(user_id, user, app_state).await
+}
+
+#[put("/{user_id}/access")]
+async fn update_user_access_route(
+    user_id: Path<i64>,
+    user: AuthenticatedUser,
+    payload: Json<UserAccessPayload>,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    update_user_access_controller(user_id, user, payload, app_state).await
+}
+
+pub fn init_user_routes(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("/users")
+            .wrap(from_fn(require_user))
+            .service(get_users_route)
+            .service(create_user_route)
+            .service(get_user_libraries_route)
+            .service(update_user_access_route),
+    );
+}
+
+// --------------------------------
+// Task Routes
+// --------------------------------
+
+#[get("")]
+async fn get_tasks_route(user: AuthenticatedUser, app_state: Data<AppState>) -> impl Responder {
+    get_tasks_controller(user, app_state).await
+}
+
+#[get("/history")]
+async fn get_task_history_route(
+    query: Query<TaskHistoryQuery>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    get_task_history_controller(query, user, app_state).await
+}
+
+#[get("/{task_id}")]
+async fn get_task_route(
+    task_id: Path<Uuid>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    get_task_controller(task_id, user, app_state).await
+}
+
+#[delete("/{task_id}")]
+async fn cancel_task_route(
+    task_id: Path<Uuid>,
+    user: AuthenticatedUser,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    cancel_task_controller(task_id, user, app_state).await
+}
+
+pub fn init_task_routes(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("/tasks")
+            .wrap(from_fn(require_user))
+            .service(get_tasks_route)
+            // Registered before the task id so `history` isn't parsed as one
+            .service(get_task_history_route)
+            .service(get_task_route)
+            .service(cancel_task_route),
+    );
+}
+
+// --------------------------------
+// HLS Routes
+// --------------------------------
+
+#[get("/{session_id}/{file:.*}")]
+async fn get_hls_file_route(
+    path: Path<(String, String)>,
+    user: AuthenticatedUser,
+    req: HttpRequest,
+    app_state: Data<AppState>,
+) -> impl Responder {
+    get_hls_file_controller(path, user, req, app_state).await
+}
+
+pub fn init_hls_routes(cfg: &mut ServiceConfig) {
+    cfg.service(
+        scope("/hls")
+            .wrap(from_fn(require_user))
+            .service(get_hls_file_route),
     );
 }
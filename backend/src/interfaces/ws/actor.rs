@@ -19,6 +19,7 @@ use crate::{
 #[derive(Clone)]
 pub struct WebSocketActor {
     pub key: Uuid,
+    pub user_id: i64,
     pub ws_connections: Option<WsConnections>,
     pub event_bus: Option<Arc<EventBus>>,
 }
@@ -51,11 +52,15 @@ impl Actor for WebSocketActor {
 
         let key = self.key.to_string();
         let key_for_ws = key.clone();
+        let user_id = self.user_id;
         let addr_clone = addr.clone();
 
-        debug!("Registering client to WebSocket connections");
+        debug!(
+            "Registering client of user {} to WebSocket connections",
+            user_id
+        );
         spawn(async move {
-            ws_connections.add(key_for_ws, addr).await;
+            ws_connections.add(key_for_ws, user_id, addr).await;
         });
 
         debug!("Publishing register client event to event bus");
@@ -151,9 +156,10 @@ where
 }
 
 impl WebSocketActor {
-    pub fn new(ws_connections: WsConnections, event_bus: Arc<EventBus>) -> Self {
+    pub fn new(ws_connections: WsConnections, event_bus: Arc<EventBus>, user_id: i64) -> Self {
         Self {
             key: Uuid::new_v4(),
+            user_id,
             ws_connections: Some(ws_connections),
             event_bus: Some(event_bus),
         }
@@ -6,8 +6,11 @@ use ts_rs::TS;
 pub enum NotificationType {
     RegisterClient,
     LibrarySaved,
+    LibraryRescanSaved,
+    LibraryChanged,
     HlsStreamInitialized,
     TaskProgressUpdated,
+    TaskFinished,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -1,21 +1,27 @@
-use actix_web::{error::ErrorInternalServerError, get, web, HttpRequest, Responder};
+use actix_web::{
+    error::ErrorInternalServerError, get, middleware::from_fn, web, HttpRequest, Responder,
+};
 use actix_web_actors::ws;
 use tracing::*;
 
-use crate::interfaces::ws::actor::WebSocketActor;
+use crate::interfaces::{
+    http_api::auth::{require_user, AuthenticatedUser},
+    ws::actor::WebSocketActor,
+};
 
-#[get("/ws")]
+#[get("/ws", wrap = "from_fn(require_user)")]
 pub async fn ws_index(
     r: HttpRequest,
     stream: web::Payload,
+    user: AuthenticatedUser,
     app_state: web::Data<crate::init::app_state::AppState>,
 ) -> impl Responder {
-    info!("Starting websocket");
+    info!("Starting websocket for user {}", user.0.id);
 
     let ws_connections = app_state.communication().ws_connections();
     let event_bus = app_state.infrastructure().event_bus();
 
-    let ws_actor = WebSocketActor::new(ws_connections.clone(), event_bus.clone());
+    let ws_actor = WebSocketActor::new(ws_connections.clone(), event_bus.clone(), user.0.id);
 
     match ws::start(ws_actor, &r, stream) {
         Ok(response) => Ok(response),
@@ -4,15 +4,23 @@ use tokio::sync::RwLock;
 
 use super::actor::WebSocketActor;
 
+#[derive(Debug, Clone)]
+struct WsConnection {
+    user_id: i64,
+    addr: Addr<WebSocketActor>,
+}
+
+/// The open WebSocket connections by their client key, every connection belongs to the
+/// user who opened it.
 #[derive(Debug, Default, Clone)]
 pub struct WsConnections {
-    connections: Arc<RwLock<HashMap<String, Addr<WebSocketActor>>>>,
+    connections: Arc<RwLock<HashMap<String, WsConnection>>>,
 }
 
 impl WsConnections {
-    pub async fn add(&self, id: String, addr: Addr<WebSocketActor>) {
+    pub async fn add(&self, id: String, user_id: i64, addr: Addr<WebSocketActor>) {
         let mut connections = self.connections.write().await;
-        connections.insert(id, addr);
+        connections.insert(id, WsConnection { user_id, addr });
     }
 
     pub async fn remove(&self, id: String) {
@@ -22,6 +30,22 @@ impl WsConnections {
 
     pub async fn get(&self, id: String) -> Option<Addr<WebSocketActor>> {
         let connections = self.connections.read().await;
-        connections.get(&id).cloned()
+        connections
+            .get(&id)
+            .map(|connection| connection.addr.clone())
+    }
+
+    /// The user who opened the connection, `None` when it is not open.
+    pub async fn user_id(&self, id: &str) -> Option<i64> {
+        let connections = self.connections.read().await;
+        connections.get(id).map(|connection| connection.user_id)
+    }
+
+    pub async fn all(&self) -> Vec<Addr<WebSocketActor>> {
+        let connections = self.connections.read().await;
+        connections
+            .values()
+            .map(|connection| connection.addr.clone())
+            .collect()
     }
 }
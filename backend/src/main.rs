@@ -21,7 +21,9 @@ async fn main() -> std::io::Result<()> {
     let _guard = SystemInitializer::init_logger(Path::new("logs"));
 
     info!("Initializing system");
-    let initializer = SystemInitializer::new(SystemConfig::default());
+    let config = SystemConfig::default();
+    let cors_origins = config.cors_origins.clone();
+    let initializer = SystemInitializer::new(config);
     let app_state = match initializer.initialize().await {
         Ok(app_state) => app_state,
         Err(e) => panic!("Failed to initialize system: {}", e),
@@ -29,19 +31,27 @@ async fn main() -> std::io::Result<()> {
 
     info!("Starting backend server");
     HttpServer::new(move || {
-        let cors = Cors::default()
-            .allow_any_origin()
+        // Only the configured origins may send credentialed cross-origin requests
+        let cors = cors_origins
+            .iter()
+            .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
             .allow_any_method()
-            .allow_any_header();
+            .allow_any_header()
+            .supports_credentials();
 
         let mut app = App::new()
             .wrap(Logger::default())
             .wrap(cors)
             .app_data(web::Data::new(app_state.clone()))
+            .configure(interfaces::http_api::routes::init_auth_routes)
+            .configure(interfaces::http_api::routes::init_user_routes)
             .configure(interfaces::http_api::routes::init_library_routes)
             .configure(interfaces::http_api::routes::init_media_routes)
             .configure(interfaces::http_api::routes::init_video_player_routes)
-            .service(Files::new("/hls", "./tmp").show_files_listing())
+            .configure(interfaces::http_api::routes::init_artwork_routes)
+            .configure(interfaces::http_api::routes::init_search_routes)
+            .configure(interfaces::http_api::routes::init_task_routes)
+            .configure(interfaces::http_api::routes::init_hls_routes)
             .service(interfaces::ws::routes::ws_index);
 
         if !cfg!(debug_assertions) {
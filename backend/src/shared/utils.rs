@@ -37,14 +37,13 @@ impl ElementFactoryTrait for ElementFactory {
     }
 }
 
-pub fn extract_ws_client_key(req: &HttpRequest) -> Result<String> {
-    match req.headers().get(WS_CLIENT_KEY_HEADER) {
-        Some(key) => key
-            .to_str()
-            .map(|s| s.to_string())
-            .map_err(|_| anyhow::anyhow!("Invalid WebSocket Client key")),
-        None => Err(anyhow::anyhow!("Missing WebSocket Client key")),
-    }
+/// The WebSocket client key of the request, `None` for clients without a connection, e.g.
+/// scripts using an API key, which poll the state of their tasks instead.
+pub fn extract_ws_client_key(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(WS_CLIENT_KEY_HEADER)
+        .and_then(|key| key.to_str().ok())
+        .map(|key| key.to_string())
 }
 
 #[cfg(test)]
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ApiKeyScope } from "./ApiKeyScope";
+
+/**
+ * An API key as it is listed, the key itself is only shown once when it is created.
+ */
+export type ApiKey = { id: bigint, name: string, scope: ApiKeyScope, created_at: string | null, last_used_at: string | null, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What a request authenticated with an API key may do. Each scope includes the ones
+ * before it, and none of them grants more than the user who owns the key may do.
+ */
+export type ApiKeyScope = /**
+ * Only reading libraries, media and artwork
+ */
+"read_only" | /**
+ * Reading and playing media, including saving the watch progress
+ */
+"playback" | /**
+ * Everything the user may do, e.g. creating and scanning libraries
+ */
+"admin";
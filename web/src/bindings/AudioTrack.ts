@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An audio stream of a video file, the index counts only the audio streams.
+ */
+export type AudioTrack = { index: number, language: string | null, title: string | null, codec: string | null, channels: number | null, };
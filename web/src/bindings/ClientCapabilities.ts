@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The codecs and containers a client can decode, names match the ones of `MediaInfo`.
+ */
+export type ClientCapabilities = { containers: Array<string>, video_codecs: Array<string>, audio_codecs: Array<string>, };
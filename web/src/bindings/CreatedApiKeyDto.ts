@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ApiKey } from "./ApiKey";
+
+/**
+ * A created API key, the key is only returned here and can't be looked up later.
+ */
+export type CreatedApiKeyDto = { key: string, api_key: ApiKey, };
@@ -1,3 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MediaInfo } from "./MediaInfo";
+import type { Subtitle } from "./Subtitle";
 
-export type Episode = { title: string | null, original_title: string | null, plot: string | null, nfo_path: string | null, video_file_path: string, subtitle_file_path: string | null, thumb_image_url: string | null, thumb_image: string | null, episode_number: string | null, runtime: string | null, };
+export type Episode = { title: string | null, original_title: string | null, plot: string | null, nfo_path: string | null, video_file_path: string, subtitle_file_path: string | null, thumb_image_url: string | null, thumb_image: string | null, episode_number: string | null, runtime: string | null, subtitles: Array<Subtitle>, media_info: MediaInfo | null, file_size: bigint | null, file_modified_at: bigint | null, nfo_modified_at: bigint | null, };
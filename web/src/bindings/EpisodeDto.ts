@@ -1,3 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MediaInfo } from "./MediaInfo";
+import type { PlaybackProgress } from "./PlaybackProgress";
 
-export type EpisodeDto = { id: bigint, title: string | null, original_title: string | null, plot: string | null, nfo_path: string | null, video_file_path: string, subtitle_file_path: string | null, thumb_image_url: string | null, thumb_image: string | null, episode_number: bigint | null, runtime: bigint | null, season_number: bigint | null, season_title: string | null, };
+export type EpisodeDto = { id: bigint, title: string | null, original_title: string | null, plot: string | null, nfo_path: string | null, video_file_path: string, subtitle_file_path: string | null, thumb_image_url: string | null, thumb_image: string | null, episode_number: bigint | null, runtime: bigint | null, season_number: bigint | null, season_title: string | null, progress: PlaybackProgress | null, media_info: MediaInfo | null, };
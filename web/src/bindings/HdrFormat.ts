@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The transfer function of a high dynamic range video.
+ */
+export type HdrFormat = "hdr10" | "hlg";
@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Movie } from "./Movie";
 import type { TvShow } from "./TvShow";
 
-export type Library = { tv_show: Array<TvShow>, };
+export type Library = { tv_show: Array<TvShow>, movie: Array<Movie>, };
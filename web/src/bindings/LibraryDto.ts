@@ -2,5 +2,6 @@
 import type { LibraryCategory } from "./LibraryCategory";
 import type { LibraryPoster } from "./LibraryPoster";
 import type { LibraryStatus } from "./LibraryStatus";
+import type { ScanSchedule } from "./ScanSchedule";
 
-export type LibraryDto = { id: bigint, name: string, category: LibraryCategory, directory: string, posters: Array<LibraryPoster>, item_count: bigint, last_scanned: string | null, current_status: LibraryStatus, auto_scan: boolean, error: string | null, storage_used: bigint, health_score: bigint, created_at: string, updated_at: string, };
+export type LibraryDto = { id: bigint, name: string, category: LibraryCategory, directory: string, posters: Array<LibraryPoster>, item_count: bigint, last_scanned: string | null, current_status: LibraryStatus, auto_scan: boolean, scan_schedule: ScanSchedule | null, error: string | null, storage_used: bigint, health_score: bigint, created_at: string, updated_at: string, };
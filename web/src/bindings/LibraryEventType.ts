@@ -1,5 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Library } from "./Library";
+import type { LibraryDiff } from "./LibraryDiff";
+import type { RescanSummary } from "./RescanSummary";
+import type { ScanTrigger } from "./ScanTrigger";
 import type { TaskIdentifier } from "./TaskIdentifier";
 
-export type LibraryEventType = { "LibraryScanned": { task_identifier: TaskIdentifier, library: Library, } } | { "LibrarySaved": { task_identifier: TaskIdentifier, library_id: bigint, library_name: string, } };
+export type LibraryEventType = { "LibraryScanned": { task_identifier: TaskIdentifier, library: Library, } } | { "LibrarySaved": { task_identifier: TaskIdentifier, library_id: bigint, library_name: string, } } | { "LibraryRescanned": { task_identifier: TaskIdentifier, library_id: bigint, entry_path: string | null, trigger: ScanTrigger, library: Library, diff: LibraryDiff, } } | { "LibraryRescanFailed": { task_identifier: TaskIdentifier, library_id: bigint, trigger: ScanTrigger, error: string, } } | { "LibraryRescanSaved": { task_identifier: TaskIdentifier, library_id: bigint, summary: RescanSummary, } } | { "LibraryChanged": { library_id: bigint, entry_path: string, summary: RescanSummary, } } | { "LibraryScheduledScanSaved": { library_id: bigint, summary: RescanSummary, } };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ScanTrigger } from "./ScanTrigger";
+import type { TaskIdentifier } from "./TaskIdentifier";
+
+export type LibraryRescanFailed = { taskIdentifier: TaskIdentifier, libraryId: bigint, trigger: ScanTrigger, error: string, };
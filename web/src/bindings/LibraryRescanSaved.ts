@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RescanSummary } from "./RescanSummary";
+import type { TaskIdentifier } from "./TaskIdentifier";
+
+export type LibraryRescanSaved = { taskIdentifier: TaskIdentifier, libraryId: bigint, summary: RescanSummary, };
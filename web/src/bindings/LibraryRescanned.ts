@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Library } from "./Library";
+import type { LibraryDiff } from "./LibraryDiff";
+import type { ScanTrigger } from "./ScanTrigger";
+import type { TaskIdentifier } from "./TaskIdentifier";
+
+export type LibraryRescanned = { taskIdentifier: TaskIdentifier, libraryId: bigint, entryPath: string | null, trigger: ScanTrigger, library: Library, diff: LibraryDiff, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The fingerprint of a playable file, either from the database or from the filesystem.
+ */
+export type MediaFileState = { video_file_path: string, file_size: bigint | null, file_modified_at: bigint | null, nfo_modified_at: bigint | null, };
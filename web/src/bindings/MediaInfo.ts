@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AudioTrack } from "./AudioTrack";
+import type { HdrFormat } from "./HdrFormat";
+import type { SubtitleTrack } from "./SubtitleTrack";
+
+/**
+ * Technical details of a video file as found by probing it. Codecs and containers use
+ * short lowercase names like `h264`, `aac` or `mp4`.
+ */
+export type MediaInfo = { container: string | null, duration: bigint | null, bitrate: bigint | null, video_codec: string | null, width: number | null, height: number | null, frame_rate: number | null, hdr_format: HdrFormat | null, audio_tracks: Array<AudioTrack>, subtitle_tracks: Array<SubtitleTrack>, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MediaSort } from "./MediaSort";
+import type { SortOrder } from "./SortOrder";
+
+export type MediaListQuery = { genre: string | null, studio: string | null, actor: string | null, year_from: bigint | null, year_to: bigint | null, rating_min: number | null, country: string | null, sort: MediaSort | null, order: SortOrder | null, offset: bigint | null, limit: bigint | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MediaItemDto } from "./MediaItemDto";
+
+/**
+ * A page of the media of a library, `total` counts every item matching the filters.
+ */
+export type MediaPageDto = { items: Array<MediaItemDto>, total: bigint, offset: bigint, limit: bigint, };
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MediaActor } from "./MediaActor";
+import type { Subtitle } from "./Subtitle";
+
+export type Movie = { title: string | null, original_title: string | null, sort_title: string | null, tagline: string | null, year: string | null, plot: string | null, genres: Array<string>, studios: Array<string>, country: string | null, premiered: string | null, rating: number | null, certification: string | null, runtime: string | null, actors: Array<MediaActor>, tmdb_id: string | null, imdb_id: string | null, wikidata_id: string | null, nfo_path: string | null, poster_path: string | null, fanart_path: string | null, video_file_path: string, subtitle_file_path: string | null, subtitles: Array<Subtitle>, file_size: bigint | null, file_modified_at: bigint | null, nfo_modified_at: bigint | null, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ClientCapabilities } from "./ClientCapabilities";
+
+export type PlayVideoPayload = { episode_id?: bigint, movie_id?: bigint, start_position?: bigint, capabilities?: ClientCapabilities, audio_track?: number, burn_in_subtitle?: number, profile?: string, };
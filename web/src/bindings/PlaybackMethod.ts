@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a video reaches the client, ordered from the cheapest to the most expensive.
+ */
+export type PlaybackMethod = "DirectPlay" | "Remux" | "Transcode";
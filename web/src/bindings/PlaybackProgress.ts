@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where the viewer stopped watching an episode, positions are in seconds.
+ */
+export type PlaybackProgress = { episode_id: bigint, position: bigint, duration: bigint | null, completed: boolean, updated_at: string | null, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PlaybackMethod } from "./PlaybackMethod";
+import type { TaskId } from "./TaskId";
+
+/**
+ * A started playback session, the player loads the stream from `stream_url`.
+ */
+export type PlaybackSessionDto = { session_id: string, task_id: TaskId, method: PlaybackMethod, stream_url: string, content_type: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How the video encoder spends bits, written as `{ "bitrate": 4000 }` or `{ "crf": 23 }`.
+ */
+export type RateControl = { "bitrate": number } | { "crf": number };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How often a library is rescanned in the background.
+ */
+export type ScanSchedule = { "Daily": { hour: number, minute: number, } } | { "Interval": { hours: number, } };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What started a rescan, each trigger saves its results with its own event handlers.
+ */
+export type ScanTrigger = "Manual" | "Watcher" | "Schedule";
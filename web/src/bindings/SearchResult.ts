@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SearchResultKind } from "./SearchResultKind";
+
+/**
+ * A match of a search, `media_id` is the tv show which a tv show or episode belongs to.
+ * The matched terms of the snippet are wrapped in `<mark>` tags.
+ */
+export type SearchResult = { kind: SearchResultKind, id: bigint, media_id: bigint | null, title: string, snippet: string, image: string | null, rank: number, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { User } from "./User";
+
+/**
+ * A started login session, browsers get the token as a cookie as well while other clients
+ * send it in an `Authorization: Bearer` header.
+ */
+export type SessionDto = { token: string, user: User, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SubtitleFormat } from "./SubtitleFormat";
+
+/**
+ * A subtitle of an episode or movie, either a sidecar file next to the video
+ * or a text stream embedded in the video container.
+ */
+export type Subtitle = { language: string | null, title: string | null, format: SubtitleFormat, file_path: string | null, stream_index: bigint | null, };
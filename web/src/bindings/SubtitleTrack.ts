@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A subtitle stream of a video file, the index counts only the subtitle streams and
+ * matches the stream index of embedded subtitles.
+ */
+export type SubtitleTrack = { index: number, language: string | null, title: string | null, codec: string | null, image_based: boolean, };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TaskId } from "./TaskId";
+import type { TaskStatus } from "./TaskStatus";
+import type { TaskType } from "./TaskType";
+
+/**
+ * The state of an async task, clients without a WebSocket connection poll it.
+ */
+export type TaskDto = { task_id: TaskId, task_type: TaskType, status: TaskStatus, progress: number, };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TaskId } from "./TaskId";
+import type { TaskStatus } from "./TaskStatus";
+import type { TaskType } from "./TaskType";
+
+/**
+ * A finished task as it is kept in the task history.
+ */
+export type TaskRecord = { task_id: TaskId, task_type: TaskType, status: TaskStatus, 
+/**
+ * Why the task failed
+ */
+error: string | null, started_at: string | null, finished_at: string | null, };
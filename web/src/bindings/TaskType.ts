@@ -1,3 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type TaskType = "MediaLibraryScan" | "PipelinePreparation" | "TestTask";
+export type TaskType = "MediaLibraryScan" | "MediaLibraryRescan" | "PipelinePreparation" | "ThumbnailExtraction" | "TrickplayGeneration" | "TestTask";
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AudioCodec } from "./AudioCodec";
+import type { RateControl } from "./RateControl";
+import type { VideoCodec } from "./VideoCodec";
+
+/**
+ * A named set of encoder settings used when a video is transcoded. Remuxed and directly
+ * played videos keep their original streams.
+ */
+export type TranscodingProfile = { name: string, video_codec: VideoCodec, rate_control: RateControl | null, preset: string | null, max_height: number | null, audio_codec: AudioCodec, audio_bitrate: number, audio_channels: number | null, };
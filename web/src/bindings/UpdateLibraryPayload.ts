@@ -1,4 +1,5 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { LibraryCategory } from "./LibraryCategory";
+import type { ScanSchedule } from "./ScanSchedule";
 
-export type UpdateLibraryPayload = { id: number, name: string, directory: string, category: LibraryCategory, };
+export type UpdateLibraryPayload = { id: number, name: string, directory: string, category: LibraryCategory, scan_schedule?: ScanSchedule, };
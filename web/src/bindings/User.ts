@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type User = { id: bigint, username: string, is_admin: boolean, 
+/**
+ * The highest certification the user may watch, e.g. `TV-14`, uncapped when `None`
+ */
+max_certification: string | null, created_at: string | null, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Replaces what a user who is not an admin may see.
+ */
+export type UserAccessPayload = { library_ids: Array<bigint>, 
+/**
+ * The highest certification the user may watch, e.g. `TV-14`, uncapped when `None`
+ */
+max_certification: string | null, };
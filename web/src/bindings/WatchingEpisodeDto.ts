@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EpisodeDto } from "./EpisodeDto";
+
+export type WatchingEpisodeDto = { media_id: bigint, media_title: string, poster_path: string | null, episode: EpisodeDto, };
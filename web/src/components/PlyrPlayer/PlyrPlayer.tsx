@@ -7,7 +7,7 @@ import 'video.js/dist/video-js.css'
 
 import './style.css'
 
-const PlyrPlayer = () => {
+const PlyrPlayer = ({ src }: { src: string }) => {
   const ref = useRef<APITypes | null>(null)
   const playerRef = useRef<any>(null)
   const videoRef = useRef<HTMLVideoElement | null>(null)
@@ -31,7 +31,7 @@ const PlyrPlayer = () => {
       const player = videojs(video, {
         autoplay: true,
         controls: false, // Let Plyr handle the controls
-        sources: [{ src: '/hls/playlist.m3u8', type: 'application/x-mpegURL' }],
+        sources: [{ src, type: 'application/x-mpegURL' }],
         html5: {
           hls: {
             overrideNative: true,
@@ -63,7 +63,7 @@ const PlyrPlayer = () => {
         if (error && error.code === 4) {
           setTimeout(() => {
             player.src({
-              src: '/hls/playlist.m3u8',
+              src,
               type: 'application/x-mpegURL',
             })
             player.load()
@@ -74,7 +74,7 @@ const PlyrPlayer = () => {
     }
 
     loadVideo()
-  }, [])
+  }, [src])
 
   return (
     <Plyr
@@ -7,10 +7,16 @@ import 'video.js/dist/video-js.css'
 import './style.css'
 import { useWebSocket } from '~/hooks/useWebSocket'
 
+// Seconds between two progress reports while the video is playing
+const PROGRESS_INTERVAL = 10
+
 export const VideoPlayer = (props: any) => {
   const videoRef = useRef<any>(null)
   const playerRef = useRef<any>(null)
-  const { options, onReady, onReset } = props
+  const lastProgressRef = useRef<number>(0)
+  const { options, onReady, onReset, onProgress, startTime } = props
+  const onProgressRef = useRef(onProgress)
+  onProgressRef.current = onProgress
   const { sendMessage } = useWebSocket()
 
   const resetPlayer = () => {
@@ -38,6 +44,9 @@ export const VideoPlayer = (props: any) => {
       const player = (playerRef.current = videojs(videoElement, options, () => {
         videojs.log('player is ready')
 
+        if (startTime) {
+          player.currentTime(startTime)
+        }
         player.play()
 
         player.on('error', (e: any) => {
@@ -48,6 +57,21 @@ export const VideoPlayer = (props: any) => {
         onReady && onReady(player)
       }))
 
+      const reportProgress = () => {
+        const currentTime = Math.floor(player.currentTime() ?? 0)
+        lastProgressRef.current = currentTime
+        onProgressRef.current?.(currentTime, Math.floor(player.duration() ?? 0))
+      }
+
+      player.on('timeupdate', () => {
+        const currentTime = Math.floor(player.currentTime() ?? 0)
+        if (Math.abs(currentTime - lastProgressRef.current) >= PROGRESS_INTERVAL) {
+          reportProgress()
+        }
+      })
+      player.on('pause', reportProgress)
+      player.on('ended', reportProgress)
+
       player.on('seeking', () => {
         const currentTime = Math.floor(player.currentTime() ?? 0)
         console.log('seeking: ', currentTime)
@@ -7,6 +7,10 @@ interface NotificationPayloadMap
     libraryName: string
     libraryId: number
   }
+  LibraryChanged: {
+    libraryId: number
+    entryPath: string
+  }
   RegisterClient: {
     clientKey: string
   }
@@ -0,0 +1,38 @@
+import { ClientCapabilities } from '~/bindings/ClientCapabilities'
+
+// Names follow the codec and container names the server reports when probing files
+const CONTAINERS: Record<string, string> = {
+  mp4: 'video/mp4',
+  webm: 'video/webm',
+  mkv: 'video/x-matroska',
+}
+
+const VIDEO_CODECS: Record<string, string> = {
+  h264: 'video/mp4; codecs="avc1.640028"',
+  hevc: 'video/mp4; codecs="hvc1.1.6.L120.90"',
+  vp9: 'video/webm; codecs="vp9"',
+  av1: 'video/mp4; codecs="av01.0.08M.08"',
+}
+
+const AUDIO_CODECS: Record<string, string> = {
+  aac: 'audio/mp4; codecs="mp4a.40.2"',
+  mp3: 'audio/mpeg',
+  opus: 'audio/webm; codecs="opus"',
+  flac: 'audio/flac',
+  ac3: 'audio/mp4; codecs="ac-3"',
+  eac3: 'audio/mp4; codecs="ec-3"',
+}
+
+const supported = (types: Record<string, string>) => {
+  const video = document.createElement('video')
+
+  return Object.entries(types)
+    .filter(([, type]) => video.canPlayType(type) !== '')
+    .map(([name]) => name)
+}
+
+export const getClientCapabilities = (): ClientCapabilities => ({
+  containers: supported(CONTAINERS),
+  video_codecs: supported(VIDEO_CODECS),
+  audio_codecs: supported(AUDIO_CODECS),
+})
@@ -16,6 +16,7 @@ import { AnimatePresence } from 'motion/react'
 
 import { EpisodeDto } from '~/bindings/EpisodeDto'
 import { MediaItemDto } from '~/bindings/MediaItemDto'
+import { PlaybackSessionDto } from '~/bindings/PlaybackSessionDto'
 import { PulseLoader } from '~/components/PulseLoader/PulseLoader'
 import { FadeIn } from '~/components/TransitionContainer/FadeIn'
 import { FadeInUp } from '~/components/TransitionContainer/FadeInUp'
@@ -30,6 +31,7 @@ import { useFeatures } from '~/hooks/feature/useFeatures'
 import { useEventBus } from '~/hooks/useEventBus'
 import { useFetch } from '~/hooks/useFetch'
 import { usePost } from '~/hooks/usePost'
+import { getClientCapabilities } from '~/lib/capabilities'
 
 enum VideoPlayerState {
   Idle = 'idle',
@@ -44,6 +46,10 @@ export default function ContentDetailPage() {
     VideoPlayerState.Idle
   )
 
+  const [playingEpisode, setPlayingEpisode] = useState<EpisodeDto | null>(null)
+  const [startTime, setStartTime] = useState<number | undefined>(undefined)
+  const [session, setSession] = useState<PlaybackSessionDto | null>(null)
+
   const playerResetRef = useRef<(() => void) | null>(null)
   const handlePlayerReset = useCallback((resetFn: () => void) => {
     playerResetRef.current = resetFn
@@ -74,14 +80,16 @@ export default function ContentDetailPage() {
     preload: 'none',
     sources: [
       {
-        src: '/hls/playlist.m3u8',
-        type: 'application/x-mpegURL',
+        src: session?.stream_url,
+        type: session?.content_type,
       },
     ],
   }
 
   const onBack = () => {
-    post('/video-player/stop')
+    if (session) {
+      post(`/video-player/${session.session_id}/stop`)
+    }
   }
 
   const handlePlay = useCallback(
@@ -94,21 +102,57 @@ export default function ContentDetailPage() {
         videoPlayerState === VideoPlayerState.Playing
       ) {
         playerResetRef.current()
-        await post('/video-player/stop')
+      }
+      if (session) {
+        await post(`/video-player/${session.session_id}/stop`)
+        setSession(null)
       }
 
-      post('/video-player/play', {
-        path: episode.video_file_path,
-      })
+      // Finished episodes start over, everything else continues where it stopped
+      const progress = episode.progress
+      const startPosition =
+        progress && !progress.completed && Number(progress.position) > 0
+          ? Number(progress.position)
+          : undefined
+      setPlayingEpisode(episode)
+      setStartTime(startPosition)
 
       onEvent('HlsStreamInitialized', () => {
         console.log('HlsStreamInitialized')
         setVideoPlayerState(VideoPlayerState.Playing)
       })
 
+      const newSession = await post<unknown, PlaybackSessionDto>(
+        '/video-player/play',
+        {
+          episode_id: episode.id,
+          start_position: startPosition,
+          capabilities: getClientCapabilities(),
+        }
+      )
+      setSession(newSession)
+
+      // Direct play has no stream to prepare, the file can be loaded right away
+      if (newSession.method === 'DirectPlay') {
+        setVideoPlayerState(VideoPlayerState.Playing)
+      }
+
       // TODO: handle the error and set the state to error or idle
     },
-    [post, onEvent, playerResetRef]
+    [post, onEvent, playerResetRef, session, videoPlayerState]
+  )
+
+  const handleProgress = useCallback(
+    (position: number, duration: number) => {
+      if (!playingEpisode) return
+
+      post('/video-player/progress', {
+        episode_id: playingEpisode.id,
+        position,
+        duration: duration > 0 ? duration : null,
+      })
+    },
+    [post, playingEpisode]
   )
 
   const renderError = (error: Error, content: React.ReactNode) =>
@@ -149,6 +193,8 @@ export default function ContentDetailPage() {
               <VideoPlayer
                 options={videoJsOptions}
                 onReset={handlePlayerReset}
+                onProgress={handleProgress}
+                startTime={startTime}
               />
             </FadeIn>
           ) : (
@@ -339,7 +385,11 @@ export default function ContentDetailPage() {
             <div className='flex flex-col sm:flex-row'>
               <div className='relative aspect-video w-full sm:aspect-[16/9] sm:w-48'>
                 <img
-                  src={episode.thumb_image || '/placeholder.svg'}
+                  src={
+                    episode.thumb_image
+                      ? `${episode.thumb_image}?w=384`
+                      : '/placeholder.svg'
+                  }
                   alt={episode.title || 'Episode'}
                   className='absolute inset-0 h-full w-full rounded-l-xl object-cover'
                 />
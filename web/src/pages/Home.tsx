@@ -5,7 +5,7 @@ import { Link } from 'react-router-dom'
 import { mutate } from 'swr'
 
 import { Tabs, TabsContent, TabsList, TabsTrigger } from '../components/ui/tabs'
-import { MediaItemDto } from '~/bindings/MediaItemDto'
+import { MediaPageDto } from '~/bindings/MediaPageDto'
 import { AsyncSwitcher } from '~/components/AsyncSwitcher/AsyncSwitcher'
 import { MatrialFadeIn } from '~/components/TransitionContainer/MatrialFadeInContainer'
 import { VerticalCard } from '~/components/VerticalCard/VerticalCard'
@@ -16,8 +16,7 @@ export default function HomePage() {
   const [_scanningLibraryIds, setScanningLibraryIds] = useState<Set<number>>(
     new Set()
   )
-  const { data, error, isLoading } =
-    useFetch<MediaItemDto[]>('/library/1/media')
+  const { data, error, isLoading } = useFetch<MediaPageDto>('/library/1/media')
 
   const { onEvent, offEvent } = useEventBus()
   const { t } = useTranslation()
@@ -38,6 +37,11 @@ export default function HomePage() {
     mutate('/library/')
   }, [])
 
+  const handleChanged = useCallback((payload: { libraryId: number }) => {
+    mutate('/library/')
+    mutate(`/library/${payload.libraryId}/media`)
+  }, [])
+
   useEffect(() => {
     mutate('/library/')
   }, [])
@@ -45,21 +49,23 @@ export default function HomePage() {
   useEffect(() => {
     onEvent('LibraryScanning', handleScanning)
     onEvent('LibrarySaved', handleSaved)
+    onEvent('LibraryChanged', handleChanged)
 
     return () => {
       offEvent('LibraryScanning', handleScanning)
       offEvent('LibrarySaved', handleSaved)
+      offEvent('LibraryChanged', handleChanged)
     }
-  }, [offEvent, onEvent, handleScanning, handleSaved])
+  }, [offEvent, onEvent, handleScanning, handleSaved, handleChanged])
 
   const renderContent = () => (
     <MatrialFadeIn delay={0.2} className='lg:col-span-1'>
       <div className='flex w-max space-x-4 p-1'>
-        {data?.map(media => (
+        {data?.items.map(media => (
           <Link to={`/content/${media.id}`} key={media.title}>
             <VerticalCard
               title={media.title}
-              posterPath={media.poster_path || ''}
+              posterPath={media.poster_path ? `${media.poster_path}?w=300` : ''}
               plot={media.plot || ''}
               category={'movie'}
               year={media?.year?.toString() || '2020'}
@@ -1,6 +1,10 @@
+import { useParams } from 'react-router-dom'
+
 import VideoPlayer from '~/components/VideoPlayer/VideoPlayer'
 
 export const Video = () => {
+  const { sessionId } = useParams<{ sessionId: string }>()
+
   const videoJsOptions = {
     controls: true,
     autoplay: true,
@@ -8,7 +12,7 @@ export const Video = () => {
     preload: 'none',
     sources: [
       {
-        src: '/hls/playlist.m3u8',
+        src: `/hls/${sessionId}/master.m3u8`,
         type: 'application/x-mpegURL',
       },
     ],
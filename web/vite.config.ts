@@ -33,8 +33,13 @@ export default defineConfig(() => {
           ...devProxy,
           proxyTimeout: 30000,
         },
+        '/artwork': devProxy,
+        '/auth': devProxy,
         '/library': devProxy,
         '/media': devProxy,
+        '/search': devProxy,
+        '/tasks': devProxy,
+        '/users': devProxy,
         '/video-player': devProxy,
         '/ws': {
           ...devProxy,